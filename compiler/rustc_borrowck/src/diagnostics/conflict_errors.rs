@@ -2212,6 +2212,7 @@ fn suggest_using_local_if_applicable(
                 _,
                 LaterUseKind::Call | LaterUseKind::Other,
                 _call_span,
+                _,
                 _
             )
         );
@@ -2227,7 +2228,7 @@ fn suggest_using_local_if_applicable(
             return;
         }
 
-        let use_span = if let BorrowExplanation::UsedLater(_, LaterUseKind::Other, use_span, _) =
+        let use_span = if let BorrowExplanation::UsedLater(_, LaterUseKind::Other, use_span, _, _) =
             explanation
         {
             Some(use_span)
@@ -3061,7 +3062,7 @@ pub(crate) fn report_borrowed_value_does_not_live_long_enough(
             // and `move` will not help here.
             (
                 Some(name),
-                BorrowExplanation::UsedLater(_, LaterUseKind::ClosureCapture, var_or_use_span, _),
+                BorrowExplanation::UsedLater(_, LaterUseKind::ClosureCapture, var_or_use_span, ..),
             ) if borrow_spans.for_coroutine() || borrow_spans.for_closure() => self
                 .report_escaping_closure_capture(
                     borrow_spans,
@@ -3231,7 +3232,7 @@ fn report_local_value_does_not_live_long_enough(
             explanation.add_explanation_to_diagnostic(&self, &mut err, "", Some(borrow_span), None);
 
             // Detect buffer reuse pattern
-            if let BorrowExplanation::UsedLater(_dropped_local, _, _, _) = explanation {
+            if let BorrowExplanation::UsedLater(_dropped_local, ..) = explanation {
                 // Check all locals at the borrow location to find Vec<&T> types
                 for (local, local_decl) in self.body.local_decls.iter_enumerated() {
                     if let ty::Adt(adt_def, args) = local_decl.ty.kind()
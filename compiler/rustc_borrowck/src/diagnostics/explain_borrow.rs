@@ -1,18 +1,20 @@
 //! Print diagnostics to explain why values are borrowed.
 
-use std::assert_matches;
+use std::collections::VecDeque;
+use std::{assert_matches, iter};
 
-use rustc_errors::{Applicability, Diag, EmissionGuarantee};
+use rustc_errors::{Applicability, Diag, EmissionGuarantee, MultiSpan};
 use rustc_hir as hir;
 use rustc_hir::intravisit::Visitor;
+use rustc_index::IndexVec;
 use rustc_infer::infer::NllRegionVariableOrigin;
 use rustc_middle::middle::resolve_bound_vars::ObjectLifetimeDefault;
 use rustc_middle::mir::{
-    Body, CallSource, CastKind, ConstraintCategory, FakeReadCause, Local, LocalInfo, Location,
-    Operand, Place, Rvalue, Statement, StatementKind, TerminatorKind,
+    BasicBlock, Body, CallSource, CastKind, ConstraintCategory, FakeReadCause, Local, LocalInfo,
+    Location, Operand, Place, Rvalue, Statement, StatementKind, TerminatorKind,
 };
 use rustc_middle::ty::adjustment::PointerCoercion;
-use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_middle::ty::{self, RegionVid, Ty, TyCtxt};
 use rustc_span::{DesugaringKind, Span, kw, sym};
 use rustc_trait_selection::error_reporting::traits::FindExprBySpan;
 use rustc_trait_selection::error_reporting::traits::call_kind::CallKind;
@@ -27,12 +29,13 @@
 
 #[derive(Debug)]
 pub(crate) enum BorrowExplanation<'tcx> {
-    UsedLater(Local, LaterUseKind, Span, Option<Span>),
-    UsedLaterInLoop(LaterUseKind, Span, Option<Span>),
+    UsedLater(Local, LaterUseKind, Span, Option<Span>, Option<Box<LoanPath<'tcx>>>),
+    UsedLaterInLoop(LaterUseKind, Span, Option<Span>, Option<Box<LoanPath<'tcx>>>),
     UsedLaterWhenDropped {
         drop_loc: Location,
         dropped_local: Local,
         should_note_order: bool,
+        loan_path: Option<Box<LoanPath<'tcx>>>,
     },
     MustBeValidFor {
         best_blame: BestBlame<'tcx>,
@@ -42,6 +45,20 @@ pub(crate) enum BorrowExplanation<'tcx> {
     Unexplained,
 }
 
+/// Why a loan is still live at a later use, as explained with `-Z borrowck-explain`.
+#[derive(Debug)]
+pub(crate) struct LoanPath<'tcx> {
+    /// The outlives constraints through which the borrow region flows into a region that is
+    /// live at the later use.
+    constraints: Vec<OutlivesConstraint<'tcx>>,
+    /// Where the borrow is created.
+    borrow_location: Location,
+    /// The basic blocks from the borrow to the later use, both included.
+    blocks: Vec<BasicBlock>,
+    /// The later use.
+    use_location: Location,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub(crate) enum LaterUseKind {
     TraitCapture,
@@ -101,6 +118,7 @@ pub(crate) fn add_explanation_to_diagnostic<G: EmissionGuarantee>(
                 later_use_kind,
                 var_or_use_span,
                 path_span,
+                _,
             ) => {
                 let message = match later_use_kind {
                     LaterUseKind::TraitCapture => "captured here by trait object",
@@ -151,7 +169,7 @@ pub(crate) fn add_explanation_to_diagnostic<G: EmissionGuarantee>(
                     }
                 }
             }
-            BorrowExplanation::UsedLaterInLoop(later_use_kind, var_or_use_span, path_span) => {
+            BorrowExplanation::UsedLaterInLoop(later_use_kind, var_or_use_span, path_span, _) => {
                 let message = match later_use_kind {
                     LaterUseKind::TraitCapture => {
                         "borrow captured here by trait object, in later iteration of loop"
@@ -187,6 +205,7 @@ pub(crate) fn add_explanation_to_diagnostic<G: EmissionGuarantee>(
                 drop_loc,
                 dropped_local,
                 should_note_order,
+                loan_path: _,
             } => {
                 let local_decl = &body.local_decls[dropped_local];
                 let mut ty = local_decl.ty;
@@ -435,6 +454,13 @@ fn visit_expr(&mut self, expr: &'hir hir::Expr<'hir>) {
             }
             _ => {}
         }
+
+        if let BorrowExplanation::UsedLater(.., Some(loan_path))
+        | BorrowExplanation::UsedLaterInLoop(.., Some(loan_path))
+        | BorrowExplanation::UsedLaterWhenDropped { loan_path: Some(loan_path), .. } = self
+        {
+            loan_path.add_to_diagnostic(cx, err, borrow_desc);
+        }
     }
 
     fn add_object_lifetime_default_note<G: EmissionGuarantee>(
@@ -523,6 +549,81 @@ fn add_lifetime_bound_suggestion_to_diagnostic<G: EmissionGuarantee>(
     }
 }
 
+impl<'tcx> LoanPath<'tcx> {
+    /// Adds notes showing the chain of outlives constraints and the control-flow path that
+    /// keep the loan live until its later use.
+    fn add_to_diagnostic<G: EmissionGuarantee>(
+        &self,
+        cx: &MirBorrowckCtxt<'_, '_, 'tcx>,
+        err: &mut Diag<'_, G>,
+        borrow_desc: &str,
+    ) {
+        let body = cx.body;
+
+        let mut constraint_labels = vec![];
+        for constraint in &self.constraints {
+            // Several constraints usually come from the same statement, and some don't
+            // come from the source at all.
+            if constraint.span.is_dummy()
+                || constraint_labels.last().is_some_and(|&(span, _)| span == constraint.span)
+            {
+                continue;
+            }
+            let step = constraint_labels.len() + 1;
+            let label = match constraint.category.description() {
+                "" => format!("{step}. the {borrow_desc}borrow flows here"),
+                category => format!("{step}. {category}makes the {borrow_desc}borrow flow here"),
+            };
+            constraint_labels.push((constraint.span, label));
+        }
+        if !constraint_labels.is_empty() {
+            err.span_note(
+                labeled_spans(constraint_labels),
+                format!(
+                    "the {borrow_desc}borrow is kept live by this chain of outlives constraints"
+                ),
+            );
+        }
+
+        let mut path_labels = vec![(
+            body.source_info(self.borrow_location).span,
+            format!("1. the {borrow_desc}borrow is created here"),
+        )];
+        let dominators = cx.dominators();
+        for (&from, &to) in iter::zip(&self.blocks, &self.blocks[1..]) {
+            let terminator = body.basic_blocks[from].terminator();
+            let label = if dominators.dominates(to, from) {
+                "loops back to the start of the loop"
+            } else if let TerminatorKind::SwitchInt { .. } = terminator.kind {
+                "takes this branch"
+            } else {
+                continue;
+            };
+            let step = path_labels.len() + 1;
+            path_labels.push((terminator.source_info.span, format!("{step}. {label}")));
+        }
+        let step = path_labels.len() + 1;
+        path_labels.push((
+            body.source_info(self.use_location).span,
+            format!("{step}. the {borrow_desc}borrow is used here, while still live"),
+        ));
+        err.span_note(
+            labeled_spans(path_labels),
+            format!(
+                "the {borrow_desc}borrow is live along this control-flow path to its later use"
+            ),
+        );
+    }
+}
+
+fn labeled_spans(labels: Vec<(Span, String)>) -> MultiSpan {
+    let mut spans = MultiSpan::from_spans(labels.iter().map(|&(span, _)| span).collect());
+    for (span, label) in labels {
+        spans.push_span_label(span, label);
+    }
+    spans
+}
+
 fn suggest_rewrite_if_let<G: EmissionGuarantee>(
     tcx: TyCtxt<'_>,
     expr: &hir::Expr<'_>,
@@ -645,10 +746,16 @@ pub(crate) fn explain_why_borrow_contains_point(
                     .move_spans(Place::from(local).as_ref(), location)
                     .or_else(|| self.borrow_spans(span, location));
 
+                let loan_path = self.loan_path(borrow, region_sub, location);
                 if use_in_later_iteration_of_loop {
                     let (later_use_kind, var_or_use_span, path_span) =
                         self.later_use_kind(borrow, spans, use_location);
-                    BorrowExplanation::UsedLaterInLoop(later_use_kind, var_or_use_span, path_span)
+                    BorrowExplanation::UsedLaterInLoop(
+                        later_use_kind,
+                        var_or_use_span,
+                        path_span,
+                        loan_path,
+                    )
                 } else {
                     // Check if the location represents a `FakeRead`, and adapt the error
                     // message to the `FakeReadCause` it is from: in particular,
@@ -660,6 +767,7 @@ pub(crate) fn explain_why_borrow_contains_point(
                         later_use_kind,
                         var_or_use_span,
                         path_span,
+                        loan_path,
                     )
                 }
             }
@@ -679,6 +787,7 @@ pub(crate) fn explain_why_borrow_contains_point(
                     drop_loc: location,
                     dropped_local: local,
                     should_note_order,
+                    loan_path: self.loan_path(borrow, region_sub, location),
                 }
             }
 
@@ -711,6 +820,79 @@ pub(crate) fn explain_why_borrow_contains_point(
         }
     }
 
+    /// Computes why `borrow` is still live at `use_location`, where `region_sub` is live, for
+    /// `-Z borrowck-explain`.
+    fn loan_path(
+        &self,
+        borrow: &BorrowData<'tcx>,
+        region_sub: RegionVid,
+        use_location: Location,
+    ) -> Option<Box<LoanPath<'tcx>>> {
+        if !self.infcx.tcx.sess.opts.unstable_opts.borrowck_explain {
+            return None;
+        }
+
+        let constraints = if region_sub == borrow.region {
+            vec![]
+        } else {
+            self.regioncx.constraint_path_between_regions(borrow.region, region_sub)?
+        };
+
+        let borrow_location = borrow.reserve_location;
+        let blocks = if borrow_location.block == use_location.block
+            && borrow_location.statement_index < use_location.statement_index
+        {
+            vec![borrow_location.block]
+        } else {
+            // Prefer a path along which the loan is live, but the region of the borrow may not
+            // be precise enough to contain one.
+            let is_live = |block| {
+                self.regioncx
+                    .region_contains_point(borrow.region, Location { block, statement_index: 0 })
+            };
+            self.control_flow_path(borrow_location.block, use_location.block, is_live).or_else(
+                || self.control_flow_path(borrow_location.block, use_location.block, |_| true),
+            )?
+        };
+
+        Some(Box::new(LoanPath { constraints, borrow_location, blocks, use_location }))
+    }
+
+    /// Finds the shortest path of at least one edge from `from` to `to` in the control-flow
+    /// graph, only going through the blocks accepted by `allowed` on the way.
+    fn control_flow_path(
+        &self,
+        from: BasicBlock,
+        to: BasicBlock,
+        allowed: impl Fn(BasicBlock) -> bool,
+    ) -> Option<Vec<BasicBlock>> {
+        let basic_blocks = &self.body.basic_blocks;
+        let mut predecessors = IndexVec::from_elem_n(None, basic_blocks.len());
+        let mut queue = VecDeque::from([from]);
+        while let Some(block) = queue.pop_front() {
+            for succ in basic_blocks[block].terminator().successors() {
+                if predecessors[succ].is_some() {
+                    continue;
+                }
+                if succ == to {
+                    let mut path = vec![to, block];
+                    let mut block = block;
+                    while block != from {
+                        block = predecessors[block].unwrap();
+                        path.push(block);
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                if allowed(succ) {
+                    predecessors[succ] = Some(block);
+                    queue.push_back(succ);
+                }
+            }
+        }
+        None
+    }
+
     /// Determine how the borrow was later used.
     /// First span returned points to the location of the conflicting use
     /// Second span if `Some` is returned in the case of closures and points
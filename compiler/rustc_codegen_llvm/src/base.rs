@@ -61,6 +61,31 @@ pub(crate) fn iter_global_aliases(llmod: &llvm::Module) -> ValueIter<'_> {
     }
 }
 
+pub(crate) fn iter_functions(llmod: &llvm::Module) -> ValueIter<'_> {
+    unsafe { ValueIter { cur: llvm::LLVMGetFirstFunction(llmod), step: llvm::LLVMGetNextFunction } }
+}
+
+/// Counts the instructions of each function defined in `llmod`, for `-Z codegen-report`.
+pub(crate) fn function_instruction_counts(llmod: &llvm::Module) -> Vec<(String, u64)> {
+    iter_functions(llmod)
+        .filter(|&llfn| !llvm::is_declaration(llfn))
+        .map(|llfn| {
+            let mut instructions = 0;
+            let mut block = Some(unsafe { llvm::LLVMGetFirstBasicBlock(llfn) });
+            while let Some(llbb) = block {
+                let mut instruction = unsafe { llvm::LLVMGetFirstInstruction(llbb) };
+                while let Some(llinst) = instruction {
+                    instructions += 1;
+                    instruction = unsafe { llvm::LLVMGetNextInstruction(llinst) };
+                }
+                block = unsafe { llvm::LLVMGetNextBasicBlock(llbb) };
+            }
+            let name = String::from_utf8_lossy(&llvm::get_value_name(llfn)).into_owned();
+            (name, instructions)
+        })
+        .collect()
+}
+
 pub(crate) fn compile_codegen_unit(
     tcx: TyCtxt<'_>,
     cgu_name: Symbol,
@@ -115,6 +115,9 @@ fn compile_codegen_unit(
     ) -> (ModuleCodegen<ModuleLlvm>, u64) {
         base::compile_codegen_unit(tcx, cgu_name)
     }
+    fn function_instruction_counts(&self, module: &ModuleLlvm) -> Vec<(String, u64)> {
+        base::function_instruction_counts(module.llmod())
+    }
 }
 
 impl WriteBackendMethods for LlvmCodegenBackend {
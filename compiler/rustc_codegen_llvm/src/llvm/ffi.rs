@@ -1126,6 +1126,8 @@ pub(crate) fn LLVMAddFunction<'a>(
         FunctionTy: &'a Type,
     ) -> &'a Value;
     pub(crate) fn LLVMDeleteFunction(Fn: &Value);
+    pub(crate) fn LLVMGetFirstFunction(M: &Module) -> Option<&Value>;
+    pub(crate) fn LLVMGetNextFunction(Fn: &Value) -> Option<&Value>;
 
     // Operations about llvm intrinsics
     pub(crate) fn LLVMLookupIntrinsicID(Name: *const c_char, NameLen: size_t) -> c_uint;
@@ -1149,6 +1151,7 @@ pub(crate) fn LLVMRustUpgradeIntrinsicFunction<'a>(
 
     // Operations on basic blocks
     pub(crate) fn LLVMGetBasicBlockParent(BB: &BasicBlock) -> &Value;
+    pub(crate) fn LLVMGetNextBasicBlock(BB: &BasicBlock) -> Option<&BasicBlock>;
     pub(crate) fn LLVMAppendBasicBlockInContext<'a>(
         C: &'a Context,
         Fn: &'a Value,
@@ -1158,6 +1161,8 @@ pub(crate) fn LLVMAppendBasicBlockInContext<'a>(
     // Operations on instructions
     pub(crate) fn LLVMIsAInstruction(Val: &Value) -> Option<&Value>;
     pub(crate) fn LLVMGetFirstBasicBlock(Fn: &Value) -> &BasicBlock;
+    pub(crate) fn LLVMGetFirstInstruction(BB: &BasicBlock) -> Option<&Value>;
+    pub(crate) fn LLVMGetNextInstruction(Inst: &Value) -> Option<&Value>;
     pub(crate) fn LLVMGetOperand(Val: &Value, Index: c_uint) -> Option<&Value>;
 
     // Operations on call sites
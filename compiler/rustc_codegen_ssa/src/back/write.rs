@@ -3,6 +3,7 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::mpsc::{Receiver, Sender, channel};
+use std::time::Instant;
 use std::{assert_matches, fs, io, mem, str, thread};
 
 use rustc_abi::Size;
@@ -33,7 +34,8 @@
 
 use crate::back::link::ensure_removed;
 use crate::back::lto::{self, SerializedModule, check_lto_allowed};
-use crate::diagnostics::ErrorCreatingRemarkDir;
+use crate::codegen_report::{CodegenReport, OptimizationTimes};
+use crate::diagnostics::{CodegenReportWriteFailure, ErrorCreatingRemarkDir};
 use crate::traits::*;
 use crate::{
     CachedModuleCodegen, CompiledModule, CompiledModules, CrateInfo, ModuleCodegen, ModuleKind,
@@ -360,6 +362,8 @@ pub struct CodegenContext {
     ///
     /// Depends on [`WriteBackendMethods::supports_parallel()`] and `--jobs-backend`.
     pub parallel: bool,
+    /// Where to record the time spent optimizing each module, for `-Z codegen-report`.
+    pub optimization_times: Option<OptimizationTimes>,
 }
 
 fn generate_thin_lto_work<B: WriteBackendMethods>(
@@ -437,6 +441,9 @@ pub(crate) fn start_async_codegen<B: WriteBackendMethods>(
     let (shared_emitter, shared_emitter_main) = SharedEmitter::new();
     let (codegen_worker_send, codegen_worker_receive) = channel();
 
+    let codegen_report =
+        tcx.sess.opts.unstable_opts.codegen_report.is_some().then(|| CodegenReport::new(tcx));
+
     let coordinator_thread = start_executing_work(
         backend.clone(),
         tcx,
@@ -447,10 +454,12 @@ pub(crate) fn start_async_codegen<B: WriteBackendMethods>(
         Arc::new(allocator_config),
         allocator_module,
         coordinator_send.clone(),
+        codegen_report.as_ref().map(|report| report.optimization_times()),
     );
 
     OngoingCodegen {
         backend,
+        codegen_report,
 
         codegen_worker_receive,
         shared_emitter_main,
@@ -828,7 +837,11 @@ fn execute_optimize_work_item<B: WriteBackendMethods>(
 ) -> WorkItemResult<B> {
     let _timer = prof.generic_activity_with_arg("codegen_module_optimize", &*module.name);
 
+    let start_time = Instant::now();
     B::optimize(cgcx, prof, &shared_emitter, &mut module, &cgcx.module_config);
+    if let Some(optimization_times) = &cgcx.optimization_times {
+        optimization_times.lock().unwrap().insert(module.name.clone(), start_time.elapsed());
+    }
 
     // After we've done the initial round of optimizations we need to
     // decide whether to synchronously codegen this module or ship it
@@ -1239,6 +1252,7 @@ fn start_executing_work<B: WriteBackendMethods>(
     allocator_config: Arc<ModuleConfig>,
     mut allocator_module: Option<ModuleCodegen<B::Module>>,
     coordinator_send: Sender<Message<B>>,
+    optimization_times: Option<OptimizationTimes>,
 ) -> thread::JoinHandle<Result<MaybeLtoModules<B>, ()>> {
     let sess = tcx.sess;
     let prof = sess.prof.clone();
@@ -1310,6 +1324,7 @@ fn start_executing_work<B: WriteBackendMethods>(
         split_dwarf_kind: tcx.sess.opts.unstable_opts.split_dwarf_kind,
         parallel,
         pointer_size: tcx.data_layout.pointer_size(),
+        optimization_times,
     };
 
     // This is the "main loop" of parallel work happening for parallel codegen.
@@ -2116,6 +2131,9 @@ fn drop(&mut self) {
 
 pub struct OngoingCodegen<B: WriteBackendMethods> {
     backend: B,
+    /// The report for `-Z codegen-report`, which is completed as codegen units are compiled and
+    /// written once they are all optimized.
+    pub(crate) codegen_report: Option<CodegenReport>,
     output_filenames: Arc<OutputFilenames>,
     // Field order below is intended to terminate the coordinator thread before two fields below
     // drop and prematurely close channels used by coordinator thread. See `Coordinator`'s
@@ -2204,6 +2222,13 @@ pub fn join(self, sess: &Session, crate_info: &CrateInfo) -> (CompiledModules, W
         // out deterministic results.
         compiled_modules.modules.sort_by(|a, b| a.name.cmp(&b.name));
 
+        if let Some(report) = self.codegen_report
+            && let Some(path) = &sess.opts.unstable_opts.codegen_report
+            && let Err(error) = report.write(path)
+        {
+            sess.dcx().emit_err(CodegenReportWriteFailure { path, error });
+        }
+
         let work_products =
             copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess, &compiled_modules);
         produce_final_output_artifacts(sess, &compiled_modules, &self.output_filenames);
@@ -761,7 +761,7 @@ pub fn codegen_crate<
         None
     };
 
-    let ongoing_codegen = start_async_codegen(backend.clone(), tcx, allocator_module);
+    let mut ongoing_codegen = start_async_codegen(backend.clone(), tcx, allocator_module);
 
     // For better throughput during parallel processing by LLVM, we used to sort
     // CGUs largest to smallest. This would lead to better thread utilization
@@ -854,6 +854,13 @@ pub fn codegen_crate<
                 // compilation hang on post-monomorphization errors.
                 tcx.dcx().abort_if_errors();
 
+                if let Some(report) = &mut ongoing_codegen.codegen_report {
+                    report.record_instruction_counts(
+                        &module.name,
+                        backend.function_instruction_counts(&module.module_llvm),
+                    );
+                }
+
                 submit_codegened_module_to_llvm(&ongoing_codegen.coordinator, module, cost);
             }
             CguReuse::PreLto => {
@@ -0,0 +1,149 @@
+//! Support for `-Z codegen-report`: attributing the cost of codegen to the source-level functions
+//! whose instances end up in each codegen unit.
+
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
+use rustc_middle::mono::MonoItem;
+use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+
+/// The time spent optimizing each codegen unit, by name. This is filled by the LLVM worker
+/// threads, so it is shared with the [`CodegenContext`](crate::back::write::CodegenContext).
+pub type OptimizationTimes = Arc<Mutex<FxHashMap<String, Duration>>>;
+
+#[derive(Default)]
+struct FunctionStats {
+    /// The number of distinct instances of the function that were codegened.
+    instantiations: u64,
+    /// The number of MIR statements and terminators in all the instances of the function.
+    mir_statements: u64,
+    /// The number of LLVM instructions generated for all the copies of all the instances of the
+    /// function, before optimizations.
+    llvm_instructions: u64,
+    /// The share of the time spent optimizing the codegen units that the function's instances
+    /// were placed in, in proportion to the number of instructions they contributed.
+    optimization_time: Duration,
+}
+
+/// A function instance placed in a codegen unit.
+struct CguItem {
+    symbol: String,
+    /// The index of the source-level function in `CodegenReport::functions`.
+    function: usize,
+    mir_statements: u64,
+    /// The number of LLVM instructions of this copy, if the backend reported them.
+    llvm_instructions: Option<u64>,
+}
+
+pub struct CodegenReport {
+    functions: FxIndexMap<String, FunctionStats>,
+    cgus: FxHashMap<String, Vec<CguItem>>,
+    optimization_times: OptimizationTimes,
+}
+
+impl CodegenReport {
+    pub(crate) fn new(tcx: TyCtxt<'_>) -> CodegenReport {
+        let mut functions: FxIndexMap<String, FunctionStats> = Default::default();
+        let mut cgus: FxHashMap<String, Vec<CguItem>> = Default::default();
+        let mut seen_instances = FxHashSet::default();
+        for cgu in tcx.collect_and_partition_mono_items(()).codegen_units {
+            let items = cgu
+                .items_in_deterministic_order(tcx)
+                .into_iter()
+                .filter_map(|(mono_item, _)| match mono_item {
+                    // Compiler-generated shims are not attributable to a source-level function.
+                    MonoItem::Fn(instance) if mono_item.is_user_defined() => {
+                        let name = with_no_trimmed_paths!(tcx.def_path_str(instance.def_id()));
+                        let entry = functions.entry(name);
+                        let function = entry.index();
+                        let stats = entry.or_default();
+                        let body = tcx.instance_mir(instance.def);
+                        let mir_statements = body
+                            .basic_blocks
+                            .iter()
+                            .map(|block| block.statements.len() as u64 + 1)
+                            .sum();
+                        // Instances that are copied into several codegen units are only counted
+                        // once, but all of their copies add to the number of LLVM instructions.
+                        if seen_instances.insert(instance) {
+                            stats.instantiations += 1;
+                            stats.mir_statements += mir_statements;
+                        }
+                        Some(CguItem {
+                            symbol: tcx.symbol_name(instance).name.to_string(),
+                            function,
+                            mir_statements,
+                            llvm_instructions: None,
+                        })
+                    }
+                    _ => None,
+                })
+                .collect();
+            cgus.insert(cgu.name().to_string(), items);
+        }
+        CodegenReport { functions, cgus, optimization_times: Default::default() }
+    }
+
+    pub(crate) fn optimization_times(&self) -> OptimizationTimes {
+        Arc::clone(&self.optimization_times)
+    }
+
+    /// Records the number of instructions of each function that the backend generated for the
+    /// codegen unit `cgu_name`, by symbol name.
+    pub(crate) fn record_instruction_counts(
+        &mut self,
+        cgu_name: &str,
+        instruction_counts: Vec<(String, u64)>,
+    ) {
+        let Some(items) = self.cgus.get_mut(cgu_name) else { return };
+        let instruction_counts: FxHashMap<_, _> = instruction_counts.into_iter().collect();
+        for item in items {
+            if let Some(&count) = instruction_counts.get(&item.symbol) {
+                item.llvm_instructions = Some(count);
+                self.functions[item.function].llvm_instructions += count;
+            }
+        }
+    }
+
+    /// Writes the report to `path` as a JSON array, with the functions that generated the most
+    /// LLVM instructions first.
+    pub(crate) fn write(mut self, path: &Path) -> io::Result<()> {
+        // Split the optimization time of each codegen unit between its functions. Without
+        // instruction counts from the backend, the size of their MIR is used instead.
+        let optimization_times = std::mem::take(&mut *self.optimization_times.lock().unwrap());
+        for (cgu_name, time) in optimization_times {
+            let Some(items) = self.cgus.get(&cgu_name) else { continue };
+            let weight = |item: &CguItem| item.llvm_instructions.unwrap_or(item.mir_statements);
+            let total: u64 = items.iter().map(weight).sum();
+            if total == 0 {
+                continue;
+            }
+            for item in items {
+                self.functions[item.function].optimization_time +=
+                    time.mul_f64(weight(item) as f64 / total as f64);
+            }
+        }
+
+        let mut functions: Vec<_> = self.functions.into_iter().collect();
+        functions.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.llvm_instructions));
+        let functions: Vec<_> = functions
+            .into_iter()
+            .map(|(name, stats)| {
+                serde_json::json!({
+                    "name": name,
+                    "instantiations": stats.instantiations,
+                    "mir_statements": stats.mir_statements,
+                    "llvm_instructions": stats.llvm_instructions,
+                    "optimization_secs": stats.optimization_time.as_secs_f64(),
+                })
+            })
+            .collect();
+        serde_json::to_writer_pretty(BufWriter::new(File::create(path)?), &functions)?;
+        Ok(())
+    }
+}
@@ -89,6 +89,13 @@ pub(crate) struct SymbolFileWriteFailure {
     pub error: Error,
 }
 
+#[derive(Diagnostic)]
+#[diag("failed to write codegen report to `{$path}`: {$error}")]
+pub(crate) struct CodegenReportWriteFailure<'a> {
+    pub path: &'a Path,
+    pub error: Error,
+}
+
 #[derive(Diagnostic)]
 #[diag("`as-needed` modifier not implemented yet for ld64")]
 pub(crate) struct Ld64UnimplementedModifier;
@@ -46,6 +46,7 @@
 pub mod back;
 pub mod base;
 pub mod codegen_attrs;
+pub mod codegen_report;
 pub mod common;
 pub mod debuginfo;
 pub mod diagnostics;
@@ -177,4 +177,11 @@ fn compile_codegen_unit(
         tcx: TyCtxt<'_>,
         cgu_name: Symbol,
     ) -> (ModuleCodegen<Self::Module>, u64);
+
+    /// Returns the number of instructions of each function defined in `module`, by symbol name,
+    /// before any optimization. This is used by `-Z codegen-report`; backends that can't tell
+    /// return nothing.
+    fn function_instruction_counts(&self, _module: &Self::Module) -> Vec<(String, u64)> {
+        Vec::new()
+    }
 }
@@ -10,6 +10,7 @@
 use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{self, Ty, TyCtxt, TypeVisitable};
 use rustc_middle::{bug, throw_inval};
+use rustc_session::config::SwitchWithOptPath;
 use rustc_span::Span;
 use rustc_span::def_id::LocalDefId;
 use tracing::{debug, instrument, trace};
@@ -444,16 +445,16 @@ fn eval_in_interpreter<'tcx, R: InterpretationResult<'tcx>>(
     debug_assert!(!tcx.is_type_const(def), "CTFE tried to evaluate type-const: {:?}", def);
 
     let is_static = tcx.is_static(def);
-    let mut ecx = InterpCx::new(
-        tcx,
-        tcx.def_span(def),
-        typing_env,
-        // Statics (and promoteds inside statics) may access mutable global memory, because unlike consts
-        // they do not have to behave "as if" they were evaluated at runtime.
-        // For consts however we want to ensure they behave "as if" they were evaluated at runtime,
-        // so we have to reject reading mutable global memory.
-        CompileTimeMachine::new(CanAccessMutGlobal::from(is_static), CheckAlignment::Error),
-    );
+    // Statics (and promoteds inside statics) may access mutable global memory, because unlike
+    // consts they do not have to behave "as if" they were evaluated at runtime.
+    // For consts however we want to ensure they behave "as if" they were evaluated at runtime,
+    // so we have to reject reading mutable global memory.
+    let mut machine =
+        CompileTimeMachine::new(CanAccessMutGlobal::from(is_static), CheckAlignment::Error);
+    if tcx.sess.opts.unstable_opts.const_eval_profile.enabled() {
+        machine.profile = Some(Default::default());
+    }
+    let mut ecx = InterpCx::new(tcx, tcx.def_span(def), typing_env, machine);
 
     let result = if let Some((value, ty)) = tcx.trivial_const(def) {
         eval_trivial_const_using_ecx(&mut ecx, cid, value, ty)
@@ -461,6 +462,17 @@ fn eval_in_interpreter<'tcx, R: InterpretationResult<'tcx>>(
         ecx.load_mir(cid.instance.def, cid.promoted)
             .and_then(|body| eval_body_using_ecx(&mut ecx, cid, body))
     };
+
+    // Evaluations that failed are profiled as well, as hitting the step limit is one of the main
+    // reasons to look at a profile.
+    if let Some(profile) = &ecx.machine.profile
+        && let SwitchWithOptPath::Enabled(ref directory) =
+            tcx.sess.opts.unstable_opts.const_eval_profile
+        && let Err(err) = profile.borrow().write(tcx, cid, directory)
+    {
+        tcx.dcx().emit_warn(diagnostics::ConstEvalProfileWriteFailed { error: err.to_string() });
+    }
+
     result.report_err().map_err(|error| report_eval_error(&ecx, cid, error))
 }
 
@@ -1,4 +1,5 @@
 use std::borrow::{Borrow, Cow};
+use std::cell::RefCell;
 use std::hash::Hash;
 use std::{fmt, mem};
 
@@ -18,6 +19,7 @@
 use tracing::debug;
 
 use super::error::*;
+use super::profile::EvalProfile;
 use crate::diagnostics::{LongRunning, LongRunningWarn};
 use crate::interpret::{
     self, AllocId, AllocInit, AllocRange, ConstAllocation, CtfeProvenance, FnArg, Frame,
@@ -70,6 +72,12 @@ pub struct CompileTimeMachine<'tcx> {
 
     /// The current retag mode.
     retag_mode: RetagMode,
+
+    /// The statistics collected for `-Z const-eval-profile`, if enabled.
+    ///
+    /// This is in a `RefCell` because allocations are counted from hooks that only get shared
+    /// access to the machine.
+    pub(super) profile: Option<RefCell<EvalProfile<'tcx>>>,
 }
 
 #[derive(Copy, Clone)]
@@ -106,6 +114,7 @@ pub(crate) fn new(
             static_root_ids: None,
             union_data_ranges: FxHashMap::default(),
             retag_mode: RetagMode::Default,
+            profile: None,
         }
     }
 }
@@ -870,6 +879,22 @@ fn increment_const_eval_counter(ecx: &mut InterpCx<'tcx, Self>) -> InterpResult<
         interp_ok(())
     }
 
+    #[inline(always)]
+    fn before_statement(ecx: &mut InterpCx<'tcx, Self>) -> InterpResult<'tcx> {
+        if let Some(profile) = &ecx.machine.profile {
+            profile.borrow_mut().step();
+        }
+        interp_ok(())
+    }
+
+    #[inline(always)]
+    fn before_terminator(ecx: &mut InterpCx<'tcx, Self>) -> InterpResult<'tcx> {
+        if let Some(profile) = &ecx.machine.profile {
+            profile.borrow_mut().step();
+        }
+        interp_ok(())
+    }
+
     #[inline(always)]
     fn expose_provenance(
         _ecx: &InterpCx<'tcx, Self>,
@@ -892,6 +917,20 @@ fn init_frame(
         }
     }
 
+    fn after_stack_push(ecx: &mut InterpCx<'tcx, Self>) -> InterpResult<'tcx> {
+        if let Some(profile) = &ecx.machine.profile {
+            profile.borrow_mut().push_frame(ecx.frame().instance());
+        }
+        interp_ok(())
+    }
+
+    fn before_stack_pop(ecx: &mut InterpCx<'tcx, Self>) -> InterpResult<'tcx> {
+        if let Some(profile) = &ecx.machine.profile {
+            profile.borrow_mut().pop_frame();
+        }
+        interp_ok(())
+    }
+
     #[inline(always)]
     fn stack<'a>(
         ecx: &'a InterpCx<'tcx, Self>,
@@ -906,6 +945,18 @@ fn stack_mut<'a>(
         &mut ecx.machine.stack
     }
 
+    fn after_local_allocation(
+        ecx: &InterpCx<'tcx, Self>,
+        _id: AllocId,
+        _kind: interpret::MemoryKind<Self::MemoryKind>,
+        size: Size,
+    ) -> InterpResult<'tcx> {
+        if let Some(profile) = &ecx.machine.profile {
+            profile.borrow_mut().allocate(size.bytes());
+        }
+        interp_ok(())
+    }
+
     fn before_access_global(
         _tcx: TyCtxtAt<'tcx>,
         machine: &Self,
@@ -14,6 +14,7 @@
 mod eval_queries;
 mod fn_queries;
 mod machine;
+mod profile;
 mod type_info;
 mod valtrees;
 
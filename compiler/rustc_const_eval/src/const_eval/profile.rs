@@ -0,0 +1,147 @@
+//! Support for `-Z const-eval-profile`: counting where the interpreter spends its time
+//! while evaluating a `const` or `static` item.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{Instance, TyCtxt};
+
+use crate::interpret::GlobalId;
+
+#[derive(Default)]
+struct FunctionStats {
+    /// The number of frames pushed for this function.
+    calls: u64,
+    /// The number of statements and terminators executed in this function itself, not
+    /// counting its callees.
+    steps: u64,
+    /// The number of bytes allocated while this function was the topmost frame.
+    alloc_bytes: u64,
+}
+
+/// The per-function statistics of a single top-level evaluation, along with the distinct call
+/// stacks they were reached through.
+#[derive(Default)]
+pub(crate) struct EvalProfile<'tcx> {
+    functions: FxIndexMap<Instance<'tcx>, FunctionStats>,
+    /// Maps a call stack, given as the index of its caller's stack and of the called function in
+    /// `functions`, to the number of steps executed directly within that stack.
+    stacks: FxIndexMap<(Option<usize>, usize), u64>,
+    /// The index in `stacks` of each frame currently on the interpreter stack.
+    frames: Vec<usize>,
+    /// The number of bytes allocated in total, including those allocated outside of any frame.
+    alloc_bytes: u64,
+}
+
+impl<'tcx> EvalProfile<'tcx> {
+    pub(crate) fn push_frame(&mut self, instance: Instance<'tcx>) {
+        let entry = self.functions.entry(instance);
+        let function = entry.index();
+        entry.or_default().calls += 1;
+        let entry = self.stacks.entry((self.frames.last().copied(), function));
+        self.frames.push(entry.index());
+        entry.or_insert(0);
+    }
+
+    pub(crate) fn pop_frame(&mut self) {
+        self.frames.pop();
+    }
+
+    pub(crate) fn step(&mut self) {
+        if let Some(&stack) = self.frames.last() {
+            let (&(_, function), steps) = self.stacks.get_index_mut(stack).unwrap();
+            *steps += 1;
+            self.functions[function].steps += 1;
+        }
+    }
+
+    pub(crate) fn allocate(&mut self, bytes: u64) {
+        self.alloc_bytes += bytes;
+        if let Some(&stack) = self.frames.last() {
+            let (_, function) = *self.stacks.get_index(stack).unwrap().0;
+            self.functions[function].alloc_bytes += bytes;
+        }
+    }
+
+    /// Appends the report for the evaluation of `cid` to the profile files in `output_directory`.
+    ///
+    /// Every evaluation in a crate is appended to the same `{crate}-{pid}.const-eval-profile.jsonl`
+    /// file as one JSON object per line, and its call stacks are appended to the accompanying
+    /// `.folded` file in the collapsed stack format that flamegraph tools consume.
+    pub(crate) fn write(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        cid: GlobalId<'tcx>,
+        output_directory: &Option<PathBuf>,
+    ) -> io::Result<()> {
+        let output_directory = if let Some(directory) = output_directory {
+            fs::create_dir_all(directory)?;
+            directory
+        } else {
+            Path::new(".")
+        };
+        let file_stem =
+            format!("{}-{}.const-eval-profile", tcx.crate_name(LOCAL_CRATE), std::process::id());
+        let append = |ext: &str, contents: &[u8]| {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(output_directory.join(format!("{file_stem}.{ext}")))?
+                .write_all(contents)
+        };
+
+        let names: Vec<String> = self
+            .functions
+            .keys()
+            .map(|instance| with_no_trimmed_paths!(instance.to_string()))
+            .collect();
+
+        // Heaviest functions first.
+        let mut functions: Vec<_> = self.functions.values().zip(&names).collect();
+        functions.sort_by_key(|(stats, _)| std::cmp::Reverse(stats.steps));
+        let functions: Vec<_> = functions
+            .into_iter()
+            .map(|(stats, name)| {
+                serde_json::json!({
+                    "name": name,
+                    "calls": stats.calls,
+                    "steps": stats.steps,
+                    "alloc_bytes": stats.alloc_bytes,
+                })
+            })
+            .collect();
+        let report = serde_json::json!({
+            "item": cid.display(tcx),
+            "steps": self.stacks.values().sum::<u64>(),
+            "alloc_bytes": self.alloc_bytes,
+            "functions": functions,
+        });
+        // Each evaluation is written with a single call so that evaluations running in parallel
+        // do not interleave their lines.
+        let mut json = serde_json::to_vec(&report)?;
+        json.push(b'\n');
+        append("jsonl", &json)?;
+
+        let mut folded = String::new();
+        for (&(mut caller, function), &steps) in &self.stacks {
+            if steps == 0 {
+                continue;
+            }
+            let mut frames = vec![function];
+            while let Some(stack) = caller {
+                let &(next, function) = self.stacks.get_index(stack).unwrap().0;
+                frames.push(function);
+                caller = next;
+            }
+            // Frames are separated by `;`, which also appears in array types.
+            let frames: Vec<_> =
+                frames.iter().rev().map(|&function| names[function].replace(';', ",")).collect();
+            folded.push_str(&format!("{} {steps}\n", frames.join(";")));
+        }
+        append("folded", folded.as_bytes())
+    }
+}
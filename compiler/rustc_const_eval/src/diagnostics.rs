@@ -35,6 +35,12 @@ pub(crate) struct NestedStaticInThreadLocal {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag("failed to write the const evaluation profile: {$error}")]
+pub(crate) struct ConstEvalProfileWriteFailed {
+    pub error: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(
     r#"encountered mutable pointer in final value of {$kind ->
@@ -54,7 +60,7 @@ pub(crate) struct MutablePtrInFinal {
 #[derive(Diagnostic)]
 #[diag("encountered `const_allocate` pointer in final value that was not made global")]
 #[note(
-    "use `const_make_global` to turn allocated pointers into immutable globals before returning"
+    "use `const_make_global` to turn allocated pointers into immutable globals before returning, or only refer to them through shared references"
 )]
 pub(crate) struct ConstHeapPtrInFinal {
     #[primary_span]
@@ -12,6 +12,13 @@
 //! so all inner allocations are marked mutable. Some of them could potentially be made immutable,
 //! but that would require relying on type information, and given how many ways Rust has to lie
 //! about type information, we want to avoid doing that.
+//!
+//! Heap allocations created with `const_allocate` are interned as immutable globals if they were
+//! made global with `const_make_global`. Those that were not are promoted to immutable globals
+//! implicitly as long as every pointer to them in the final value is immutable, i.e. derived from
+//! a shared reference to memory without interior mutability: such memory can neither be mutated
+//! nor deallocated by anyone using the value, so it is fine for it to outlive the evaluation. This
+//! is what makes `Vec::leak` usable in constants.
 
 use hir::def::DefKind;
 use rustc_ast::Mutability;
@@ -260,6 +267,10 @@ pub fn intern_const_alloc_recursive<'tcx, M: CompileTimeMachine<'tcx>>(
     // We need to distinguish "has just been interned" from "was already in `tcx`",
     // so we track this in a separate set.
     let mut just_interned: FxHashSet<_> = std::iter::once(base_alloc_id).collect();
+    // The heap allocations that were not made global, but that we promote to immutable globals
+    // since they were reached through an immutable pointer. All other pointers to them must be
+    // immutable as well.
+    let mut promoted_heap_allocs = FxHashSet::default();
     // Whether we encountered a bad mutable pointer.
     // We want to first report "dangling" and then "mutable", so we need to delay reporting these
     // errors.
@@ -284,6 +295,13 @@ pub fn intern_const_alloc_recursive<'tcx, M: CompileTimeMachine<'tcx>>(
             continue;
         }
 
+        // Heap allocations that were not made global are only promoted if *all* pointers to them
+        // are immutable. Otherwise, someone could still mutate or deallocate the memory, e.g.
+        // through the pointer inside a `Vec` or `Box`, or through a shared reference to a `Cell`.
+        if !prov.immutable() && promoted_heap_allocs.contains(&alloc_id) {
+            return Err(InternError::ConstAllocNotGlobal);
+        }
+
         // Ensure that this is derived from a shared reference. Crucially, we check this *before*
         // checking whether the `alloc_id` has already been interned. The point of this check is to
         // ensure that when there are multiple pointers to the same allocation, they are *all*
@@ -326,10 +344,23 @@ pub fn intern_const_alloc_recursive<'tcx, M: CompileTimeMachine<'tcx>>(
             debug_assert!(!ecx.memory.alloc_map.contains_key(&alloc_id));
             continue;
         }
-        // We always intern with `inner_mutability`, and furthermore we ensured above that if
-        // that is "immutable", then there are *no* mutable pointers anywhere in the newly
-        // interned memory -- justifying that we can indeed intern immutably. However this also
-        // means we can *not* easily intern immutably here if `prov.immutable()` is true and
+        let mut mutability = inner_mutability;
+        if let Some((kind, alloc)) = ecx.memory.alloc_map.get_mut(&alloc_id)
+            && let MemoryKind::Machine(const_eval::MemoryKind::Heap { was_made_global }) = kind
+            && !*was_made_global
+            && prov.immutable()
+        {
+            // This is like an implicit call to `const_make_global`: the allocation becomes an
+            // immutable global, even inside a `static mut`.
+            *was_made_global = true;
+            alloc.mutability = Mutability::Not;
+            mutability = Mutability::Not;
+            promoted_heap_allocs.insert(alloc_id);
+        }
+        // Other than that, we always intern with `inner_mutability`, and furthermore we ensured
+        // above that if that is "immutable", then there are *no* mutable pointers anywhere in the
+        // newly interned memory -- justifying that we can indeed intern immutably. However this
+        // also means we can *not* easily intern immutably here if `prov.immutable()` is true and
         // `inner_mutability` is `Mut`: there might be other pointers to that allocation, and
         // we'd have to somehow check that they are *all* immutable before deciding that this
         // allocation can be made immutable. In the future we could consider analyzing all
@@ -337,7 +368,7 @@ pub fn intern_const_alloc_recursive<'tcx, M: CompileTimeMachine<'tcx>>(
         // okay with losing some potential for immutability here. This can anyway only affect
         // `static mut`.
         just_interned.insert(alloc_id);
-        let next = intern_shallow(ecx, alloc_id, inner_mutability, disambiguator.as_deref_mut())?;
+        let next = intern_shallow(ecx, alloc_id, mutability, disambiguator.as_deref_mut())?;
         todo.extend(next);
     }
     if found_bad_mutable_ptr {
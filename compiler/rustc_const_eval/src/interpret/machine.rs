@@ -355,6 +355,12 @@ fn atomic_fence(
         singlethread: bool,
     ) -> InterpResult<'tcx>;
 
+    /// Called before a statement is executed.
+    #[inline]
+    fn before_statement(_ecx: &mut InterpCx<'tcx, Self>) -> InterpResult<'tcx> {
+        interp_ok(())
+    }
+
     /// Called before a basic block terminator is executed.
     #[inline]
     fn before_terminator(_ecx: &mut InterpCx<'tcx, Self>) -> InterpResult<'tcx> {
@@ -472,6 +478,17 @@ fn init_local_allocation(
         align: Align,
     ) -> InterpResult<'tcx, Self::AllocExtra>;
 
+    /// Called after an allocation local to this machine has been created.
+    #[inline]
+    fn after_local_allocation(
+        _ecx: &InterpCx<'tcx, Self>,
+        _id: AllocId,
+        _kind: MemoryKind<Self::MemoryKind>,
+        _size: Size,
+    ) -> InterpResult<'tcx> {
+        interp_ok(())
+    }
+
     /// Hook for performing extra checks on a memory read access.
     /// `ptr` will always be a pointer with the provenance in `prov` pointing to the beginning of
     /// `range`.
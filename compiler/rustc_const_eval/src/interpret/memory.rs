@@ -290,9 +290,11 @@ pub fn insert_allocation(
         );
         // This cannot be merged with the `adjust_global_allocation` code path
         // since here we have an allocation that already uses `M::Bytes`.
-        let extra = M::init_local_allocation(self, id, kind, alloc.size(), alloc.align)?;
+        let size = alloc.size();
+        let extra = M::init_local_allocation(self, id, kind, size, alloc.align)?;
         let alloc = alloc.with_extra(extra);
         self.memory.alloc_map.insert(id, (kind, alloc));
+        M::after_local_allocation(self, id, kind, size)?;
         M::adjust_alloc_root_pointer(self, Pointer::from(id), Some(kind))
     }
 
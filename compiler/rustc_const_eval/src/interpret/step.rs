@@ -52,6 +52,7 @@ pub fn step(&mut self) -> InterpResult<'tcx, bool> {
         let basic_block = &self.body().basic_blocks[loc.block];
 
         if let Some(stmt) = basic_block.statements.get(loc.statement_index) {
+            M::before_statement(self)?;
             let old_frames = self.frame_idx();
             self.eval_statement(stmt)?;
             // Make sure we are not updating `statement_index` of the wrong frame.
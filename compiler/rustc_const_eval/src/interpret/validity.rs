@@ -712,7 +712,8 @@ fn check_safe_pointer(
                     let Some(global_alloc) = self.ecx.tcx.try_get_global_alloc(alloc_id) else {
                         if self.ecx.memory.alloc_map.contains_key(&alloc_id) {
                             // This can happen when interning didn't complete due to, e.g.
-                            // missing `make_global`. This must mean other errors are already
+                            // a heap allocation that was neither made global nor only reachable
+                            // through shared references. This must mean other errors are already
                             // being reported.
                             self.ecx.tcx.dcx().delayed_bug(
                                 "interning did not complete, there should be an error",
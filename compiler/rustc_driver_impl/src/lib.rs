@@ -47,21 +47,24 @@
 use rustc_lint::unerased_lint_store;
 use rustc_metadata::creader::MetadataLoader;
 use rustc_metadata::locator;
+use rustc_middle::lint::LintLevelSource;
 use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_parse::lexer::StripTokens;
 use rustc_parse::{new_parser_from_file, new_parser_from_source_str, unwrap_or_emit_fatal};
 use rustc_session::config::{
-    CG_OPTIONS, CrateType, ErrorOutputType, Input, OptionDesc, OutFileName, OutputType, Sysroot,
-    UnstableOptions, Z_OPTIONS, nightly_options, parse_target_triple,
+    CG_OPTIONS, CrateType, ErrorOutputType, Input, OptionDesc, OutFileName, OutputType, PrintKind,
+    Sysroot, UnstableOptions, Z_OPTIONS, nightly_options, parse_target_triple,
 };
 use rustc_session::getopts::{self, Matches};
 use rustc_session::lint::{Lint, LintId};
 use rustc_session::output::invalid_output_for_target;
 use rustc_session::{EarlyDiagCtxt, Session, config};
 use rustc_span::def_id::LOCAL_CRATE;
-use rustc_span::{DUMMY_SP, FileName};
+use rustc_span::{DUMMY_SP, FileName, Span};
 use rustc_target::json::ToJson;
 use rustc_target::spec::{Target, TargetTuple};
+use serde_json::json;
 use tracing::trace;
 
 #[allow(unused_macros)]
@@ -313,6 +316,10 @@ pub fn run_compiler(at_args: &[String], callbacks: &mut (dyn Callbacks + Send))
 
             tcx.ensure_ok().analysis(());
 
+            if sess.opts.prints.iter().any(|print| print.kind == PrintKind::LintLevels) {
+                print_lint_levels(tcx);
+            }
+
             if let Some(metrics_dir) = &sess.opts.unstable_opts.metrics_dir {
                 dump_feature_usage_metrics(tcx, metrics_dir);
             }
@@ -355,6 +362,73 @@ fn dump_feature_usage_metrics(tcx: TyCtxt<'_>, metrics_dir: &Path) {
     }
 }
 
+/// Prints every lint level set in the crate, for `--print=lint-levels`.
+fn print_lint_levels(tcx: TyCtxt<'_>) {
+    let sm = tcx.sess.source_map();
+    let span_to_json = |span: Span| {
+        let loc = sm.lookup_char_pos(span.lo());
+        json!({
+            "file": sm.filename_for_diagnostics(&loc.file.name).to_string(),
+            "line": loc.line,
+            "column": loc.col_display + 1,
+        })
+    };
+
+    let lint_levels: Vec<_> = rustc_lint::lint_level_overrides(tcx)
+        .into_iter()
+        .filter_map(|lint_level| {
+            let source = match lint_level.src {
+                LintLevelSource::Default => return None,
+                LintLevelSource::Node { name, span, reason } => {
+                    let kind = match sm.lookup_char_pos(span.lo()).file.name {
+                        FileName::CliCrateAttr(_) => "crate-attr",
+                        _ => "attribute",
+                    };
+                    json!({
+                        "kind": kind,
+                        "name": name.as_str(),
+                        "span": span_to_json(span),
+                        "reason": reason.map(|reason| reason.to_string()),
+                    })
+                }
+                // Cargo passes the `[lints]` table on the command line as well.
+                LintLevelSource::CommandLine(name, level) => json!({
+                    "kind": "command-line",
+                    "name": name.as_str(),
+                    "level": level.as_str(),
+                }),
+            };
+            let owner = lint_level.hir_id.owner;
+            let item = if owner.def_id.is_top_level_module() {
+                tcx.crate_name(LOCAL_CRATE).to_string()
+            } else {
+                with_no_trimmed_paths!(tcx.def_path_str(owner.to_def_id()))
+            };
+            let mut entry = json!({
+                "lint": lint_level.lint.to_string(),
+                "level": lint_level.level.as_str(),
+                "scope": {
+                    "item": item,
+                    "span": span_to_json(tcx.hir_span(lint_level.hir_id)),
+                },
+                "source": source,
+            });
+            if let Some(fulfilled) = lint_level.fulfilled {
+                entry["fulfilled"] = fulfilled.into();
+            }
+            Some(entry)
+        })
+        .collect();
+
+    let mut content = serde_json::to_string_pretty(&lint_levels).unwrap();
+    content.push('\n');
+    for req in &tcx.sess.opts.prints {
+        if req.kind == PrintKind::LintLevels {
+            req.out.overwrite(&content, tcx.sess);
+        }
+    }
+}
+
 /// Extract output directory and file from matches.
 fn make_output(matches: &getopts::Matches) -> (Option<PathBuf>, Option<OutFileName>) {
     let odir = matches.opt_str("out-dir").map(|o| PathBuf::from(&o));
@@ -626,9 +700,15 @@ fn print_crate_info(
     #[allow(unused_imports)]
     use {do_not_use_safe_print as safe_print, do_not_use_safe_print as safe_println};
 
-    // NativeStaticLibs and LinkArgs are special - printed during linking
+    // NativeStaticLibs and LinkArgs are special - printed during linking,
+    // and LintLevels is printed after analysis
     // (empty iterator returns true)
-    if sess.opts.prints.iter().all(|p| p.kind == NativeStaticLibs || p.kind == LinkArgs) {
+    if sess
+        .opts
+        .prints
+        .iter()
+        .all(|p| p.kind == NativeStaticLibs || p.kind == LinkArgs || p.kind == LintLevels)
+    {
         return Compilation::Continue;
     }
 
@@ -821,6 +901,7 @@ fn print_crate_info(
             // Any output here interferes with Cargo's parsing of other printed output
             NativeStaticLibs => {}
             LinkArgs => {}
+            LintLevels => {}
             SplitDebuginfo => {
                 use rustc_target::spec::SplitDebuginfo::{Off, Packed, Unpacked};
 
@@ -291,6 +291,9 @@ pub struct DiagInner {
     pub sort_span: Span,
 
     pub is_lint: Option<IsLint>,
+    /// The path of the item a lint was emitted in, used to match it against the
+    /// entries of a `--lint-baseline`.
+    pub lint_item_path: Option<String>,
 
     pub long_ty_path: Option<PathBuf>,
     /// With `-Ztrack_diagnostics` enabled,
@@ -317,6 +320,7 @@ pub fn new_with_messages(level: Level, messages: Vec<(DiagMessage, Style)>) -> S
             args: Default::default(),
             sort_span: DUMMY_SP,
             is_lint: None,
+            lint_item_path: None,
             long_ty_path: None,
             emitted_at: DiagLocation::caller(),
         }
@@ -1207,6 +1211,12 @@ pub fn is_lint(
         self
     }
 
+    /// Records the path of the item this lint is emitted in, see [`DiagInner::lint_item_path`].
+    pub fn lint_item_path(&mut self, path: String) -> &mut Self {
+        self.lint_item_path = Some(path);
+        self
+    }
+
     with_fn! { with_code,
     /// Add an error code.
     pub fn code(&mut self, code: ErrCode) -> &mut Self {
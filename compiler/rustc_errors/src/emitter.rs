@@ -80,6 +80,12 @@ fn should_show_explain(&self) -> bool {
         true
     }
 
+    /// Checks if should show the summary of emitted errors and warnings
+    /// ("aborting due to 2 previous errors")
+    fn should_show_error_count(&self) -> bool {
+        true
+    }
+
     /// Checks if we can use colors in the current output stream.
     fn supports_color(&self) -> bool {
         false
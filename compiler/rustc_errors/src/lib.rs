@@ -69,6 +69,7 @@
 use crate::emitter::TimingEvent;
 use crate::formatting::DiagMessageAddArg;
 pub use crate::formatting::format_diag_message;
+use crate::lint_baseline::LintBaseline;
 use crate::timings::TimingRecord;
 
 pub mod annotate_snippet_emitter_writer;
@@ -79,8 +80,10 @@
 pub mod emitter;
 pub mod formatting;
 pub mod json;
+pub mod lint_baseline;
 mod lock;
 pub mod markdown;
+pub mod sarif;
 pub mod timings;
 
 pub type PResult<'a, T> = Result<T, Diag<'a>>;
@@ -368,6 +371,9 @@ struct DiagCtxtInner {
 
     /// Controlled by `-Z hint-msrv`; this allows avoiding emitting lints which would raise MSRV.
     msrv: Option<RustcVersion>,
+
+    /// Controlled by `--lint-baseline`; lints recorded in the baseline aren't emitted.
+    lint_baseline: Option<LintBaseline>,
 }
 
 /// A key denoting where from a diagnostic was stashed.
@@ -487,6 +493,11 @@ pub fn with_msrv(mut self, msrv: RustcVersion) -> Self {
         self
     }
 
+    pub fn with_lint_baseline(mut self, lint_baseline: LintBaseline) -> Self {
+        self.inner.get_mut().lint_baseline = Some(lint_baseline);
+        self
+    }
+
     pub fn new(emitter: Box<DynEmitter>) -> Self {
         Self { inner: Lock::new(DiagCtxtInner::new(emitter)) }
     }
@@ -535,6 +546,7 @@ pub fn reset_err_count(&self) {
             fulfilled_expectations,
             ice_file: _,
             msrv: _,
+            lint_baseline: _,
         } = inner.deref_mut();
 
         // For the `Vec`s and `HashMap`s, we overwrite with an empty container to free the
@@ -769,7 +781,7 @@ pub fn print_error_count(&self) {
         // `emit_stashed_diagnostics` by now.
         assert!(inner.stashed_diagnostics.is_empty());
 
-        if inner.treat_err_as_bug() {
+        if inner.treat_err_as_bug() || !inner.emitter.should_show_error_count() {
             return;
         }
 
@@ -880,6 +892,15 @@ pub fn emit_timing_section_end(&self, record: TimingRecord) {
         self.inner.borrow_mut().emitter.emit_timing_section(record, TimingEvent::End);
     }
 
+    /// Writes the lints recorded with `--lint-baseline` in update mode to the
+    /// baseline file.
+    pub fn write_lint_baseline(&self) -> std::io::Result<()> {
+        match &self.inner.borrow().lint_baseline {
+            Some(lint_baseline) => lint_baseline.write(),
+            None => Ok(()),
+        }
+    }
+
     pub fn emit_future_breakage_report(&self) {
         let inner = &mut *self.inner.borrow_mut();
         let diags = std::mem::take(&mut inner.future_breakage_diagnostics);
@@ -918,11 +939,11 @@ pub fn emit_unused_externs(
         inner.emitter.emit_unused_externs(lint_level, unused_externs)
     }
 
-    /// This methods steals all [`LintExpectationId`]s that are stored inside
-    /// [`DiagCtxtInner`] and indicate that the linked expectation has been fulfilled.
+    /// Returns all [`LintExpectationId`]s that are stored inside [`DiagCtxtInner`] and
+    /// indicate that the linked expectation has been fulfilled.
     #[must_use]
-    pub fn steal_fulfilled_expectation_ids(&self) -> FxIndexSet<LintExpectationId> {
-        std::mem::take(&mut self.inner.borrow_mut().fulfilled_expectations)
+    pub fn fulfilled_expectation_ids(&self) -> FxIndexSet<LintExpectationId> {
+        self.inner.borrow().fulfilled_expectations.clone()
     }
 
     /// Trigger an ICE if there are any delayed bugs and no hard errors.
@@ -1203,6 +1224,7 @@ fn new(emitter: Box<DynEmitter>) -> Self {
             fulfilled_expectations: Default::default(),
             ice_file: None,
             msrv: None,
+            lint_baseline: None,
         }
     }
 
@@ -1324,6 +1346,16 @@ fn emit_diagnostic(
         }
 
         TRACK_DIAGNOSTIC(diagnostic, &mut |mut diagnostic| {
+            // This is checked after `TRACK_DIAGNOSTIC` has seen the lint, so that
+            // it is replayed, and matched against the baseline again, when
+            // incremental compilation reuses the query that emitted it.
+            if let Some(lint_baseline) = &mut self.lint_baseline
+                && lint_baseline.suppresses(&diagnostic, self.emitter.source_map())
+            {
+                self.suppressed_expected_diag = true;
+                return None;
+            }
+
             if let Some(code) = diagnostic.code {
                 self.emitted_diagnostic_codes.insert(code);
             }
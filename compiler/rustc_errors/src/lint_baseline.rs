@@ -0,0 +1,152 @@
+//! Baselines of known lint occurrences, for `--lint-baseline`.
+//!
+//! A baseline lets a crate turn on a lint without fixing or `#[allow]`ing all
+//! of its existing occurrences first: the occurrences recorded in the baseline
+//! file are suppressed, and only new ones are reported.
+//!
+//! Occurrences are keyed by the lint name, the path of the item the lint was
+//! emitted in (empty for lints emitted before HIR is built) and a fingerprint
+//! of the source text of the primary span, so that they keep matching when
+//! the code around them moves. The same key can occur several times, so the
+//! baseline records a count for each key and suppresses at most that many
+//! occurrences.
+
+use std::hash::Hash;
+use std::path::PathBuf;
+use std::{fs, io};
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::stable_hash::StableHasher;
+use rustc_hashes::Hash64;
+use rustc_span::source_map::SourceMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{DiagInner, Level};
+
+#[cfg(test)]
+mod tests;
+
+/// Version of the baseline file format.
+const VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintBaselineMode {
+    /// Suppress the occurrences recorded in the baseline.
+    Check,
+    /// Record every occurrence, and write them to the baseline file when
+    /// compilation finishes. Nothing is reported.
+    Update,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+struct BaselineKey {
+    lint: String,
+    item: String,
+    fingerprint: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BaselineEntry {
+    #[serde(flatten)]
+    key: BaselineKey,
+    count: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BaselineFile {
+    version: u32,
+    entries: Vec<BaselineEntry>,
+}
+
+pub struct LintBaseline {
+    path: PathBuf,
+    mode: LintBaselineMode,
+    /// How many more occurrences of each key are suppressed in `Check` mode,
+    /// or how many were seen so far in `Update` mode.
+    counts: FxIndexMap<BaselineKey, usize>,
+}
+
+impl LintBaseline {
+    /// Loads the baseline at `path`. In `Update` mode the file is only written,
+    /// so it doesn't need to exist yet.
+    pub fn load(path: PathBuf, mode: LintBaselineMode) -> io::Result<LintBaseline> {
+        let mut counts = FxIndexMap::default();
+        if mode == LintBaselineMode::Check {
+            let file: BaselineFile = serde_json::from_str(&fs::read_to_string(&path)?)?;
+            if file.version != VERSION {
+                return Err(io::Error::other(format!(
+                    "unsupported version {}, expected {VERSION}",
+                    file.version
+                )));
+            }
+            for BaselineEntry { key, count } in file.entries {
+                *counts.entry(key).or_default() += count;
+            }
+        }
+        Ok(LintBaseline { path, mode, counts })
+    }
+
+    /// Returns `true` if the lint diagnostic `diag` is covered by the baseline
+    /// and must not be emitted.
+    pub(crate) fn suppresses(&mut self, diag: &DiagInner, sm: Option<&SourceMap>) -> bool {
+        // Forced warnings are meant to always be shown.
+        if !matches!(diag.level, Level::Warning | Level::Error) {
+            return false;
+        }
+        let Some(key) = BaselineKey::new(diag, sm) else {
+            return false;
+        };
+        match self.mode {
+            LintBaselineMode::Check => match self.counts.get_mut(&key) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    true
+                }
+                _ => false,
+            },
+            LintBaselineMode::Update => {
+                *self.counts.entry(key).or_default() += 1;
+                true
+            }
+        }
+    }
+
+    /// Writes the recorded occurrences to the baseline file. Does nothing in
+    /// `Check` mode.
+    pub fn write(&self) -> io::Result<()> {
+        if self.mode == LintBaselineMode::Check {
+            return Ok(());
+        }
+        let mut entries: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(key, &count)| BaselineEntry { key: key.clone(), count })
+            .collect();
+        // Keep the file stable regardless of the order lints are emitted in, so
+        // that regenerating it produces small diffs.
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        let mut contents =
+            serde_json::to_string_pretty(&BaselineFile { version: VERSION, entries })?;
+        contents.push('\n');
+        fs::write(&self.path, contents)
+    }
+}
+
+impl BaselineKey {
+    fn new(diag: &DiagInner, sm: Option<&SourceMap>) -> Option<BaselineKey> {
+        let lint = diag.is_lint.as_ref()?.name.clone();
+        let item = diag.lint_item_path.clone().unwrap_or_default();
+        // Whitespace is ignored, so that reformatting the code doesn't turn
+        // known occurrences into new ones.
+        let snippet = diag
+            .span
+            .primary_span()
+            .and_then(|span| sm?.span_to_snippet(span).ok())
+            .map(|snippet| snippet.split_whitespace().collect::<Vec<_>>().join(" "))
+            .unwrap_or_default();
+        let mut hasher = StableHasher::new();
+        snippet.hash(&mut hasher);
+        let fingerprint = format!("{:016x}", hasher.finish::<Hash64>());
+        Some(BaselineKey { lint, item, fingerprint })
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use rustc_span::source_map::FilePathMapping;
+use rustc_span::{BytePos, FileName, Span};
+
+use super::*;
+use crate::DiagCtxt;
+use crate::emitter::Emitter;
+
+/// Records the messages of the emitted diagnostics.
+struct Recorder {
+    sm: Arc<SourceMap>,
+    emitted: Arc<Mutex<Vec<String>>>,
+}
+
+impl Emitter for Recorder {
+    fn emit_diagnostic(&mut self, diag: DiagInner) {
+        let message = crate::formatting::format_diag_messages(&diag.messages, &diag.args);
+        self.emitted.lock().unwrap().push(message.to_string());
+    }
+
+    fn source_map(&self) -> Option<&SourceMap> {
+        Some(&self.sm)
+    }
+}
+
+/// Emits an `unused_variables` warning in `item` for each `(lo, hi)` span of `code`
+/// and returns the messages of the warnings that were not suppressed.
+fn emit_lints(
+    baseline: &Path,
+    mode: LintBaselineMode,
+    code: &str,
+    lints: &[(&str, u32, u32)],
+) -> Vec<String> {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Arc::new(SourceMap::new(FilePathMapping::empty()));
+        let file_name =
+            sm.path_mapping().to_real_filename(sm.working_dir(), PathBuf::from("test.rs"));
+        let file = sm.new_source_file(FileName::Real(file_name), code.to_owned());
+        let emitted = Arc::new(Mutex::new(Vec::new()));
+        let dcx = DiagCtxt::new(Box::new(Recorder { sm, emitted: emitted.clone() }))
+            .with_lint_baseline(LintBaseline::load(baseline.to_owned(), mode).unwrap());
+        for &(item, lo, hi) in lints {
+            let span =
+                Span::with_root_ctxt(file.start_pos + BytePos(lo), file.start_pos + BytePos(hi));
+            let mut diag = dcx.handle().struct_span_warn(span, format!("lint at {lo} in {item}"));
+            diag.is_lint("unused_variables".to_owned(), false, None);
+            diag.lint_item_path(item.to_owned());
+            diag.emit();
+        }
+        dcx.handle().write_lint_baseline().unwrap();
+        emitted.lock().unwrap().clone()
+    })
+}
+
+#[test]
+fn update_then_check() {
+    let baseline = std::env::temp_dir().join(format!("lint-baseline-{}.json", std::process::id()));
+
+    let code = "let a = 1;\nlet b = 2;\n";
+    let emitted = emit_lints(
+        &baseline,
+        LintBaselineMode::Update,
+        code,
+        &[("foo", 4, 5), ("foo", 15, 16), ("bar", 4, 5)],
+    );
+    assert!(emitted.is_empty());
+
+    // Known occurrences are suppressed even though lines moved and were
+    // reformatted, new ones are reported.
+    let code = "\n\nlet   a = 1;\nlet b = 2;\nlet c = 3;\n";
+    let emitted = emit_lints(
+        &baseline,
+        LintBaselineMode::Check,
+        code,
+        &[("foo", 8, 9), ("foo", 19, 20), ("foo", 30, 31), ("bar", 8, 9), ("bar", 19, 20)],
+    );
+    assert_eq!(emitted, ["lint at 30 in foo", "lint at 19 in bar"]);
+
+    // Each entry only suppresses as many occurrences as were recorded.
+    let emitted =
+        emit_lints(&baseline, LintBaselineMode::Check, code, &[("bar", 8, 9), ("bar", 8, 9)]);
+    assert_eq!(emitted, ["lint at 8 in bar"]);
+
+    fs::remove_file(&baseline).unwrap();
+}
+
+#[test]
+fn missing_baseline() {
+    let baseline = std::env::temp_dir().join("lint-baseline-that-does-not-exist.json");
+    assert!(LintBaseline::load(baseline.clone(), LintBaselineMode::Check).is_err());
+    assert!(LintBaseline::load(baseline, LintBaselineMode::Update).is_ok());
+}
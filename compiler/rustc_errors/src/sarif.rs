@@ -0,0 +1,502 @@
+//! A SARIF 2.1.0 emitter for errors.
+//!
+//! Unlike the JSON emitter, which writes one object per diagnostic as soon as
+//! it is emitted, a SARIF log is a single document describing a whole run of
+//! the tool. This emitter therefore buffers every diagnostic it receives and
+//! only writes the log when it is dropped, which happens when the owning
+//! `DiagCtxt` goes away at the end of the compilation session.
+//!
+//! The mapping from diagnostics to SARIF is:
+//!
+//! - every diagnostic becomes a `result`, whose `ruleId` is the error code or
+//!   the lint name (if any), and every distinct rule is listed once in the
+//!   `rules` of the tool driver;
+//! - primary spans become `locations`, with their labels as messages;
+//! - secondary spans and child diagnostics (notes, helps) become
+//!   `relatedLocations`;
+//! - the macro backtrace of a primary span becomes a `stack`, innermost frame
+//!   first;
+//! - every substitution of a suggestion becomes a `fix`.
+//!
+//! See <https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html>.
+
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use derive_setters::Setters;
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::IntoDynSyncSend;
+use rustc_error_messages::DiagArgMap;
+use rustc_lint_defs::Applicability;
+use rustc_span::source_map::SourceMap;
+use rustc_span::{FileName, Span};
+use serde::Serialize;
+
+use crate::diagnostic::IsLint;
+use crate::emitter::Emitter;
+use crate::formatting::{format_diag_message, format_diag_messages};
+use crate::{CodeSuggestion, DiagInner, Level, MultiSpan, Subdiag, Suggestions};
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+#[derive(Setters)]
+pub struct SarifEmitter {
+    #[setters(skip)]
+    dst: IntoDynSyncSend<Box<dyn Write + Send>>,
+    #[setters(skip)]
+    sm: Option<Arc<SourceMap>>,
+    /// The name of the tool reported in the log, e.g. `rustc` or `rustdoc`.
+    #[setters(skip)]
+    tool_name: &'static str,
+    /// Don't write anything if no diagnostic was emitted. This is used for the
+    /// emitter of the `EarlyDiagCtxt`, which is alive at the same time as the
+    /// one of the session and shouldn't add an empty log to the output.
+    skip_empty_log: bool,
+    #[setters(skip)]
+    rules: FxIndexMap<String, ReportingDescriptor>,
+    #[setters(skip)]
+    results: Vec<SarifResult>,
+}
+
+impl SarifEmitter {
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        sm: Option<Arc<SourceMap>>,
+        tool_name: &'static str,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst: IntoDynSyncSend(dst),
+            sm,
+            tool_name,
+            skip_empty_log: false,
+            rules: FxIndexMap::default(),
+            results: Vec::new(),
+        }
+    }
+
+    fn write_log(&mut self) -> io::Result<()> {
+        let log = SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: [Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: self.tool_name,
+                        information_uri: "https://www.rust-lang.org/",
+                        rules: self.rules.values().collect(),
+                    },
+                },
+                results: &self.results,
+                column_kind: "unicodeCodePoints",
+            }],
+        };
+        serde_json::to_writer_pretty(&mut *self.dst, &log)?;
+        self.dst.write_all(b"\n")?;
+        self.dst.flush()
+    }
+
+    /// Returns the index of the rule for the given code or lint name in the
+    /// `rules` of the driver, registering it on first use.
+    fn rule_index(&mut self, diag: &DiagInner) -> Option<(String, usize)> {
+        let (id, help_uri) = if let Some(code) = diag.code {
+            let id = code.to_string();
+            let help_uri = format!("https://doc.rust-lang.org/error_codes/{id}.html");
+            (id, Some(help_uri))
+        } else if let Some(IsLint { name, .. }) = &diag.is_lint {
+            (name.clone(), None)
+        } else {
+            return None;
+        };
+        let entry = self.rules.entry(id.clone());
+        let index = entry.index();
+        entry.or_insert_with(|| ReportingDescriptor {
+            id: id.clone(),
+            help_uri,
+            properties: RuleProperties { is_lint: diag.is_lint.is_some() },
+        });
+        Some((id, index))
+    }
+
+    fn physical_location(&self, span: Span) -> Option<PhysicalLocation> {
+        let sm = self.sm.as_ref()?;
+        if span.is_dummy() {
+            return None;
+        }
+        let start = sm.lookup_char_pos(span.lo());
+        let end = sm.lookup_char_pos(span.hi());
+        // Spans into `<anon>` or other virtual files don't have an artifact we
+        // could point to.
+        let FileName::Real(_) = start.file.name else {
+            return None;
+        };
+        let file_name = sm.filename_for_diagnostics(&start.file.name).to_string();
+        let byte_offset = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        Some(PhysicalLocation {
+            artifact_location: ArtifactLocation { uri: path_to_uri(&file_name) },
+            region: Region {
+                start_line: start.line,
+                start_column: start.col.0 + 1,
+                end_line: end.line,
+                end_column: end.col.0 + 1,
+                byte_offset,
+                byte_length: byte_end - byte_offset,
+            },
+        })
+    }
+
+    fn location(&self, span: Span, message: Option<String>) -> Location {
+        Location {
+            id: None,
+            physical_location: self.physical_location(span),
+            message: message.map(Message::new),
+        }
+    }
+
+    fn macro_backtrace_stack(&self, span: Span) -> Option<Stack> {
+        let mut frames: Vec<_> = span
+            .macro_backtrace()
+            .map(|expn| StackFrame {
+                location: self.location(
+                    expn.call_site,
+                    Some(format!("in this expansion of `{}`", expn.kind.descr())),
+                ),
+            })
+            .collect();
+        if frames.is_empty() {
+            return None;
+        }
+        frames.insert(0, StackFrame { location: self.location(span, None) });
+        Some(Stack { message: Message::new("macro backtrace".to_owned()), frames })
+    }
+
+    fn related_locations(
+        &self,
+        msp: &MultiSpan,
+        children: &[Subdiag],
+        args: &DiagArgMap,
+    ) -> Vec<Location> {
+        let labels = msp.span_labels().into_iter().filter(|label| !label.is_primary).map(|label| {
+            let message = label.label.as_ref().map(|m| format_diag_message(m, args).to_string());
+            self.location(label.span, message)
+        });
+        let children = children.iter().flat_map(|child| self.child_locations(child, args));
+        labels.chain(children).collect()
+    }
+
+    /// A child diagnostic is attached to each of its primary spans, or reported
+    /// without a physical location if it doesn't have any.
+    fn child_locations(&self, child: &Subdiag, args: &DiagArgMap) -> Vec<Location> {
+        let message =
+            format!("{}: {}", child.level.to_str(), format_diag_messages(&child.messages, args));
+        let spans = child.span.primary_spans();
+        if spans.is_empty() {
+            return vec![Location {
+                id: None,
+                physical_location: None,
+                message: Some(Message::new(message)),
+            }];
+        }
+        spans.iter().map(|&span| self.location(span, Some(message.clone()))).collect()
+    }
+
+    fn fixes(&self, sugg: &CodeSuggestion, args: &DiagArgMap) -> Vec<Fix> {
+        let description = format_diag_message(&sugg.msg, args).to_string();
+        sugg.substitutions
+            .iter()
+            .filter_map(|substitution| {
+                // SARIF groups the replacements of a fix by artifact.
+                let mut changes: FxIndexMap<String, Vec<Replacement>> = FxIndexMap::default();
+                for part in &substitution.parts {
+                    let PhysicalLocation { artifact_location, region } =
+                        self.physical_location(part.span)?;
+                    changes.entry(artifact_location.uri).or_default().push(Replacement {
+                        deleted_region: region,
+                        inserted_content: ArtifactContent { text: part.snippet.clone() },
+                    });
+                }
+                Some(Fix {
+                    description: Message::new(description.clone()),
+                    artifact_changes: changes
+                        .into_iter()
+                        .map(|(uri, replacements)| ArtifactChange {
+                            artifact_location: ArtifactLocation { uri },
+                            replacements,
+                        })
+                        .collect(),
+                    properties: FixProperties { applicability: sugg.applicability },
+                })
+            })
+            .collect()
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: DiagInner) {
+        let level = match diag.level {
+            Level::Bug | Level::Fatal | Level::Error | Level::DelayedBug => "error",
+            Level::ForceWarning | Level::Warning => "warning",
+            Level::Note | Level::OnceNote | Level::Help | Level::OnceHelp => "note",
+            // Failure notes only point to further information about the other
+            // results, e.g. `rustc --explain`.
+            Level::FailureNote => return,
+            Level::Allow | Level::Expect => "none",
+        };
+        let rule = self.rule_index(&diag);
+        let primary_spans = diag.span.span_labels().into_iter().filter(|label| label.is_primary);
+        let mut locations = Vec::new();
+        let mut stacks = Vec::new();
+        for label in primary_spans {
+            let message =
+                label.label.as_ref().map(|m| format_diag_message(m, &diag.args).to_string());
+            locations.push(self.location(label.span, message));
+            stacks.extend(self.macro_backtrace_stack(label.span));
+        }
+        let mut related_locations = self.related_locations(&diag.span, &diag.children, &diag.args);
+        for (id, location) in related_locations.iter_mut().enumerate() {
+            location.id = Some(id);
+        }
+        let suggestions = match &diag.suggestions {
+            Suggestions::Enabled(suggestions) => &suggestions[..],
+            Suggestions::Sealed(suggestions) => &suggestions[..],
+            Suggestions::Disabled => &[],
+        };
+        let fixes = suggestions.iter().flat_map(|sugg| self.fixes(sugg, &diag.args)).collect();
+
+        self.results.push(SarifResult {
+            rule_id: rule.as_ref().map(|(id, _)| id.clone()),
+            rule_index: rule.map(|(_, index)| index),
+            level,
+            message: Message::new(format_diag_messages(&diag.messages, &diag.args).to_string()),
+            locations,
+            related_locations,
+            stacks,
+            fixes,
+        });
+    }
+
+    fn source_map(&self) -> Option<&SourceMap> {
+        self.sm.as_deref()
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+
+    fn should_show_error_count(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        if self.skip_empty_log && self.results.is_empty() {
+            return;
+        }
+        if let Err(e) = self.write_log() {
+            panic!("failed to print diagnostics: {e:?}");
+        }
+    }
+}
+
+/// Converts a file name as displayed in diagnostics to a URI reference.
+/// Absolute paths become `file` URIs, relative paths stay relative.
+fn path_to_uri(path: &str) -> String {
+    // Windows paths may come with a verbatim prefix, `\\?\C:\` or `\\?\UNC\server\`.
+    let path = match path.strip_prefix(r"\\?\UNC\") {
+        Some(unc) => format!(r"\\{unc}"),
+        None => path.strip_prefix(r"\\?\").unwrap_or(path).to_owned(),
+    };
+    let path = path.replace('\\', "/");
+    let mut uri = String::with_capacity(path.len());
+    let rest = if let Some(unc) = path.strip_prefix("//") {
+        // A UNC path, whose server becomes the authority of the URI.
+        uri.push_str("file://");
+        unc
+    } else if path.starts_with('/') {
+        uri.push_str("file://");
+        path.as_str()
+    } else if let [drive, b':', ..] = path.as_bytes()
+        && drive.is_ascii_alphabetic()
+    {
+        // A Windows path with a drive letter, which becomes the first path segment.
+        uri.push_str("file:///");
+        uri.push_str(&path[..2]);
+        &path[2..]
+    } else {
+        path.as_str()
+    };
+    for byte in rest.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
+
+// The following data types are provided just for serialisation.
+
+#[derive(Serialize)]
+struct SarifLog<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: [Run<'a>; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool<'a>,
+    results: &'a [SarifResult],
+    column_kind: &'static str,
+}
+
+#[derive(Serialize)]
+struct Tool<'a> {
+    driver: ToolComponent<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent<'a> {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: Vec<&'a ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    /// The error code (e.g. "E1234") or the lint name.
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+    properties: RuleProperties,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RuleProperties {
+    is_lint: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    /// "error", "warning", "note" or "none".
+    level: &'static str,
+    message: Message,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    /// Macro backtraces of the primary spans.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stacks: Vec<Stack>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+impl Message {
+    fn new(text: String) -> Message {
+        Message { text }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    /// Only set for related locations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    physical_location: Option<PhysicalLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    /// 1-based, character offset.
+    start_column: usize,
+    end_line: usize,
+    /// 1-based, character offset, exclusive.
+    end_column: usize,
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+#[derive(Serialize)]
+struct Stack {
+    message: Message,
+    frames: Vec<StackFrame>,
+}
+
+#[derive(Serialize)]
+struct StackFrame {
+    location: Location,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+    properties: FixProperties,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: ArtifactContent,
+}
+
+#[derive(Serialize)]
+struct ArtifactContent {
+    text: String,
+}
+
+#[derive(Serialize)]
+struct FixProperties {
+    applicability: Applicability,
+}
@@ -0,0 +1,192 @@
+use std::path::PathBuf;
+use std::str;
+use std::sync::Mutex;
+
+use rustc_span::BytePos;
+use rustc_span::source_map::FilePathMapping;
+use serde_json::{Value, json};
+
+use super::*;
+use crate::DiagCtxt;
+use crate::codes::E0308;
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+fn filename(sm: &SourceMap, path: &str) -> FileName {
+    FileName::Real(sm.path_mapping().to_real_filename(sm.working_dir(), PathBuf::from(path)))
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+/// Emits diagnostics for `code` through `f` and returns the SARIF log, if any.
+fn test_log(code: &str, skip_empty_log: bool, f: impl FnOnce(&DiagCtxt)) -> Option<Value> {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Arc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(filename(&sm, "src/test.rs"), code.to_owned());
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let se = SarifEmitter::new(Box::new(Shared { data: output.clone() }), Some(sm), "rustc")
+            .skip_empty_log(skip_empty_log);
+        let dcx = DiagCtxt::new(Box::new(se));
+        f(&dcx);
+        drop(dcx);
+
+        let bytes = output.lock().unwrap();
+        let actual_output = str::from_utf8(&bytes).unwrap();
+        if actual_output.is_empty() {
+            None
+        } else {
+            Some(serde_json::from_str(actual_output).unwrap())
+        }
+    })
+}
+
+#[test]
+fn single_log() {
+    let log = test_log("fn main() {}\n", false, |dcx| {
+        dcx.handle().struct_span_err(span(3, 7), "foo").with_code(E0308).emit();
+        dcx.handle().struct_span_err(span(0, 2), "bar").with_code(E0308).emit();
+        dcx.handle().struct_span_warn(span(10, 12), "baz").emit();
+    })
+    .unwrap();
+
+    assert_eq!(log["version"], "2.1.0");
+    let runs = log["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 1);
+    let run = &runs[0];
+    assert_eq!(run["tool"]["driver"]["name"], "rustc");
+    assert_eq!(
+        run["tool"]["driver"]["rules"],
+        json!([{
+            "id": "E0308",
+            "helpUri": "https://doc.rust-lang.org/error_codes/E0308.html",
+            "properties": { "isLint": false },
+        }])
+    );
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["ruleId"], "E0308");
+    assert_eq!(results[0]["ruleIndex"], 0);
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(results[0]["message"]["text"], "foo");
+    assert_eq!(
+        results[0]["locations"],
+        json!([{
+            "physicalLocation": {
+                "artifactLocation": { "uri": "src/test.rs" },
+                "region": {
+                    "startLine": 1,
+                    "startColumn": 4,
+                    "endLine": 1,
+                    "endColumn": 8,
+                    "byteOffset": 3,
+                    "byteLength": 4,
+                },
+            },
+        }])
+    );
+    assert_eq!(results[1]["ruleIndex"], 0);
+    assert_eq!(results[2]["level"], "warning");
+    assert!(results[2].get("ruleId").is_none());
+}
+
+#[test]
+fn labels_and_children() {
+    let code = "let x: u32 = \"a\";\n";
+    let log = test_log(code, false, |dcx| {
+        dcx.handle()
+            .struct_span_err(span(13, 16), "mismatched types")
+            .with_span_label(span(13, 16), "expected `u32`, found `&str`")
+            .with_span_label(span(7, 10), "expected due to this")
+            .with_span_note(span(4, 5), "binding declared here")
+            .with_note("a note without a span")
+            .emit();
+    })
+    .unwrap();
+
+    let result = &log["runs"][0]["results"][0];
+    assert_eq!(result["locations"][0]["message"]["text"], "expected `u32`, found `&str`");
+    let related = result["relatedLocations"].as_array().unwrap();
+    assert_eq!(related.len(), 3);
+    assert_eq!(related[0]["id"], 0);
+    assert_eq!(related[0]["message"]["text"], "expected due to this");
+    assert_eq!(related[0]["physicalLocation"]["region"]["startColumn"], 8);
+    assert_eq!(related[1]["message"]["text"], "note: binding declared here");
+    assert_eq!(related[1]["physicalLocation"]["region"]["byteOffset"], 4);
+    assert_eq!(
+        related[2],
+        json!({ "id": 2, "message": { "text": "note: a note without a span" } })
+    );
+}
+
+#[test]
+fn suggestions_become_fixes() {
+    let log = test_log("let x = 1;\n", false, |dcx| {
+        dcx.handle()
+            .struct_span_warn(span(4, 5), "unused variable: `x`")
+            .with_span_suggestion(
+                span(4, 5),
+                "if this is intentional, prefix it with an underscore",
+                "_x",
+                Applicability::MaybeIncorrect,
+            )
+            .emit();
+    })
+    .unwrap();
+
+    let result = &log["runs"][0]["results"][0];
+    assert_eq!(
+        result["fixes"],
+        json!([{
+            "description": { "text": "if this is intentional, prefix it with an underscore" },
+            "artifactChanges": [{
+                "artifactLocation": { "uri": "src/test.rs" },
+                "replacements": [{
+                    "deletedRegion": {
+                        "startLine": 1,
+                        "startColumn": 5,
+                        "endLine": 1,
+                        "endColumn": 6,
+                        "byteOffset": 4,
+                        "byteLength": 1,
+                    },
+                    "insertedContent": { "text": "_x" },
+                }],
+            }],
+            "properties": { "applicability": "MaybeIncorrect" },
+        }])
+    );
+}
+
+#[test]
+fn empty_log() {
+    let log = test_log("", false, |_| {}).unwrap();
+    assert_eq!(log["runs"][0]["results"], json!([]));
+    assert_eq!(test_log("", true, |_| {}), None);
+}
+
+#[test]
+fn uris() {
+    assert_eq!(path_to_uri("src/main.rs"), "src/main.rs");
+    assert_eq!(path_to_uri("/home/user/my crate/lib.rs"), "file:///home/user/my%20crate/lib.rs");
+    assert_eq!(path_to_uri("C:\\src\\lib.rs"), "file:///C:/src/lib.rs");
+    assert_eq!(path_to_uri("C:/my crate/a:b.rs"), "file:///C:/my%20crate/a%3Ab.rs");
+    assert_eq!(path_to_uri("\\\\?\\C:\\src\\lib.rs"), "file:///C:/src/lib.rs");
+    assert_eq!(path_to_uri("\\\\server\\share\\lib.rs"), "file://server/share/lib.rs");
+    assert_eq!(path_to_uri("\\\\?\\UNC\\server\\share\\lib.rs"), "file://server/share/lib.rs");
+}
@@ -1217,6 +1217,7 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) {
                         tcx.par_hir_for_each_module(|module| {
                             tcx.ensure_ok().check_mod_deathness(module)
                         });
+                        rustc_passes::dead::check_dead_code_pub_in_dependencies(tcx);
                     },
                     &mut || {
                         sess.time("lint_checking", || {
@@ -709,6 +709,7 @@ macro_rules! untracked {
     // Make sure that changing an [UNTRACKED] option leaves the hash unchanged.
     // tidy-alphabetical-start
     untracked!(assert_incr_state, Some(IncrementalStateAssertion::Loaded));
+    untracked!(codegen_report, Some(PathBuf::from("abc")));
     untracked!(codegen_source_order, true);
     untracked!(deduplicate_diagnostics, false);
     untracked!(dump_dep_graph, true);
@@ -788,6 +789,7 @@ macro_rules! tracked {
     tracked!(autodiff, vec![AutoDiff::Enable, AutoDiff::NoTT]);
     tracked!(autodiff_post_passes, Some("function(mem2reg,instsimplify,simplifycfg)".to_string()));
     tracked!(binary_dep_depinfo, true);
+    tracked!(borrowck_explain, true);
     tracked!(box_noalias, false);
     tracked!(
         branch_protection,
@@ -809,6 +811,7 @@ macro_rules! tracked {
     );
     tracked!(crate_attr, vec!["abc".to_string()]);
     tracked!(cross_crate_inline_threshold, InliningThreshold::Always);
+    tracked!(dead_code_pub_in_dependencies, vec![String::from("abc")]);
     tracked!(debug_info_type_line_numbers, true);
     tracked!(debuginfo_for_profiling, true);
     tracked!(default_visibility, Some(rustc_target::spec::SymbolVisibility::Hidden));
@@ -873,6 +876,7 @@ macro_rules! tracked {
     tracked!(precise_enum_drop_elaboration, false);
     tracked!(profile_sample_use, Some(PathBuf::from("abc")));
     tracked!(profiler_runtime, "abc".to_string());
+    tracked!(record_extern_item_uses, true);
     tracked!(reg_struct_return, true);
     tracked!(regparm, Some(3));
     tracked!(relax_elf_relocations, Some(true));
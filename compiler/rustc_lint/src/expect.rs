@@ -4,7 +4,7 @@
 use rustc_middle::ty::TyCtxt;
 use rustc_session::lint::builtin::UNFULFILLED_LINT_EXPECTATIONS;
 use rustc_session::lint::{LintExpectationId, StableLintExpectationId};
-use rustc_span::Symbol;
+use rustc_span::{AttrId, Symbol};
 
 use crate::lints::{Expectation, ExpectationNote};
 
@@ -25,30 +25,41 @@ fn lint_expectations(tcx: TyCtxt<'_>, (): ()) -> Vec<(StableLintExpectationId, L
     expectations
 }
 
-fn check_expectations(tcx: TyCtxt<'_>, tool_filter: Option<Symbol>) {
-    let lint_expectations = tcx.lint_expectations(());
-    let fulfilled_expectations = tcx.dcx().steal_fulfilled_expectation_ids();
+/// Turns a `LintExpectationId` into an `(AttrId, lint_index)` pair, which is the same for the
+/// unstable and stable ids of an expectation.
+fn canonicalize_expectation_id(tcx: TyCtxt<'_>, expect_id: &LintExpectationId) -> (AttrId, u16) {
+    match *expect_id {
+        LintExpectationId::Unstable(id) => (id.attr_id, id.lint_index),
+        LintExpectationId::Stable(id) => {
+            // We are only called from `eval_always` code, so looking at the attribute's
+            // `AttrId` is ok.
+            (tcx.hir_attrs(id.hir_id)[id.attr_index as usize].id(), id.lint_index)
+        }
+    }
+}
 
-    // Turn a `LintExpectationId` into a `(AttrId, lint_index)` pair.
-    let canonicalize_id = |expect_id: &LintExpectationId| {
-        let (attr_id, lint_index) = match *expect_id {
-            LintExpectationId::Unstable(id) => (id.attr_id, id.lint_index),
-            LintExpectationId::Stable(id) => {
-                // We are an `eval_always` query, so looking at the attribute's `AttrId` is ok.
-                (tcx.hir_attrs(id.hir_id)[id.attr_index as usize].id(), id.lint_index)
-            }
-        };
-        (attr_id, lint_index)
-    };
+/// Returns a predicate telling whether an expectation was fulfilled by the lints emitted so far.
+pub(crate) fn fulfilled_expectations(tcx: TyCtxt<'_>) -> impl Fn(StableLintExpectationId) -> bool {
+    let fulfilled_expectations: FxHashSet<_> = tcx
+        .dcx()
+        .fulfilled_expectation_ids()
+        .iter()
+        .map(|expect_id| canonicalize_expectation_id(tcx, expect_id))
+        .collect();
+    move |expect_id| {
+        fulfilled_expectations
+            .contains(&canonicalize_expectation_id(tcx, &LintExpectationId::Stable(expect_id)))
+    }
+}
 
-    let fulfilled_expectations: FxHashSet<_> =
-        fulfilled_expectations.iter().map(canonicalize_id).collect();
+fn check_expectations(tcx: TyCtxt<'_>, tool_filter: Option<Symbol>) {
+    let lint_expectations = tcx.lint_expectations(());
+    let is_fulfilled = fulfilled_expectations(tcx);
 
     for (expect_id, expectation) in lint_expectations {
         let hir_id = expect_id.hir_id;
-        let expect_id = canonicalize_id(&LintExpectationId::Stable(*expect_id));
 
-        if !fulfilled_expectations.contains(&expect_id)
+        if !is_fulfilled(*expect_id)
             && tool_filter.is_none_or(|filter| expectation.lint_tool == Some(filter))
         {
             let rationale = expectation.reason.map(|rationale| ExpectationNote { rationale });
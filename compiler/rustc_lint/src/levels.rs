@@ -154,6 +154,47 @@ fn skippable_lints(tcx: TyCtxt<'_>, (): ()) -> UnordSet<LintId> {
     skippable.into()
 }
 
+/// A lint level set by an attribute or on the command line, as printed by
+/// `--print=lint-levels`.
+#[derive(Debug)]
+pub struct LintLevelOverride {
+    pub lint: LintId,
+    /// The node the level applies to, along with everything it contains.
+    pub hir_id: HirId,
+    pub level: Level,
+    pub src: LintLevelSource,
+    /// For `#[expect]`, whether the expected lint was emitted.
+    pub fulfilled: Option<bool>,
+}
+
+/// Collects every lint level set in the crate, in the order of the nodes they apply to.
+///
+/// Expectations are only known to be fulfilled once all lints have been emitted, so this has to
+/// be called at the end of the analysis.
+pub fn lint_level_overrides(tcx: TyCtxt<'_>) -> Vec<LintLevelOverride> {
+    let is_fulfilled = crate::expect::fulfilled_expectations(tcx);
+
+    let mut overrides = vec![];
+    for owner in tcx.hir_crate_items(()).owners() {
+        let map = tcx.shallow_lint_levels_on(owner);
+        for (&local_id, specs) in map.specs.iter() {
+            let hir_id = HirId { owner, local_id };
+            for (&lint, level_spec) in specs {
+                let fulfilled =
+                    level_spec.lint_id().filter(|_| level_spec.is_expect()).map(&is_fulfilled);
+                overrides.push(LintLevelOverride {
+                    lint,
+                    hir_id,
+                    level: level_spec.level(),
+                    src: level_spec.src,
+                    fulfilled,
+                });
+            }
+        }
+    }
+    overrides
+}
+
 #[instrument(level = "trace", skip(tcx), ret)]
 fn shallow_lint_levels_on(tcx: TyCtxt<'_>, owner: hir::OwnerId) -> ShallowLintLevelMap {
     let store = unerased_lint_store(tcx.sess);
@@ -143,7 +143,7 @@
 pub use early::diagnostics::DiagAndSess;
 pub use early::{EarlyCheckNode, check_ast_node};
 pub use late::{check_crate, late_lint_mod, unerased_lint_store};
-pub use levels::LintLevelsBuilder;
+pub use levels::{LintLevelOverride, LintLevelsBuilder, lint_level_overrides};
 pub use passes::{EarlyLintPass, LateLintPass};
 pub use rustc_errors::BufferedEarlyLint;
 pub use rustc_session::lint::Level::{self, *};
@@ -36,6 +36,7 @@ pub fn lint_vec() -> crate::LintVec {
             CONST_ITEM_MUTATION,
             DEAD_CODE,
             DEAD_CODE_PUB_IN_BINARY,
+            DEAD_CODE_PUB_IN_DEPENDENCIES,
             DEPENDENCY_ON_UNIT_NEVER_TYPE_FALLBACK,
             DEPRECATED,
             DEPRECATED_IN_FUTURE,
@@ -52,6 +53,7 @@ pub fn lint_vec() -> crate::LintVec {
             FLOAT_LITERAL_F32_FALLBACK,
             FORBIDDEN_LINT_GROUPS,
             FUNCTION_ITEM_REFERENCES,
+            GAPS_BETWEEN_RANGES,
             HIDDEN_GLOB_REEXPORTS,
             ILL_FORMED_ATTRIBUTE_INPUT,
             INCOMPLETE_INCLUDE,
@@ -823,6 +825,55 @@ pub fn lint_vec() -> crate::LintVec {
     crate_level_only
 }
 
+declare_lint! {
+    /// The `dead_code_pub_in_dependencies` lint detects `pub` items of the crates named with
+    /// `-Z dead-code-pub-in-dependencies` that no other crate in the dependency graph uses.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs multiple crates)
+    /// // util.rs
+    /// pub fn used() {}
+    /// pub fn helper() {}
+    ///
+    /// // main.rs
+    /// fn main() {
+    ///     util::used();
+    /// }
+    /// ```
+    ///
+    /// compiled with:
+    ///
+    /// ```text
+    /// rustc --crate-type=lib -Z record-extern-item-uses util.rs
+    /// rustc -Z record-extern-item-uses -Z dead-code-pub-in-dependencies=util \
+    ///     -W dead_code_pub_in_dependencies --extern util main.rs
+    /// ```
+    ///
+    /// produces:
+    ///
+    /// ```text
+    /// warning: function `util::helper` is never used outside of `util`
+    ///  --> util.rs:2:1
+    ///   |
+    /// 2 | pub fn helper() {}
+    ///   | ^^^^^^^^^^^^^^^
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// The `pub` items of the internal library crates of a workspace are often only meant to be
+    /// used by its binaries. This lint finds the ones that none of the crates linked into the
+    /// final crate use, so that they can be removed or made private. It relies on the items of
+    /// other crates used by each crate, which is recorded in the metadata of the crates built with
+    /// `-Z record-extern-item-uses`. If a crate outside of the standard library was built without
+    /// it, nothing is reported and a warning names that crate instead.
+    pub DEAD_CODE_PUB_IN_DEPENDENCIES,
+    Allow,
+    "detect public items of dependencies that no crate in the dependency graph uses",
+    crate_level_only
+}
+
 declare_lint! {
     /// The `unused_attributes` lint detects attributes that were not used by
     /// the compiler.
@@ -983,6 +1034,42 @@ pub fn lint_vec() -> crate::LintVec {
     "detects off-by-one errors with exclusive range patterns"
 }
 
+declare_lint! {
+    /// The `gaps_between_ranges` lint detects values that lie between two [range patterns] of a
+    /// `match` and are only matched by a wildcard.
+    ///
+    /// [range patterns]: https://doc.rust-lang.org/nightly/reference/patterns.html#range-patterns
+    ///
+    /// ### Example
+    ///
+    /// ```rust,compile_fail
+    /// #![deny(gaps_between_ranges)]
+    /// const HEADER_END: u8 = 0x0f;
+    /// const PAYLOAD_START: u8 = 0x11;
+    ///
+    /// fn main() {
+    ///     let x = 123u8;
+    ///     match x {
+    ///         0..=HEADER_END => { println!("header"); }
+    ///         PAYLOAD_START..=0x7f => { println!("payload"); }
+    ///         _ => { println!("unknown"); }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// When the bounds of range patterns are computed, for example from named constants, it is
+    /// easy to leave values out between two ranges by mistake. These values then silently end up
+    /// in the wildcard arm. This lint is "allow" by default because many matches leave such gaps
+    /// on purpose.
+    pub GAPS_BETWEEN_RANGES,
+    Allow,
+    "detects values between range patterns that are only matched by a wildcard"
+}
+
 declare_lint! {
     /// The `bindings_with_variant_name` lint detects pattern bindings with
     /// the same name as one of the matched variants.
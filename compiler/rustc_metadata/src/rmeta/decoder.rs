@@ -1575,6 +1575,10 @@ fn get_exportable_items(&self, tcx: TyCtxt<'_>) -> impl Iterator<Item = DefId> {
         self.root.exportable_items.decode((self, tcx)).map(move |index| self.local_def_id(index))
     }
 
+    fn get_extern_item_uses(&self, tcx: TyCtxt<'_>) -> Option<impl Iterator<Item = DefId>> {
+        Some(self.root.extern_item_uses?.decode((self, tcx)))
+    }
+
     fn get_stable_order_of_exportable_impls(
         &self,
         tcx: TyCtxt<'_>,
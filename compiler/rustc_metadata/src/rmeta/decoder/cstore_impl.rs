@@ -411,6 +411,9 @@ fn into_args(self) -> (DefId, SimplifiedType) {
     debugger_visualizers => { cdata.get_debugger_visualizers(tcx) }
 
     exportable_items => { tcx.arena.alloc_from_iter(cdata.get_exportable_items(tcx)) }
+    extern_item_uses => {
+        cdata.get_extern_item_uses(tcx).map(|uses| &*tcx.arena.alloc_from_iter(uses))
+    }
     stable_order_of_exportable_impls => {
         tcx.arena.alloc(cdata.get_stable_order_of_exportable_impls(tcx).collect())
     }
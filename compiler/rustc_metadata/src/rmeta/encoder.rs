@@ -697,6 +697,8 @@ macro_rules! stat {
         let stable_order_of_exportable_impls =
             stat!("exportable-items", || self.encode_stable_order_of_exportable_impls());
 
+        let extern_item_uses = stat!("extern-item-uses", || self.encode_extern_item_uses());
+
         // Encode exported symbols info. This is prefetched in `encode_metadata`.
         let (exported_non_generic_symbols, exported_generic_symbols) =
             stat!("exported-symbols", || {
@@ -771,6 +773,7 @@ macro_rules! stat {
                 impls,
                 incoherent_impls,
                 exportable_items,
+                extern_item_uses,
                 stable_order_of_exportable_impls,
                 exported_non_generic_symbols,
                 exported_generic_symbols,
@@ -2270,6 +2273,16 @@ fn encode_exportable_items(&mut self) -> LazyArray<DefIndex> {
         self.lazy_array(self.tcx.exportable_items(LOCAL_CRATE).iter().map(|def_id| def_id.index))
     }
 
+    /// Encodes the items of other crates used by this crate, if `-Z record-extern-item-uses` asks
+    /// for it.
+    fn encode_extern_item_uses(&mut self) -> Option<LazyArray<DefId>> {
+        if self.is_proc_macro || !self.tcx.sess.opts.unstable_opts.record_extern_item_uses {
+            return None;
+        }
+        let uses = self.tcx.extern_item_uses(LOCAL_CRATE)?;
+        Some(self.lazy_array(uses.iter().copied()))
+    }
+
     fn encode_stable_order_of_exportable_impls(&mut self) -> LazyArray<(DefIndex, usize)> {
         empty_proc_macro!(self);
         let stable_order_of_exportable_impls =
@@ -2,7 +2,7 @@
 
 use rustc_data_structures::fx::FxIndexMap;
 use rustc_data_structures::sorted_map::SortedMap;
-use rustc_errors::{Diag, DiagLocation, Diagnostic, MultiSpan};
+use rustc_errors::{Diag, DiagCtxtHandle, DiagLocation, Diagnostic, MultiSpan};
 use rustc_hir::{HirId, ItemLocalId};
 use rustc_lint_defs::EditionFcw;
 use rustc_macros::{Decodable, Encodable, StableHash};
@@ -375,6 +375,30 @@ fn explain_lint_level_source(
     }
 }
 
+/// Wraps a lint decorator to record the path of the item the lint is emitted in,
+/// which is used to match the lint against the entries of a `--lint-baseline`.
+///
+/// The path is only computed once the lint is decorated, so that allowed lints
+/// do not pay for it.
+pub(crate) struct WithLintItemPath<D, F> {
+    pub(crate) decorator: D,
+    pub(crate) item_path: F,
+}
+
+impl<'a, D, F> Diagnostic<'a, ()> for WithLintItemPath<D, F>
+where
+    D: Diagnostic<'a, ()>,
+    F: FnOnce() -> Option<String>,
+{
+    fn into_diag(self, dcx: DiagCtxtHandle<'a>, level: rustc_errors::Level) -> Diag<'a, ()> {
+        let mut diag = self.decorator.into_diag(dcx, level);
+        if let Some(item_path) = (self.item_path)() {
+            diag.lint_item_path(item_path);
+        }
+        diag
+    }
+}
+
 /// The innermost function for emitting lints implementing the [`trait@Diagnostic`] trait.
 ///
 /// If you are looking to implement a lint, look for higher level functions,
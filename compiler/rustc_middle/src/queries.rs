@@ -2407,6 +2407,13 @@
         separate_provide_extern
     }
 
+    /// The items of other crates that a crate uses, for the `dead_code_pub_in_dependencies` lint.
+    /// This is `None` for the dependencies that were built without `-Z record-extern-item-uses`.
+    query extern_item_uses(_: CrateNum) -> Option<&'tcx [DefId]> {
+        desc { "collecting the items of other crates used by a crate" }
+        separate_provide_extern
+    }
+
     query stable_order_of_exportable_impls(_: CrateNum) -> &'tcx FxIndexMap<DefId, usize> {
         desc { "fetching the stable impl's order" }
         separate_provide_extern
@@ -53,7 +53,7 @@
 use crate::hir::{ProjectedMaybeOwner, ProjectedOwnerInfo};
 use crate::ich::StableHashState;
 use crate::infer::canonical::{CanonicalParamEnvCache, CanonicalVarKind};
-use crate::lint::emit_lint_base;
+use crate::lint::{WithLintItemPath, emit_lint_base};
 use crate::metadata::ModChild;
 use crate::middle::codegen_fn_attrs::{CodegenFnAttrs, TargetFeature};
 use crate::middle::resolve_bound_vars;
@@ -64,6 +64,7 @@
 use crate::traits;
 use crate::traits::solve::{ExternalConstraints, ExternalConstraintsData, PredefinedOpaques};
 use crate::ty::predicate::ExistentialPredicateStableCmpExt as _;
+use crate::ty::print::with_no_trimmed_paths;
 use crate::ty::region::RegionExt;
 use crate::ty::{
     self, AdtDef, AdtDefData, AdtKind, Binder, Clause, Clauses, Const, FnSigKind, GenericArg,
@@ -2505,9 +2506,18 @@ pub fn emit_node_span_lint(
         decorator: impl for<'a> Diagnostic<'a, ()>,
     ) {
         let level_spec = self.lint_level_spec_at_node(lint, hir_id);
+        let item_path = move || self.lint_item_path(hir_id);
+        let decorator = WithLintItemPath { decorator, item_path };
         emit_lint_base(self.sess, lint, level_spec, Some(span.into()), decorator)
     }
 
+    /// The path of the item owning `hir_id`, if it is needed to match lints against a
+    /// `--lint-baseline`.
+    fn lint_item_path(self, hir_id: HirId) -> Option<String> {
+        self.sess.opts.lint_baseline.as_ref()?;
+        Some(with_no_trimmed_paths!(self.def_path_str(hir_id.owner.to_def_id())))
+    }
+
     /// Find the appropriate span where `use` and outer attributes can be inserted at.
     pub fn crate_level_attribute_injection_span(self) -> Span {
         let node = self.hir_node(hir::CRATE_HIR_ID);
@@ -2548,6 +2558,8 @@ pub fn emit_node_lint(
         decorator: impl for<'a> Diagnostic<'a, ()>,
     ) {
         let level_spec = self.lint_level_spec_at_node(lint, id);
+        let item_path = move || self.lint_item_path(id);
+        let decorator = WithLintItemPath { decorator, item_path };
         emit_lint_base(self.sess, lint, level_spec, None, decorator);
     }
 
@@ -1363,6 +1363,15 @@ fn report_non_exhaustive_match<'p, 'tcx>(
         }
     }
 
+    let arm_with_pattern = |pattern: String, is_never_pattern: bool| {
+        if is_never_pattern && cx.tcx.features().never_patterns() {
+            // Arms with a never pattern don't take a body.
+            pattern
+        } else {
+            // ignore-tidy-todo
+            format!("{pattern} => todo!()")
+        }
+    };
     // Whether we suggest the actual missing patterns or `_`.
     let suggest_the_witnesses = witnesses.len() < 4;
     let suggested_arm = if suggest_the_witnesses {
@@ -1371,17 +1380,13 @@ fn report_non_exhaustive_match<'p, 'tcx>(
             .map(|witness| cx.print_witness_pat(witness))
             .collect::<Vec<String>>()
             .join(" | ");
-        if witnesses.iter().all(|p| p.is_never_pattern()) && cx.tcx.features().never_patterns() {
-            // Arms with a never pattern don't take a body.
-            pattern
-        } else {
-            // ignore-tidy-todo
-            format!("{pattern} => todo!()")
-        }
+        arm_with_pattern(pattern, witnesses.iter().all(|p| p.is_never_pattern()))
     } else {
         // ignore-tidy-todo
         format!("_ => todo!()")
     };
+    // Where to insert the suggested arms: the span, what goes before the arms, what separates
+    // them, and what goes after them.
     let mut suggestion = None;
     let sm = cx.tcx.sess.source_map();
     match arms {
@@ -1394,7 +1399,9 @@ fn report_non_exhaustive_match<'p, 'tcx>(
             };
             suggestion = Some((
                 braces_span,
-                format!(" {{{indentation}{more}{suggested_arm},{indentation}}}",),
+                format!(" {{{indentation}{more}"),
+                format!(",{indentation}{more}"),
+                format!(",{indentation}}}"),
             ));
         }
         [only] => {
@@ -1420,7 +1427,9 @@ fn report_non_exhaustive_match<'p, 'tcx>(
             };
             suggestion = Some((
                 only.span.shrink_to_hi(),
-                format!("{comma}{pre_indentation}{suggested_arm}"),
+                format!("{comma}{pre_indentation}"),
+                format!(",{pre_indentation}"),
+                String::new(),
             ));
         }
         [.., prev, last] => {
@@ -1443,7 +1452,9 @@ fn report_non_exhaustive_match<'p, 'tcx>(
                 if let Some(spacing) = spacing {
                     suggestion = Some((
                         last.span.shrink_to_hi(),
-                        format!("{comma}{spacing}{suggested_arm}"),
+                        format!("{comma}{spacing}"),
+                        format!(",{spacing}"),
+                        String::new(),
                     ));
                 }
             }
@@ -1474,8 +1485,29 @@ fn report_non_exhaustive_match<'p, 'tcx>(
     if would_be_exhaustive_without_guards {
         err.subdiagnostic(NonExhaustiveMatchAllArmsGuarded);
     }
-    if let Some((span, sugg)) = suggestion {
-        err.span_suggestion_verbose(span, msg, sugg, Applicability::HasPlaceholders);
+    if let Some((span, before, separator, after)) = suggestion {
+        if witnesses.len() > 1 {
+            // Also give tools a way to fill in the whole match in one step, with one arm per
+            // missing pattern. Their bodies are `todo!()`, so the result compiles as is.
+            let arms = cx
+                .print_missing_arm_pats(&witnesses)
+                .into_iter()
+                .map(|(witness, pattern)| arm_with_pattern(pattern, witness.is_never_pattern()))
+                .collect::<Vec<_>>()
+                .join(&separator);
+            err.tool_only_span_suggestion(
+                span,
+                "add a match arm for each missing pattern",
+                format!("{before}{arms}{after}"),
+                Applicability::MachineApplicable,
+            );
+        }
+        err.span_suggestion_verbose(
+            span,
+            msg,
+            format!("{before}{suggested_arm}{after}"),
+            Applicability::HasPlaceholders,
+        );
     } else {
         err.help(msg);
     }
@@ -3,32 +3,34 @@
 // expectations such as `#[expect(unused)]` and `#[expect(dead_code)]` is live, and everything else
 // is dead.
 
-use std::mem;
 use std::ops::ControlFlow;
 use std::sync::atomic::Ordering;
+use std::{iter, mem};
 
 use hir::def_id::{LocalDefIdMap, LocalDefIdSet};
 use rustc_abi::FieldIdx;
 use rustc_data_structures::fx::{FxHashSet, FxIndexSet};
 use rustc_errors::{ErrorGuaranteed, MultiSpan};
 use rustc_hir::def::{CtorOf, DefKind, Res};
-use rustc_hir::def_id::{DefId, LocalDefId, LocalModId};
+use rustc_hir::def_id::{DefId, LOCAL_CRATE, LocalDefId, LocalModId};
 use rustc_hir::intravisit::{self, Visitor};
 use rustc_hir::{self as hir, ForeignItemId, ItemId, Node, PatKind, QPath, find_attr};
 use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 use rustc_middle::middle::dead_code::{DeadCodeLivenessSnapshot, DeadCodeLivenessSummary};
 use rustc_middle::middle::privacy::Level;
-use rustc_middle::query::Providers;
+use rustc_middle::query::{LocalCrate, Providers};
 use rustc_middle::ty::{self, AssocTag, TyCtxt};
 use rustc_middle::{bug, span_bug};
 use rustc_session::config::CrateType;
-use rustc_session::lint::builtin::{DEAD_CODE, DEAD_CODE_PUB_IN_BINARY};
+use rustc_session::lint::builtin::{
+    DEAD_CODE, DEAD_CODE_PUB_IN_BINARY, DEAD_CODE_PUB_IN_DEPENDENCIES,
+};
 use rustc_session::lint::{self, Lint, StableLintExpectationId};
 use rustc_span::{Symbol, kw};
 
 use crate::diagnostics::{
-    ChangeFields, DeadCodePubInBinaryNote, IgnoredDerivedImpls, MultipleDeadCodes, ParentInfo,
-    UselessAssignment,
+    ChangeFields, DeadCodePubInBinaryNote, DeadCodePubInDependenciesUnknownUses,
+    DeadCodePubInDependency, IgnoredDerivedImpls, MultipleDeadCodes, ParentInfo, UselessAssignment,
 };
 
 /// Any local definition that may call something in its body block should be explored. For example,
@@ -1460,7 +1462,189 @@ fn lint_dead_codes<'tcx>(
     }
 }
 
+/// Collects the items of other crates that the local crate refers to, through paths, method calls
+/// and type-relative paths like `Type::function`.
+struct ExternItemUsesVisitor<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    maybe_typeck_results: Option<&'tcx ty::TypeckResults<'tcx>>,
+    uses: FxIndexSet<DefId>,
+}
+
+impl<'tcx> ExternItemUsesVisitor<'tcx> {
+    fn record(&mut self, def_id: Option<DefId>) {
+        let Some(mut def_id) = def_id else { return };
+        if def_id.is_local() {
+            return;
+        }
+        // Building a value through a constructor or a variant uses its struct or enum, which is
+        // what the items of the dependencies are checked against.
+        if let DefKind::Ctor(..) = self.tcx.def_kind(def_id) {
+            def_id = self.tcx.parent(def_id);
+        }
+        if let DefKind::Variant = self.tcx.def_kind(def_id) {
+            def_id = self.tcx.parent(def_id);
+        }
+        self.uses.insert(def_id);
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for ExternItemUsesVisitor<'tcx> {
+    fn visit_nested_body(&mut self, body: hir::BodyId) {
+        let old_maybe_typeck_results =
+            self.maybe_typeck_results.replace(self.tcx.typeck_body(body));
+        self.visit_body(self.tcx.hir_body(body));
+        self.maybe_typeck_results = old_maybe_typeck_results;
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        if let hir::ExprKind::MethodCall(..) = expr.kind
+            && let Some(typeck_results) = self.maybe_typeck_results
+        {
+            self.record(typeck_results.type_dependent_def_id(expr.hir_id));
+        }
+        intravisit::walk_expr(self, expr)
+    }
+
+    fn visit_qpath(&mut self, qpath: &'tcx QPath<'tcx>, id: hir::HirId, _: rustc_span::Span) {
+        // Type-relative paths outside of bodies, like in `<T as Trait>::Assoc`, are not resolved
+        // by type checking.
+        if let QPath::TypeRelative(..) = qpath
+            && let Some(typeck_results) = self.maybe_typeck_results
+            && typeck_results.hir_owner == id.owner
+        {
+            self.record(typeck_results.qpath_res(qpath, id).opt_def_id());
+        }
+        intravisit::walk_qpath(self, qpath, id)
+    }
+
+    fn visit_path(&mut self, path: &hir::Path<'tcx>, _: hir::HirId) {
+        self.record(path.res.opt_def_id());
+        intravisit::walk_path(self, path)
+    }
+}
+
+fn extern_item_uses(tcx: TyCtxt<'_>, _: LocalCrate) -> Option<&[DefId]> {
+    let mut visitor =
+        ExternItemUsesVisitor { tcx, maybe_typeck_results: None, uses: Default::default() };
+    tcx.hir_visit_all_item_likes_in_crate(&mut visitor);
+    Some(tcx.arena.alloc_from_iter(visitor.uses))
+}
+
+/// Reports the `pub` items of the crates named with `-Z dead-code-pub-in-dependencies` that no
+/// other crate linked into the local crate uses.
+///
+/// An item counts as used if any crate refers to it, according to the `extern_item_uses` recorded
+/// in the metadata of each crate. Types also count as used when one of their inherent associated
+/// items is, since their values may be obtained without ever naming the type.
+pub fn check_dead_code_pub_in_dependencies(tcx: TyCtxt<'_>) {
+    let checked_crates = &tcx.sess.opts.unstable_opts.dead_code_pub_in_dependencies;
+    if checked_crates.is_empty()
+        || tcx.lint_level_spec_at_node(DEAD_CODE_PUB_IN_DEPENDENCIES, hir::CRATE_HIR_ID).level()
+            == lint::Level::Allow
+    {
+        return;
+    }
+
+    // The crates built without `-Z record-extern-item-uses` may use any of the checked items, so
+    // nothing can be reported reliably if there are any. The standard library crates can't depend
+    // on the checked crates, so it doesn't matter for them.
+    let mut used = FxHashSet::default();
+    let mut unknown_uses = vec![];
+    for &cnum in iter::once(&LOCAL_CRATE).chain(tcx.crates(())) {
+        match tcx.extern_item_uses(cnum) {
+            Some(uses) => used.extend(uses.iter().copied()),
+            None => {
+                let in_sysroot = tcx
+                    .crate_extern_paths(cnum)
+                    .iter()
+                    .any(|path| path.starts_with(&tcx.sess.target_tlib_path.dir));
+                if !in_sysroot {
+                    unknown_uses.push(tcx.crate_name(cnum));
+                }
+            }
+        }
+    }
+    if !unknown_uses.is_empty() {
+        tcx.dcx().emit_warn(DeadCodePubInDependenciesUnknownUses {
+            num: unknown_uses.len(),
+            crates: unknown_uses.into(),
+        });
+        return;
+    }
+
+    for &cnum in tcx.crates(()) {
+        let krate = tcx.crate_name(cnum);
+        if !checked_crates.iter().any(|name| *name == krate.as_str()) {
+            continue;
+        }
+
+        // Collect the public items of the crate, module by module. Items that are reexported
+        // from other crates are checked with their own crate.
+        let mut modules = vec![cnum.as_def_id()];
+        let mut items = FxIndexSet::default();
+        while let Some(module) = modules.pop() {
+            for child in tcx.module_children(module) {
+                let Res::Def(def_kind, def_id) = child.res else { continue };
+                if !child.vis.is_public() || def_id.krate != cnum {
+                    continue;
+                }
+                match def_kind {
+                    DefKind::Mod => modules.push(def_id),
+                    DefKind::Fn
+                    | DefKind::Const { .. }
+                    | DefKind::Static { .. }
+                    | DefKind::Trait
+                    | DefKind::TyAlias => {
+                        items.insert(def_id);
+                    }
+                    DefKind::Struct | DefKind::Enum | DefKind::Union => {
+                        items.insert(def_id);
+                        for &impl_def_id in tcx.inherent_impls(def_id) {
+                            items.extend(
+                                tcx.associated_item_def_ids(impl_def_id)
+                                    .iter()
+                                    .copied()
+                                    .filter(|&assoc| tcx.visibility(assoc).is_public()),
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for &def_id in &items {
+            if used.contains(&def_id) {
+                continue;
+            }
+            if let DefKind::Struct | DefKind::Enum | DefKind::Union = tcx.def_kind(def_id)
+                && tcx.inherent_impls(def_id).iter().any(|&impl_def_id| {
+                    tcx.associated_item_def_ids(impl_def_id)
+                        .iter()
+                        .any(|assoc| used.contains(assoc))
+                })
+            {
+                continue;
+            }
+            tcx.emit_node_span_lint(
+                DEAD_CODE_PUB_IN_DEPENDENCIES,
+                hir::CRATE_HIR_ID,
+                tcx.def_span(def_id),
+                DeadCodePubInDependency {
+                    descr: tcx.def_descr(def_id),
+                    name: tcx.def_path_str(def_id),
+                    krate,
+                },
+            );
+        }
+    }
+}
+
 pub(crate) fn provide(providers: &mut Providers) {
-    *providers =
-        Providers { live_symbols_and_ignored_derived_traits, check_mod_deathness, ..*providers };
+    *providers = Providers {
+        live_symbols_and_ignored_derived_traits,
+        check_mod_deathness,
+        extern_item_uses,
+        ..*providers
+    };
 }
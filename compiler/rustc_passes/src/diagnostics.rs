@@ -849,6 +849,28 @@ pub(crate) enum MultipleDeadCodes<'tcx> {
 )]
 pub(crate) struct DeadCodePubInBinaryNote;
 
+#[derive(Diagnostic)]
+#[diag("{$descr} `{$name}` is never used outside of `{$krate}`")]
+#[help("consider removing it, or making it private to `{$krate}`")]
+pub(crate) struct DeadCodePubInDependency {
+    pub descr: &'static str,
+    pub name: String,
+    pub krate: Symbol,
+}
+
+#[derive(Diagnostic)]
+#[diag("not checking for `pub` items of dependencies that no crate uses")]
+#[note(
+    "the items used by {$crates} are unknown, since {$num ->
+        [one] it was
+        *[other] they were
+    } built without `-Z record-extern-item-uses`"
+)]
+pub(crate) struct DeadCodePubInDependenciesUnknownUses {
+    pub crates: DiagSymbolList,
+    pub num: usize,
+}
+
 #[derive(Subdiagnostic)]
 #[note(
     "it is impossible to refer to the {$dead_descr} `{$dead_name}` because it is shadowed by this enum variant with the same name"
@@ -109,6 +109,33 @@ pub(crate) struct GappedRange {
     pub first_range: String, // a printed pattern
 }
 
+#[derive(Diagnostic)]
+#[diag("`{$gap}` is not matched by any of the ranges around it")]
+#[note("these values fall through to the wildcard arm")]
+pub(crate) struct GapBetweenRanges {
+    #[label("this range ends right before `{$gap}`")]
+    pub first_range: Span,
+    #[suggestion(
+        "if this arm should also match these values, add them to its pattern",
+        code = " | {gap}",
+        applicability = "maybe-incorrect",
+        style = "verbose"
+    )]
+    pub suggestion: Span,
+    pub gap: String, // a printed pattern
+    #[subdiagnostic]
+    /// All these ranges start right after `gap`.
+    pub gap_with: Vec<RangeAfterGap>,
+}
+
+#[derive(Subdiagnostic)]
+#[label("this range starts right after `{$gap}`")]
+pub(crate) struct RangeAfterGap {
+    #[primary_span]
+    pub span: Span,
+    pub gap: String, // a printed pattern
+}
+
 #[derive(Diagnostic)]
 #[diag("some variants are not matched explicitly")]
 #[help("ensure that all variants are matched explicitly by adding the suggested match arms")]
@@ -108,6 +108,17 @@ fn lint_non_contiguous_range_endpoints(
     ) {
     }
 
+    /// Lint that there is a gap `gap` between the range `pat` and all the ranges in `gapped_with`
+    /// that is matched by none of the ranges in the match, only by a wildcard.
+    /// The default implementation does nothing.
+    fn lint_gap_between_ranges(
+        &self,
+        _pat: &DeconstructedPat<Self>,
+        _gap: IntRange,
+        _gapped_with: &[&DeconstructedPat<Self>],
+    ) {
+    }
+
     /// Check if we may need to perform additional deref-pattern-specific validation.
     fn match_may_contain_deref_pats(&self) -> bool {
         true
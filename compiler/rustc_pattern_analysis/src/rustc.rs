@@ -1,6 +1,7 @@
 use std::cell::Cell;
+use std::cmp::Ordering;
 use std::fmt;
-use std::iter::once;
+use std::iter::{self, once};
 
 use rustc_abi::{FIRST_VARIANT, FieldIdx, Integer, VariantIdx};
 use rustc_arena::DroplessArena;
@@ -877,6 +878,53 @@ pub fn print_witness_pat(&self, pat: &WitnessPat<'p, 'tcx>) -> String {
             }
         }
     }
+
+    /// Prints the patterns of the arms to add to a match to make it exhaustive, one per witness.
+    ///
+    /// Witnesses are already split at the right nesting level, so they do not overlap, except with
+    /// the wildcards that stand for non-exhaustive or unlistable constructors. These are put last,
+    /// and the other witnesses are ordered as their constructors are declared. Witnesses that print
+    /// the same are only listed once. Each printed pattern is returned along with its witness.
+    pub fn print_missing_arm_pats<'a>(
+        &self,
+        witnesses: &'a [WitnessPat<'p, 'tcx>],
+    ) -> Vec<(&'a WitnessPat<'p, 'tcx>, String)> {
+        let mut witnesses: Vec<_> = witnesses.iter().collect();
+        witnesses.sort_by(|a, b| cmp_in_declaration_order(self.tcx, a, b));
+        let mut arms: Vec<(_, String)> = Vec::with_capacity(witnesses.len());
+        for witness in witnesses {
+            let pat = self.print_witness_pat(witness);
+            if !arms.iter().any(|(_, arm_pat)| *arm_pat == pat) {
+                arms.push((witness, pat));
+            }
+        }
+        arms
+    }
+}
+
+/// Orders witnesses by the declaration order of their constructors, then of their fields, with
+/// wildcards last.
+fn cmp_in_declaration_order(
+    tcx: TyCtxt<'_>,
+    a: &WitnessPat<'_, '_>,
+    b: &WitnessPat<'_, '_>,
+) -> Ordering {
+    let (a_is_wild, b_is_wild) = (would_print_as_wildcard(tcx, a), would_print_as_wildcard(tcx, b));
+    let ctor_order = match (a.ctor(), b.ctor()) {
+        _ if a_is_wild || b_is_wild => a_is_wild.cmp(&b_is_wild),
+        (Variant(a), Variant(b)) => a.cmp(b),
+        (Bool(a), Bool(b)) => a.cmp(b),
+        (IntRange(a), IntRange(b)) => a.lo.cmp(&b.lo),
+        (Slice(a), Slice(b)) => (a.arity(), matches!(a.kind, SliceKind::VarLen(..)))
+            .cmp(&(b.arity(), matches!(b.kind, SliceKind::VarLen(..)))),
+        _ => Ordering::Equal,
+    };
+    ctor_order.then_with(|| {
+        iter::zip(a.iter_fields(), b.iter_fields())
+            .map(|(a, b)| cmp_in_declaration_order(tcx, a, b))
+            .find(|order| order.is_ne())
+            .unwrap_or(Ordering::Equal)
+    })
 }
 
 /// Returns `true` if the given pattern would be printed as a wildcard (`_`).
@@ -1029,6 +1077,45 @@ fn lint_non_contiguous_range_endpoints(
         }
     }
 
+    fn lint_gap_between_ranges(
+        &self,
+        pat: &crate::pat::DeconstructedPat<Self>,
+        gap: IntRange,
+        gapped_with: &[&crate::pat::DeconstructedPat<Self>],
+    ) {
+        let &thir_pat = pat.data();
+        // A single value missed by an exclusive range like `lo..gap` is already reported by
+        // `non_contiguous_range_endpoints`.
+        if gap.is_singleton()
+            && let thir::PatKind::Range(range) = &thir_pat.kind
+            && range.end == rustc_hir::RangeEnd::Excluded
+        {
+            return;
+        }
+        let gap_as_pat = self.print_pat_range(&gap, *pat.ty());
+        self.tcx.emit_node_span_lint(
+            lint::builtin::GAPS_BETWEEN_RANGES,
+            self.match_lint_level,
+            thir_pat.span,
+            diagnostics::GapBetweenRanges {
+                // Point at this range.
+                first_range: thir_pat.span,
+                // Suggest matching the gap in the same arm.
+                suggestion: thir_pat.span.shrink_to_hi(),
+                // That's the gap that only the wildcard matches.
+                gap: gap_as_pat.clone(),
+                // All these ranges start right after the gap.
+                gap_with: gapped_with
+                    .iter()
+                    .map(|pat| diagnostics::RangeAfterGap {
+                        span: pat.data().span,
+                        gap: gap_as_pat.clone(),
+                    })
+                    .collect(),
+            },
+        );
+    }
+
     fn match_may_contain_deref_pats(&self) -> bool {
         self.internal_state.has_lowered_deref_pat.get()
     }
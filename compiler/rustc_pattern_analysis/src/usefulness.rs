@@ -1686,6 +1686,40 @@ fn collect_non_contiguous_range_endpoints<'p, Cx: PatCx>(
     }
 }
 
+/// Collect ranges that leave a gap between them that only a wildcard matches, like `0..=9` and
+/// `15..=20` followed by `_`.
+fn collect_gaps_between_ranges<'p, Cx: PatCx>(
+    cx: &Cx,
+    gap_range: &IntRange,
+    matrix: &Matrix<'p, Cx>,
+) {
+    // Ranges that end right before the gap.
+    let mut before: SmallVec<[_; 1]> = Default::default();
+    // Ranges that start right after the gap.
+    let mut after: SmallVec<[_; 1]> = Default::default();
+    // Look through the column for ranges around the gap.
+    for pat in matrix.heads() {
+        let PatOrWild::Pat(pat) = pat else { continue };
+        let Constructor::IntRange(this_range) = pat.ctor() else { continue };
+        // Gaps between single values are usually deliberate.
+        if this_range.is_singleton() {
+            continue;
+        }
+        if this_range.hi == gap_range.lo {
+            before.push(pat)
+        } else if this_range.lo == gap_range.hi {
+            after.push(pat)
+        }
+    }
+
+    if after.is_empty() {
+        return;
+    }
+    for pat_before in before {
+        cx.lint_gap_between_ranges(pat_before, *gap_range, after.as_slice());
+    }
+}
+
 /// The core of the algorithm.
 ///
 /// This recursively computes witnesses of the non-exhaustiveness of `matrix` (if any). Also tracks
@@ -1774,13 +1808,18 @@ fn compute_exhaustiveness_and_usefulness<'a, 'p, Cx: PatCx>(
         matrix.unspecialize(spec_matrix);
     }
 
-    // Detect singleton gaps between ranges.
+    // Detect gaps between ranges. Gaps that are only matched by a wildcard are reported even when
+    // they span more than one value.
     if missing_ctors.iter().any(|c| matches!(c, Constructor::IntRange(..))) {
+        let has_wildcard = matrix.heads().any(|pat| matches!(pat.ctor(), Constructor::Wildcard));
         for missing in &missing_ctors {
             if let Constructor::IntRange(gap) = missing {
                 if gap.is_singleton() {
                     collect_non_contiguous_range_endpoints(mcx.tycx, gap, matrix);
                 }
+                if has_wildcard {
+                    collect_gaps_between_ranges(mcx.tycx, gap, matrix);
+                }
             }
         }
     }
@@ -17,6 +17,7 @@
 use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
 use rustc_data_structures::stable_hash::{StableHasher, StableOrd};
 use rustc_errors::emitter::HumanReadableErrorType;
+use rustc_errors::lint_baseline::LintBaselineMode;
 use rustc_errors::{ColorConfig, DiagCtxtFlags};
 use rustc_feature::UnstableFeatures;
 use rustc_hashes::Hash64;
@@ -836,6 +837,8 @@ pub enum ErrorOutputType {
         json_rendered: HumanReadableErrorType,
         color_config: ColorConfig,
     },
+    /// A single SARIF 2.1.0 log for the whole compilation, consumed by code scanning tools.
+    Sarif,
 }
 
 #[derive(Clone, Hash, Debug)]
@@ -1436,6 +1439,7 @@ fn default() -> Options {
             debuginfo: DebugInfo::None,
             lint_opts: Vec::new(),
             lint_cap: None,
+            lint_baseline: None,
             describe_lints: false,
             output_types: OutputTypes(BTreeMap::new()),
             search_paths: vec![],
@@ -2130,6 +2134,22 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "Limit on the number of parallel jobs used by linker",
             "<N>",
         ),
+        opt(
+            Unstable,
+            Opt,
+            "",
+            "lint-baseline",
+            "Only report lint occurrences that are not recorded in the given baseline file",
+            "<PATH>",
+        ),
+        opt(
+            Unstable,
+            Flag,
+            "",
+            "update-lint-baseline",
+            "Record all lint occurrences in the `--lint-baseline` file instead of reporting them",
+            "",
+        ),
     ];
     options.extend(verbose_only.into_iter().map(|mut opt| {
         opt.is_verbose_help_only = true;
@@ -2306,6 +2326,7 @@ pub fn parse_error_format(
                 kind: HumanReadableErrorType { short: false, unicode: true },
                 color_config,
             },
+            Some("sarif") => ErrorOutputType::Sarif,
             Some(arg) => {
                 early_dcx.set_error_format(ErrorOutputType::HumanReadable {
                     color_config,
@@ -2313,7 +2334,7 @@ pub fn parse_error_format(
                 });
                 early_dcx.early_fatal(format!(
                     "argument for `--error-format` must be `human`, `human-unicode`, \
-                    `json`, `pretty-json` or `short` (instead was `{arg}`)"
+                    `json`, `pretty-json`, `sarif` or `short` (instead was `{arg}`)"
                 ))
             }
         }
@@ -2374,6 +2395,7 @@ fn check_error_format_stability(
     }
     let format = match format {
         ErrorOutputType::Json { pretty: true, .. } => "pretty-json",
+        ErrorOutputType::Sarif => "sarif",
         ErrorOutputType::HumanReadable { kind, .. } => match kind {
             HumanReadableErrorType { unicode: true, .. } => "human-unicode",
             _ => return,
@@ -2664,6 +2686,23 @@ fn parse_logical_env(
     vars
 }
 
+pub fn parse_lint_baseline(
+    early_dcx: &EarlyDiagCtxt,
+    matches: &getopts::Matches,
+) -> Option<(PathBuf, LintBaselineMode)> {
+    let update = matches.opt_present("update-lint-baseline");
+    match matches.opt_str("lint-baseline") {
+        Some(path) => Some((
+            PathBuf::from(path),
+            if update { LintBaselineMode::Update } else { LintBaselineMode::Check },
+        )),
+        None if update => {
+            early_dcx.early_fatal("`--update-lint-baseline` requires `--lint-baseline`")
+        }
+        None => None,
+    }
+}
+
 // JUSTIFICATION: before wrapper fn is available
 #[allow(rustc::bad_opt_access)]
 pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::Matches) -> Options {
@@ -2926,6 +2965,8 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
 
     let logical_env = parse_logical_env(early_dcx, matches);
 
+    let lint_baseline = parse_lint_baseline(early_dcx, matches);
+
     let sysroot = Sysroot::new(matches.opt_str("sysroot").map(PathBuf::from));
 
     let real_source_base_dir = |suffix: &str, confirm: &str| {
@@ -3006,6 +3047,7 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
         debuginfo,
         lint_opts,
         lint_cap,
+        lint_baseline,
         describe_lints,
         output_types,
         search_paths,
@@ -35,6 +35,7 @@ pub enum PrintKind {
     FileNames,
     HostTuple,
     LinkArgs,
+    LintLevels,
     NativeStaticLibs,
     RelocationModels,
     SplitDebuginfo,
@@ -69,6 +70,7 @@ fn name(self) -> &'static str {
             FileNames => "file-names",
             HostTuple => "host-tuple",
             LinkArgs => "link-args",
+            LintLevels => "lint-levels",
             NativeStaticLibs => "native-static-libs",
             RelocationModels => "relocation-models",
             SplitDebuginfo => "split-debuginfo",
@@ -115,6 +117,7 @@ fn is_stable(self) -> bool {
             BackendHasZstd => false,     // (perma-unstable, for use by compiletest)
             CheckCfg => false,
             CrateRootLintLevels => false,
+            LintLevels => false,
             SupportedCrateTypes => false,
             TargetSpecJson => false,
             TargetSpecJsonSchema => false,
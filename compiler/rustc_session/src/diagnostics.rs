@@ -1,4 +1,6 @@
+use std::io;
 use std::num::{NonZero, ParseIntError};
+use std::path::PathBuf;
 
 use rustc_ast::token;
 use rustc_ast::util::literal::LitError;
@@ -708,6 +710,20 @@ pub(crate) struct FailedToCreateProfiler {
     pub(crate) err: String,
 }
 
+#[derive(Diagnostic)]
+#[diag("failed to read lint baseline `{$path}`: {$err}")]
+pub(crate) struct FailedToReadLintBaseline {
+    pub(crate) path: PathBuf,
+    pub(crate) err: io::Error,
+}
+
+#[derive(Diagnostic)]
+#[diag("failed to write lint baseline `{$path}`: {$err}")]
+pub(crate) struct FailedToWriteLintBaseline {
+    pub(crate) path: PathBuf,
+    pub(crate) err: io::Error,
+}
+
 #[derive(Diagnostic)]
 #[diag("unexpected `--cfg {$cfg}` flag")]
 #[note("config `{$cfg_name}` is only supposed to be controlled by `{$controlled_by}`")]
@@ -8,6 +8,7 @@
 use rustc_data_structures::fx::FxIndexMap;
 use rustc_data_structures::profiling::TimePassesFormat;
 use rustc_data_structures::stable_hash::StableHasher;
+use rustc_errors::lint_baseline::LintBaselineMode;
 use rustc_errors::{ColorConfig, TerminalUrl};
 use rustc_feature::UnstableFeatures;
 use rustc_hashes::Hash64;
@@ -342,6 +343,8 @@ pub struct Options {
         debuginfo: DebugInfo [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED_NO_CRATE_HASH],
         lint_cap: Option<lint::Level> [TRACKED_NO_CRATE_HASH],
+        /// The `--lint-baseline` file, and whether it is checked or updated.
+        lint_baseline: Option<(PathBuf, LintBaselineMode)> [UNTRACKED],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         search_paths: Vec<SearchPath> [UNTRACKED],
@@ -2365,6 +2368,9 @@ pub(crate) fn parse_rust_version(slot: &mut Option<RustcVersion>, v: Option<&str
     binary_dep_depinfo: bool = (false, parse_bool, [TRACKED],
         "include artifacts (sysroot, crate dependencies) used during compilation in dep-info \
         (default: no)"),
+    borrowck_explain: bool = (false, parse_bool, [TRACKED],
+        "explain borrowck errors by showing the chain of outlives constraints and the \
+        control-flow path that keep a loan live (default: no)"),
     box_noalias: bool = (true, parse_bool, [TRACKED],
         "emit noalias metadata for box (default: yes)"),
     #[rustc_lint_opt_deny_field_access("use `Session::branch_protection` instead of this field")]
@@ -2384,8 +2390,15 @@ pub(crate) fn parse_rust_version(slot: &mut Option<RustcVersion>, v: Option<&str
         "the backend to use"),
     codegen_emit_retag: Option<CodegenRetagOptions> = (None, parse_codegen_retag_options, [TRACKED],
         "emit retag function calls in generated code"),
+    codegen_report: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the number of instantiations, MIR statements and LLVM instructions of each \
+        function, and its share of the optimization time, to this file as JSON"),
     codegen_source_order: bool = (false, parse_bool, [UNTRACKED],
         "emit mono items in the order of spans in source files (default: no)"),
+    const_eval_profile: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "output per-function step counts, call counts and allocated bytes of each evaluation \
+        of a constant or static"),
     contract_checks: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "emit runtime checks for contract pre- and post-conditions (default: no)"),
     coverage_options: CoverageOptions = (CoverageOptions::default(), parse_coverage_options, [TRACKED],
@@ -2394,6 +2407,9 @@ pub(crate) fn parse_rust_version(slot: &mut Option<RustcVersion>, v: Option<&str
         "inject the given attribute in the crate"),
     cross_crate_inline_threshold: InliningThreshold = (InliningThreshold::Sometimes(100), parse_inlining_threshold, [TRACKED],
         "threshold to allow cross crate inlining of functions"),
+    dead_code_pub_in_dependencies: Vec<String> = (Vec::new(), parse_comma_list, [TRACKED],
+        "report the `pub` items of these crates that no other crate uses with the \
+        `dead_code_pub_in_dependencies` lint (comma separated list of crate names)"),
     debug_info_type_line_numbers: bool = (false, parse_bool, [TRACKED],
         "emit type and line information for additional data types (default: no)"),
     debuginfo_compression: DebugInfoCompression = (DebugInfoCompression::None, parse_debuginfo_compression, [TRACKED],
@@ -2747,6 +2763,9 @@ pub(crate) fn parse_rust_version(slot: &mut Option<RustcVersion>, v: Option<&str
         "enable queries of the dependency graph for regression testing (default: no)"),
     randomize_layout: bool = (false, parse_bool, [TRACKED],
         "randomize the layout of types (default: no)"),
+    record_extern_item_uses: bool = (false, parse_bool, [TRACKED],
+        "record the items of other crates used by this crate in its metadata, for the \
+        `dead_code_pub_in_dependencies` lint (default: no)"),
     reg_struct_return: bool = (false, parse_bool, [TRACKED] { TARGET_MODIFIER: RegStructReturn },
         "On x86-32 targets, it overrides the default ABI to return small structs in registers.
         It is UNSOUND to link together crates that use different values for this flag!"),
@@ -15,6 +15,8 @@
 use rustc_errors::codes::*;
 use rustc_errors::emitter::{DynEmitter, HumanReadableErrorType, OutputTheme, stderr_destination};
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::lint_baseline::LintBaseline;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::timings::TimingSectionHandler;
 use rustc_errors::{
     Diag, DiagCtxt, DiagCtxtHandle, DiagMessage, Diagnostic, ErrorGuaranteed, FatalAbort,
@@ -502,6 +504,11 @@ pub fn finish_diagnostics(&self) -> Option<ErrorGuaranteed> {
         guar = guar.or(self.check_miri_unleashed_features());
         guar = guar.or(self.dcx().emit_stashed_diagnostics());
         self.dcx().print_error_count();
+        if let Some((path, _)) = &self.opts.lint_baseline
+            && let Err(err) = self.dcx().write_lint_baseline()
+        {
+            self.dcx().emit_err(diagnostics::FailedToWriteLintBaseline { path: path.clone(), err });
+        }
         if self.opts.json_future_incompat {
             self.dcx().emit_future_breakage_report();
         }
@@ -1254,6 +1261,11 @@ fn default_emitter(sopts: &config::Options, source_map: Arc<SourceMap>) -> Box<D
             .track_diagnostics(track_diagnostics)
             .terminal_url(terminal_url),
         ),
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::new(
+            Box::new(io::BufWriter::new(io::stderr())),
+            source_map,
+            if sopts.actually_rustdoc { "rustdoc" } else { "rustc" },
+        )),
     }
 }
 
@@ -1292,6 +1304,15 @@ pub fn build_session(
         dcx = dcx.with_msrv(msrv);
     }
 
+    if let Some((path, mode)) = &sopts.lint_baseline {
+        match LintBaseline::load(path.clone(), *mode) {
+            Ok(lint_baseline) => dcx = dcx.with_lint_baseline(lint_baseline),
+            Err(err) => dcx
+                .handle()
+                .emit_fatal(diagnostics::FailedToReadLintBaseline { path: path.clone(), err }),
+        }
+    }
+
     let host_triple = TargetTuple::from_tuple(config::host_tuple());
     let (host, target_warnings) =
         Target::search(&host_triple, sopts.sysroot.path(), sopts.unstable_opts.unstable_options)
@@ -1797,6 +1818,14 @@ fn mk_emitter(output: ErrorOutputType) -> Box<DynEmitter> {
                 color_config,
             ))
         }
+        config::ErrorOutputType::Sarif => Box::new(
+            SarifEmitter::new(
+                Box::new(io::BufWriter::new(io::stderr())),
+                Some(Arc::new(SourceMap::new(FilePathMapping::empty()))),
+                "rustc",
+            )
+            .skip_empty_log(true),
+        ),
     };
     emitter
 }
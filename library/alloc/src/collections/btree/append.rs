@@ -1,6 +1,9 @@
 use core::alloc::Allocator;
+use core::mem;
 
-use super::node::{self, Root};
+use super::map::MIN_LEN;
+use super::node::ForceResult::*;
+use super::node::{self, NodeRef, Root, SplitResult, marker};
 
 impl<K, V> Root<K, V> {
     /// Pushes all key-value pairs to the end of the tree, incrementing a
@@ -63,4 +66,103 @@ pub(super) fn bulk_push<I, A: Allocator + Clone>(
         }
         self.fix_right_border_of_plentiful();
     }
+
+    /// Moves all key-value pairs of `right` to the end of the tree. Every key
+    /// in `right` must be greater than every key in `self`. If both trees
+    /// respect all `BTreeMap` tree invariants, then so does the combined tree.
+    ///
+    /// Unlike `bulk_push`, this moves whole subtrees of `right` over, in time
+    /// proportional to the height of the trees.
+    pub(super) fn append_tree<A: Allocator + Clone>(&mut self, mut right: Root<K, V>, alloc: A) {
+        if right.height() == 0 && right.len() == 0 {
+            unsafe { right.into_dying().deallocate_and_ascend(alloc) };
+            return;
+        }
+        if self.height() == 0 && self.len() == 0 {
+            let empty = mem::replace(self, right);
+            unsafe { empty.into_dying().deallocate_and_ascend(alloc) };
+            return;
+        }
+
+        // Use the first key-value pair of `right` to separate the trees.
+        let mut emptied_internal_root = false;
+        let Ok(first_kv) = right.borrow_mut().first_leaf_edge().right_kv() else { unreachable!() };
+        let ((key, val), _) = first_kv
+            .forget_node_type()
+            .remove_kv_tracking(|| emptied_internal_root = true, alloc.clone());
+        if emptied_internal_root {
+            right.pop_internal_level(alloc.clone());
+        }
+
+        self.join(key, val, right, alloc);
+    }
+
+    /// Joins the tree, a key-value pair and the tree `right` into one tree,
+    /// where every key in `self` is smaller than `key`, and every key in
+    /// `right` is greater. The shorter tree is hung into the border of the
+    /// taller one, after which that border is fixed up.
+    fn join<A: Allocator + Clone>(&mut self, key: K, val: V, right: Root<K, V>, alloc: A) {
+        if self.height() == right.height() {
+            let mut root = self.push_internal_level(alloc.clone());
+            root.push(key, val, right);
+            let mut root_kv = root.last_kv().consider_for_balancing();
+            if root_kv.can_merge() {
+                root_kv.merge_tracking_parent(alloc.clone());
+                self.fix_top(alloc);
+            } else {
+                // Stealing keeps both children at `MIN_LEN` or above, because
+                // together they hold at least `CAPACITY` elements.
+                let (left_len, right_len) = (root_kv.left_child_len(), root_kv.right_child_len());
+                if left_len < MIN_LEN {
+                    root_kv.bulk_steal_right(MIN_LEN - left_len);
+                } else if right_len < MIN_LEN {
+                    root_kv.bulk_steal_left(MIN_LEN - right_len);
+                }
+            }
+        } else if self.height() > right.height() {
+            let mut node = self.borrow_mut();
+            while node.height() > right.height() + 1 {
+                node = node.last_edge_child();
+            }
+            let Internal(node) = node.force() else { unreachable!() };
+            if let Some(SplitResult { kv, right, .. }) =
+                node.last_edge().insert_recursing(key, val, right, alloc.clone())
+            {
+                self.push_internal_level(alloc.clone()).push(kv.0, kv.1, right);
+            }
+            self.fix_right_border(alloc);
+        } else {
+            let left = mem::replace(self, right);
+            let mut node = self.borrow_mut();
+            while node.height() > left.height() + 1 {
+                node = node.first_edge_child();
+            }
+            let Internal(node) = node.force() else { unreachable!() };
+            let mut first_edge = node.first_edge();
+            // The former first child goes to the right of the new key.
+            let first_child = first_edge.replace_child(left);
+            if let Some(SplitResult { kv, right, .. }) =
+                first_edge.insert_recursing(key, val, first_child, alloc.clone())
+            {
+                self.push_internal_level(alloc.clone()).push(kv.0, kv.1, right);
+            }
+            self.fix_left_border(alloc);
+        }
+    }
+}
+
+impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+    fn last_edge_child(self) -> Self {
+        match self.force() {
+            Internal(internal) => internal.last_edge().descend(),
+            Leaf(_) => unreachable!(),
+        }
+    }
+
+    fn first_edge_child(self) -> Self {
+        match self.force() {
+            Internal(internal) => internal.first_edge().descend(),
+            Leaf(_) => unreachable!(),
+        }
+    }
 }
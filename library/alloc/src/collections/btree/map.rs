@@ -14,9 +14,9 @@
 use super::navigate::{LazyLeafRange, LeafRange};
 use super::node::ForceResult::*;
 use super::node::{self, Handle, NodeRef, Root, marker};
-use super::search::SearchBound;
 use super::search::SearchResult::*;
-use super::set_val::SetValZST;
+use super::search::{SearchBound, check_range_bounds};
+use super::set_val::{IsSetVal, SetValZST};
 use crate::alloc::{Allocator, Global};
 use crate::vec::Vec;
 
@@ -1583,6 +1583,143 @@ pub fn split_off<Q: ?Sized + Ord>(&mut self, key: &Q) -> Self
         }
     }
 
+    /// Removes the elements (key-value pairs) in the specified range from the map, and returns
+    /// them in a new map.
+    ///
+    /// Rather than moving elements one by one, this detaches whole subtrees from the map, so it
+    /// takes O(log(n) + m) time, where m is the number of removed elements, and reuses the nodes
+    /// that held them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_range_removal)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut a: BTreeMap<i32, &str> = [(1, "a"), (2, "b"), (3, "c"), (17, "d"), (41, "e")].into();
+    ///
+    /// let b = a.split_off_range(2..=17);
+    ///
+    /// assert_eq!(a.into_iter().collect::<Vec<_>>(), [(1, "a"), (41, "e")]);
+    /// assert_eq!(b.into_iter().collect::<Vec<_>>(), [(2, "b"), (3, "c"), (17, "d")]);
+    /// ```
+    #[unstable(feature = "btree_range_removal", issue = "none")]
+    pub fn split_off_range<Q: ?Sized, R>(&mut self, range: R) -> Self
+    where
+        Q: Ord,
+        K: Borrow<Q> + Ord,
+        R: RangeBounds<Q>,
+        A: Clone,
+    {
+        let (start, end) = (range.start_bound(), range.end_bound());
+        check_range_bounds(start, end, <V as IsSetVal>::is_set_val());
+
+        let alloc = (*self.alloc).clone();
+        let Some(root) = self.root.as_mut() else {
+            return Self::new_in(alloc);
+        };
+
+        let mut middle = match start {
+            Bound::Unbounded => mem::replace(root, Root::new(alloc.clone())),
+            _ => root.split_off_bound(SearchBound::from_range(start), alloc.clone()),
+        };
+        // The upper end of the range is the lower end of the tail that goes back.
+        let tail_bound = match end {
+            Bound::Included(key) => Some(SearchBound::Excluded(key)),
+            Bound::Excluded(key) => Some(SearchBound::Included(key)),
+            Bound::Unbounded => None,
+        };
+        if let Some(tail_bound) = tail_bound {
+            let tail = middle.split_off_bound(tail_bound, alloc.clone());
+            root.append_tree(tail, alloc);
+        }
+
+        let middle_len = middle.reborrow().calc_length();
+        self.length -= middle_len;
+        BTreeMap {
+            root: Some(middle),
+            length: middle_len,
+            alloc: self.alloc.clone(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Removes the elements (key-value pairs) in the specified range from the map, and returns
+    /// an iterator over them in ascending key order.
+    ///
+    /// Unlike [`extract_if`], the removal happens up front, in one go: dropping the iterator
+    /// drops the remaining removed elements rather than leaving them in the map. See
+    /// [`split_off_range`] for the cost of the removal.
+    ///
+    /// [`extract_if`]: BTreeMap::extract_if
+    /// [`split_off_range`]: BTreeMap::split_off_range
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_range_removal)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map: BTreeMap<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
+    /// let removed: Vec<_> = map.extract_range(2..5).collect();
+    /// assert_eq!(removed, [(2, 20), (3, 30), (4, 40)]);
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [0, 1, 5, 6, 7]);
+    /// ```
+    #[unstable(feature = "btree_range_removal", issue = "none")]
+    pub fn extract_range<Q: ?Sized, R>(&mut self, range: R) -> IntoIter<K, V, A>
+    where
+        Q: Ord,
+        K: Borrow<Q> + Ord,
+        R: RangeBounds<Q>,
+        A: Clone,
+    {
+        self.split_off_range(range).into_iter()
+    }
+
+    /// Removes the elements (key-value pairs) in the specified range from the map, and returns
+    /// how many were removed.
+    ///
+    /// See [`split_off_range`] for the cost of the removal.
+    ///
+    /// [`split_off_range`]: BTreeMap::split_off_range
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_range_removal)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map: BTreeMap<i32, i32> = (0..100).map(|x| (x, x)).collect();
+    /// assert_eq!(map.remove_range(10..), 90);
+    /// assert_eq!(map.len(), 10);
+    /// assert_eq!(map.last_key_value(), Some((&9, &9)));
+    /// ```
+    #[unstable(feature = "btree_range_removal", issue = "none")]
+    pub fn remove_range<Q: ?Sized, R>(&mut self, range: R) -> usize
+    where
+        Q: Ord,
+        K: Borrow<Q> + Ord,
+        R: RangeBounds<Q>,
+        A: Clone,
+    {
+        self.split_off_range(range).len()
+    }
+
     /// Creates an iterator that visits elements (key-value pairs) in the specified range in
     /// ascending key order and uses a closure to determine if an element
     /// should be removed.
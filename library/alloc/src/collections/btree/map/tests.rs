@@ -2428,6 +2428,119 @@ fn test_split_off_large_random_sorted() {
     assert!(right.into_iter().eq(data.into_iter().filter(|x| x.0 >= key)));
 }
 
+#[test]
+fn test_split_off_range_bounds() {
+    let pairs = (0..MIN_INSERTS_HEIGHT_2).map(|i| (i, i));
+    let n = MIN_INSERTS_HEIGHT_2;
+    let cases: [(Bound<usize>, Bound<usize>); 9] = [
+        (Unbounded, Unbounded),
+        (Included(0), Unbounded),
+        (Excluded(0), Unbounded),
+        (Unbounded, Included(n - 1)),
+        (Unbounded, Excluded(n - 1)),
+        (Included(10), Excluded(20)),
+        (Excluded(10), Included(20)),
+        (Included(30), Included(30)),
+        (Included(n), Unbounded),
+    ];
+    for range in cases {
+        let mut map = BTreeMap::from_iter(pairs.clone());
+        let removed = map.split_off_range(range);
+        map.check();
+        removed.check();
+        assert_eq!(map.len() + removed.len(), n);
+        assert!(removed.keys().all(|k| range.contains(k)), "{range:?}");
+        assert!(map.keys().all(|k| !range.contains(k)), "{range:?}");
+    }
+}
+
+#[test]
+fn test_split_off_range_random() {
+    let mut rng = DeterministicRng::new();
+    // Miri is too slow
+    let sizes: &[usize] = if cfg!(miri) { &[0, 1, 12, 100] } else { &[0, 1, 12, 100, 500, 1529] };
+    for &len in sizes {
+        let mut data = Vec::from_iter((0..len).map(|_| (rng.next() % 2000, ())));
+        // Insertion in non-ascending order creates some variation in node length.
+        let map = BTreeMap::from_iter(data.iter().copied());
+        data.sort();
+        data.dedup();
+        for _ in 0..20 {
+            let (a, b) = (rng.next() % 2100, rng.next() % 2100);
+            let (start, end) = (cmp::min(a, b), cmp::max(a, b));
+            let mut map = map.clone();
+            let removed = map.split_off_range(start..end);
+            map.check();
+            removed.check();
+            let keys = data.iter().map(|kv| kv.0);
+            assert!(map.keys().copied().eq(keys.clone().filter(|&k| k < start || k >= end)));
+            assert!(removed.keys().copied().eq(keys.filter(|&k| k >= start && k < end)));
+        }
+    }
+}
+
+#[test]
+fn test_split_off_range_uneven_heights() {
+    // Leave a short tail behind a tall head and the other way around, so that
+    // the remaining trees get joined at different heights.
+    let n = MIN_INSERTS_HEIGHT_2 * 10;
+    for (start, end) in [(n - 20, n - 3), (3, 20), (1, n - 1), (n / 2, n - 1), (1, n / 2)] {
+        let mut map = BTreeMap::from_iter((0..n).map(|i| (i, i)));
+        let removed = map.split_off_range(start..end);
+        map.check();
+        removed.check();
+        assert!(map.keys().copied().eq((0..start).chain(end..n)));
+        assert!(removed.keys().copied().eq(start..end));
+    }
+}
+
+#[test]
+#[should_panic(expected = "range start is greater than range end in BTreeMap")]
+fn test_split_off_range_backwards() {
+    let mut map = BTreeMap::from([(1, 1), (2, 2)]);
+    map.split_off_range(2..1);
+}
+
+#[test]
+fn test_extract_range() {
+    let mut map = BTreeMap::from_iter((0..MIN_INSERTS_HEIGHT_1).map(|i| (i, i.to_string())));
+    let removed: Vec<_> = map.extract_range(3..=5).collect();
+    map.check();
+    assert_eq!(removed, [(3, "3".to_string()), (4, "4".to_string()), (5, "5".to_string())]);
+    assert_eq!(map.len(), MIN_INSERTS_HEIGHT_1 - 3);
+}
+
+#[test]
+fn test_extract_range_drops_unconsumed() {
+    let a = CrashTestDummy::new(0);
+    let b = CrashTestDummy::new(1);
+    let c = CrashTestDummy::new(2);
+    let mut map = BTreeMap::new();
+    map.insert(0, a.spawn(Panic::Never));
+    map.insert(1, b.spawn(Panic::Never));
+    map.insert(2, c.spawn(Panic::Never));
+
+    let mut removed = map.extract_range(1..);
+    drop(removed.next());
+    drop(removed);
+    map.check();
+    assert_eq!(map.len(), 1);
+    assert_eq!(a.dropped(), 0);
+    assert_eq!(b.dropped(), 1);
+    assert_eq!(c.dropped(), 1);
+}
+
+#[test]
+fn test_remove_range() {
+    let mut map = BTreeMap::from_iter((0..MIN_INSERTS_HEIGHT_2).map(|i| (i, i)));
+    assert_eq!(map.remove_range(10..20), 10);
+    map.check();
+    assert_eq!(map.remove_range(10..20), 0);
+    assert_eq!(map.remove_range(..), MIN_INSERTS_HEIGHT_2 - 10);
+    map.check();
+    assert!(map.is_empty());
+}
+
 #[test]
 #[cfg_attr(not(panic = "unwind"), ignore = "test requires unwinding support")]
 fn test_into_iter_drop_leak_height_0() {
@@ -1047,6 +1047,46 @@ fn insert<A: Allocator + Clone>(
     }
 }
 
+impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::Edge> {
+    /// Inserts a new key-value pair and an edge that will go to the right of that new pair
+    /// between this edge and the key-value pair to the right of this edge. This method splits
+    /// the node if there isn't enough room, and tries to insert the split off portion into the
+    /// parent node recursively, until the root is reached.
+    ///
+    /// If the returned result is some `SplitResult`, the `left` field will be the root node.
+    pub(super) fn insert_recursing<A: Allocator + Clone>(
+        self,
+        key: K,
+        val: V,
+        edge: Root<K, V>,
+        alloc: A,
+    ) -> Option<SplitResult<'a, K, V, marker::LeafOrInternal>> {
+        let mut split = self.insert(key, val, edge, alloc.clone())?.forget_node_type();
+        loop {
+            split = match split.left.ascend() {
+                Ok(parent) => parent
+                    .insert(split.kv.0, split.kv.1, split.right, alloc.clone())?
+                    .forget_node_type(),
+                Err(root) => return Some(SplitResult { left: root, ..split }),
+            };
+        }
+    }
+
+    /// Replaces the child this edge links to with `child`, and returns the
+    /// former child as a separate tree.
+    pub(super) fn replace_child(&mut self, child: Root<K, V>) -> Root<K, V> {
+        assert!(child.height == self.node.height - 1);
+        let old_child = unsafe {
+            mem::replace(self.node.edge_area_mut(self.idx), MaybeUninit::new(child.node))
+                .assume_init()
+        };
+        unsafe { self.reborrow_mut() }.correct_parent_link();
+        let mut old_child = NodeRef { height: child.height, node: old_child, _marker: PhantomData };
+        old_child.clear_parent_link();
+        old_child
+    }
+}
+
 impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge> {
     /// Inserts a new key-value pair between the key-value pairs to the right and left of
     /// this edge. This method splits the node if there isn't enough room, and tries to
@@ -19,6 +19,30 @@ pub(super) enum SearchBound<T> {
     AllExcluded,
 }
 
+/// Panics if `start` and `end` do not delimit a valid range, in the same way
+/// for every method of `BTreeMap` and `BTreeSet` taking a range.
+pub(super) fn check_range_bounds<Q: ?Sized + Ord>(start: Bound<&Q>, end: Bound<&Q>, is_set: bool) {
+    match (start, end) {
+        (Bound::Excluded(s), Bound::Excluded(e)) if s == e => {
+            if is_set {
+                panic!("range start and end are equal and excluded in BTreeSet")
+            } else {
+                panic!("range start and end are equal and excluded in BTreeMap")
+            }
+        }
+        (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e))
+            if s > e =>
+        {
+            if is_set {
+                panic!("range start is greater than range end in BTreeSet")
+            } else {
+                panic!("range start is greater than range end in BTreeMap")
+            }
+        }
+        _ => {}
+    }
+}
+
 impl<T> SearchBound<T> {
     pub(super) fn from_range(range_bound: Bound<T>) -> Self {
         match range_bound {
@@ -104,25 +128,7 @@ pub(super) fn search_tree_for_bifurcation<'r, Q: ?Sized, R>(
         // Inlining these variables should be avoided. We assume the bounds reported by `range`
         // remain the same, but an adversarial implementation could change between calls (#81138).
         let (start, end) = (range.start_bound(), range.end_bound());
-        match (start, end) {
-            (Bound::Excluded(s), Bound::Excluded(e)) if s == e => {
-                if is_set {
-                    panic!("range start and end are equal and excluded in BTreeSet")
-                } else {
-                    panic!("range start and end are equal and excluded in BTreeMap")
-                }
-            }
-            (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e))
-                if s > e =>
-            {
-                if is_set {
-                    panic!("range start is greater than range end in BTreeSet")
-                } else {
-                    panic!("range start is greater than range end in BTreeMap")
-                }
-            }
-            _ => {}
-        }
+        check_range_bounds(start, end, is_set);
         let mut lower_bound = SearchBound::from_range(start);
         let mut upper_bound = SearchBound::from_range(end);
         loop {
@@ -237,7 +243,7 @@ unsafe fn find_key_index<Q: ?Sized>(&self, key: &Q, start_index: usize) -> Index
     /// the matching child node, if `self` is an internal node.
     ///
     /// The result is meaningful only if the tree is ordered by key.
-    fn find_lower_bound_index<'r, Q>(
+    pub(super) fn find_lower_bound_index<'r, Q>(
         &self,
         bound: SearchBound<&'r Q>,
     ) -> (usize, SearchBound<&'r Q>)
@@ -1182,6 +1182,102 @@ pub fn split_off<Q: ?Sized + Ord>(&mut self, value: &Q) -> Self
         BTreeSet { map: self.map.split_off(value) }
     }
 
+    /// Removes the elements in the specified range from the set, and returns them in a new set.
+    ///
+    /// Rather than moving elements one by one, this detaches whole subtrees from the set, so it
+    /// takes O(log(n) + m) time, where m is the number of removed elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_range_removal)]
+    /// use std::collections::BTreeSet;
+    ///
+    /// let mut a = BTreeSet::from([1, 2, 3, 17, 41]);
+    ///
+    /// let b = a.split_off_range(2..=17);
+    ///
+    /// assert_eq!(a.into_iter().collect::<Vec<_>>(), [1, 41]);
+    /// assert_eq!(b.into_iter().collect::<Vec<_>>(), [2, 3, 17]);
+    /// ```
+    #[unstable(feature = "btree_range_removal", issue = "none")]
+    pub fn split_off_range<K: ?Sized, R>(&mut self, range: R) -> Self
+    where
+        K: Ord,
+        T: Borrow<K> + Ord,
+        R: RangeBounds<K>,
+        A: Clone,
+    {
+        BTreeSet { map: self.map.split_off_range(range) }
+    }
+
+    /// Removes the elements in the specified range from the set, and returns an iterator over
+    /// them in ascending order.
+    ///
+    /// Dropping the iterator drops the remaining removed elements rather than leaving them in
+    /// the set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_range_removal)]
+    /// use std::collections::BTreeSet;
+    ///
+    /// let mut set: BTreeSet<i32> = (0..8).collect();
+    /// let removed: Vec<_> = set.extract_range(2..5).collect();
+    /// assert_eq!(removed, [2, 3, 4]);
+    /// assert_eq!(set.into_iter().collect::<Vec<_>>(), [0, 1, 5, 6, 7]);
+    /// ```
+    #[unstable(feature = "btree_range_removal", issue = "none")]
+    pub fn extract_range<K: ?Sized, R>(&mut self, range: R) -> IntoIter<T, A>
+    where
+        K: Ord,
+        T: Borrow<K> + Ord,
+        R: RangeBounds<K>,
+        A: Clone,
+    {
+        IntoIter { iter: self.map.extract_range(range) }
+    }
+
+    /// Removes the elements in the specified range from the set, and returns how many were
+    /// removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_range_removal)]
+    /// use std::collections::BTreeSet;
+    ///
+    /// let mut set: BTreeSet<i32> = (0..100).collect();
+    /// assert_eq!(set.remove_range(..90), 90);
+    /// assert_eq!(set.first(), Some(&90));
+    /// ```
+    #[unstable(feature = "btree_range_removal", issue = "none")]
+    pub fn remove_range<K: ?Sized, R>(&mut self, range: R) -> usize
+    where
+        K: Ord,
+        T: Borrow<K> + Ord,
+        R: RangeBounds<K>,
+        A: Clone,
+    {
+        self.map.remove_range(range)
+    }
+
     /// Creates an iterator that visits elements in the specified range in ascending order and
     /// uses a closure to determine if an element should be removed.
     ///
@@ -826,6 +826,34 @@ fn test_split_off_large_random_sorted() {
     assert!(right.into_iter().eq(data.into_iter().filter(|x| *x >= key)));
 }
 
+#[test]
+fn test_split_off_range() {
+    let mut data = rand_data(1529);
+    data.sort();
+    let (start, end) = (data[100], data[1200]);
+
+    let mut set = BTreeSet::from_iter(data.clone());
+    let middle = set.split_off_range(start..=end);
+
+    assert!(set.into_iter().eq(data.iter().copied().filter(|x| *x < start || *x > end)));
+    assert!(middle.into_iter().eq(data.iter().copied().filter(|x| *x >= start && *x <= end)));
+}
+
+#[test]
+fn test_extract_and_remove_range() {
+    let mut set = BTreeSet::from_iter(0..100);
+    assert!(set.extract_range(10..20).eq(10..20));
+    assert_eq!(set.remove_range(50..), 50);
+    assert!(set.into_iter().eq((0..10).chain(20..50)));
+}
+
+#[test]
+#[should_panic(expected = "range start and end are equal and excluded in BTreeSet")]
+fn test_split_off_range_equal_excluded() {
+    let mut set = BTreeSet::from([1, 2, 3]);
+    set.split_off_range((Excluded(2), Excluded(2)));
+}
+
 #[test]
 fn from_array() {
     let set = BTreeSet::from([1, 2, 3, 4]);
@@ -2,8 +2,8 @@
 use core::borrow::Borrow;
 
 use super::node::ForceResult::*;
-use super::node::Root;
-use super::search::SearchResult::*;
+use super::node::{Handle, Root};
+use super::search::SearchBound;
 
 impl<K, V> Root<K, V> {
     /// Calculates the length of both trees that result from splitting up
@@ -36,6 +36,20 @@ pub(super) fn split_off<Q: ?Sized + Ord, A: Allocator + Clone>(
         key: &Q,
         alloc: A,
     ) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        self.split_off_bound(SearchBound::Included(key), alloc)
+    }
+
+    /// Split off a tree with the key-value pairs that are within the given
+    /// lower bound, i.e. those at and after an included key, or after an
+    /// excluded key. Same requirements and guarantees as `split_off`.
+    pub(super) fn split_off_bound<Q: ?Sized + Ord, A: Allocator + Clone>(
+        &mut self,
+        mut bound: SearchBound<&Q>,
+        alloc: A,
+    ) -> Self
     where
         K: Borrow<Q>,
     {
@@ -45,11 +59,10 @@ pub(super) fn split_off<Q: ?Sized + Ord, A: Allocator + Clone>(
         let mut right_node = right_root.borrow_mut();
 
         loop {
-            let mut split_edge = match left_node.search_node(key) {
-                // key is going to the right tree
-                Found(kv) => kv.left_edge(),
-                GoDown(edge) => edge,
-            };
+            let (idx, lower_bound) = left_node.find_lower_bound_index(bound);
+            bound = lower_bound;
+            // Everything from this edge onwards is going to the right tree.
+            let mut split_edge = unsafe { Handle::new_edge(left_node, idx) };
 
             split_edge.move_suffix(&mut right_node);
 
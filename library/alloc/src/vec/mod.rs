@@ -3257,8 +3257,9 @@ pub fn resize_with<F>(&mut self, new_len: usize, f: F)
     /// # drop(unsafe { Box::from_raw(static_ref) });
     /// ```
     #[stable(feature = "vec_leak", since = "1.47.0")]
+    #[rustc_const_unstable(feature = "const_heap", issue = "79597")]
     #[inline]
-    pub fn leak<'a>(self) -> &'a mut [T]
+    pub const fn leak<'a>(self) -> &'a mut [T]
     where
         A: 'a,
     {
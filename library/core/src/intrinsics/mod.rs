@@ -2848,6 +2848,10 @@ pub const fn overflow_checks() -> bool {
 /// Convert the allocation this pointer points to into immutable global memory.
 /// The pointer must point to the beginning of a heap allocation.
 /// This operation only makes sense during compile time. At runtime, it does nothing.
+///
+/// Heap allocations that are only reachable through shared references from the final value of a
+/// constant or static are made global implicitly, so this is only needed for allocations that are
+/// referred to through raw pointers.
 #[rustc_const_unstable(feature = "const_heap", issue = "79597")]
 #[rustc_nounwind]
 #[rustc_intrinsic]
@@ -6,5 +6,7 @@
 pub use crate::os::net::linux_ext::addr::SocketAddrExt;
 #[unstable(feature = "unix_socket_ancillary_data", issue = "76915")]
 pub use crate::os::net::linux_ext::socket::UnixSocketExt;
+#[unstable(feature = "tcp_socket_builder", issue = "none")]
+pub use crate::os::net::linux_ext::tcp::TcpSocketExt;
 #[stable(feature = "tcp_quickack", since = "1.89.0")]
 pub use crate::os::net::linux_ext::tcp::TcpStreamExt;
@@ -4,10 +4,17 @@
 
 #![stable(feature = "metadata_ext", since = "1.1.0")]
 
-use crate::fs::Metadata;
+use crate::alloc::{Layout, alloc_zeroed, dealloc, handle_alloc_error};
+use crate::fs::{File, Metadata, OpenOptions};
+use crate::ops::{Deref, DerefMut};
 #[allow(deprecated)]
 use crate::os::linux::raw;
-use crate::sys::AsInner;
+use crate::ptr::NonNull;
+use crate::sys::{AsInner, AsInnerMut};
+use crate::{fmt, io, slice};
+
+#[cfg(test)]
+mod tests;
 
 /// OS-specific extensions to [`fs::Metadata`].
 ///
@@ -401,3 +408,232 @@ fn st_blocks(&self) -> u64 {
         self.as_inner().as_inner().st_blocks as u64
     }
 }
+
+/// Linux-specific extensions to [`fs::File`].
+///
+/// [`fs::File`]: crate::fs::File
+#[unstable(feature = "linux_file_io_ext", issue = "none")]
+pub impl(self) trait FileExt {
+    /// Allocates or deallocates disk space for the byte range starting at
+    /// `offset` and spanning `len` bytes, as described by `mode`.
+    ///
+    /// This corresponds to the [`fallocate(2)`] system call. Not every mode
+    /// is supported by every filesystem; unsupported modes fail with an
+    /// error of kind [`Unsupported`](io::ErrorKind::Unsupported).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(linux_file_io_ext)]
+    /// use std::fs::File;
+    /// use std::os::linux::fs::{AllocateMode, FileExt};
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let file = File::create("log.bin")?;
+    ///     // Reserve 64 MiB up front so that appends cannot run out of space.
+    ///     file.allocate(0, 64 << 20, AllocateMode::KeepSize)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`fallocate(2)`]: https://man7.org/linux/man-pages/man2/fallocate.2.html
+    fn allocate(&self, offset: u64, len: u64, mode: AllocateMode) -> io::Result<()>;
+
+    /// Announces how the byte range starting at `offset` and spanning `len`
+    /// bytes is going to be accessed, so that the kernel can tune caching
+    /// and readahead. A `len` of zero extends the range to the end of the
+    /// file.
+    ///
+    /// This corresponds to the [`posix_fadvise(2)`] function. The advice is
+    /// only a hint and does not affect the semantics of later I/O.
+    ///
+    /// [`posix_fadvise(2)`]: https://man7.org/linux/man-pages/man2/posix_fadvise.2.html
+    fn advise(&self, offset: u64, len: u64, advice: Advice) -> io::Result<()>;
+}
+
+#[unstable(feature = "linux_file_io_ext", issue = "none")]
+impl FileExt for File {
+    fn allocate(&self, offset: u64, len: u64, mode: AllocateMode) -> io::Result<()> {
+        let mode = match mode {
+            AllocateMode::Extend => 0,
+            AllocateMode::KeepSize => libc::FALLOC_FL_KEEP_SIZE,
+            AllocateMode::PunchHole => libc::FALLOC_FL_PUNCH_HOLE | libc::FALLOC_FL_KEEP_SIZE,
+            AllocateMode::ZeroRange => libc::FALLOC_FL_ZERO_RANGE,
+        };
+        self.as_inner().allocate(offset, len, mode)
+    }
+
+    fn advise(&self, offset: u64, len: u64, advice: Advice) -> io::Result<()> {
+        let advice = match advice {
+            Advice::Normal => libc::POSIX_FADV_NORMAL,
+            Advice::Sequential => libc::POSIX_FADV_SEQUENTIAL,
+            Advice::Random => libc::POSIX_FADV_RANDOM,
+            Advice::NoReuse => libc::POSIX_FADV_NOREUSE,
+            Advice::WillNeed => libc::POSIX_FADV_WILLNEED,
+            Advice::DontNeed => libc::POSIX_FADV_DONTNEED,
+        };
+        self.as_inner().advise(offset, len, advice)
+    }
+}
+
+/// How [`FileExt::allocate`] manipulates the disk space of a byte range.
+#[unstable(feature = "linux_file_io_ext", issue = "none")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AllocateMode {
+    /// Allocates the range, growing the file if the range extends past its
+    /// end. Newly allocated space reads as zeros.
+    Extend,
+    /// Allocates the range without changing the size of the file, so that
+    /// later writes past the end of the file do not need to allocate.
+    KeepSize,
+    /// Deallocates the range. It reads as zeros afterwards, and the size of
+    /// the file is unchanged.
+    PunchHole,
+    /// Zeroes the range, allocating it if needed, typically without writing
+    /// the zeros to the device. The file grows if the range extends past its
+    /// end.
+    ZeroRange,
+}
+
+/// Expected access pattern passed to [`FileExt::advise`].
+#[unstable(feature = "linux_file_io_ext", issue = "none")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Advice {
+    /// No particular access pattern; this is the default.
+    Normal,
+    /// The range will be read sequentially, from lower offsets to higher ones.
+    Sequential,
+    /// The range will be accessed in random order.
+    Random,
+    /// The range will be accessed only once.
+    NoReuse,
+    /// The range will be accessed in the near future.
+    WillNeed,
+    /// The range will not be accessed in the near future.
+    DontNeed,
+}
+
+/// Linux-specific extensions to [`fs::OpenOptions`].
+///
+/// [`fs::OpenOptions`]: crate::fs::OpenOptions
+#[unstable(feature = "linux_file_io_ext", issue = "none")]
+pub impl(self) trait OpenOptionsExt {
+    /// Sets the option to bypass the page cache (`O_DIRECT`).
+    ///
+    /// Direct I/O transfers data straight between the device and the caller's
+    /// buffers. The kernel then requires buffer addresses, lengths and file
+    /// offsets to be aligned, typically to the logical block size of the
+    /// device; misaligned requests fail with an error of kind
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput). [`AlignedBuf`] provides
+    /// suitably aligned buffers.
+    ///
+    /// Not every filesystem supports direct I/O, in which case opening the
+    /// file fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(linux_file_io_ext)]
+    /// use std::fs::OpenOptions;
+    /// use std::os::linux::fs::{AlignedBuf, OpenOptionsExt};
+    /// use std::os::unix::fs::FileExt;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let file = OpenOptions::new().read(true).direct(true).open("data.bin")?;
+    ///     let mut buf = AlignedBuf::zeroed(4096, 4096)?;
+    ///     file.read_exact_at(&mut buf, 0)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    fn direct(&mut self, direct: bool) -> &mut Self;
+}
+
+#[unstable(feature = "linux_file_io_ext", issue = "none")]
+impl OpenOptionsExt for OpenOptions {
+    fn direct(&mut self, direct: bool) -> &mut OpenOptions {
+        self.as_inner_mut().direct(direct);
+        self
+    }
+}
+
+/// A zero-initialized heap buffer whose address is aligned to a given
+/// boundary, as required for [direct I/O](OpenOptionsExt::direct).
+///
+/// The buffer dereferences to a byte slice, so it can be passed to any read or
+/// write method.
+#[unstable(feature = "linux_file_io_ext", issue = "none")]
+pub struct AlignedBuf {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+impl AlignedBuf {
+    /// Allocates a buffer of `len` zero bytes whose address is a multiple of
+    /// `align`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput)
+    /// if `align` is not a power of two, or if `len` rounded up to `align`
+    /// overflows `isize`.
+    #[unstable(feature = "linux_file_io_ext", issue = "none")]
+    pub fn zeroed(len: usize, align: usize) -> io::Result<AlignedBuf> {
+        let layout = Layout::from_size_align(len, align)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let ptr = if len == 0 {
+            layout.dangling_ptr()
+        } else {
+            NonNull::new(unsafe { alloc_zeroed(layout) })
+                .unwrap_or_else(|| handle_alloc_error(layout))
+        };
+        Ok(AlignedBuf { ptr, layout })
+    }
+
+    /// Returns the alignment of the buffer's address.
+    #[unstable(feature = "linux_file_io_ext", issue = "none")]
+    pub fn align(&self) -> usize {
+        self.layout.align()
+    }
+}
+
+#[unstable(feature = "linux_file_io_ext", issue = "none")]
+unsafe impl Send for AlignedBuf {}
+#[unstable(feature = "linux_file_io_ext", issue = "none")]
+unsafe impl Sync for AlignedBuf {}
+
+#[unstable(feature = "linux_file_io_ext", issue = "none")]
+impl Deref for AlignedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.layout.size()) }
+    }
+}
+
+#[unstable(feature = "linux_file_io_ext", issue = "none")]
+impl DerefMut for AlignedBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.layout.size()) }
+    }
+}
+
+#[unstable(feature = "linux_file_io_ext", issue = "none")]
+impl Drop for AlignedBuf {
+    fn drop(&mut self) {
+        if self.layout.size() != 0 {
+            unsafe { dealloc(self.ptr.as_ptr(), self.layout) }
+        }
+    }
+}
+
+#[unstable(feature = "linux_file_io_ext", issue = "none")]
+impl fmt::Debug for AlignedBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlignedBuf")
+            .field("len", &self.layout.size())
+            .field("align", &self.layout.align())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,68 @@
+use super::*;
+use crate::io::{Read, Write};
+
+#[test]
+fn allocate_extends_file() {
+    let dir = crate::test_helpers::tmpdir();
+    let file = File::create(dir.join("allocate.bin")).unwrap();
+
+    match file.allocate(0, 8192, AllocateMode::KeepSize) {
+        Ok(()) => assert_eq!(file.metadata().unwrap().len(), 0),
+        // Not every filesystem a test directory may live on supports it.
+        Err(e) if e.kind() == io::ErrorKind::Unsupported => return,
+        Err(e) => panic!("{e}"),
+    }
+    file.allocate(0, 8192, AllocateMode::Extend).unwrap();
+    assert_eq!(file.metadata().unwrap().len(), 8192);
+}
+
+#[test]
+fn advise_accepts_every_advice() {
+    let dir = crate::test_helpers::tmpdir();
+    let path = dir.join("advise.bin");
+    let mut file = File::create(&path).unwrap();
+    file.write_all(b"some data").unwrap();
+
+    for advice in [
+        Advice::Normal,
+        Advice::Sequential,
+        Advice::Random,
+        Advice::NoReuse,
+        Advice::WillNeed,
+        Advice::DontNeed,
+    ] {
+        file.advise(0, 0, advice).unwrap();
+    }
+
+    let mut contents = String::new();
+    File::open(&path).unwrap().read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "some data");
+}
+
+#[test]
+fn aligned_buf() {
+    for (len, align) in [(0, 4096), (1, 512), (4096, 4096), (10000, 64)] {
+        let mut buf = AlignedBuf::zeroed(len, align).unwrap();
+        assert_eq!(buf.len(), len);
+        assert_eq!(buf.align(), align);
+        assert_eq!(buf.as_ptr().addr() % align, 0);
+        assert!(buf.iter().all(|&b| b == 0));
+        buf.fill(0xaa);
+    }
+
+    let err = AlignedBuf::zeroed(16, 3).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn open_direct() {
+    let dir = crate::test_helpers::tmpdir();
+    let path = dir.join("direct.bin");
+    File::create(&path).unwrap().write_all(&[1; 4096]).unwrap();
+
+    // tmpfs and some other filesystems reject O_DIRECT altogether.
+    let Ok(file) = OpenOptions::new().read(true).direct(true).open(&path) else { return };
+    let mut buf = AlignedBuf::zeroed(4096, 4096).unwrap();
+    crate::os::unix::fs::FileExt::read_exact_at(&file, &mut buf, 0).unwrap();
+    assert!(buf.iter().all(|&b| b == 1));
+}
@@ -2,8 +2,10 @@
 //!
 //! [`std::net`]: crate::net
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::os::unix::net::TcpSocket;
 use crate::sys::AsInner;
-#[cfg(target_os = "linux")]
+#[cfg(any(target_os = "android", target_os = "linux"))]
 use crate::time::Duration;
 use crate::{io, net};
 
@@ -106,6 +108,62 @@
     #[unstable(feature = "tcp_deferaccept", issue = "119639")]
     #[cfg(target_os = "linux")]
     fn deferaccept(&self) -> io::Result<Duration>;
+
+    /// Sets the idle time after which keepalive probes start being sent
+    /// (`TCP_KEEPIDLE`).
+    ///
+    /// Keepalive itself is enabled with `SO_KEEPALIVE`, for instance through
+    /// [`TcpSocket::set_keepalive`]. The duration is rounded down to whole
+    /// seconds, with a minimum of one second.
+    ///
+    /// See [`man 7 tcp`](https://man7.org/linux/man-pages/man7/tcp.7.html)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(tcp_keepalive_tuning)]
+    /// use std::net::TcpStream;
+    /// use std::os::linux::net::TcpStreamExt;
+    /// use std::time::Duration;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///         .expect("Couldn't connect to the server...");
+    /// stream.set_keepalive_idle(Duration::from_secs(60)).expect("set_keepalive_idle call failed");
+    /// ```
+    #[unstable(feature = "tcp_keepalive_tuning", issue = "none")]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn set_keepalive_idle(&self, idle: Duration) -> io::Result<()>;
+
+    /// Gets the value of the `TCP_KEEPIDLE` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpStreamExt::set_keepalive_idle`].
+    #[unstable(feature = "tcp_keepalive_tuning", issue = "none")]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn keepalive_idle(&self) -> io::Result<Duration>;
+
+    /// Sets the time between two keepalive probes (`TCP_KEEPINTVL`).
+    ///
+    /// The duration is rounded down to whole seconds, with a minimum of one
+    /// second.
+    #[unstable(feature = "tcp_keepalive_tuning", issue = "none")]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn set_keepalive_interval(&self, interval: Duration) -> io::Result<()>;
+
+    /// Gets the value of the `TCP_KEEPINTVL` option on this socket.
+    #[unstable(feature = "tcp_keepalive_tuning", issue = "none")]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn keepalive_interval(&self) -> io::Result<Duration>;
+
+    /// Sets the number of unanswered keepalive probes after which the
+    /// connection is dropped (`TCP_KEEPCNT`).
+    #[unstable(feature = "tcp_keepalive_tuning", issue = "none")]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn set_keepalive_retries(&self, retries: u32) -> io::Result<()>;
+
+    /// Gets the value of the `TCP_KEEPCNT` option on this socket.
+    #[unstable(feature = "tcp_keepalive_tuning", issue = "none")]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn keepalive_retries(&self) -> io::Result<u32>;
 }
 
 #[stable(feature = "tcp_quickack", since = "1.89.0")]
@@ -127,4 +185,104 @@ fn set_deferaccept(&self, accept: Duration) -> io::Result<()> {
     fn deferaccept(&self) -> io::Result<Duration> {
         self.as_inner().as_inner().deferaccept()
     }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn set_keepalive_idle(&self, idle: Duration) -> io::Result<()> {
+        self.as_inner().as_inner().set_keepalive_idle(idle)
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn keepalive_idle(&self) -> io::Result<Duration> {
+        self.as_inner().as_inner().keepalive_idle()
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn set_keepalive_interval(&self, interval: Duration) -> io::Result<()> {
+        self.as_inner().as_inner().set_keepalive_interval(interval)
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn keepalive_interval(&self) -> io::Result<Duration> {
+        self.as_inner().as_inner().keepalive_interval()
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn set_keepalive_retries(&self, retries: u32) -> io::Result<()> {
+        self.as_inner().as_inner().set_keepalive_retries(retries)
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn keepalive_retries(&self) -> io::Result<u32> {
+        self.as_inner().as_inner().keepalive_retries()
+    }
+}
+
+/// Os-specific extensions for [`TcpSocket`]
+#[unstable(feature = "tcp_socket_builder", issue = "none")]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub impl(self) trait TcpSocketExt {
+    /// Enables TCP Fast Open (`TCP_FASTOPEN`) on a socket that is about to
+    /// listen, accepting at most `queue_len` pending Fast Open requests.
+    ///
+    /// A `queue_len` of zero disables Fast Open. Whether the server side is
+    /// allowed at all depends on the `net.ipv4.tcp_fastopen` sysctl.
+    ///
+    /// See [`man 7 tcp`](https://man7.org/linux/man-pages/man7/tcp.7.html)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(tcp_socket_builder)]
+    /// use std::os::linux::net::TcpSocketExt;
+    /// use std::os::unix::net::TcpSocket;
+    ///
+    /// let addr = "127.0.0.1:8080".parse().unwrap();
+    /// let socket = TcpSocket::for_addr(&addr).expect("Couldn't create the socket...");
+    /// socket.set_fastopen(16).expect("set_fastopen call failed");
+    /// socket.bind(&addr).expect("bind call failed");
+    /// let listener = socket.listen(128).expect("listen call failed");
+    /// ```
+    fn set_fastopen(&self, queue_len: u32) -> io::Result<()>;
+
+    /// Gets the value of the `TCP_FASTOPEN` option on this socket.
+    fn fastopen(&self) -> io::Result<u32>;
+
+    /// Sets the value of the `TCP_KEEPIDLE` option on this socket.
+    ///
+    /// See [`TcpStreamExt::set_keepalive_idle`].
+    fn set_keepalive_idle(&self, idle: Duration) -> io::Result<()>;
+
+    /// Sets the value of the `TCP_KEEPINTVL` option on this socket.
+    ///
+    /// See [`TcpStreamExt::set_keepalive_interval`].
+    fn set_keepalive_interval(&self, interval: Duration) -> io::Result<()>;
+
+    /// Sets the value of the `TCP_KEEPCNT` option on this socket.
+    ///
+    /// See [`TcpStreamExt::set_keepalive_retries`].
+    fn set_keepalive_retries(&self, retries: u32) -> io::Result<()>;
+}
+
+#[unstable(feature = "tcp_socket_builder", issue = "none")]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl TcpSocketExt for TcpSocket {
+    fn set_fastopen(&self, queue_len: u32) -> io::Result<()> {
+        self.as_inner().set_fastopen(queue_len)
+    }
+
+    fn fastopen(&self) -> io::Result<u32> {
+        self.as_inner().fastopen()
+    }
+
+    fn set_keepalive_idle(&self, idle: Duration) -> io::Result<()> {
+        self.as_inner().set_keepalive_idle(idle)
+    }
+
+    fn set_keepalive_interval(&self, interval: Duration) -> io::Result<()> {
+        self.as_inner().set_keepalive_interval(interval)
+    }
+
+    fn set_keepalive_retries(&self, retries: u32) -> io::Result<()> {
+        self.as_inner().set_keepalive_retries(retries)
+    }
 }
@@ -54,3 +54,43 @@ macro_rules! t {
     stream.set_deferaccept(zero).expect("set_deferaccept failed");
     assert_eq!(stream.deferaccept().unwrap(), zero);
 }
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn keepalive_tuning() {
+    use crate::net::tests::LOCALHOST_IP4;
+    use crate::net::{TcpListener, TcpStream};
+    use crate::os::net::linux_ext::tcp::TcpStreamExt;
+    use crate::time::Duration;
+
+    macro_rules! t {
+        ($e:expr) => {
+            match $e {
+                Ok(t) => t,
+                Err(e) => panic!("received error for `{}`: {}", stringify!($e), e),
+            }
+        };
+    }
+
+    let listener = t!(TcpListener::bind(LOCALHOST_IP4));
+    let addr = t!(listener.local_addr());
+    let stream = t!(TcpStream::connect(&("localhost", addr.port())));
+
+    t!(stream.set_keepalive_idle(Duration::from_secs(30)));
+    assert_eq!(t!(stream.keepalive_idle()), Duration::from_secs(30));
+    t!(stream.set_keepalive_interval(Duration::from_millis(2500)));
+    assert_eq!(t!(stream.keepalive_interval()), Duration::from_secs(2));
+    t!(stream.set_keepalive_retries(4));
+    assert_eq!(t!(stream.keepalive_retries()), 4);
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn fastopen() {
+    use crate::os::net::linux_ext::tcp::TcpSocketExt;
+    use crate::os::unix::net::TcpSocket;
+
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_fastopen(8).unwrap();
+    assert_eq!(socket.fastopen().unwrap(), 8);
+}
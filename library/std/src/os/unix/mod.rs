@@ -93,6 +93,17 @@ mod platform {
 pub mod net;
 pub mod process;
 pub mod raw;
+#[cfg(not(any(
+    target_os = "emscripten",
+    target_os = "espidf",
+    target_os = "horizon",
+    target_os = "l4re",
+    target_os = "nuttx",
+    target_os = "rtems",
+    target_os = "vita",
+    target_os = "vxworks",
+)))]
+pub mod signal;
 pub mod thread;
 pub mod xdg;
 
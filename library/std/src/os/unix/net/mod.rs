@@ -10,6 +10,7 @@
 mod datagram;
 mod listener;
 mod stream;
+mod tcp_socket;
 #[cfg(all(test, not(target_os = "emscripten")))]
 mod tests;
 #[cfg(any(
@@ -37,6 +38,8 @@
 pub use self::listener::*;
 #[stable(feature = "unix_socket", since = "1.10.0")]
 pub use self::stream::*;
+#[unstable(feature = "tcp_socket_builder", issue = "none")]
+pub use self::tcp_socket::*;
 #[cfg(any(
     target_os = "android",
     target_os = "linux",
@@ -0,0 +1,358 @@
+use crate::net::{SocketAddr, TcpListener, TcpStream, UdpSocket};
+use crate::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
+use crate::sys::net::Socket;
+use crate::sys::{AsInner, FromInner};
+use crate::time::Duration;
+use crate::{fmt, io, sys};
+
+/// A TCP socket that has not been turned into a stream or a listener yet.
+///
+/// [`TcpStream::connect`] and [`TcpListener::bind`] create, configure and
+/// connect or bind a socket in a single step, which leaves no room for
+/// options that only take effect before that step, such as buffer sizes,
+/// address reuse, or the backlog of a listener. A `TcpSocket` splits those
+/// steps apart: create it, set options on it, optionally [`bind`] it, and
+/// finally [`listen`] or [`connect`].
+///
+/// Unlike [`TcpListener::bind`], no option is set by default; in particular
+/// `SO_REUSEADDR` is only set if requested with
+/// [`SocketExt::set_reuse_address`].
+///
+/// [`bind`]: TcpSocket::bind
+/// [`listen`]: TcpSocket::listen
+/// [`connect`]: TcpSocket::connect
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(tcp_socket_builder)]
+/// use std::os::unix::net::{SocketExt, TcpSocket};
+///
+/// fn main() -> std::io::Result<()> {
+///     let addr = "127.0.0.1:8080".parse().unwrap();
+///     let socket = TcpSocket::for_addr(&addr)?;
+///     socket.set_reuse_address(true)?;
+///     socket.set_recv_buffer_size(1 << 20)?;
+///     socket.bind(&addr)?;
+///     let listener = socket.listen(1024)?;
+///     for stream in listener.incoming() {
+///         // ...
+///     }
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "tcp_socket_builder", issue = "none")]
+pub struct TcpSocket(Socket);
+
+impl TcpSocket {
+    /// Creates a new IPv4 TCP socket.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn new_v4() -> io::Result<TcpSocket> {
+        Socket::new(libc::AF_INET, libc::SOCK_STREAM).map(TcpSocket)
+    }
+
+    /// Creates a new IPv6 TCP socket.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn new_v6() -> io::Result<TcpSocket> {
+        Socket::new(libc::AF_INET6, libc::SOCK_STREAM).map(TcpSocket)
+    }
+
+    /// Creates a new TCP socket of the address family of `addr`.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn for_addr(addr: &SocketAddr) -> io::Result<TcpSocket> {
+        match addr {
+            SocketAddr::V4(_) => TcpSocket::new_v4(),
+            SocketAddr::V6(_) => TcpSocket::new_v6(),
+        }
+    }
+
+    /// Binds the socket to `addr`.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn bind(&self, addr: &SocketAddr) -> io::Result<()> {
+        self.0.bind(addr)
+    }
+
+    /// Starts listening for incoming connections, with a queue of at most
+    /// `backlog` pending connections.
+    ///
+    /// The kernel may silently cap the backlog; on Linux the limit is set by
+    /// `net.core.somaxconn`. If the socket has not been bound, it is bound to
+    /// an ephemeral port first.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn listen(self, backlog: u32) -> io::Result<TcpListener> {
+        self.0.listen(backlog)?;
+        Ok(TcpListener::from_inner(sys::net::TcpListener::from_inner(self.0)))
+    }
+
+    /// Connects the socket to `addr`.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn connect(self, addr: &SocketAddr) -> io::Result<TcpStream> {
+        self.0.connect(addr)?;
+        Ok(TcpStream::from_inner(sys::net::TcpStream::from_inner(self.0)))
+    }
+
+    /// Connects the socket to `addr`, failing if the connection is not
+    /// established within `timeout`.
+    ///
+    /// See [`TcpStream::connect_timeout`] for details.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn connect_timeout(self, addr: &SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+        self.0.connect_timeout(addr, timeout)?;
+        Ok(TcpStream::from_inner(sys::net::TcpStream::from_inner(self.0)))
+    }
+
+    /// Returns the local address the socket is bound to.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.socket_addr()
+    }
+
+    /// Sets the value of the `TCP_NODELAY` option on this socket.
+    ///
+    /// See [`TcpStream::set_nodelay`] for details.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        self.0.set_nodelay(nodelay)
+    }
+
+    /// Gets the value of the `TCP_NODELAY` option on this socket.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn nodelay(&self) -> io::Result<bool> {
+        self.0.nodelay()
+    }
+
+    /// Sets the value of the `SO_KEEPALIVE` option on this socket.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn set_keepalive(&self, keepalive: bool) -> io::Result<()> {
+        self.0.set_keepalive(keepalive)
+    }
+
+    /// Gets the value of the `SO_KEEPALIVE` option on this socket.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn keepalive(&self) -> io::Result<bool> {
+        self.0.keepalive()
+    }
+
+    /// Sets the value of the `IPV6_V6ONLY` option on this socket.
+    ///
+    /// See [`TcpListener::set_only_v6`] for details. This option can only be
+    /// changed before the socket is bound.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn set_only_v6(&self, only_v6: bool) -> io::Result<()> {
+        unsafe {
+            sys::net::setsockopt(
+                &self.0,
+                libc::IPPROTO_IPV6,
+                libc::IPV6_V6ONLY,
+                only_v6 as libc::c_int,
+            )
+        }
+    }
+
+    /// Gets the value of the `IPV6_V6ONLY` option on this socket.
+    #[unstable(feature = "tcp_socket_builder", issue = "none")]
+    pub fn only_v6(&self) -> io::Result<bool> {
+        let raw: libc::c_int =
+            unsafe { sys::net::getsockopt(&self.0, libc::IPPROTO_IPV6, libc::IPV6_V6ONLY)? };
+        Ok(raw != 0)
+    }
+}
+
+impl AsInner<Socket> for TcpSocket {
+    #[inline]
+    fn as_inner(&self) -> &Socket {
+        &self.0
+    }
+}
+
+#[unstable(feature = "tcp_socket_builder", issue = "none")]
+impl fmt::Debug for TcpSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_struct("TcpSocket");
+        builder.field("fd", &self.as_raw_fd());
+        if let Ok(addr) = self.local_addr() {
+            builder.field("local", &addr);
+        }
+        builder.finish()
+    }
+}
+
+#[unstable(feature = "tcp_socket_builder", issue = "none")]
+impl AsFd for TcpSocket {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+#[unstable(feature = "tcp_socket_builder", issue = "none")]
+impl AsRawFd for TcpSocket {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+#[unstable(feature = "tcp_socket_builder", issue = "none")]
+impl FromRawFd for TcpSocket {
+    #[inline]
+    unsafe fn from_raw_fd(fd: RawFd) -> TcpSocket {
+        TcpSocket(unsafe { Socket::from_raw_fd(fd) })
+    }
+}
+
+#[unstable(feature = "tcp_socket_builder", issue = "none")]
+impl IntoRawFd for TcpSocket {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.0.into_raw_fd()
+    }
+}
+
+#[unstable(feature = "tcp_socket_builder", issue = "none")]
+impl From<OwnedFd> for TcpSocket {
+    #[inline]
+    fn from(fd: OwnedFd) -> TcpSocket {
+        TcpSocket(Socket::from_inner(FromInner::from_inner(fd)))
+    }
+}
+
+#[unstable(feature = "tcp_socket_builder", issue = "none")]
+impl From<TcpSocket> for OwnedFd {
+    #[inline]
+    fn from(socket: TcpSocket) -> OwnedFd {
+        unsafe { OwnedFd::from_raw_fd(socket.into_raw_fd()) }
+    }
+}
+
+/// Unix-specific socket options for [`TcpSocket`] and the socket types of
+/// [`std::net`].
+///
+/// [`std::net`]: crate::net
+#[unstable(feature = "tcp_socket_builder", issue = "none")]
+pub impl(self) trait SocketExt {
+    /// Sets the size of the receive buffer (`SO_RCVBUF`) in bytes.
+    ///
+    /// The kernel may adjust the value: Linux, for instance, doubles it to
+    /// make room for bookkeeping and clamps it to `net.core.rmem_max`.
+    fn set_recv_buffer_size(&self, size: usize) -> io::Result<()>;
+
+    /// Gets the size of the receive buffer (`SO_RCVBUF`) in bytes.
+    fn recv_buffer_size(&self) -> io::Result<usize>;
+
+    /// Sets the size of the send buffer (`SO_SNDBUF`) in bytes.
+    ///
+    /// The kernel may adjust the value, as for
+    /// [`set_recv_buffer_size`](SocketExt::set_recv_buffer_size).
+    fn set_send_buffer_size(&self, size: usize) -> io::Result<()>;
+
+    /// Gets the size of the send buffer (`SO_SNDBUF`) in bytes.
+    fn send_buffer_size(&self) -> io::Result<usize>;
+
+    /// Sets the value of the `SO_REUSEADDR` option, which allows binding to
+    /// an address that still has connections lingering in `TIME_WAIT`.
+    fn set_reuse_address(&self, reuse: bool) -> io::Result<()>;
+
+    /// Gets the value of the `SO_REUSEADDR` option.
+    fn reuse_address(&self) -> io::Result<bool>;
+
+    /// Sets the value of the `SO_REUSEPORT` option, which allows several
+    /// sockets to bind to the same address and port.
+    ///
+    /// On Linux, incoming connections or datagrams are then balanced between
+    /// those sockets. It must be set on every socket before it is bound.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_vendor = "apple",
+    ))]
+    fn set_reuse_port(&self, reuse: bool) -> io::Result<()>;
+
+    /// Gets the value of the `SO_REUSEPORT` option.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_vendor = "apple",
+    ))]
+    fn reuse_port(&self) -> io::Result<bool>;
+}
+
+macro_rules! impl_socket_ext {
+    ($($t:ty => |$s:ident| $socket:expr;)*) => {$(
+        #[unstable(feature = "tcp_socket_builder", issue = "none")]
+        impl SocketExt for $t {
+            fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+                let $s = self;
+                $socket.set_recv_buffer_size(size)
+            }
+
+            fn recv_buffer_size(&self) -> io::Result<usize> {
+                let $s = self;
+                $socket.recv_buffer_size()
+            }
+
+            fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+                let $s = self;
+                $socket.set_send_buffer_size(size)
+            }
+
+            fn send_buffer_size(&self) -> io::Result<usize> {
+                let $s = self;
+                $socket.send_buffer_size()
+            }
+
+            fn set_reuse_address(&self, reuse: bool) -> io::Result<()> {
+                let $s = self;
+                $socket.set_reuse_address(reuse)
+            }
+
+            fn reuse_address(&self) -> io::Result<bool> {
+                let $s = self;
+                $socket.reuse_address()
+            }
+
+            #[cfg(any(
+                target_os = "android",
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd",
+                target_vendor = "apple",
+            ))]
+            fn set_reuse_port(&self, reuse: bool) -> io::Result<()> {
+                let $s = self;
+                $socket.set_reuse_port(reuse)
+            }
+
+            #[cfg(any(
+                target_os = "android",
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd",
+                target_vendor = "apple",
+            ))]
+            fn reuse_port(&self) -> io::Result<bool> {
+                let $s = self;
+                $socket.reuse_port()
+            }
+        }
+    )*};
+}
+
+impl_socket_ext! {
+    TcpSocket => |s| s.0;
+    TcpStream => |s| s.as_inner().socket();
+    TcpListener => |s| s.as_inner().socket();
+    UdpSocket => |s| s.as_inner().socket();
+}
@@ -826,3 +826,68 @@ fn test_send_vectored_with_ancillary_unix_datagram() {
         unreachable!("must be ScmRights");
     }
 }
+
+#[test]
+fn tcp_socket_listen_and_connect() {
+    use crate::net::{Ipv4Addr, SocketAddr as InetAddr};
+
+    let addr = InetAddr::from((Ipv4Addr::LOCALHOST, 0));
+    let socket = or_panic!(TcpSocket::for_addr(&addr));
+    or_panic!(socket.set_reuse_address(true));
+    assert!(or_panic!(socket.reuse_address()));
+    or_panic!(socket.bind(&addr));
+    let local = or_panic!(socket.local_addr());
+    assert_ne!(local.port(), 0);
+    let listener = or_panic!(socket.listen(16));
+    assert_eq!(or_panic!(listener.local_addr()), local);
+
+    let client = or_panic!(TcpSocket::new_v4());
+    or_panic!(client.set_nodelay(true));
+    or_panic!(client.set_keepalive(true));
+    let mut stream = or_panic!(client.connect(&local));
+    assert!(or_panic!(stream.nodelay()));
+
+    let (mut accepted, _) = or_panic!(listener.accept());
+    or_panic!(stream.write_all(b"hello"));
+    let mut buf = [0; 5];
+    or_panic!(accepted.read_exact(&mut buf));
+    assert_eq!(&buf, b"hello");
+}
+
+#[test]
+fn socket_buffer_sizes() {
+    use crate::net::UdpSocket;
+
+    let socket = or_panic!(UdpSocket::bind("127.0.0.1:0"));
+    or_panic!(socket.set_recv_buffer_size(64 * 1024));
+    // The kernel is free to round the value, Linux for instance doubles it.
+    assert!(or_panic!(socket.recv_buffer_size()) >= 32 * 1024);
+    or_panic!(socket.set_send_buffer_size(64 * 1024));
+    assert!(or_panic!(socket.send_buffer_size()) >= 32 * 1024);
+}
+
+#[test]
+#[cfg(any(
+    target_os = "android",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_vendor = "apple",
+))]
+fn tcp_socket_reuse_port() {
+    use crate::net::{Ipv4Addr, SocketAddr as InetAddr};
+
+    let first = or_panic!(TcpSocket::new_v4());
+    or_panic!(first.set_reuse_port(true));
+    assert!(or_panic!(first.reuse_port()));
+    or_panic!(first.bind(&InetAddr::from((Ipv4Addr::LOCALHOST, 0))));
+    let addr = or_panic!(first.local_addr());
+    let _first = or_panic!(first.listen(16));
+
+    let second = or_panic!(TcpSocket::new_v4());
+    or_panic!(second.set_reuse_port(true));
+    or_panic!(second.bind(&addr));
+    let _second = or_panic!(second.listen(16));
+}
@@ -0,0 +1,224 @@
+//! Safe subscription to Unix signals.
+//!
+//! Installing a signal handler by hand is notoriously hard to get right: the
+//! handler may run at any point of any thread, so it may only call a small set
+//! of async-signal-safe functions. This module takes care of that part and
+//! hands the signals back to regular code through a [`Signals`] subscription,
+//! which can be polled, waited on with a timeout, registered with an event
+//! loop through its file descriptor, or forwarded into an [`mpmc`] channel.
+//!
+//! While at least one subscription to a signal exists, the default action of
+//! that signal (for example terminating the process on `SIGINT`) is replaced.
+//! The previous disposition is restored when the last subscription to the
+//! signal is dropped.
+//!
+//! # Child processes
+//!
+//! Subscriptions are not inherited by child processes. Signal handlers are
+//! reset to their default action by `exec`, and the file descriptors backing a
+//! subscription are close-on-exec. A signal delivered to a child between
+//! `fork` and `exec` (for example while running a
+//! [`pre_exec`](super::process::CommandExt::pre_exec) closure) is ignored by
+//! the handler rather than reported to the parent's subscriptions.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! #![feature(unix_signal_subscription)]
+//! use std::os::unix::signal::{Signal, Signals};
+//! use std::time::Duration;
+//!
+//! fn main() -> std::io::Result<()> {
+//!     let signals = Signals::new(&[Signal::INT, Signal::TERM])?;
+//!     loop {
+//!         // do some work...
+//!         if let Some(signal) = signals.recv_timeout(Duration::from_millis(100))? {
+//!             println!("received {signal:?}, shutting down");
+//!             return Ok(());
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`mpmc`]: crate::sync::mpmc
+
+#![unstable(feature = "unix_signal_subscription", issue = "none")]
+
+use crate::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+use crate::sync::mpmc;
+use crate::time::{Duration, Instant};
+use crate::{fmt, io, sys, thread};
+
+#[cfg(test)]
+mod tests;
+
+/// A Unix signal number.
+///
+/// The associated constants cover the signals most programs care about; any
+/// other signal supported by the platform can be built with
+/// [`Signal::from_raw`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Signal(libc::c_int);
+
+impl Signal {
+    /// Hangup detected on the controlling terminal, or reload request.
+    pub const HUP: Signal = Signal(libc::SIGHUP);
+    /// Interrupt from the keyboard, usually `Ctrl-C`.
+    pub const INT: Signal = Signal(libc::SIGINT);
+    /// Quit from the keyboard.
+    pub const QUIT: Signal = Signal(libc::SIGQUIT);
+    /// Termination request.
+    pub const TERM: Signal = Signal(libc::SIGTERM);
+    /// User-defined signal 1.
+    pub const USR1: Signal = Signal(libc::SIGUSR1);
+    /// User-defined signal 2.
+    pub const USR2: Signal = Signal(libc::SIGUSR2);
+    /// A child process stopped or terminated.
+    pub const CHLD: Signal = Signal(libc::SIGCHLD);
+    /// Write to a pipe with no readers.
+    ///
+    /// Note that std ignores this signal at startup by default, so that writes
+    /// to broken pipes fail with an error instead.
+    pub const PIPE: Signal = Signal(libc::SIGPIPE);
+    /// Timer set by `alarm` expired.
+    pub const ALRM: Signal = Signal(libc::SIGALRM);
+    /// The controlling terminal was resized.
+    pub const WINCH: Signal = Signal(libc::SIGWINCH);
+
+    /// Creates a `Signal` from a raw signal number.
+    ///
+    /// The number is not checked here; [`Signals::new`] rejects signals that
+    /// cannot be subscribed to.
+    pub const fn from_raw(signum: libc::c_int) -> Signal {
+        Signal(signum)
+    }
+
+    /// Returns the raw signal number.
+    pub const fn as_raw(self) -> libc::c_int {
+        self.0
+    }
+}
+
+impl fmt::Debug for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.0 {
+            libc::SIGHUP => "SIGHUP",
+            libc::SIGINT => "SIGINT",
+            libc::SIGQUIT => "SIGQUIT",
+            libc::SIGTERM => "SIGTERM",
+            libc::SIGUSR1 => "SIGUSR1",
+            libc::SIGUSR2 => "SIGUSR2",
+            libc::SIGCHLD => "SIGCHLD",
+            libc::SIGPIPE => "SIGPIPE",
+            libc::SIGALRM => "SIGALRM",
+            libc::SIGWINCH => "SIGWINCH",
+            signum => return f.debug_tuple("Signal").field(&signum).finish(),
+        };
+        f.write_str(name)
+    }
+}
+
+/// A subscription to a set of signals.
+///
+/// Signals delivered to the process while the subscription is alive are
+/// queued and can be retrieved with [`try_recv`], [`recv`] or
+/// [`recv_timeout`]. As with the kernel's own bookkeeping, repeated deliveries
+/// of the same signal may be coalesced if they are not received in time.
+///
+/// Several subscriptions may coexist, including to the same signal; each of
+/// them receives every delivery.
+///
+/// The file descriptor returned by [`as_fd`](AsFd::as_fd) becomes readable
+/// whenever a signal is pending, which allows waiting for signals from an
+/// event loop. Only use it for readiness notifications: reading from it
+/// directly consumes pending signals.
+///
+/// [`try_recv`]: Signals::try_recv
+/// [`recv`]: Signals::recv
+/// [`recv_timeout`]: Signals::recv_timeout
+pub struct Signals {
+    inner: sys::signal::Subscription,
+}
+
+impl Signals {
+    /// Subscribes to `signals`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput)
+    /// for signals that cannot be caught (`SIGKILL`, `SIGSTOP`), for
+    /// synchronous faults (`SIGSEGV`, `SIGBUS`, `SIGILL`, `SIGFPE`), and for
+    /// signal numbers that are out of range.
+    ///
+    /// Returns an error of kind [`QuotaExceeded`](io::ErrorKind::QuotaExceeded)
+    /// if the process already has too many live subscriptions.
+    pub fn new(signals: &[Signal]) -> io::Result<Signals> {
+        let raw: Vec<libc::c_int> = signals.iter().map(|s| s.0).collect();
+        Ok(Signals { inner: sys::signal::Subscription::new(&raw)? })
+    }
+
+    /// Returns a pending signal, if any, without blocking.
+    pub fn try_recv(&self) -> io::Result<Option<Signal>> {
+        Ok(self.inner.try_recv()?.map(Signal))
+    }
+
+    /// Blocks the current thread until a signal is delivered.
+    pub fn recv(&self) -> io::Result<Signal> {
+        let signal = self.inner.recv_deadline(None)?;
+        Ok(Signal(signal.expect("waiting without a deadline cannot time out")))
+    }
+
+    /// Blocks the current thread until a signal is delivered or `timeout`
+    /// elapses, in which case `None` is returned.
+    pub fn recv_timeout(&self, timeout: Duration) -> io::Result<Option<Signal>> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.recv_deadline(deadline),
+            None => self.recv().map(Some),
+        }
+    }
+
+    /// Blocks the current thread until a signal is delivered or `deadline` is
+    /// reached, in which case `None` is returned.
+    pub fn recv_deadline(&self, deadline: Instant) -> io::Result<Option<Signal>> {
+        Ok(self.inner.recv_deadline(Some(deadline))?.map(Signal))
+    }
+
+    /// Moves the subscription onto a new thread that sends every received
+    /// signal into `sender`.
+    ///
+    /// The thread exits once sending fails because every receiver has been
+    /// dropped; since it only notices this when a signal arrives, the
+    /// subscription may outlive the receivers until then. The returned handle
+    /// yields the error that stopped the thread, if any.
+    pub fn forward(
+        self,
+        sender: mpmc::Sender<Signal>,
+    ) -> io::Result<thread::JoinHandle<io::Result<()>>> {
+        thread::Builder::new().name("signal forwarder".into()).spawn(move || {
+            loop {
+                let signal = self.recv()?;
+                if sender.send(signal).is_err() {
+                    return Ok(());
+                }
+            }
+        })
+    }
+}
+
+impl AsFd for Signals {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+impl AsRawFd for Signals {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_fd().as_raw_fd()
+    }
+}
+
+impl fmt::Debug for Signals {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signals").field("fd", &self.as_raw_fd()).finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,61 @@
+use super::*;
+use crate::io::ErrorKind;
+
+// These tests raise real signals in the test process. Each test uses a
+// distinct signal so that concurrently running tests do not steal each
+// other's deliveries.
+
+#[test]
+#[cfg_attr(miri, ignore)] // Miri does not support signal handlers
+fn recv_raised_signal() {
+    let signals = Signals::new(&[Signal::USR1]).unwrap();
+    assert_eq!(signals.try_recv().unwrap(), None);
+
+    assert_eq!(unsafe { libc::raise(libc::SIGUSR1) }, 0);
+    assert_eq!(signals.recv_timeout(Duration::from_secs(10)).unwrap(), Some(Signal::USR1));
+    assert_eq!(signals.try_recv().unwrap(), None);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // Miri does not support signal handlers
+fn every_subscription_receives() {
+    let a = Signals::new(&[Signal::USR2]).unwrap();
+    let b = Signals::new(&[Signal::USR2, Signal::HUP]).unwrap();
+
+    assert_eq!(unsafe { libc::raise(libc::SIGUSR2) }, 0);
+    assert_eq!(a.recv().unwrap(), Signal::USR2);
+    assert_eq!(b.recv().unwrap(), Signal::USR2);
+
+    // Dropping one subscription must keep the handler installed for the other.
+    drop(a);
+    assert_eq!(unsafe { libc::raise(libc::SIGUSR2) }, 0);
+    assert_eq!(b.recv().unwrap(), Signal::USR2);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // Miri does not support signal handlers
+fn recv_timeout_expires() {
+    let signals = Signals::new(&[Signal::WINCH]).unwrap();
+    let start = Instant::now();
+    assert_eq!(signals.recv_timeout(Duration::from_millis(50)).unwrap(), None);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // Miri does not support signal handlers
+fn forward_into_channel() {
+    let signals = Signals::new(&[Signal::ALRM]).unwrap();
+    let (tx, rx) = mpmc::channel();
+    let _forwarder = signals.forward(tx).unwrap();
+
+    assert_eq!(unsafe { libc::raise(libc::SIGALRM) }, 0);
+    assert_eq!(rx.recv_timeout(Duration::from_secs(10)).unwrap(), Signal::ALRM);
+}
+
+#[test]
+fn uncatchable_signals_are_rejected() {
+    for signal in [libc::SIGKILL, libc::SIGSTOP, libc::SIGSEGV, 0, -1, 1000] {
+        let err = Signals::new(&[Signal::from_raw(signal)]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput, "{signal}");
+    }
+}
@@ -168,6 +168,7 @@
 use crate::num::NonZero;
 use crate::path::Path;
 use crate::sys::{AsInner, AsInnerMut, FromInner, IntoInner, process as imp};
+use crate::time::{Duration, Instant};
 use crate::{fmt, format_args_nl, fs, str};
 
 /// Representation of a running or exited child process.
@@ -1140,6 +1141,42 @@ pub fn output(&mut self) -> io::Result<Output> {
         Ok(Output { status: ExitStatus(status), stdout, stderr })
     }
 
+    /// Executes the command as a child process like [`output`], but kills the
+    /// child if it has not exited after `timeout`.
+    ///
+    /// Stdin, stdout and stderr are set up as for [`output`].
+    ///
+    /// # Errors
+    ///
+    /// If the child does not exit in time, it is killed and reaped, and an
+    /// error of kind [`TimedOut`](io::ErrorKind::TimedOut) is returned. The
+    /// output collected so far is discarded. Otherwise, errors are reported as
+    /// for [`output`].
+    ///
+    /// Only the child itself is killed: if it spawned processes of its own
+    /// that keep running, they are left alone.
+    ///
+    /// [`output`]: Command::output
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(child_wait_timeout)]
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// match Command::new("sleep").arg("60").output_with_timeout(Duration::from_secs(1)) {
+    ///     Ok(output) => println!("status: {}", output.status),
+    ///     Err(e) if e.kind() == std::io::ErrorKind::TimedOut => println!("killed"),
+    ///     Err(e) => println!("failed to run: {e}"),
+    /// }
+    /// ```
+    #[unstable(feature = "child_wait_timeout", issue = "none")]
+    pub fn output_with_timeout(&mut self, timeout: Duration) -> io::Result<Output> {
+        let (status, stdout, stderr) = imp::output_with_timeout(&mut self.inner, timeout)?;
+        Ok(Output { status: ExitStatus(status), stdout, stderr })
+    }
+
     /// Executes a command as a child process, waiting for it to finish and
     /// collecting its status.
     ///
@@ -2435,6 +2472,58 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         Ok(self.handle.try_wait()?.map(ExitStatus))
     }
 
+    /// Waits for the child to exit for at most `timeout`.
+    ///
+    /// If the child exits in time, `Ok(Some(status))` is returned, and the
+    /// child is reaped as by [`wait`]. If the timeout elapses first,
+    /// `Ok(None)` is returned and the child keeps running; it can then be
+    /// waited on again, or [killed](Child::kill).
+    ///
+    /// The stdin handle to the child process, if any, will be closed before
+    /// waiting, as with [`wait`].
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On Linux, this waits on a pidfd for the child, opening one if the child
+    /// was not spawned with one. Elsewhere on Unix, and on Linux kernels
+    /// without pidfd support, the calling thread sleeps until a `SIGCHLD` is
+    /// delivered, briefly catching that signal while it waits. On Windows,
+    /// this waits on the process handle.
+    ///
+    /// [`wait`]: Child::wait
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(child_wait_timeout)]
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// let mut child = Command::new("sleep").arg("60").spawn()?;
+    /// if child.wait_timeout(Duration::from_secs(1))?.is_none() {
+    ///     child.kill()?;
+    ///     child.wait()?;
+    /// }
+    /// # std::io::Result::Ok(())
+    /// ```
+    #[unstable(feature = "child_wait_timeout", issue = "none")]
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.wait_deadline(deadline),
+            None => self.wait().map(Some),
+        }
+    }
+
+    /// Waits for the child to exit until `deadline` is reached.
+    ///
+    /// This behaves like [`wait_timeout`](Child::wait_timeout), with the timeout
+    /// expressed as a point in time.
+    #[unstable(feature = "child_wait_timeout", issue = "none")]
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        drop(self.stdin.take());
+        Ok(self.handle.wait_deadline(deadline)?.map(ExitStatus))
+    }
+
     /// Simultaneously waits for the child to exit and collect all remaining
     /// output on the stdout/stderr handles, returning an `Output`
     /// instance.
@@ -667,3 +667,60 @@ fn terminate_exited_process() {
     assert!(p.kill().is_ok());
     assert!(p.kill().is_ok());
 }
+
+#[test]
+#[cfg_attr(
+    any(target_os = "vxworks", all(target_vendor = "apple", not(target_os = "macos"))),
+    ignore = "no shell available"
+)]
+fn test_wait_timeout() {
+    use crate::time::{Duration, Instant};
+
+    let mut sleeper = if cfg!(target_os = "windows") {
+        Command::new("cmd").args(&["/C", "ping -n 60 127.0.0.1 > NUL"]).spawn().unwrap()
+    } else {
+        shell_cmd().arg("-c").arg("sleep 60").spawn().unwrap()
+    };
+    let start = Instant::now();
+    assert!(sleeper.wait_timeout(Duration::from_millis(100)).unwrap().is_none());
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    sleeper.kill().unwrap();
+    assert!(sleeper.wait_timeout(Duration::from_secs(60)).unwrap().is_some());
+
+    let mut prog = if cfg!(target_os = "windows") {
+        Command::new("cmd").args(&["/C", "exit 1"]).spawn().unwrap()
+    } else {
+        shell_cmd().arg("-c").arg("false").spawn().unwrap()
+    };
+    let status = prog.wait_timeout(Duration::from_secs(60)).unwrap().unwrap();
+    assert_eq!(status.code(), Some(1));
+    assert_eq!(prog.wait().unwrap().code(), Some(1));
+}
+
+#[test]
+#[cfg_attr(
+    any(target_os = "vxworks", all(target_vendor = "apple", not(target_os = "macos"))),
+    ignore = "no shell available"
+)]
+fn test_output_with_timeout() {
+    use crate::time::Duration;
+
+    let output = if cfg!(target_os = "windows") {
+        Command::new("cmd").args(&["/C", "echo hello"]).output_with_timeout(Duration::from_secs(60))
+    } else {
+        shell_cmd().arg("-c").arg("echo hello").output_with_timeout(Duration::from_secs(60))
+    }
+    .unwrap();
+    assert!(output.status.success());
+    assert!(str::from_utf8(&output.stdout).unwrap().starts_with("hello"));
+
+    let err = if cfg!(target_os = "windows") {
+        Command::new("cmd")
+            .args(&["/C", "ping -n 60 127.0.0.1 > NUL"])
+            .output_with_timeout(Duration::from_millis(100))
+    } else {
+        shell_cmd().arg("-c").arg("exec sleep 60").output_with_timeout(Duration::from_millis(100))
+    }
+    .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+}
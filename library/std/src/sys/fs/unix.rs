@@ -514,6 +514,8 @@ pub struct OpenOptions {
     // system-specific
     custom_flags: i32,
     mode: mode_t,
+    #[cfg(target_os = "linux")]
+    direct: bool,
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -1292,6 +1294,8 @@ pub fn new() -> OpenOptions {
             // system-specific
             custom_flags: 0,
             mode: 0o666,
+            #[cfg(target_os = "linux")]
+            direct: false,
         }
     }
 
@@ -1321,6 +1325,10 @@ pub fn custom_flags(&mut self, flags: i32) {
     pub fn mode(&mut self, mode: u32) {
         self.mode = mode as mode_t;
     }
+    #[cfg(target_os = "linux")]
+    pub fn direct(&mut self, direct: bool) {
+        self.direct = direct;
+    }
 
     fn get_access_mode(&self) -> io::Result<c_int> {
         match (self.read, self.write, self.append) {
@@ -1376,22 +1384,44 @@ fn get_creation_mode(&self) -> io::Result<c_int> {
             (_, _, true) => libc::O_CREAT | libc::O_EXCL,
         })
     }
+
+    #[cfg(target_os = "linux")]
+    fn get_io_mode(&self) -> c_int {
+        if self.direct { libc::O_DIRECT } else { 0 }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn get_io_mode(&self) -> c_int {
+        0
+    }
 }
 
 impl fmt::Debug for OpenOptions {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let OpenOptions { read, write, append, truncate, create, create_new, custom_flags, mode } =
-            self;
-        f.debug_struct("OpenOptions")
-            .field("read", read)
+        let OpenOptions {
+            read,
+            write,
+            append,
+            truncate,
+            create,
+            create_new,
+            custom_flags,
+            mode,
+            #[cfg(target_os = "linux")]
+            direct,
+        } = self;
+        let mut f = f.debug_struct("OpenOptions");
+        f.field("read", read)
             .field("write", write)
             .field("append", append)
             .field("truncate", truncate)
             .field("create", create)
             .field("create_new", create_new)
             .field("custom_flags", custom_flags)
-            .field("mode", &Mode(*mode))
-            .finish()
+            .field("mode", &Mode(*mode));
+        #[cfg(target_os = "linux")]
+        f.field("direct", direct);
+        f.finish()
     }
 }
 
@@ -1404,6 +1434,7 @@ pub fn open_c(path: &CStr, opts: &OpenOptions) -> io::Result<File> {
         let flags = libc::O_CLOEXEC
             | opts.get_access_mode()?
             | opts.get_creation_mode()?
+            | opts.get_io_mode()
             | (opts.custom_flags as c_int & !libc::O_ACCMODE);
         // The third argument of `open64` is documented to have type `mode_t`. On
         // some platforms (like macOS, where `open64` is actually `open`), `mode_t` is `u16`.
@@ -1635,6 +1666,38 @@ pub fn truncate(&self, size: u64) -> io::Result<()> {
         cvt_r(|| unsafe { ftruncate64(self.as_raw_fd(), size) }).map(drop)
     }
 
+    #[cfg(target_os = "linux")]
+    pub fn allocate(&self, offset: u64, len: u64, mode: c_int) -> io::Result<()> {
+        #[cfg(not(target_env = "gnu"))]
+        use libc::fallocate as fallocate64;
+        #[cfg(target_env = "gnu")]
+        use libc::fallocate64;
+
+        let offset: off64_t =
+            offset.try_into().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let len: off64_t =
+            len.try_into().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        cvt_r(|| unsafe { fallocate64(self.as_raw_fd(), mode, offset, len) }).map(drop)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn advise(&self, offset: u64, len: u64, advice: c_int) -> io::Result<()> {
+        #[cfg(not(target_env = "gnu"))]
+        use libc::posix_fadvise as posix_fadvise64;
+        #[cfg(target_env = "gnu")]
+        use libc::posix_fadvise64;
+
+        let offset: off64_t =
+            offset.try_into().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let len: off64_t =
+            len.try_into().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        // `posix_fadvise` returns the error number instead of setting `errno`.
+        match unsafe { posix_fadvise64(self.as_raw_fd(), offset, len, advice) } {
+            0 => Ok(()),
+            err => Err(io::Error::from_raw_os_error(err)),
+        }
+    }
+
     pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.0.read(buf)
     }
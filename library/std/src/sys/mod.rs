@@ -23,6 +23,7 @@
 pub mod platform_version;
 pub mod process;
 pub mod random;
+pub mod signal;
 pub mod stdio;
 pub mod sync;
 pub mod thread;
@@ -23,7 +23,7 @@
 
 pub(super) use libc as netc;
 
-use super::{socket_addr_from_c, socket_addr_to_c};
+use super::{socket_addr_from_c, socket_addr_to_c, sockname};
 pub use crate::sys::{cvt, cvt_r};
 
 #[expect(non_camel_case_types)]
@@ -476,6 +476,126 @@ pub fn nodelay(&self) -> io::Result<bool> {
         Ok(raw != 0)
     }
 
+    pub fn bind(&self, addr: &SocketAddr) -> io::Result<()> {
+        let (addr, len) = socket_addr_to_c(addr);
+        cvt(unsafe { libc::bind(self.as_raw_fd(), addr.as_ptr(), len as _) })?;
+        Ok(())
+    }
+
+    pub fn listen(&self, backlog: u32) -> io::Result<()> {
+        let backlog = cmp::min(backlog, c_int::MAX as u32) as c_int;
+        cvt(unsafe { libc::listen(self.as_raw_fd(), backlog) })?;
+        Ok(())
+    }
+
+    pub fn socket_addr(&self) -> io::Result<SocketAddr> {
+        unsafe { sockname(|buf, len| libc::getsockname(self.as_raw_fd(), buf, len)) }
+    }
+
+    pub fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        let size = cmp::min(size, c_int::MAX as usize) as c_int;
+        unsafe { setsockopt(self, libc::SOL_SOCKET, libc::SO_RCVBUF, size) }
+    }
+
+    pub fn recv_buffer_size(&self) -> io::Result<usize> {
+        let raw: c_int = unsafe { getsockopt(self, libc::SOL_SOCKET, libc::SO_RCVBUF)? };
+        Ok(raw as usize)
+    }
+
+    pub fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        let size = cmp::min(size, c_int::MAX as usize) as c_int;
+        unsafe { setsockopt(self, libc::SOL_SOCKET, libc::SO_SNDBUF, size) }
+    }
+
+    pub fn send_buffer_size(&self) -> io::Result<usize> {
+        let raw: c_int = unsafe { getsockopt(self, libc::SOL_SOCKET, libc::SO_SNDBUF)? };
+        Ok(raw as usize)
+    }
+
+    pub fn set_reuse_address(&self, reuse: bool) -> io::Result<()> {
+        unsafe { setsockopt(self, libc::SOL_SOCKET, libc::SO_REUSEADDR, reuse as c_int) }
+    }
+
+    pub fn reuse_address(&self) -> io::Result<bool> {
+        let raw: c_int = unsafe { getsockopt(self, libc::SOL_SOCKET, libc::SO_REUSEADDR)? };
+        Ok(raw != 0)
+    }
+
+    #[cfg(any(
+        target_os = "android",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_vendor = "apple",
+    ))]
+    pub fn set_reuse_port(&self, reuse: bool) -> io::Result<()> {
+        unsafe { setsockopt(self, libc::SOL_SOCKET, libc::SO_REUSEPORT, reuse as c_int) }
+    }
+
+    #[cfg(any(
+        target_os = "android",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_vendor = "apple",
+    ))]
+    pub fn reuse_port(&self) -> io::Result<bool> {
+        let raw: c_int = unsafe { getsockopt(self, libc::SOL_SOCKET, libc::SO_REUSEPORT)? };
+        Ok(raw != 0)
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn set_keepalive_idle(&self, idle: Duration) -> io::Result<()> {
+        let secs = cmp::min(cmp::max(idle.as_secs(), 1), c_int::MAX as u64) as c_int;
+        unsafe { setsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPIDLE, secs) }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn keepalive_idle(&self) -> io::Result<Duration> {
+        let raw: c_int = unsafe { getsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPIDLE)? };
+        Ok(Duration::from_secs(raw as u64))
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn set_keepalive_interval(&self, interval: Duration) -> io::Result<()> {
+        let secs = cmp::min(cmp::max(interval.as_secs(), 1), c_int::MAX as u64) as c_int;
+        unsafe { setsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPINTVL, secs) }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn keepalive_interval(&self) -> io::Result<Duration> {
+        let raw: c_int = unsafe { getsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPINTVL)? };
+        Ok(Duration::from_secs(raw as u64))
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn set_keepalive_retries(&self, retries: u32) -> io::Result<()> {
+        let retries = cmp::min(retries, c_int::MAX as u32) as c_int;
+        unsafe { setsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPCNT, retries) }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn keepalive_retries(&self) -> io::Result<u32> {
+        let raw: c_int = unsafe { getsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPCNT)? };
+        Ok(raw as u32)
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn set_fastopen(&self, queue_len: u32) -> io::Result<()> {
+        let queue_len = cmp::min(queue_len, c_int::MAX as u32) as c_int;
+        unsafe { setsockopt(self, libc::IPPROTO_TCP, libc::TCP_FASTOPEN, queue_len) }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn fastopen(&self) -> io::Result<u32> {
+        let raw: c_int = unsafe { getsockopt(self, libc::IPPROTO_TCP, libc::TCP_FASTOPEN)? };
+        Ok(raw as u32)
+    }
+
     #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
     pub fn set_quickack(&self, quickack: bool) -> io::Result<()> {
         unsafe { setsockopt(self, libc::IPPROTO_TCP, libc::TCP_QUICKACK, quickack as c_int) }
@@ -38,6 +38,37 @@
     getpid, read_output,
 };
 
+/// Waits until `try_wait` reports an exit status or `deadline` is reached, by
+/// polling with an exponential backoff. Only used on targets without signals,
+/// where there is no `SIGCHLD` to block on while waiting for a child process.
+#[cfg(any(
+    target_os = "emscripten",
+    target_os = "l4re",
+    target_os = "motor",
+    target_os = "rtems",
+    target_os = "vxworks"
+))]
+pub(crate) fn wait_deadline_by_polling<S>(
+    deadline: crate::time::Instant,
+    mut try_wait: impl FnMut() -> crate::io::Result<Option<S>>,
+) -> crate::io::Result<Option<S>> {
+    use crate::thread;
+    use crate::time::{Duration, Instant};
+
+    let mut delay = Duration::from_millis(1);
+    loop {
+        if let Some(status) = try_wait()? {
+            return Ok(Some(status));
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(None);
+        }
+        thread::sleep(delay.min(deadline - now));
+        delay = (delay * 2).min(Duration::from_millis(50));
+    }
+}
+
 #[cfg(any(
     all(
         target_family = "unix",
@@ -76,6 +107,95 @@ pub fn output(cmd: &mut Command) -> crate::io::Result<(ExitStatus, Vec<u8>, Vec<
     Ok((status, stdout, stderr))
 }
 
+#[cfg(any(
+    all(
+        target_family = "unix",
+        not(any(
+            target_os = "espidf",
+            target_os = "horizon",
+            target_os = "vita",
+            target_os = "nuttx"
+        ))
+    ),
+    target_os = "windows",
+    target_os = "motor"
+))]
+pub fn output_with_timeout(
+    cmd: &mut Command,
+    timeout: crate::time::Duration,
+) -> crate::io::Result<(ExitStatus, Vec<u8>, Vec<u8>)> {
+    use crate::time::Instant;
+    use crate::{io, thread};
+
+    let deadline = Instant::now().checked_add(timeout);
+    let (mut process, mut pipes) = cmd.spawn(Stdio::MakePipe, false)?;
+
+    drop(pipes.stdin.take());
+    // Collect the output on another thread, so that a child blocked on a full
+    // pipe keeps making progress while we wait for it to exit.
+    let (out, err) = (pipes.stdout.take(), pipes.stderr.take());
+    let reader = thread::Builder::new().spawn(move || {
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        match (out, err) {
+            (None, None) => {}
+            (Some(out), None) => {
+                out.read_to_end(&mut stdout)?;
+            }
+            (None, Some(err)) => {
+                err.read_to_end(&mut stderr)?;
+            }
+            (Some(out), Some(err)) => read_output(out, &mut stdout, err, &mut stderr)?,
+        }
+        io::Result::Ok((stdout, stderr))
+    });
+    let reader = match reader {
+        Ok(reader) => reader,
+        Err(e) => {
+            let _ = process.kill();
+            let _ = process.wait();
+            return Err(e);
+        }
+    };
+
+    let status = match deadline {
+        Some(deadline) => process.wait_deadline(deadline)?,
+        None => Some(process.wait()?),
+    };
+    let Some(status) = status else {
+        process.kill()?;
+        process.wait()?;
+        // The reader thread is not joined: descendants of the child may still
+        // hold the pipes open. It exits on its own once they are closed.
+        return Err(io::const_error!(
+            io::ErrorKind::TimedOut,
+            "process did not exit before the timeout elapsed",
+        ));
+    };
+
+    let (stdout, stderr) = reader.join().unwrap_or_else(|e| crate::panic::resume_unwind(e))?;
+    Ok((status, stdout, stderr))
+}
+
+#[cfg(not(any(
+    all(
+        target_family = "unix",
+        not(any(
+            target_os = "espidf",
+            target_os = "horizon",
+            target_os = "vita",
+            target_os = "nuttx"
+        ))
+    ),
+    target_os = "windows",
+    target_os = "motor"
+)))]
+pub fn output_with_timeout(
+    _cmd: &mut Command,
+    _timeout: crate::time::Duration,
+) -> crate::io::Result<(ExitStatus, Vec<u8>, Vec<u8>)> {
+    Err(crate::io::Error::UNSUPPORTED_PLATFORM)
+}
+
 #[cfg(not(any(
     all(
         target_family = "unix",
@@ -4,6 +4,7 @@
 use crate::num::NonZero;
 use crate::process::StdioPipes;
 use crate::sys::pal::fuchsia::*;
+use crate::time::Instant;
 use crate::{fmt, io, mem, ptr};
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -227,6 +228,21 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         }
         Ok(Some(ExitStatus(proc_info.return_code)))
     }
+
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let remaining = i64::try_from(remaining.as_nanos()).unwrap_or(i64::MAX);
+        let zx_deadline = zx_clock_get_monotonic().saturating_add(remaining);
+
+        let status = unsafe {
+            zx_object_wait_one(self.handle.raw(), ZX_TASK_TERMINATED, zx_deadline, ptr::null_mut())
+        };
+        match status {
+            0 => self.try_wait(),
+            x if x == ZX_ERR_TIMED_OUT => Ok(None),
+            _ => Err(io::Error::from_raw_os_error(status)),
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
@@ -3,6 +3,7 @@
 use crate::os::fd::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 use crate::sys::fd::FileDesc;
 use crate::sys::{AsInner, FromInner, IntoInner, cvt};
+use crate::time::Instant;
 
 #[cfg(test)]
 mod tests;
@@ -15,6 +16,13 @@ pub fn kill(&self) -> io::Result<()> {
         self.send_signal(libc::SIGKILL)
     }
 
+    /// Opens a pidfd referring to the process `pid`, which must be a child of
+    /// this process that has not been reaped yet for the pidfd to be waitable.
+    pub fn open(pid: libc::pid_t) -> io::Result<PidFd> {
+        let pidfd = cvt(unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) })?;
+        Ok(unsafe { PidFd::from_raw_fd(pidfd as RawFd) })
+    }
+
     #[cfg(any(test, target_env = "gnu", target_env = "musl"))]
     pub fn current_process() -> io::Result<PidFd> {
         let pid = crate::process::id();
@@ -121,6 +129,28 @@ pub fn wait(&self) -> io::Result<ExitStatus> {
     pub fn try_wait(&self) -> io::Result<Option<ExitStatus>> {
         self.waitid(libc::WEXITED | libc::WNOHANG)
     }
+
+    pub fn wait_deadline(&self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        loop {
+            if let Some(exit_status) = self.try_wait()? {
+                return Ok(Some(exit_status));
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            // A pidfd becomes readable once the process terminates. Round the
+            // timeout up so that we do not spin on a zero timeout.
+            let timeout = remaining.as_nanos().div_ceil(1_000_000).min(libc::c_int::MAX as u128);
+            let mut pollfd =
+                libc::pollfd { fd: self.0.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+            match cvt(unsafe { libc::poll(&mut pollfd, 1, timeout as libc::c_int) }) {
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
 }
 
 impl AsInner<FileDesc> for PidFd {
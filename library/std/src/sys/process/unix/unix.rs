@@ -17,6 +17,9 @@
 use crate::sys::cvt;
 #[cfg(target_os = "linux")]
 use crate::sys::process::PidFd;
+#[cfg(any(target_os = "emscripten", target_os = "l4re", target_os = "rtems"))]
+use crate::sys::process::wait_deadline_by_polling;
+use crate::time::Instant;
 use crate::{fmt, mem, sys};
 
 cfg_select! {
@@ -1060,6 +1063,53 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
             Ok(Some(ExitStatus::new(status)))
         }
     }
+
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        if let Some(status) = self.status {
+            return Ok(Some(status));
+        }
+        // Children spawned without `create_pidfd` can still be waited on
+        // through a pidfd, as long as they have not been reaped yet (which
+        // `self.status` being unset guarantees).
+        #[cfg(target_os = "linux")]
+        {
+            let opened;
+            let pid_fd = match self.pidfd.as_ref() {
+                Some(pid_fd) => Some(pid_fd),
+                None => {
+                    opened = PidFd::open(self.pid).ok();
+                    opened.as_ref()
+                }
+            };
+            if let Some(pid_fd) = pid_fd {
+                let status = pid_fd.wait_deadline(deadline)?;
+                if let Some(status) = status {
+                    self.status = Some(status)
+                }
+                return Ok(status);
+            }
+        }
+        // Without a pidfd, sleep until a SIGCHLD arrives. The subscription is
+        // made before checking on the child, so that an exit in between is not
+        // missed. SIGCHLD is ignored by default, so temporarily catching it does
+        // not change the behavior of the program.
+        #[cfg(not(any(target_os = "emscripten", target_os = "l4re", target_os = "rtems")))]
+        {
+            let sigchld = sys::signal::Subscription::new(&[libc::SIGCHLD])?;
+            loop {
+                if let Some(status) = self.try_wait()? {
+                    return Ok(Some(status));
+                }
+                // Any child exiting wakes us up, so check again even on success.
+                if sigchld.recv_deadline(Some(deadline))?.is_none() {
+                    return self.try_wait();
+                }
+            }
+        }
+        // These targets have no signals to wait for a SIGCHLD with.
+        #[cfg(any(target_os = "emscripten", target_os = "l4re", target_os = "rtems"))]
+        return wait_deadline_by_polling(deadline, || self.try_wait());
+    }
 }
 
 /// Unix exit statuses
@@ -5,7 +5,9 @@
 use crate::io::{self, ErrorKind};
 use crate::num::NonZero;
 use crate::process::StdioPipes;
+use crate::sys::process::wait_deadline_by_polling;
 use crate::sys::{cvt, thread};
+use crate::time::Instant;
 use crate::{fmt, sys};
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -200,6 +202,10 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
             Ok(Some(ExitStatus::new(status)))
         }
     }
+
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        wait_deadline_by_polling(deadline, || self.try_wait())
+    }
 }
 
 /// Unix exit statuses
@@ -6,6 +6,7 @@
 use crate::process::StdioPipes;
 use crate::sys::fs::File;
 use crate::sys::unsupported;
+use crate::time::Instant;
 use crate::{fmt, io};
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -290,6 +291,10 @@ pub fn wait(&mut self) -> io::Result<ExitStatus> {
     pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         self.0
     }
+
+    pub fn wait_deadline(&mut self, _deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        self.0
+    }
 }
 
 pub struct CommandArgs<'a> {
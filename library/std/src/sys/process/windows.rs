@@ -24,6 +24,7 @@
 use crate::sys::pal::api::{self, WinError, utf16};
 use crate::sys::pal::{ensure_no_nuls, fill_utf16_buf};
 use crate::sys::{IntoInner, cvt, path, stdio};
+use crate::time::Instant;
 use crate::{cmp, env, fmt, ptr};
 
 mod child_pipe;
@@ -757,6 +758,21 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         }
     }
 
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            // Round up so that we do not return before the deadline, and stay
+            // below `INFINITE`, waiting again if the deadline is further away.
+            let timeout = remaining.as_nanos().div_ceil(1_000_000).min((c::INFINITE - 1) as u128);
+            match unsafe { c::WaitForSingleObject(self.handle.as_raw_handle(), timeout as u32) } {
+                c::WAIT_OBJECT_0 => return self.try_wait(),
+                c::WAIT_TIMEOUT if remaining.is_zero() => return Ok(None),
+                c::WAIT_TIMEOUT => {}
+                _ => return Err(io::Error::last_os_error()),
+            }
+        }
+    }
+
     pub fn handle(&self) -> &Handle {
         &self.handle
     }
@@ -0,0 +1,21 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+cfg_select! {
+    all(
+        unix,
+        not(any(
+            target_os = "emscripten",
+            target_os = "espidf",
+            target_os = "horizon",
+            target_os = "l4re",
+            target_os = "nuttx",
+            target_os = "rtems",
+            target_os = "vita",
+            target_os = "vxworks",
+        )),
+    ) => {
+        mod unix;
+        pub use unix::*;
+    }
+    _ => {}
+}
@@ -0,0 +1,250 @@
+//! Self-pipe based signal delivery.
+//!
+//! Every subscription owns a non-blocking pipe. The process-wide handler
+//! installed for a subscribed signal only writes the signal number into the
+//! pipe of each subscription interested in it, which is async-signal-safe;
+//! everything else happens on the receiving side, outside of signal context.
+
+use crate::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd};
+use crate::sync::Mutex;
+use crate::sync::atomic::{Atomic, AtomicI32, AtomicU64, AtomicUsize, Ordering};
+use crate::sys::fd::FileDesc;
+use crate::sys::io::{errno, set_errno};
+use crate::sys::pal::cvt;
+use crate::time::{Duration, Instant};
+use crate::{hint, io, mem, ptr};
+
+/// Signal numbers must be strictly smaller than this to be subscribed to, so
+/// that a set of signals fits in a `u64`.
+const SIGNAL_LIMIT: libc::c_int = 64;
+
+/// Maximum number of live subscriptions in the process.
+const MAX_SUBSCRIPTIONS: usize = 32;
+
+/// A subscription as seen by the signal handler.
+struct Slot {
+    /// Write end of the subscription's pipe, or -1 if the slot is free.
+    write_fd: Atomic<libc::c_int>,
+    /// Bitset of the signals this subscription is interested in.
+    mask: Atomic<u64>,
+}
+
+static SLOTS: [Slot; MAX_SUBSCRIPTIONS] =
+    [const { Slot { write_fd: AtomicI32::new(-1), mask: AtomicU64::new(0) } }; MAX_SUBSCRIPTIONS];
+
+/// Number of signal handlers currently executing. Used to make sure no
+/// handler still holds a file descriptor that is about to be closed.
+static HANDLERS_RUNNING: Atomic<usize> = AtomicUsize::new(0);
+
+/// Process that created the subscriptions. A child created by `fork` shares
+/// the pipes with its parent until it calls `exec`, so handlers running in it
+/// must not forward anything.
+static OWNER_PID: Atomic<libc::pid_t> = AtomicI32::new(0);
+
+/// State that is only ever touched outside of signal context.
+struct Registry {
+    /// Number of subscriptions interested in each signal.
+    subscribers: [usize; SIGNAL_LIMIT as usize],
+    /// Disposition in place before we installed our handler, restored once the
+    /// last subscriber of the signal goes away.
+    previous: [Option<libc::sigaction>; SIGNAL_LIMIT as usize],
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    subscribers: [0; SIGNAL_LIMIT as usize],
+    previous: [const { None }; SIGNAL_LIMIT as usize],
+});
+
+extern "C" fn handler(signum: libc::c_int) {
+    // `write` may clobber errno, which the interrupted code could be about to
+    // inspect.
+    let saved_errno = errno();
+    HANDLERS_RUNNING.fetch_add(1, Ordering::SeqCst);
+
+    if unsafe { libc::getpid() } == OWNER_PID.load(Ordering::Relaxed) {
+        let bit = 1u64 << signum;
+        let byte = signum as u8;
+        for slot in &SLOTS {
+            if slot.mask.load(Ordering::SeqCst) & bit == 0 {
+                continue;
+            }
+            let fd = slot.write_fd.load(Ordering::SeqCst);
+            if fd >= 0 {
+                // If the pipe is full the signal is already pending for this
+                // subscription, and dropping it mirrors how the kernel
+                // coalesces standard signals.
+                unsafe { libc::write(fd, (&raw const byte).cast(), 1) };
+            }
+        }
+    }
+
+    HANDLERS_RUNNING.fetch_sub(1, Ordering::SeqCst);
+    set_errno(saved_errno);
+}
+
+fn validate(signum: libc::c_int) -> io::Result<()> {
+    if signum <= 0 || signum >= SIGNAL_LIMIT {
+        return Err(io::const_error!(io::ErrorKind::InvalidInput, "invalid signal number"));
+    }
+    match signum {
+        // These cannot be caught at all.
+        libc::SIGKILL | libc::SIGSTOP => {
+            Err(io::const_error!(io::ErrorKind::InvalidInput, "signal cannot be caught"))
+        }
+        // These are synchronous faults: returning from a handler re-executes the
+        // faulting instruction. std also relies on SIGSEGV and SIGBUS to report
+        // stack overflows.
+        libc::SIGSEGV | libc::SIGBUS | libc::SIGILL | libc::SIGFPE => Err(io::const_error!(
+            io::ErrorKind::InvalidInput,
+            "synchronous fault signals cannot be subscribed to",
+        )),
+        _ => Ok(()),
+    }
+}
+
+unsafe fn install(signum: libc::c_int) -> io::Result<libc::sigaction> {
+    unsafe {
+        let mut action: libc::sigaction = mem::zeroed();
+        action.sa_sigaction = handler as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        action.sa_flags = libc::SA_RESTART;
+        cvt(libc::sigemptyset(&mut action.sa_mask))?;
+        let mut previous: libc::sigaction = mem::zeroed();
+        cvt(libc::sigaction(signum, &action, &mut previous))?;
+        Ok(previous)
+    }
+}
+
+impl Registry {
+    fn subscribe(&mut self, signum: libc::c_int) -> io::Result<()> {
+        let i = signum as usize;
+        if self.subscribers[i] == 0 {
+            self.previous[i] = Some(unsafe { install(signum)? });
+        }
+        self.subscribers[i] += 1;
+        Ok(())
+    }
+
+    fn unsubscribe(&mut self, signum: libc::c_int) {
+        let i = signum as usize;
+        self.subscribers[i] -= 1;
+        if self.subscribers[i] == 0 {
+            if let Some(previous) = self.previous[i].take() {
+                unsafe { libc::sigaction(signum, &previous, ptr::null_mut()) };
+            }
+        }
+    }
+}
+
+fn signals_in(mask: u64) -> impl Iterator<Item = libc::c_int> {
+    (1..SIGNAL_LIMIT).filter(move |&signum| mask & (1 << signum) != 0)
+}
+
+pub struct Subscription {
+    read: FileDesc,
+    slot: usize,
+    mask: u64,
+}
+
+impl Subscription {
+    pub fn new(signals: &[libc::c_int]) -> io::Result<Subscription> {
+        let mut mask = 0u64;
+        for &signum in signals {
+            validate(signum)?;
+            mask |= 1 << signum;
+        }
+
+        let (read, write) = crate::sys::pipe::pipe()?;
+        read.set_nonblocking(true)?;
+        write.set_nonblocking(true)?;
+
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(slot) = SLOTS.iter().position(|s| s.write_fd.load(Ordering::SeqCst) < 0) else {
+            return Err(io::const_error!(
+                io::ErrorKind::QuotaExceeded,
+                "too many signal subscriptions in this process",
+            ));
+        };
+
+        for signum in signals_in(mask) {
+            if let Err(e) = registry.subscribe(signum) {
+                for subscribed in signals_in(mask).take_while(|&s| s != signum) {
+                    registry.unsubscribe(subscribed);
+                }
+                return Err(e);
+            }
+        }
+
+        OWNER_PID.store(unsafe { libc::getpid() }, Ordering::Relaxed);
+        SLOTS[slot].write_fd.store(write.into_raw_fd(), Ordering::SeqCst);
+        SLOTS[slot].mask.store(mask, Ordering::SeqCst);
+        drop(registry);
+
+        Ok(Subscription { read, slot, mask })
+    }
+
+    pub fn try_recv(&self) -> io::Result<Option<libc::c_int>> {
+        let mut byte = 0u8;
+        match self.read.read(crate::slice::from_mut(&mut byte)) {
+            Ok(0) => Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+            Ok(_) => Ok(Some(libc::c_int::from(byte))),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Blocks until a signal arrives or `deadline` passes. `None` waits forever.
+    pub fn recv_deadline(&self, deadline: Option<Instant>) -> io::Result<Option<libc::c_int>> {
+        loop {
+            if let Some(signum) = self.try_recv()? {
+                return Ok(Some(signum));
+            }
+
+            let timeout = match deadline {
+                None => -1,
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining == Duration::ZERO {
+                        return Ok(None);
+                    }
+                    // Round up so that we never spin on a zero timeout.
+                    remaining.as_nanos().div_ceil(1_000_000).min(libc::c_int::MAX as u128)
+                        as libc::c_int
+                }
+            };
+
+            let mut pollfd =
+                libc::pollfd { fd: self.read.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+            match cvt(unsafe { libc::poll(&mut pollfd, 1, timeout) }) {
+                Ok(_) => {}
+                // Most likely one of our own handlers; the pipe is checked again
+                // at the top of the loop.
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub fn as_fd(&self) -> BorrowedFd<'_> {
+        self.read.as_fd()
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+
+        let slot = &SLOTS[self.slot];
+        slot.mask.store(0, Ordering::SeqCst);
+        let write_fd = slot.write_fd.swap(-1, Ordering::SeqCst);
+        // A handler that loaded the old descriptor has not finished yet; wait
+        // for it before closing, so that it cannot write into a reused fd.
+        while HANDLERS_RUNNING.load(Ordering::SeqCst) != 0 {
+            hint::spin_loop();
+        }
+        drop(unsafe { FileDesc::from_raw_fd(write_fd) });
+
+        for signum in signals_in(self.mask) {
+            registry.unsubscribe(signum);
+        }
+    }
+}
@@ -7,6 +7,7 @@
 
 use rustc_data_structures::fx::FxIndexMap;
 use rustc_errors::DiagCtxtHandle;
+use rustc_errors::lint_baseline::LintBaselineMode;
 use rustc_session::config::{
     self, CodegenOptions, CrateType, ErrorOutputType, Externs, Input, JsonUnusedExterns,
     OptionsTargetModifiers, OutFileName, Sysroot, UnstableOptions, get_cmd_lint_options,
@@ -105,6 +106,8 @@ pub(crate) struct Options {
     pub(crate) describe_lints: bool,
     /// What level to cap lints at.
     pub(crate) lint_cap: Option<Level>,
+    /// Baseline of known lint occurrences to suppress or regenerate.
+    pub(crate) lint_baseline: Option<(PathBuf, LintBaselineMode)>,
 
     // Options specific to running doctests
     /// Whether we should run doctests instead of generating docs.
@@ -198,6 +201,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("lint_opts", &self.lint_opts)
             .field("describe_lints", &self.describe_lints)
             .field("lint_cap", &self.lint_cap)
+            .field("lint_baseline", &self.lint_baseline)
             .field("should_test", &self.should_test)
             .field("test_args", &self.test_args)
             .field("test_run_directory", &self.test_run_directory)
@@ -597,6 +601,7 @@ fn println_condition(condition: Condition) {
         }
 
         let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(early_dcx, matches);
+        let lint_baseline = config::parse_lint_baseline(early_dcx, matches);
 
         let externs = parse_externs(early_dcx, matches, &unstable_opts);
         let extern_html_root_urls = match parse_extern_html_roots(matches) {
@@ -936,6 +941,7 @@ fn println_condition(condition: Condition) {
             lint_opts,
             describe_lints,
             lint_cap,
+            lint_baseline,
             should_test,
             test_args,
             show_coverage,
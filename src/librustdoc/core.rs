@@ -9,6 +9,7 @@
 use rustc_errors::codes::*;
 use rustc_errors::emitter::{DynEmitter, HumanReadableErrorType, OutputTheme, stderr_destination};
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_feature::UnstableFeatures;
 use rustc_hir::def::Res;
 use rustc_hir::def_id::{DefId, DefIdMap, DefIdSet, LocalDefId};
@@ -161,8 +162,8 @@ pub(crate) fn document_hidden(&self) -> bool {
 
 /// Creates a new `DiagCtxt` that can be used to emit warnings and errors.
 ///
-/// If the given `error_format` is `ErrorOutputType::Json` and no `SourceMap` is given, a new one
-/// will be created for the `DiagCtxt`.
+/// If the given `error_format` is `ErrorOutputType::Json` or `ErrorOutputType::Sarif` and no
+/// `SourceMap` is given, a new one will be created for the `DiagCtxt`.
 pub(crate) fn new_dcx(
     error_format: ErrorOutputType,
     source_map: Option<Arc<source_map::SourceMap>>,
@@ -199,6 +200,21 @@ pub(crate) fn new_dcx(
                 .terminal_url(TerminalUrl::No),
             )
         }
+        ErrorOutputType::Sarif => {
+            let source_map = source_map.unwrap_or_else(|| {
+                Arc::new(source_map::SourceMap::new(source_map::FilePathMapping::empty()))
+            });
+            // The session of the documented crate writes the log of the run, this one is only
+            // written if rustdoc itself reported something.
+            Box::new(
+                SarifEmitter::new(
+                    Box::new(io::BufWriter::new(io::stderr())),
+                    Some(source_map),
+                    "rustdoc",
+                )
+                .skip_empty_log(true),
+            )
+        }
     };
 
     rustc_errors::DiagCtxt::new(emitter).with_flags(unstable_opts.dcx_flags(true))
@@ -224,6 +240,7 @@ pub(crate) fn create_config(
         lint_opts,
         describe_lints,
         lint_cap,
+        lint_baseline,
         scrape_examples_options,
         remap_path_prefix,
         remap_path_scope,
@@ -273,6 +290,7 @@ pub(crate) fn create_config(
         crate_types,
         lint_opts,
         lint_cap,
+        lint_baseline,
         cg: codegen_options,
         externs,
         target_triple: target,
@@ -468,6 +468,22 @@ fn opts() -> Vec<RustcOptGroup> {
             "Remap source names in compiler messages",
             "FROM=TO",
         ),
+        opt(
+            Unstable,
+            Opt,
+            "",
+            "lint-baseline",
+            "Only report lint occurrences that are not recorded in the given baseline file",
+            "PATH",
+        ),
+        opt(
+            Unstable,
+            Flag,
+            "",
+            "update-lint-baseline",
+            "Record all lint occurrences in the `--lint-baseline` file instead of reporting them",
+            "",
+        ),
         opt(Unstable, Opt, "", "index-page", "Markdown file to be used as index page", "PATH"),
         opt(
             Unstable,
@@ -84,6 +84,13 @@ function run_tests {
     done
     # Smoke-test `./miri run --dep`.
     ./miri run $FEATURES $TARGET_FLAG --dep tests/pass-dep/getrandom.rs
+    # Smoke-test recording a host trace and replaying it (that test only supports Linux).
+    if [[ "${TEST_TARGET-$HOST_TARGET}" == *-linux-* ]]; then
+      TRACE="$(mktemp)"
+      ./miri run $FEATURES $TARGET_FLAG --dep -Zmiri-disable-isolation "-Zmiri-record=$TRACE" tests/pass-dep/libc/libc-host-trace.rs
+      ./miri run $FEATURES $TARGET_FLAG --dep "-Zmiri-replay=$TRACE" tests/pass-dep/libc/libc-host-trace.rs
+      rm "$TRACE"
+    fi
   fi
   if [ -n "${TEST_BENCH-}" ]; then
     # Check that the benchmarks build and run, but only once.
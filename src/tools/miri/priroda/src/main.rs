@@ -108,7 +108,7 @@ fn create_ecx<'tcx>(tcx: TyCtxt<'tcx>) -> MiriInterpCx<'tcx> {
     // their program name, arguments, environment snapshot, and `MIRI_CWD`.
     let config = MiriConfig::default();
     // FIXME: report interpreter initialization failures instead of panicking.
-    miri::create_ecx(tcx, entry_id, entry_type, &config, None).unwrap()
+    miri::create_ecx(tcx, entry_id, entry_type, &config, None, None).unwrap()
 }
 
 /// Structured source information for frontends.
@@ -14,6 +14,9 @@
 pub enum MiriAllocParams {
     Global,
     Isolated(Rc<RefCell<IsolatedAlloc>>),
+    /// Memory that is owned by native code and was handed to Miri via `miri_adopt_native_alloc`.
+    /// Such bytes are neither initialized nor freed by Miri.
+    Native(*mut u8),
 }
 
 /// Allocation bytes that explicitly handle the layout of the data they're storing.
@@ -28,7 +31,8 @@ pub struct MiriAllocBytes {
     /// * Otherwise, `self.ptr` points to memory allocated with `self.layout`.
     ptr: *mut u8,
     /// Whether this instance of `MiriAllocBytes` had its allocation created by calling `alloc::alloc()`
-    /// (`Global`) or the discrete allocator (`Isolated`)
+    /// (`Global`) or the discrete allocator (`Isolated`), or whether it is borrowed from native
+    /// code (`Native`)
     params: MiriAllocParams,
 }
 
@@ -36,7 +40,12 @@ impl Clone for MiriAllocBytes {
     fn clone(&self) -> Self {
         let bytes: Cow<'_, [u8]> = Cow::Borrowed(self);
         let align = Align::from_bytes(self.layout.align().to_u64()).unwrap();
-        MiriAllocBytes::from_bytes(bytes, align, self.params.clone())
+        // A copy of native memory is owned by Miri, so it needs to be allocated by us.
+        let params = match &self.params {
+            MiriAllocParams::Native(_) => MiriAllocParams::Global,
+            params => params.clone(),
+        };
+        MiriAllocBytes::from_bytes(bytes, align, params)
     }
 }
 
@@ -56,6 +65,8 @@ fn drop(&mut self) {
                 MiriAllocParams::Global => alloc::dealloc(self.ptr, alloc_layout),
                 MiriAllocParams::Isolated(alloc) =>
                     alloc.borrow_mut().dealloc(self.ptr, alloc_layout),
+                // Native code owns this memory and is responsible for freeing it.
+                MiriAllocParams::Native(_) => {}
             }
         }
     }
@@ -121,6 +132,8 @@ fn from_bytes<'a>(
             match params {
                 MiriAllocParams::Global => alloc::alloc(layout),
                 MiriAllocParams::Isolated(alloc) => alloc.borrow_mut().alloc(layout),
+                MiriAllocParams::Native(_) =>
+                    unreachable!("native memory cannot be created from bytes"),
             }
         };
         let alloc_bytes = MiriAllocBytes::alloc_with(size.to_u64(), align, params, alloc_fn)
@@ -141,6 +154,9 @@ fn zeroed(size: Size, align: Align, params: MiriAllocParams) -> Option<Self> {
             match params {
                 MiriAllocParams::Global => alloc::alloc_zeroed(layout),
                 MiriAllocParams::Isolated(alloc) => alloc.borrow_mut().alloc_zeroed(layout),
+                // Native memory already exists and holds data the native code put there, so we
+                // must not zero it. This is the only way to build an `Allocation` around it.
+                MiriAllocParams::Native(ptr) => *ptr,
             }
         };
         MiriAllocBytes::alloc_with(size, align, params, alloc_fn).ok()
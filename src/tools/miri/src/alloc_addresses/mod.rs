@@ -174,7 +174,8 @@ fn addr_from_alloc_id_uncached(
                                 .instantiate(*this.tcx, instance.args)
                                 .skip_norm_wip(),
                         );
-                        let fn_ptr = crate::shims::native_lib::build_libffi_closure(this, fn_sig)?;
+                        let fn_ptr =
+                            crate::shims::native_lib::build_libffi_closure(this, instance, fn_sig)?;
 
                         #[expect(
                             clippy::as_conversions,
@@ -0,0 +1,436 @@
+//! Heap profiling.
+//!
+//! With `-Zmiri-alloc-profile=<file>`, Miri records the backtrace of every heap allocation and
+//! keeps track of how many bytes are live over the course of the execution. At the end, it writes
+//! a profile in the format of Valgrind's Massif tool, which can be viewed with `ms_print` or
+//! `massif-visualizer`. Time is measured in executed basic blocks. The profile contains the heap
+//! size over time and, for the point where the heap was largest, a tree showing which backtraces
+//! the live bytes were allocated by. In addition, Miri prints the source locations that allocated
+//! most often.
+
+use std::cmp::Reverse;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+use rustc_abi::Size;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::Span;
+
+use crate::diagnostics::prune_stacktrace;
+use crate::*;
+
+/// The number of heap size samples we keep at most. When there are more, we drop every other
+/// sample and halve the sampling rate.
+const MAX_SAMPLES: usize = 100;
+
+/// The number of allocation sites listed at the end of the execution.
+const REPORTED_CALLSITES: usize = 10;
+
+/// A frame of an allocation backtrace.
+type ProfileFrame<'tcx> = (ty::Instance<'tcx>, Span);
+
+/// All allocations made with the same backtrace.
+struct Site<'tcx> {
+    /// The backtrace, innermost frame first.
+    frames: Vec<ProfileFrame<'tcx>>,
+    /// The most relevant location in the backtrace, used to group sites in the summary.
+    callsite: Span,
+    /// The number of bytes of the allocations from this site that are currently live.
+    live_bytes: u64,
+    /// The number of allocations made from this site.
+    allocs: u64,
+    /// The total number of bytes allocated from this site.
+    total_bytes: u64,
+}
+
+/// The heap at the point where it was largest.
+#[derive(Default)]
+struct Peak {
+    time: u64,
+    bytes: u64,
+    /// The live bytes of each site at that point.
+    site_bytes: Vec<u64>,
+}
+
+/// The state of the heap profiler.
+pub struct AllocProfile<'tcx> {
+    path: PathBuf,
+    /// The name of the program, for the profile header.
+    cmd: String,
+    sites: Vec<Site<'tcx>>,
+    site_ids: FxHashMap<Vec<ProfileFrame<'tcx>>, usize>,
+    /// The size and site of each live heap allocation.
+    live: FxHashMap<AllocId, (u64, usize)>,
+    live_bytes: u64,
+    peak: Peak,
+    /// Set when `live_bytes` reached a new peak that `peak.site_bytes` does not reflect yet.
+    peak_pending: bool,
+    /// The heap size at various points in time.
+    samples: Vec<(u64, u64)>,
+    /// The minimal number of basic blocks between two samples.
+    sample_interval: u64,
+}
+
+impl<'tcx> AllocProfile<'tcx> {
+    pub fn new(path: PathBuf, cmd: String) -> Self {
+        AllocProfile {
+            path,
+            cmd,
+            sites: Vec::new(),
+            site_ids: FxHashMap::default(),
+            live: FxHashMap::default(),
+            live_bytes: 0,
+            peak: Peak::default(),
+            peak_pending: false,
+            samples: vec![(0, 0)],
+            sample_interval: 1,
+        }
+    }
+
+    /// Records a new heap allocation.
+    pub fn record_alloc(&mut self, ecx: &MiriInterpCx<'tcx>, id: AllocId, size: Size) {
+        let (stacktrace, _) = prune_stacktrace(ecx.generate_stacktrace(), &ecx.machine);
+        let frames = stacktrace.iter().map(|frame| (frame.instance, frame.span)).collect();
+        let callsite = ecx.machine.current_user_relevant_span();
+        self.add_alloc(frames, callsite, id, size.bytes(), ecx.machine.basic_block_count);
+    }
+
+    fn add_alloc(
+        &mut self,
+        frames: Vec<ProfileFrame<'tcx>>,
+        callsite: Span,
+        id: AllocId,
+        size: u64,
+        time: u64,
+    ) {
+        let site = match self.site_ids.get(&frames) {
+            Some(&site) => site,
+            None => {
+                let site = self.sites.len();
+                self.site_ids.insert(frames.clone(), site);
+                self.sites.push(Site {
+                    frames,
+                    callsite,
+                    live_bytes: 0,
+                    allocs: 0,
+                    total_bytes: 0,
+                });
+                site
+            }
+        };
+
+        let entry = &mut self.sites[site];
+        entry.live_bytes = entry.live_bytes.strict_add(size);
+        entry.allocs = entry.allocs.strict_add(1);
+        entry.total_bytes = entry.total_bytes.strict_add(size);
+        self.live.insert(id, (size, site));
+        self.live_bytes = self.live_bytes.strict_add(size);
+
+        if self.live_bytes > self.peak.bytes {
+            self.peak.time = time;
+            self.peak.bytes = self.live_bytes;
+            self.peak_pending = true;
+        }
+        self.sample(time);
+    }
+
+    /// Records that a heap allocation is freed.
+    pub fn record_dealloc(&mut self, id: AllocId, time: u64) {
+        let Some((size, site)) = self.live.remove(&id) else {
+            return;
+        };
+        // The heap is about to shrink, so if it is at its peak, now is the time to remember how
+        // the peak is made up.
+        self.capture_peak();
+        let entry = &mut self.sites[site];
+        entry.live_bytes = entry.live_bytes.strict_sub(size);
+        self.live_bytes = self.live_bytes.strict_sub(size);
+        self.sample(time);
+    }
+
+    fn capture_peak(&mut self) {
+        if self.peak_pending {
+            self.peak.site_bytes = self.sites.iter().map(|site| site.live_bytes).collect();
+            self.peak_pending = false;
+        }
+    }
+
+    fn sample(&mut self, time: u64) {
+        let last = self.samples.last().unwrap().0;
+        if time.strict_sub(last) < self.sample_interval {
+            return;
+        }
+        self.samples.push((time, self.live_bytes));
+        if self.samples.len() >= MAX_SAMPLES.strict_mul(2) {
+            // Keep the first sample and every other one after it.
+            let mut idx = 0;
+            self.samples.retain(|_| {
+                idx += 1;
+                idx % 2 == 1
+            });
+            self.sample_interval = self.sample_interval.strict_mul(2);
+        }
+    }
+
+    /// Writes the profile and prints a summary. Returns `false` if writing the profile failed.
+    pub fn finish(&mut self, tcx: TyCtxt<'tcx>, time: u64) -> bool {
+        self.capture_peak();
+        let sm = tcx.sess.source_map();
+        let out = self.to_massif(time, |(instance, span)| {
+            let lo = sm.lookup_char_pos(span.lo());
+            format!("{instance} ({}:{})", lo.file.name.prefer_remapped_unconditionally(), lo.line)
+        });
+        if let Err(err) = fs::write(&self.path, out) {
+            tcx.dcx()
+                .err(format!("failed to write heap profile to `{}`: {err}", self.path.display()));
+            return false;
+        }
+
+        let mut summary = format!(
+            "heap profile written to `{}`; the heap peaked at {} bytes after {} basic blocks",
+            self.path.display(),
+            self.peak.bytes,
+            self.peak.time,
+        );
+        let mut callsites: FxHashMap<Span, (u64, u64)> = FxHashMap::default();
+        for site in &self.sites {
+            let (allocs, bytes) = callsites.entry(site.callsite).or_default();
+            *allocs = allocs.strict_add(site.allocs);
+            *bytes = bytes.strict_add(site.total_bytes);
+        }
+        let mut callsites: Vec<_> = callsites.into_iter().collect();
+        // Sort by number of allocations, and then by location to make the output deterministic.
+        callsites.sort_by(|(span1, (allocs1, _)), (span2, (allocs2, _))| {
+            allocs2.cmp(allocs1).then_with(|| span1.cmp(span2))
+        });
+        if !callsites.is_empty() {
+            summary.push_str("\nthe locations that allocated most often are:");
+        }
+        for (span, (allocs, bytes)) in callsites.into_iter().take(REPORTED_CALLSITES) {
+            let span = sm.span_to_diagnostic_string(span);
+            write!(summary, "\n    {allocs} allocations ({bytes} bytes) at {span}").unwrap();
+        }
+        tcx.dcx().note(summary);
+        true
+    }
+
+    /// Renders the profile in the Massif format. `frame_label` describes a frame of an allocation
+    /// backtrace.
+    fn to_massif(&self, end: u64, frame_label: impl Fn(ProfileFrame<'tcx>) -> String) -> String {
+        let mut out = String::new();
+        writeln!(out, "desc: (none)").unwrap();
+        writeln!(out, "cmd: {}", self.cmd).unwrap();
+        writeln!(out, "time_unit: i").unwrap();
+
+        // The peak gets a detailed snapshot; all other samples only record the heap size.
+        let mut snapshots: Vec<(u64, u64, bool)> = self
+            .samples
+            .iter()
+            .filter(|&&(time, _)| time != self.peak.time)
+            .map(|&(time, bytes)| (time, bytes, false))
+            .collect();
+        if self.peak.bytes > 0 {
+            snapshots.push((self.peak.time, self.peak.bytes, true));
+        }
+        snapshots.sort_by_key(|&(time, ..)| time);
+        if snapshots.last().is_none_or(|&(time, ..)| time < end) {
+            snapshots.push((end, self.live_bytes, false));
+        }
+
+        for (idx, (time, bytes, peak)) in snapshots.into_iter().enumerate() {
+            writeln!(out, "#-----------").unwrap();
+            writeln!(out, "snapshot={idx}").unwrap();
+            writeln!(out, "#-----------").unwrap();
+            writeln!(out, "time={time}").unwrap();
+            writeln!(out, "mem_heap_B={bytes}").unwrap();
+            writeln!(out, "mem_heap_extra_B=0").unwrap();
+            writeln!(out, "mem_stacks_B=0").unwrap();
+            if peak {
+                writeln!(out, "heap_tree=peak").unwrap();
+                let sites: Vec<_> = (0..self.sites.len())
+                    .filter(|&site| self.peak.site_bytes.get(site).is_some_and(|&b| b > 0))
+                    .collect();
+                writeln!(
+                    out,
+                    "n{}: {bytes} (heap allocation functions) malloc/new/new[], --alloc-fns, etc.",
+                    self.children(&sites, 0).len()
+                )
+                .unwrap();
+                self.write_tree(&mut out, &frame_label, &sites, 0);
+            } else {
+                writeln!(out, "heap_tree=empty").unwrap();
+            }
+        }
+        out
+    }
+
+    /// Groups the given sites by their frame at the given depth. Sites whose backtrace is not
+    /// that deep are left out. The groups are sorted by their size at the peak, largest first.
+    fn children(
+        &self,
+        sites: &[usize],
+        depth: usize,
+    ) -> Vec<(ProfileFrame<'tcx>, Vec<usize>, u64)> {
+        let mut children: Vec<(ProfileFrame<'tcx>, Vec<usize>, u64)> = Vec::new();
+        for &site in sites {
+            let Some(&frame) = self.sites[site].frames.get(depth) else {
+                continue;
+            };
+            let bytes = self.peak.site_bytes[site];
+            match children.iter_mut().find(|(f, ..)| *f == frame) {
+                Some((_, group, total)) => {
+                    group.push(site);
+                    *total = total.strict_add(bytes);
+                }
+                None => children.push((frame, vec![site], bytes)),
+            }
+        }
+        children.sort_by_key(|&(_, _, bytes)| Reverse(bytes));
+        children
+    }
+
+    fn write_tree(
+        &self,
+        out: &mut String,
+        frame_label: &impl Fn(ProfileFrame<'tcx>) -> String,
+        sites: &[usize],
+        depth: usize,
+    ) {
+        for (frame, group, bytes) in self.children(sites, depth) {
+            writeln!(
+                out,
+                "{:indent$}n{}: {bytes} 0x0: {}",
+                "",
+                self.children(&group, depth.strict_add(1)).len(),
+                frame_label(frame),
+                indent = depth.strict_add(1),
+            )
+            .unwrap();
+            self.write_tree(out, frame_label, &group, depth.strict_add(1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZero;
+
+    use rustc_span::DUMMY_SP;
+
+    use super::*;
+
+    fn alloc_id(id: u64) -> AllocId {
+        AllocId(NonZero::new(id).unwrap())
+    }
+
+    /// Creates a profile and records allocations (with a positive size) and deallocations
+    /// (with a zero size) at the given times.
+    fn profile(events: &[(u64, u64, u64)]) -> AllocProfile<'static> {
+        let mut profile = AllocProfile::new(PathBuf::from("massif.out"), "prog arg".to_string());
+        for &(time, id, size) in events {
+            if size > 0 {
+                profile.add_alloc(Vec::new(), DUMMY_SP, alloc_id(id), size, time);
+            } else {
+                profile.record_dealloc(alloc_id(id), time);
+            }
+        }
+        profile
+    }
+
+    #[test]
+    fn sample_halves_rate() {
+        let mut profile = profile(&[]);
+        for time in 1..200 {
+            profile.sample(time);
+        }
+        // The 200th sample made us drop every other sample.
+        assert_eq!(profile.samples.len(), MAX_SAMPLES);
+        assert_eq!(profile.samples[0], (0, 0));
+        assert_eq!(profile.samples[1], (2, 0));
+        assert_eq!(profile.samples.last(), Some(&(198, 0)));
+        assert_eq!(profile.sample_interval, 2);
+
+        // Samples closer together than the interval are skipped.
+        profile.sample(199);
+        assert_eq!(profile.samples.len(), MAX_SAMPLES);
+        profile.sample(200);
+        assert_eq!(profile.samples.last(), Some(&(200, 0)));
+    }
+
+    #[test]
+    fn capture_peak() {
+        let mut profile = profile(&[(1, 1, 16), (2, 2, 8)]);
+        assert!(profile.peak_pending);
+        assert_eq!((profile.peak.time, profile.peak.bytes), (2, 24));
+        assert!(profile.peak.site_bytes.is_empty());
+
+        // Freeing memory captures the peak.
+        profile.record_dealloc(alloc_id(1), 3);
+        assert!(!profile.peak_pending);
+        assert_eq!(profile.peak.site_bytes, [24]);
+        assert_eq!(profile.live_bytes, 8);
+
+        // Growing the heap again without reaching the peak leaves it alone...
+        profile.add_alloc(Vec::new(), DUMMY_SP, alloc_id(3), 4, 4);
+        profile.record_dealloc(alloc_id(3), 5);
+        assert_eq!((profile.peak.time, profile.peak.bytes), (2, 24));
+        assert_eq!(profile.peak.site_bytes, [24]);
+
+        // ...but exceeding it records a new one.
+        profile.add_alloc(Vec::new(), DUMMY_SP, alloc_id(4), 32, 6);
+        assert!(profile.peak_pending);
+        profile.capture_peak();
+        assert_eq!((profile.peak.time, profile.peak.bytes), (6, 40));
+        assert_eq!(profile.peak.site_bytes, [40]);
+
+        // Freeing memory the profile does not know about changes nothing.
+        profile.record_dealloc(alloc_id(5), 7);
+        assert_eq!(profile.live_bytes, 40);
+    }
+
+    #[test]
+    fn to_massif() {
+        let mut profile = profile(&[(5, 1, 16), (9, 2, 8), (12, 1, 0)]);
+        profile.capture_peak();
+        let snapshot = |idx: u64, time: u64, bytes: u64, tree: &str| {
+            format!(
+                "#-----------\nsnapshot={idx}\n#-----------\ntime={time}\nmem_heap_B={bytes}\n\
+                 mem_heap_extra_B=0\nmem_stacks_B=0\nheap_tree={tree}\n"
+            )
+        };
+        let header = "desc: (none)\ncmd: prog arg\ntime_unit: i\n";
+        let massif = |end| profile.to_massif(end, |_| unreachable!());
+
+        // The peak gets a detailed snapshot, and the end of the execution a final one.
+        let peak = format!(
+            "{}n0: 24 (heap allocation functions) malloc/new/new[], --alloc-fns, etc.\n",
+            snapshot(2, 9, 24, "peak")
+        );
+        assert_eq!(
+            massif(20),
+            [
+                header,
+                &snapshot(0, 0, 0, "empty"),
+                &snapshot(1, 5, 16, "empty"),
+                &peak,
+                &snapshot(3, 12, 8, "empty"),
+                &snapshot(4, 20, 8, "empty"),
+            ]
+            .concat()
+        );
+        // No extra snapshot when the last sample is at the end of the execution.
+        assert_eq!(
+            massif(12),
+            [
+                header,
+                &snapshot(0, 0, 0, "empty"),
+                &snapshot(1, 5, 16, "empty"),
+                &peak,
+                &snapshot(3, 12, 8, "empty"),
+            ]
+            .concat()
+        );
+    }
+}
@@ -41,8 +41,8 @@
 use std::sync::atomic::{AtomicU32, Ordering};
 
 use miri::{
-    BacktraceStyle, BorrowTrackerMethod, GenmcConfig, GenmcCtx, MiriConfig, ProvenanceMode,
-    TreeBorrowsParams, ValidationMode, entry_fn, run_genmc_mode,
+    BacktraceStyle, BorrowTrackerMethod, ExploreCtx, GenmcConfig, GenmcCtx, MiriConfig,
+    ProvenanceMode, TreeBorrowsParams, ValidationMode, entry_fn, run_explore_mode, run_genmc_mode,
 };
 use rustc_codegen_ssa::traits::CodegenBackend;
 use rustc_data_structures::sync::{self, DynSync};
@@ -192,11 +192,32 @@ fn after_analysis<'tcx>(
                     optimizations is usually marginal at best.");
         }
 
+        // Replaying never communicates with the host: the trace says which host results were
+        // observed. (This is checked here rather than with the other flags so that it is reported
+        // as a regular diagnostic.)
+        if matches!(config.host_trace, Some(miri::HostTraceMode::Replay(_)))
+            && config.isolated_op == miri::IsolatedOp::Allow
+        {
+            tcx.dcx().fatal("`-Zmiri-replay` cannot be combined with `-Zmiri-disable-isolation`");
+        }
+
         // Invoke the interpreter.
         let res = if config.genmc_config.is_some() {
             assert!(self.many_seeds.is_none());
             run_genmc_mode(tcx, &config, |genmc_ctx: Rc<GenmcCtx>| {
-                miri::eval_entry(tcx, entry_def_id, entry_type, &config, Some(genmc_ctx))
+                miri::eval_entry(tcx, entry_def_id, entry_type, &config, Some(genmc_ctx), None)
+            })
+        } else if config.explore.is_some() {
+            assert!(self.many_seeds.is_none());
+            run_explore_mode(&config, |explore_ctx: Rc<ExploreCtx>| {
+                miri::eval_entry(
+                    tcx,
+                    entry_def_id,
+                    entry_type,
+                    &config,
+                    /* genmc_ctx */ None,
+                    Some(explore_ctx),
+                )
             })
         } else if let Some(many_seeds) = self.many_seeds.take() {
             assert!(config.seed.is_none());
@@ -204,10 +225,17 @@ fn after_analysis<'tcx>(
                 let mut config = config.clone();
                 config.seed = Some(seed);
                 eprintln!("Trying seed: {seed}");
-                miri::eval_entry(tcx, entry_def_id, entry_type, &config, /* genmc_ctx */ None)
+                miri::eval_entry(
+                    tcx,
+                    entry_def_id,
+                    entry_type,
+                    &config,
+                    /* genmc_ctx */ None,
+                    /* explore_ctx */ None,
+                )
             })
         } else {
-            miri::eval_entry(tcx, entry_def_id, entry_type, &config, None)
+            miri::eval_entry(tcx, entry_def_id, entry_type, &config, None, None)
         };
         // Process interpreter result.
         if let Err(return_code) = res {
@@ -392,6 +420,9 @@ fn main() -> ExitCode {
     // Parse our arguments and split them across `rustc` and `miri`.
     let mut many_seeds: Option<Range<u32>> = None;
     let mut many_seeds_keep_going = false;
+    let mut explore_preemption_bound: Option<u32> = None;
+    let mut explore_max_executions: Option<u64> = None;
+    let mut explore_keep_going = false;
     let mut miri_config = MiriConfig::default();
     miri_config.env = env_snapshot;
 
@@ -456,6 +487,13 @@ fn main() -> ExitCode {
                         "`-Zmiri-tree-borrows` is required before `-Zmiri-tree-borrows-relax-custom-allocator-uniqueness`"
                     ),
             };
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-tree-borrows-history=") {
+            if !matches!(miri_config.borrow_tracker, Some(BorrowTrackerMethod::TreeBorrows(_))) {
+                fatal_error!(
+                    "`-Zmiri-tree-borrows` is required before `-Zmiri-tree-borrows-history`"
+                );
+            }
+            miri_config.tree_borrows_history = Some(param.into());
         } else if arg == "-Zmiri-disable-data-race-detector" {
             miri_config.data_race_detector = false;
             miri_config.weak_memory_emulation = false;
@@ -483,6 +521,16 @@ fn main() -> ExitCode {
                         "-Zmiri-isolation-error must be `abort`, `hide`, `warn`, or `warn-nobacktrace`"
                     ),
             };
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-record=") {
+            if miri_config.host_trace.is_some() {
+                fatal_error!("Only one of `-Zmiri-record` and `-Zmiri-replay` can be set");
+            }
+            miri_config.host_trace = Some(miri::HostTraceMode::Record(param.into()));
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-replay=") {
+            if miri_config.host_trace.is_some() {
+                fatal_error!("Only one of `-Zmiri-record` and `-Zmiri-replay` can be set");
+            }
+            miri_config.host_trace = Some(miri::HostTraceMode::Replay(param.into()));
         } else if arg == "-Zmiri-ignore-leaks" {
             miri_config.ignore_leaks = true;
             miri_config.collect_leak_backtraces = false;
@@ -528,6 +576,39 @@ fn main() -> ExitCode {
             many_seeds = Some(0..64);
         } else if arg == "-Zmiri-many-seeds-keep-going" {
             many_seeds_keep_going = true;
+        } else if arg == "-Zmiri-explore" {
+            miri_config.explore.get_or_insert_default();
+            // Randomness would make the executions diverge from the schedules we replay.
+            miri_config.fixed_scheduling = true;
+            miri_config.preemption_rate = 0.0;
+            miri_config.address_reuse_cross_thread_rate = 0.0;
+            miri_config.cmpxchg_weak_failure_rate = 0.0;
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-explore-preemption-bound=") {
+            let bound = param.parse::<u32>().unwrap_or_else(|_| {
+                fatal_error!(
+                    "-Zmiri-explore-preemption-bound must be an integer that fits into u32"
+                )
+            });
+            explore_preemption_bound = Some(bound);
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-explore-max-executions=") {
+            let max = param.parse::<u64>().unwrap_or_else(|_| {
+                fatal_error!("-Zmiri-explore-max-executions must be an integer that fits into u64")
+            });
+            explore_max_executions = Some(max);
+        } else if arg == "-Zmiri-explore-keep-going" {
+            explore_keep_going = true;
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-fuzz-runs=") {
+            miri_config.fuzz.runs = param.parse::<u64>().unwrap_or_else(|_| {
+                fatal_error!("-Zmiri-fuzz-runs must be an integer that fits into u64")
+            });
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-fuzz-corpus=") {
+            miri_config.fuzz.corpus = Some(param.into());
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-fuzz-artifact=") {
+            miri_config.fuzz.artifact = param.into();
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-fuzz-max-len=") {
+            miri_config.fuzz.max_len = param.parse::<usize>().unwrap_or_else(|_| {
+                fatal_error!("-Zmiri-fuzz-max-len must be an integer that fits into usize")
+            });
         } else if let Some(trimmed_arg) = arg.strip_prefix("-Zmiri-genmc") {
             if let Err(msg) = GenmcConfig::parse_arg(&mut miri_config.genmc_config, trimmed_arg) {
                 fatal_error!("{msg}");
@@ -557,6 +638,8 @@ fn main() -> ExitCode {
             miri_config.tracked_alloc_ids.extend(ids.into_iter().map(miri::AllocId));
         } else if arg == "-Zmiri-track-alloc-accesses" {
             miri_config.track_alloc_accesses = true;
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-alloc-profile=") {
+            miri_config.alloc_profile = Some(param.into());
         } else if let Some(param) = arg.strip_prefix("-Zmiri-address-reuse-rate=") {
             miri_config.address_reuse_rate = parse_rate(param)
                 .unwrap_or_else(|err| fatal_error!("-Zmiri-address-reuse-rate {err}"));
@@ -658,6 +741,51 @@ fn main() -> ExitCode {
     if many_seeds.is_some() && miri_config.seed.is_some() {
         fatal_error!("Only one of `-Zmiri-seed` and `-Zmiri-many-seeds can be set");
     }
+    // A host trace covers a single execution.
+    if miri_config.host_trace.is_some()
+        && (many_seeds.is_some()
+            || miri_config.genmc_config.is_some()
+            || miri_config.explore.is_some())
+    {
+        fatal_error!(
+            "`-Zmiri-record` and `-Zmiri-replay` are not supported with `-Zmiri-many-seeds`, `-Zmiri-explore` or GenMC mode"
+        );
+    }
+    // A heap profile covers a single execution.
+    if miri_config.alloc_profile.is_some()
+        && (many_seeds.is_some()
+            || miri_config.genmc_config.is_some()
+            || miri_config.explore.is_some())
+    {
+        fatal_error!(
+            "`-Zmiri-alloc-profile` is not supported with `-Zmiri-many-seeds`, `-Zmiri-explore` or GenMC mode"
+        );
+    }
+    // Apply the exploration settings.
+    if let Some(explore) = &mut miri_config.explore {
+        if let Some(bound) = explore_preemption_bound {
+            explore.preemption_bound = bound;
+        }
+        explore.max_executions = explore_max_executions;
+        explore.keep_going = explore_keep_going;
+    } else if explore_preemption_bound.is_some()
+        || explore_max_executions.is_some()
+        || explore_keep_going
+    {
+        fatal_error!("the `-Zmiri-explore-*` settings require `-Zmiri-explore` to be set");
+    }
+    if miri_config.explore.is_some() {
+        // Exploration is an alternative to running many seeds or GenMC.
+        if many_seeds.is_some() || miri_config.genmc_config.is_some() {
+            fatal_error!(
+                "`-Zmiri-explore` cannot be combined with `-Zmiri-many-seeds` or GenMC mode"
+            );
+        }
+        // The vector clocks of the data race detector tell which accesses are independent.
+        if !miri_config.data_race_detector {
+            fatal_error!("`-Zmiri-explore` cannot be used when the data race detector is disabled");
+        }
+    }
     // We cannot emulate weak memory without the data race detector.
     if miri_config.weak_memory_emulation && !miri_config.data_race_detector {
         fatal_error!(
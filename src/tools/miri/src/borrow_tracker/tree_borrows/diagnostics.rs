@@ -128,6 +128,16 @@ impl History {
     pub fn push(&mut self, event: Event) {
         self.events.push(event);
     }
+
+    /// The number of events recorded so far.
+    pub fn event_count(&self) -> usize {
+        self.events.len()
+    }
+
+    /// The events recorded after the first `start` events.
+    pub fn events_since(&self, start: usize) -> &[Event] {
+        &self.events[start..]
+    }
 }
 
 impl HistoryData {
@@ -249,6 +259,7 @@ pub fn give_pointer_debug_name(
         let idx = self.tag_mapping.get(&tag).unwrap();
         if let Some(node) = self.nodes.get_mut(idx) {
             node.debug_info.add_name(name);
+            self.record_name(tag, name);
         } else {
             eprintln!("Tag {tag:?} (to be named '{name}') not found!");
         }
@@ -394,7 +405,8 @@ pub fn build<'tcx>(self) -> InterpErrorKind<'tcx> {
             conflicting_tag_name,
             true,
         );
-        err_machine_stop!(TerminationInfo::TreeBorrowsUb { title, details, history })
+        let alloc_id = self.access_info.alloc_id;
+        err_machine_stop!(TerminationInfo::TreeBorrowsUb { title, details, history, alloc_id })
     }
 }
 
@@ -409,7 +421,8 @@ pub fn no_valid_exposed_references_error<'tcx>(
     );
     let details = vec![format!("there are no exposed tags which may perform this access here")];
     let history = HistoryData::default();
-    err_machine_stop!(TerminationInfo::TreeBorrowsUb { title, details, history })
+    let alloc_id = *alloc_id;
+    err_machine_stop!(TerminationInfo::TreeBorrowsUb { title, details, history, alloc_id })
 }
 
 type S = &'static str;
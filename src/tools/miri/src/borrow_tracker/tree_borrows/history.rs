@@ -0,0 +1,673 @@
+//! The full history of a tree, for `-Zmiri-tree-borrows-history`.
+//!
+//! With that flag, every tree records all retags, accesses and exposures that happen in its
+//! allocation, together with the permission transitions they cause. When Tree Borrows reports
+//! Undefined Behavior, the history of the allocation involved is rendered as an HTML timeline or
+//! as a Graphviz graph, which show how the permissions of every tag evolved up to the operation
+//! that was UB.
+
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::path::Path;
+
+use rustc_abi::Size;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_span::Span;
+
+use super::diagnostics::AccessCause;
+use super::perms::{PermTransition, Permission};
+use super::tree::{LocationState, Tree};
+use crate::*;
+
+/// Everything that happened in the tree of an allocation, in order.
+#[derive(Clone, Debug)]
+pub struct TreeHistory {
+    size: Size,
+    steps: Vec<Step>,
+    /// The names given to tags, see `NodeDebugInfo::name`. Unlike the names in the tree, these
+    /// are kept when the tag is garbage collected.
+    names: FxHashMap<BorTag, String>,
+    /// Whether the last step was Undefined Behavior.
+    failed: bool,
+}
+
+#[derive(Clone, Debug)]
+struct Step {
+    kind: StepKind,
+    span: Span,
+    /// The permissions that changed during this step, with the offsets they changed at.
+    transitions: Vec<(BorTag, Range<u64>, PermTransition)>,
+}
+
+/// An operation on the tree.
+#[derive(Clone, Debug)]
+pub(super) enum StepKind {
+    /// The allocation was created, with `tag` as the root.
+    Allocate { tag: BorTag },
+    /// `tag` was derived from `parent`, or from a wildcard pointer if `parent` is `None`.
+    /// `perms` are its initial permissions, and it has `outside` everywhere else.
+    Retag {
+        tag: BorTag,
+        parent: Option<BorTag>,
+        protected: bool,
+        perms: Vec<(Range<u64>, Permission)>,
+        outside: Permission,
+    },
+    /// An access through `tag`, or through a wildcard pointer if `tag` is `None`.
+    Access { tag: Option<BorTag>, cause: AccessCause, range: AllocRange },
+    /// The protector of `tag` ended, which acts as an access on the locations it accessed.
+    ProtectorEnd { tag: BorTag },
+    /// `tag` was exposed, so that wildcard pointers may be used in its place.
+    Expose { tag: BorTag },
+}
+
+impl StepKind {
+    pub(super) fn access(prov: ProvenanceExtra, cause: AccessCause, range: AllocRange) -> Self {
+        StepKind::Access { tag: prov.and_then(Some), cause, range }
+    }
+}
+
+/// How a history is rendered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryFormat {
+    Html,
+    Graphviz,
+}
+
+impl HistoryFormat {
+    /// Graphviz for `.dot` and `.gv` files, HTML otherwise.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("dot" | "gv") => HistoryFormat::Graphviz,
+            _ => HistoryFormat::Html,
+        }
+    }
+}
+
+impl Tree {
+    /// Starts recording the history of this tree. Must be called right after the tree is created.
+    pub fn record_history(&mut self, span: Span) {
+        let root = self.nodes.get(self.roots[0]).unwrap();
+        let mut names = FxHashMap::default();
+        if let Some(name) = &root.debug_info.name {
+            names.insert(root.tag, name.clone());
+        }
+        let allocate =
+            Step { kind: StepKind::Allocate { tag: root.tag }, span, transitions: Vec::new() };
+        self.history = Some(Box::new(TreeHistory {
+            size: self.locations.size(),
+            steps: vec![allocate],
+            names,
+            failed: false,
+        }));
+    }
+
+    /// The recorded history, if any.
+    pub fn history(&self) -> Option<&TreeHistory> {
+        self.history.as_deref()
+    }
+
+    /// Performs `op` on the tree and, if the history is recorded, records it as a step along with
+    /// the transitions it caused.
+    pub(super) fn record_step<'tcx>(
+        &mut self,
+        kind: StepKind,
+        span: Span,
+        op: impl FnOnce(&mut Self) -> InterpResult<'tcx>,
+    ) -> InterpResult<'tcx> {
+        if self.history.is_none() {
+            return op(self);
+        }
+        // The transitions are also added to the history of the nodes, so everything that is
+        // added there while `op` runs belongs to this step.
+        let marks: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|(idx, node)| (idx, node.debug_info.history.event_count()))
+            .collect();
+        let mut failed = false;
+        let res = op(self).inspect_err_kind(|_| failed = true);
+
+        let mut transitions: Vec<(BorTag, Range<u64>, PermTransition)> = Vec::new();
+        for (idx, count) in marks {
+            let node = self.nodes.get(idx).unwrap();
+            for event in node.debug_info.history.events_since(count) {
+                // A single access creates one event per internal range; merge them again.
+                if let Some((tag, range, transition)) = transitions.last_mut()
+                    && *tag == node.tag
+                    && *transition == event.transition
+                    && range.end == event.transition_range.start
+                {
+                    range.end = event.transition_range.end;
+                } else {
+                    transitions.push((node.tag, event.transition_range.clone(), event.transition));
+                }
+            }
+        }
+        let history = self.history.as_mut().unwrap();
+        history.steps.push(Step { kind, span, transitions });
+        history.failed = failed;
+        res
+    }
+
+    /// Records that `tag` was created, see `Tree::new_child`.
+    pub(super) fn record_retag(
+        &mut self,
+        tag: BorTag,
+        parent_prov: ProvenanceExtra,
+        base_offset: Size,
+        inside_perms: &DedupRangeMap<LocationState>,
+        outside_perm: Permission,
+        protected: bool,
+        span: Span,
+    ) {
+        let Some(history) = &mut self.history else {
+            return;
+        };
+        let base_offset = base_offset.bytes();
+        let perms = inside_perms
+            .iter_all()
+            .map(|(range, state)| {
+                (
+                    range.start.strict_add(base_offset)..range.end.strict_add(base_offset),
+                    state.permission(),
+                )
+            })
+            .collect();
+        let parent = match parent_prov {
+            ProvenanceExtra::Concrete(parent) => Some(parent),
+            ProvenanceExtra::Wildcard => None,
+        };
+        let kind = StepKind::Retag { tag, parent, protected, perms, outside: outside_perm };
+        history.steps.push(Step { kind, span, transitions: Vec::new() });
+    }
+
+    /// Records that `tag` is about to be exposed, unless it already is.
+    pub(super) fn record_exposure(&mut self, tag: BorTag, span: Span) {
+        let Some(history) = &mut self.history else {
+            return;
+        };
+        let idx = self.tag_mapping.get(&tag).unwrap();
+        if !self.nodes.get(idx).unwrap().is_exposed {
+            let kind = StepKind::Expose { tag };
+            history.steps.push(Step { kind, span, transitions: Vec::new() });
+        }
+    }
+
+    /// Records the name given to `tag`, see `Tree::give_pointer_debug_name`.
+    pub(super) fn record_name(&mut self, tag: BorTag, name: &str) {
+        if let Some(history) = &mut self.history {
+            let names = history.names.entry(tag).or_default();
+            if !names.is_empty() {
+                names.push_str(", ");
+            }
+            names.push_str(name);
+        }
+    }
+}
+
+/// The permissions of the tags at some point of the history.
+struct TreeState {
+    perms: FxHashMap<BorTag, DedupRangeMap<Permission>>,
+}
+
+impl TreeState {
+    fn apply(&mut self, size: Size, step: &Step) {
+        match &step.kind {
+            StepKind::Allocate { tag } => {
+                self.perms.insert(*tag, DedupRangeMap::new(size, Permission::new_unique()));
+            }
+            StepKind::Retag { tag, perms, outside, .. } => {
+                let mut map = DedupRangeMap::new(size, *outside);
+                for (range, perm) in perms {
+                    set_perm(&mut map, range, *perm);
+                }
+                self.perms.insert(*tag, map);
+            }
+            StepKind::Access { .. } | StepKind::ProtectorEnd { .. } | StepKind::Expose { .. } => {}
+        }
+        for (tag, range, transition) in &step.transitions {
+            if let Some(map) = self.perms.get_mut(tag) {
+                set_perm(map, range, transition.endpoint());
+            }
+        }
+    }
+
+    /// The permissions of `tag`, with the ranges they apply to if they differ across the
+    /// allocation.
+    fn describe(&self, tag: BorTag) -> Option<String> {
+        let map = self.perms.get(&tag)?;
+        let mut ranges: Vec<(Range<u64>, Permission)> = Vec::new();
+        for (range, &perm) in map.iter_all() {
+            match ranges.last_mut() {
+                Some((last, last_perm)) if *last_perm == perm => last.end = range.end,
+                _ => ranges.push((range, perm)),
+            }
+        }
+        Some(match &ranges[..] {
+            [] => String::from("(empty)"),
+            [(_, perm)] => perm.short_name().trim_end().to_owned(),
+            _ =>
+                ranges
+                    .iter()
+                    .map(|(range, perm)| {
+                        format!(
+                            "[{:#x}..{:#x}] {}",
+                            range.start,
+                            range.end,
+                            perm.short_name().trim_end()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" "),
+        })
+    }
+}
+
+fn set_perm(map: &mut DedupRangeMap<Permission>, range: &Range<u64>, perm: Permission) {
+    let len = Size::from_bytes(range.end.strict_sub(range.start));
+    for (_range, p) in map.iter_mut(Size::from_bytes(range.start), len) {
+        *p = perm;
+    }
+}
+
+impl TreeHistory {
+    fn tag_name(&self, tag: BorTag) -> String {
+        match self.names.get(&tag) {
+            Some(name) => format!("{tag:?} ({name})"),
+            None => format!("{tag:?}"),
+        }
+    }
+
+    fn describe_step(&self, step: &Step) -> String {
+        let tag_or_wildcard = |tag: Option<BorTag>| {
+            match tag {
+                Some(tag) => self.tag_name(tag),
+                None => String::from("<wildcard>"),
+            }
+        };
+        match &step.kind {
+            StepKind::Allocate { tag } =>
+                format!(
+                    "allocation of {} bytes with root {}",
+                    self.size.bytes(),
+                    self.tag_name(*tag)
+                ),
+            StepKind::Retag { tag, parent, protected, .. } =>
+                format!(
+                    "retag: {} derived from {}{}",
+                    self.tag_name(*tag),
+                    tag_or_wildcard(*parent),
+                    if *protected { ", protected" } else { "" },
+                ),
+            StepKind::Access { tag, cause, range } => {
+                let access = match cause {
+                    AccessCause::Explicit(kind) => kind.to_string(),
+                    AccessCause::Reborrow(kind) => format!("{kind} for a reborrow"),
+                    AccessCause::Dealloc => String::from("deallocation"),
+                    AccessCause::FnExit(kind) => format!("{kind} for a protector release"),
+                };
+                format!("{access} through {} at {range}", tag_or_wildcard(*tag))
+            }
+            StepKind::ProtectorEnd { tag } =>
+                format!("protector release of {}", self.tag_name(*tag)),
+            StepKind::Expose { tag } => format!("{} exposed", self.tag_name(*tag)),
+        }
+    }
+
+    /// All tags in the order they were created, with their parents.
+    fn tags(&self) -> Vec<(BorTag, Option<BorTag>)> {
+        self.steps
+            .iter()
+            .filter_map(|step| {
+                match step.kind {
+                    StepKind::Allocate { tag } => Some((tag, None)),
+                    StepKind::Retag { tag, parent, .. } => Some((tag, parent)),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Renders the history. `error` and `details` describe the UB of the last step, and `span`
+    /// describes the location of a step.
+    pub fn render(
+        &self,
+        alloc_id: AllocId,
+        error: &str,
+        details: &[String],
+        format: HistoryFormat,
+        span: impl Fn(Span) -> String,
+    ) -> String {
+        match format {
+            HistoryFormat::Html => self.to_html(alloc_id, error, details, span),
+            HistoryFormat::Graphviz => self.to_graphviz(alloc_id, error, details, span),
+        }
+    }
+
+    /// A table with one row per step and one column per tag, showing the permissions of every
+    /// tag after every step.
+    fn to_html(
+        &self,
+        alloc_id: AllocId,
+        error: &str,
+        details: &[String],
+        span: impl Fn(Span) -> String,
+    ) -> String {
+        let tags = self.tags();
+        let mut out = String::new();
+        writeln!(out, "<!DOCTYPE html>").unwrap();
+        writeln!(out, "<html>").unwrap();
+        writeln!(out, "<head>").unwrap();
+        writeln!(out, "<meta charset=\"utf-8\">").unwrap();
+        writeln!(out, "<title>Tree Borrows history of {alloc_id:?}</title>").unwrap();
+        writeln!(
+            out,
+            "<style>\n\
+             body {{ font-family: sans-serif; }}\n\
+             table {{ border-collapse: collapse; }}\n\
+             th, td {{ border: 1px solid #ccc; padding: 2px 6px; vertical-align: top; }}\n\
+             td.perm {{ font-family: monospace; white-space: nowrap; }}\n\
+             td.changed {{ background: #ffeeba; }}\n\
+             tr.ub td {{ background: #f8d7da; }}\n\
+             tr.ub td.changed {{ background: #f1aeb5; }}\n\
+             .error {{ color: #b00020; }}\n\
+             </style>"
+        )
+        .unwrap();
+        writeln!(out, "</head>").unwrap();
+        writeln!(out, "<body>").unwrap();
+        writeln!(out, "<h1>Tree Borrows history of {alloc_id:?}</h1>").unwrap();
+        if self.failed {
+            writeln!(out, "<p class=\"error\">Undefined Behavior: {}</p>", escape_html(error))
+                .unwrap();
+            writeln!(out, "<ul>").unwrap();
+            for detail in details {
+                writeln!(out, "<li>{}</li>", escape_html(detail)).unwrap();
+            }
+            writeln!(out, "</ul>").unwrap();
+        }
+
+        writeln!(out, "<table>").unwrap();
+        write!(out, "<tr><th>#</th><th>location</th><th>operation</th>").unwrap();
+        for (idx, &(tag, parent)) in tags.iter().enumerate() {
+            let parent = match parent {
+                Some(parent) => format!("child of {parent:?}"),
+                None if idx == 0 => String::from("root"),
+                None => String::from("wildcard root"),
+            };
+            write!(
+                out,
+                "<th>{}<br>{}</th>",
+                escape_html(&self.tag_name(tag)),
+                escape_html(&parent)
+            )
+            .unwrap();
+        }
+        writeln!(out, "</tr>").unwrap();
+
+        let mut state = TreeState { perms: FxHashMap::default() };
+        for (idx, step) in self.steps.iter().enumerate() {
+            state.apply(self.size, step);
+            let is_ub = self.failed && idx.strict_add(1) == self.steps.len();
+            let mut operation = escape_html(&self.describe_step(step));
+            if is_ub {
+                write!(operation, "<br><strong>Undefined Behavior</strong>").unwrap();
+            }
+            write!(
+                out,
+                "<tr{}><td>{idx}</td><td>{}</td><td>{operation}</td>",
+                if is_ub { " class=\"ub\"" } else { "" },
+                escape_html(&span(step.span)),
+            )
+            .unwrap();
+            for &(tag, _) in &tags {
+                let Some(perms) = state.describe(tag) else {
+                    write!(out, "<td></td>").unwrap();
+                    continue;
+                };
+                let changes: Vec<String> = step
+                    .transitions
+                    .iter()
+                    .filter(|(t, ..)| *t == tag)
+                    .map(|(_, range, transition)| {
+                        format!("[{:#x}..{:#x}] {transition}", range.start, range.end)
+                    })
+                    .collect();
+                if changes.is_empty() {
+                    write!(out, "<td class=\"perm\">{}</td>", escape_html(&perms)).unwrap();
+                } else {
+                    write!(
+                        out,
+                        "<td class=\"perm changed\" title=\"{}\">{}</td>",
+                        escape_html(&changes.join("\n")),
+                        escape_html(&perms),
+                    )
+                    .unwrap();
+                }
+            }
+            writeln!(out, "</tr>").unwrap();
+        }
+        writeln!(out, "</table>").unwrap();
+        writeln!(out, "</body>").unwrap();
+        writeln!(out, "</html>").unwrap();
+        out
+    }
+
+    /// The tree next to the timeline of steps, with an edge from every step to the tags it
+    /// created, used or changed.
+    fn to_graphviz(
+        &self,
+        alloc_id: AllocId,
+        error: &str,
+        details: &[String],
+        span: impl Fn(Span) -> String,
+    ) -> String {
+        let mut out = String::new();
+        let mut title = format!("Tree Borrows history of {alloc_id:?}");
+        if self.failed {
+            write!(title, "\nUndefined Behavior: {error}").unwrap();
+            for detail in details {
+                write!(title, "\n{detail}").unwrap();
+            }
+        }
+        writeln!(out, "digraph history {{").unwrap();
+        writeln!(out, "    label=\"{}\";", escape_dot(&title)).unwrap();
+        writeln!(out, "    labelloc=t;").unwrap();
+        writeln!(out, "    node [shape=box, fontname=\"monospace\"];").unwrap();
+
+        let mut state = TreeState { perms: FxHashMap::default() };
+        for step in &self.steps {
+            state.apply(self.size, step);
+        }
+        writeln!(out, "    subgraph cluster_tree {{").unwrap();
+        writeln!(out, "        label=\"tree\";").unwrap();
+        for (tag, parent) in self.tags() {
+            let label =
+                format!("{}\n{}", self.tag_name(tag), state.describe(tag).unwrap_or_default());
+            writeln!(out, "        tag{} [label=\"{}\"];", tag.get(), escape_dot(&label)).unwrap();
+            if let Some(parent) = parent {
+                writeln!(out, "        tag{} -> tag{};", parent.get(), tag.get()).unwrap();
+            }
+        }
+        writeln!(out, "    }}").unwrap();
+
+        writeln!(out, "    subgraph cluster_timeline {{").unwrap();
+        writeln!(out, "        label=\"timeline\";").unwrap();
+        for (idx, step) in self.steps.iter().enumerate() {
+            let label = format!("{idx}: {}\n{}", self.describe_step(step), span(step.span));
+            let style = if self.failed && idx.strict_add(1) == self.steps.len() {
+                ", color=red, fontcolor=red, penwidth=2"
+            } else {
+                ""
+            };
+            writeln!(out, "        step{idx} [label=\"{}\"{style}];", escape_dot(&label)).unwrap();
+            if let Some(prev) = idx.checked_sub(1) {
+                writeln!(out, "        step{prev} -> step{idx} [weight=10];").unwrap();
+            }
+        }
+        writeln!(out, "    }}").unwrap();
+
+        for (idx, step) in self.steps.iter().enumerate() {
+            let used = match step.kind {
+                StepKind::Allocate { tag } | StepKind::Retag { tag, .. } => Some((tag, "created")),
+                StepKind::Access { tag: Some(tag), .. } => Some((tag, "accessed")),
+                StepKind::ProtectorEnd { tag } => Some((tag, "released")),
+                StepKind::Expose { tag } => Some((tag, "exposed")),
+                StepKind::Access { tag: None, .. } => None,
+            };
+            if let Some((tag, what)) = used {
+                writeln!(
+                    out,
+                    "    step{idx} -> tag{} [label=\"{what}\", style=dotted, constraint=false];",
+                    tag.get()
+                )
+                .unwrap();
+            }
+            for (tag, range, transition) in &step.transitions {
+                let label = format!("[{:#x}..{:#x}] {transition}", range.start, range.end);
+                writeln!(
+                    out,
+                    "    step{idx} -> tag{} [label=\"{}\", style=dashed, color=orange, constraint=false];",
+                    tag.get(),
+                    escape_dot(&label),
+                )
+                .unwrap();
+            }
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZero;
+
+    use rustc_span::DUMMY_SP;
+
+    use super::*;
+    use crate::borrow_tracker::AccessKind;
+    use crate::borrow_tracker::tree_borrows::tree::AccessRelatedness;
+
+    fn tag(id: u64) -> BorTag {
+        BorTag::new(id).unwrap()
+    }
+
+    fn step(kind: StepKind, transitions: Vec<(BorTag, Range<u64>, PermTransition)>) -> Step {
+        Step { kind, span: DUMMY_SP, transitions }
+    }
+
+    fn access(id: u64, kind: AccessKind) -> StepKind {
+        let range = alloc_range(Size::ZERO, Size::from_bytes(4));
+        StepKind::Access { tag: Some(tag(id)), cause: AccessCause::Explicit(kind), range }
+    }
+
+    /// A child `<2>` of the root `<1>` gets disabled by a write through the root, and is then
+    /// used for a read.
+    fn history(failed: bool) -> TreeHistory {
+        let reserved = Permission::new_reserved_frz();
+        let disable = Permission::perform_access(
+            AccessKind::Write,
+            AccessRelatedness::ForeignAccess,
+            reserved,
+            /* protected */ false,
+        )
+        .unwrap();
+        let retag = StepKind::Retag {
+            tag: tag(2),
+            parent: Some(tag(1)),
+            protected: false,
+            perms: vec![(0..4, reserved)],
+            outside: reserved,
+        };
+        TreeHistory {
+            size: Size::from_bytes(4),
+            steps: vec![
+                step(StepKind::Allocate { tag: tag(1) }, Vec::new()),
+                step(retag, Vec::new()),
+                step(access(1, AccessKind::Write), vec![(tag(2), 0..4, disable)]),
+                step(access(2, AccessKind::Read), Vec::new()),
+            ],
+            names: [(tag(2), String::from("y"))].into_iter().collect(),
+            failed,
+        }
+    }
+
+    fn render(history: &TreeHistory, format: HistoryFormat) -> String {
+        let alloc_id = AllocId(NonZero::new(7).unwrap());
+        let details = [String::from("the accessed tag <2> is Disabled")];
+        history.render(alloc_id, "read access through <2> is forbidden", &details, format, |_| {
+            String::from("main.rs:1:1")
+        })
+    }
+
+    #[test]
+    fn html() {
+        let out = render(&history(true), HistoryFormat::Html);
+        for line in [
+            "<h1>Tree Borrows history of alloc7</h1>",
+            "<p class=\"error\">Undefined Behavior: read access through &lt;2&gt; is forbidden</p>",
+            "<li>the accessed tag &lt;2&gt; is Disabled</li>",
+            "<tr><th>#</th><th>location</th><th>operation</th>\
+             <th>&lt;1&gt;<br>root</th><th>&lt;2&gt; (y)<br>child of &lt;1&gt;</th></tr>",
+            "<tr><td>0</td><td>main.rs:1:1</td><td>allocation of 4 bytes with root &lt;1&gt;</td>\
+             <td class=\"perm\">Unq</td><td></td></tr>",
+            "<tr><td>1</td><td>main.rs:1:1</td><td>retag: &lt;2&gt; (y) derived from &lt;1&gt;</td>\
+             <td class=\"perm\">Unq</td><td class=\"perm\">Res</td></tr>",
+            "<tr><td>2</td><td>main.rs:1:1</td><td>write access through &lt;1&gt; at [0x0..0x4]</td>\
+             <td class=\"perm\">Unq</td>\
+             <td class=\"perm changed\" title=\"[0x0..0x4] from Reserved to Disabled\">Dis</td></tr>",
+            // The last step is highlighted as the UB.
+            "<tr class=\"ub\"><td>3</td><td>main.rs:1:1</td>\
+             <td>read access through &lt;2&gt; (y) at [0x0..0x4]<br><strong>Undefined Behavior</strong></td>\
+             <td class=\"perm\">Unq</td><td class=\"perm\">Dis</td></tr>",
+        ] {
+            assert!(out.lines().any(|l| l == line), "missing `{line}` in:\n{out}");
+        }
+
+        // Without UB, nothing is highlighted.
+        let out = render(&history(false), HistoryFormat::Html);
+        assert!(!out.contains("<p class=\"error\">"), "{out}");
+        assert!(!out.contains("<tr class=\"ub\">"), "{out}");
+        assert!(!out.contains("<strong>Undefined Behavior</strong>"), "{out}");
+    }
+
+    #[test]
+    fn graphviz() {
+        let out = render(&history(true), HistoryFormat::Graphviz);
+        for line in [
+            "digraph history {",
+            "    label=\"Tree Borrows history of alloc7\\nUndefined Behavior: read access through \
+             <2> is forbidden\\nthe accessed tag <2> is Disabled\";",
+            "        tag1 [label=\"<1>\\nUnq\"];",
+            "        tag2 [label=\"<2> (y)\\nDis\"];",
+            "        tag1 -> tag2;",
+            "        step0 [label=\"0: allocation of 4 bytes with root <1>\\nmain.rs:1:1\"];",
+            "        step2 -> step3 [weight=10];",
+            // The last step is highlighted as the UB.
+            "        step3 [label=\"3: read access through <2> (y) at [0x0..0x4]\\nmain.rs:1:1\", \
+             color=red, fontcolor=red, penwidth=2];",
+            "    step1 -> tag2 [label=\"created\", style=dotted, constraint=false];",
+            "    step2 -> tag2 [label=\"[0x0..0x4] from Reserved to Disabled\", style=dashed, \
+             color=orange, constraint=false];",
+            "    step3 -> tag2 [label=\"accessed\", style=dotted, constraint=false];",
+        ] {
+            assert!(out.lines().any(|l| l == line), "missing `{line}` in:\n{out}");
+        }
+
+        // Without UB, nothing is highlighted.
+        let out = render(&history(false), HistoryFormat::Graphviz);
+        let header = "digraph history {\n    label=\"Tree Borrows history of alloc7\";\n";
+        assert!(out.starts_with(header), "{out}");
+        assert!(!out.contains("color=red"), "{out}");
+    }
+}
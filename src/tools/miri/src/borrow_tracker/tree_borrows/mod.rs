@@ -1,3 +1,6 @@
+use std::path::Path;
+use std::{fs, io};
+
 use rustc_abi::Size;
 use rustc_hir::find_attr;
 use rustc_middle::mir::Mutability;
@@ -5,6 +8,7 @@
 use rustc_middle::ty::{self, Ty};
 
 use self::foreign_access_skipping::IdempotentForeignAccess;
+use self::history::{HistoryFormat, StepKind};
 use self::tree::LocationState;
 use crate::borrow_tracker::{AccessKind, GlobalState, GlobalStateInner, ProtectorKind};
 use crate::concurrency::data_race::{NaReadType, NaWriteType};
@@ -12,6 +16,7 @@
 
 pub mod diagnostics;
 mod foreign_access_skipping;
+mod history;
 mod perms;
 mod tree;
 mod tree_visitor;
@@ -37,7 +42,11 @@ pub fn new_allocation(
     ) -> Self {
         let tag = state.root_ptr_tag(id, machine); // Fresh tag for the root
         let span = machine.current_user_relevant_span();
-        Tree::new(tag, size, span)
+        let mut tree = Tree::new(tag, size, span);
+        if machine.tree_borrows_history.is_some() {
+            tree.record_history(span);
+        }
+        tree
     }
 
     /// Check that an access on the entire range is permitted, and update
@@ -59,15 +68,10 @@ pub fn before_memory_access(
         );
         let global = machine.borrow_tracker.as_ref().unwrap();
         let span = machine.current_user_relevant_span();
-        self.perform_access(
-            prov,
-            range,
-            access_kind,
-            diagnostics::AccessCause::Explicit(access_kind),
-            global,
-            alloc_id,
-            span,
-        )
+        let cause = diagnostics::AccessCause::Explicit(access_kind);
+        self.record_step(StepKind::access(prov, cause, range), span, |tree| {
+            tree.perform_access(prov, range, access_kind, cause, global, alloc_id, span)
+        })
     }
 
     /// Check that this pointer has permission to deallocate this range.
@@ -80,7 +84,9 @@ pub fn before_memory_deallocation(
     ) -> InterpResult<'tcx> {
         let global = machine.borrow_tracker.as_ref().unwrap();
         let span = machine.current_user_relevant_span();
-        self.dealloc(prov, alloc_range(Size::ZERO, size), global, alloc_id, span)
+        let range = alloc_range(Size::ZERO, size);
+        let step = StepKind::access(prov, diagnostics::AccessCause::Dealloc, range);
+        self.record_step(step, span, |tree| tree.dealloc(prov, range, global, alloc_id, span))
     }
 
     /// A tag just lost its protector.
@@ -97,7 +103,9 @@ pub fn release_protector(
         alloc_id: AllocId, // diagnostics
     ) -> InterpResult<'tcx> {
         let span = machine.current_user_relevant_span();
-        self.perform_protector_end_access(tag, global, alloc_id, span)?;
+        self.record_step(StepKind::ProtectorEnd { tag }, span, |tree| {
+            tree.perform_protector_end_access(tag, global, alloc_id, span)
+        })?;
 
         self.update_exposure_for_protector_release(tag);
 
@@ -383,15 +391,20 @@ fn tb_reborrow(
                     size: Size::from_bytes(perm_range.end - perm_range.start),
                 };
 
-                tree_borrows.perform_access(
-                    parent_prov,
-                    range_in_alloc,
-                    access,
-                    diagnostics::AccessCause::Reborrow(access),
-                    this.machine.borrow_tracker.as_ref().unwrap(),
-                    alloc_id,
-                    this.machine.current_user_relevant_span(),
-                )?;
+                let cause = diagnostics::AccessCause::Reborrow(access);
+                let span = this.machine.current_user_relevant_span();
+                let step = StepKind::access(parent_prov, cause, range_in_alloc);
+                tree_borrows.record_step(step, span, |tree| {
+                    tree.perform_access(
+                        parent_prov,
+                        range_in_alloc,
+                        access,
+                        cause,
+                        this.machine.borrow_tracker.as_ref().unwrap(),
+                        alloc_id,
+                        span,
+                    )
+                })?;
 
                 // Also inform the data race model (but only if any bytes are actually affected).
                 if range_in_alloc.size.bytes() > 0 {
@@ -549,7 +562,11 @@ fn tb_expose_tag(&self, alloc_id: AllocId, tag: BorTag) -> InterpResult<'tcx> {
                 let global = this.machine.borrow_tracker.as_ref().unwrap();
                 let protected_tags = &global.borrow().protected_tags;
                 let protected = protected_tags.contains_key(&tag);
-                alloc_extra.borrow_tracker_tb().borrow_mut().expose_tag(tag, protected);
+                let mut tree_borrows = alloc_extra.borrow_tracker_tb().borrow_mut();
+                if this.machine.tree_borrows_history.is_some() {
+                    tree_borrows.record_exposure(tag, this.machine.current_user_relevant_span());
+                }
+                tree_borrows.expose_tag(tag, protected);
             }
             AllocKind::Function
             | AllocKind::VTable
@@ -571,6 +588,31 @@ fn print_tree(&mut self, alloc_id: AllocId, show_unnamed: bool) -> InterpResult<
         tree_borrows.print_tree(&borrow_tracker.protected_tags, show_unnamed)
     }
 
+    /// Writes the recorded history of the tree of `alloc_id` to `path`. `error` and `details`
+    /// describe the Undefined Behavior that ended the history.
+    fn tb_write_history(
+        &self,
+        alloc_id: AllocId,
+        path: &Path,
+        error: &str,
+        details: &[String],
+    ) -> io::Result<()> {
+        let this = self.eval_context_ref();
+        let Some(alloc_extra) = this.get_alloc_extra(alloc_id).discard_err() else {
+            return Err(io::Error::other(format!("{alloc_id:?} no longer exists")));
+        };
+        let tree_borrows = alloc_extra.borrow_tracker_tb().borrow();
+        let Some(history) = tree_borrows.history() else {
+            return Err(io::Error::other(format!("no history was recorded for {alloc_id:?}")));
+        };
+        let format = HistoryFormat::from_path(path);
+        let sm = this.tcx.sess.source_map();
+        let rendered = history.render(alloc_id, error, details, format, |span| {
+            sm.span_to_diagnostic_string(span)
+        });
+        fs::write(path, rendered)
+    }
+
     /// Give a name to the pointer, usually the name it has in the source code (for debugging).
     /// The name given is `name` and the pointer that receives it is the `nth_parent`
     /// of `ptr` (with 0 representing `ptr` itself)
@@ -23,6 +23,7 @@
     no_valid_exposed_references_error,
 };
 use super::foreign_access_skipping::IdempotentForeignAccess;
+use super::history::TreeHistory;
 use super::perms::{PermTransition, Permission};
 use super::tree_visitor::{ChildrenVisitMode, ContinueTraversal, NodeAppArgs, TreeVisitor};
 use super::unimap::{UniIndex, UniKeyMap, UniValMap};
@@ -304,6 +305,8 @@ pub struct Tree {
     ///
     /// Has array size 2 because that still ensures the minimum size for SmallVec.
     pub(super) roots: SmallVec<[UniIndex; 2]>,
+    /// Everything that happened in this tree, if `-Zmiri-tree-borrows-history` is set.
+    pub(super) history: Option<Box<TreeHistory>>,
 }
 
 /// A node in the borrow tree. Each node is uniquely identified by a tag via
@@ -377,7 +380,13 @@ pub fn new(root_tag: BorTag, size: Size, span: Span) -> Self {
             let exposed_cache = ExposedCache::default();
             DedupRangeMap::new(size, LocationTree { perms, exposed_cache })
         };
-        Self { roots: SmallVec::from_slice(&[root_idx]), nodes, locations, tag_mapping }
+        Self {
+            roots: SmallVec::from_slice(&[root_idx]),
+            nodes,
+            locations,
+            tag_mapping,
+            history: None,
+        }
     }
 }
 
@@ -468,6 +477,16 @@ pub(super) fn new_child(
             self.update_idempotent_foreign_access_after_retag(parent_idx, min_sifa);
         }
 
+        self.record_retag(
+            new_tag,
+            parent_prov,
+            base_offset,
+            &inside_perms,
+            outside_perm,
+            protected,
+            span,
+        );
+
         interp_ok(())
     }
 
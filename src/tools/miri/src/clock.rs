@@ -1,7 +1,8 @@
 use std::cell::Cell;
+use std::rc::Rc;
 use std::time::{Duration, Instant as StdInstant, SystemTime};
 
-use crate::MiriMachine;
+use crate::{HostTrace, MiriMachine};
 
 /// When using a virtual clock, this defines how many nanoseconds we pretend are passing for each
 /// basic block.
@@ -68,6 +69,9 @@ enum MonotonicClockKind {
         /// The "epoch" for this machine's monotone clock:
         /// the moment we consider to be time = 0.
         epoch: StdInstant,
+        /// The host trace in which the clock readings are recorded or from which they are
+        /// replayed, if any. Readings are stored relative to `epoch`.
+        trace: Option<Rc<HostTrace>>,
     },
     Virtual {
         /// The "current virtual time".
@@ -77,9 +81,9 @@ enum MonotonicClockKind {
 
 impl MonotonicClock {
     /// Create a new clock based on the availability of communication with the host.
-    pub fn new(communicate: bool) -> Self {
+    pub fn new(communicate: bool, trace: Option<Rc<HostTrace>>) -> Self {
         let kind = if communicate {
-            MonotonicClockKind::Host { epoch: StdInstant::now() }
+            MonotonicClockKind::Host { epoch: StdInstant::now(), trace }
         } else {
             MonotonicClockKind::Virtual { nanoseconds: 0.into() }
         };
@@ -102,6 +106,9 @@ pub fn tick(&self) {
     /// Sleep for the desired duration.
     pub fn sleep(&self, duration: Duration) {
         match &self.kind {
+            MonotonicClockKind::Host { trace: Some(trace), .. } if trace.is_replaying() => {
+                // The replayed clock readings already account for the time we slept.
+            }
             MonotonicClockKind::Host { .. } => std::thread::sleep(duration),
             MonotonicClockKind::Virtual { nanoseconds } => {
                 // Just pretend that we have slept for some time.
@@ -117,7 +124,7 @@ pub fn sleep(&self, duration: Duration) {
     /// Return the `epoch` instant (time = 0), to convert between monotone instants and absolute durations.
     pub fn epoch(&self) -> Instant {
         match &self.kind {
-            MonotonicClockKind::Host { epoch } => Instant { kind: InstantKind::Host(*epoch) },
+            MonotonicClockKind::Host { epoch, .. } => Instant { kind: InstantKind::Host(*epoch) },
             MonotonicClockKind::Virtual { .. } =>
                 Instant { kind: InstantKind::Virtual { nanoseconds: 0 } },
         }
@@ -125,8 +132,12 @@ pub fn epoch(&self) -> Instant {
 
     pub fn now(&self) -> Instant {
         match &self.kind {
-            MonotonicClockKind::Host { .. } =>
+            MonotonicClockKind::Host { trace: None, .. } =>
                 Instant { kind: InstantKind::Host(StdInstant::now()) },
+            MonotonicClockKind::Host { epoch, trace: Some(trace) } => {
+                let elapsed = trace.traced_infallible("now", || epoch.elapsed(), Duration::ZERO);
+                Instant { kind: InstantKind::Host(*epoch + elapsed) }
+            }
             MonotonicClockKind::Virtual { nanoseconds } =>
                 Instant { kind: InstantKind::Virtual { nanoseconds: nanoseconds.get() } },
         }
@@ -192,10 +203,10 @@ pub fn timeout(
         // First let's figure out what "zero" means for the given clock and style.
         let zero = match clock {
             TimeoutClock::RealTime => {
-                assert!(self.communicate(), "cannot have `RealTime` timeout with isolation");
+                assert!(self.observes_host(), "cannot have `RealTime` timeout with isolation");
                 Deadline::RealTime(match style {
                     TimeoutStyle::Absolute => SystemTime::UNIX_EPOCH,
-                    TimeoutStyle::Relative => SystemTime::now(),
+                    TimeoutStyle::Relative => self.system_time_now(),
                 })
             }
             TimeoutClock::Monotonic =>
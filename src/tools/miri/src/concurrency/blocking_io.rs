@@ -1,6 +1,7 @@
 use std::cell::RefMut;
 use std::collections::BTreeMap;
 use std::io;
+use std::str::SplitAsciiWhitespace;
 use std::time::Duration;
 
 use mio::event::Source;
@@ -62,6 +63,42 @@ fn from(event: &mio::event::Event) -> Self {
     }
 }
 
+/// The readiness events of one poll, stored in the host trace as the number of events followed by
+/// the id and the readiness of each source that received one.
+struct PolledEvents(Vec<(FdId, Readiness)>);
+
+impl TraceValue for PolledEvents {
+    fn encode(&self, out: &mut String) {
+        self.0.len().encode(out);
+        for (id, readiness) in &self.0 {
+            id.to_usize().encode(out);
+            readiness.readable.encode(out);
+            readiness.writable.encode(out);
+            readiness.read_closed.encode(out);
+            readiness.write_closed.encode(out);
+            readiness.error.encode(out);
+        }
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        let len = usize::decode(tokens)?;
+        let events = (0..len)
+            .map(|_| {
+                let id = FdId::new_unchecked(usize::decode(tokens)?);
+                let readiness = Readiness {
+                    readable: bool::decode(tokens)?,
+                    writable: bool::decode(tokens)?,
+                    read_closed: bool::decode(tokens)?,
+                    write_closed: bool::decode(tokens)?,
+                    error: bool::decode(tokens)?,
+                };
+                Some((id, readiness))
+            })
+            .collect::<Option<_>>()?;
+        Some(PolledEvents(events))
+    }
+}
+
 struct BlockingIoSource {
     /// The source file description which is registered into the poll. We only store weak references
     /// such that source file descriptions can be destroyed whilst they are registered. They will
@@ -84,13 +121,18 @@ struct BlockingIoSource {
 /// once a certain [`Readiness`] for an I/O source is satisfied.
 ///
 /// Since blocking host I/O is inherently non-deterministic, no method on this
-/// manager should be called when isolation is enabled. The only exception is
-/// the [`BlockingIoManager::new`] function to create the manager. Everywhere else,
-/// we assert that isolation is disabled!
+/// manager should be called when isolation is enabled, unless we are replaying
+/// a host trace. The only exception is the [`BlockingIoManager::new`] function
+/// to create the manager. Everywhere else, we assert that the host is observed!
+///
+/// When replaying a host trace, there is no [`Poll`] and the sources have no host
+/// counterpart: the events of every poll are taken from the trace instead.
 pub struct BlockingIoManager {
     /// Poll instance to monitor I/O events from the OS.
     /// This is only [`None`] when Miri is run with isolation enabled.
     poll: Option<Poll>,
+    /// Whether we are replaying a host trace that was recorded with isolation disabled.
+    replays_host: bool,
     /// Buffer used to store the ready I/O events when calling [`Poll::poll`].
     /// This is not part of the state and only stored to avoid allocating a
     /// new buffer for every poll.
@@ -103,9 +145,10 @@ pub struct BlockingIoManager {
 impl BlockingIoManager {
     /// Create a new blocking I/O manager instance based on the availability
     /// of communication with the host.
-    pub fn new(communicate: bool) -> Result<Self, io::Error> {
+    pub fn new(communicate: bool, replays_host: bool) -> Result<Self, io::Error> {
         let manager = Self {
             poll: communicate.then_some(Poll::new()?),
+            replays_host,
             events: Events::with_capacity(IO_EVENT_CAPACITY),
             sources: BTreeMap::default(),
         };
@@ -119,27 +162,49 @@ fn poll<'tcx>(
         ecx: &mut MiriInterpCx<'tcx>,
         timeout: Option<Duration>,
     ) -> InterpResult<'tcx, Result<(), io::Error>> {
-        let poll = ecx
-            .machine
-            .blocking_io
-            .poll
-            .as_mut()
-            .expect("Blocking I/O should not be called with isolation enabled");
-
-        // Poll for new I/O events from OS and store them in the events buffer.
-        if let Err(err) = poll.poll(&mut ecx.machine.blocking_io.events, timeout) {
-            return interp_ok(Err(err));
+        let machine = &mut ecx.machine;
+        if timeout == Some(Duration::ZERO) && machine.blocking_io.sources.is_empty() {
+            // Without any sources, there cannot be any events, and there is nothing to wait for.
+            return interp_ok(Ok(()));
+        }
+
+        let mut poll_host = || {
+            let BlockingIoManager { poll, events, .. } = &mut machine.blocking_io;
+            let poll =
+                poll.as_mut().expect("Blocking I/O should not be called with isolation enabled");
+            // Poll for new I/O events from OS and store them in the events buffer.
+            let result = poll.poll(events, timeout).map(|()| {
+                let events = events.iter().map(|event| {
+                    // We know all tokens are valid `FdId`.
+                    (FdId::new_unchecked(event.token().0), Readiness::from(event))
+                });
+                PolledEvents(events.collect())
+            });
+            interp_ok(result)
+        };
+        // The events are recorded in the host trace, or replayed from it, like any other result
+        // of a host operation. `MiriMachine::traced` would borrow `machine.blocking_io` as well.
+        let result = match &machine.host_trace {
+            None => poll_host(),
+            Some(trace) => trace.traced("poll", poll_host),
+        }?;
+        let events = match result {
+            Ok(PolledEvents(events)) => events,
+            Err(err) => return interp_ok(Err(err)),
         };
+        if ecx.machine.replaying()
+            && let Some((fd_id, _)) = events.iter().find(|(fd_id, _)| {
+                !ecx.machine.blocking_io.sources.get(fd_id).is_some_and(|s| !s.fd.is_closed())
+            })
+        {
+            throw_machine_stop!(TerminationInfo::ReplayDiverged(format!(
+                "the recorded run received an event for {fd_id:?}, which is not an open host source"
+            )));
+        }
 
-        let event_fds = ecx
-            .machine
-            .blocking_io
-            .events
-            .iter()
-            .map(|event| {
-                let token = event.token();
-                // We know all tokens are valid `FdId`.
-                let fd_id = FdId::new_unchecked(token.0);
+        let event_fds = events
+            .into_iter()
+            .map(|(fd_id, readiness)| {
                 let source = ecx
                     .machine
                     .blocking_io
@@ -152,7 +217,7 @@ fn poll<'tcx>(
 
                 assert_eq!(fd.id(), fd_id);
                 // Update the readiness of the source.
-                *fd.get_readiness_mut() |= Readiness::from(event);
+                *fd.get_readiness_mut() |= readiness;
                 // Put FD into `event_fds` list.
                 fd
             })
@@ -193,23 +258,26 @@ fn poll<'tcx>(
 
     /// Register a source file description to the blocking I/O poll.
     pub fn register(&mut self, source_fd: FileDescriptionRef<dyn SourceFileDescription>) {
-        let poll =
-            self.poll.as_ref().expect("Blocking I/O should not be called with isolation enabled");
-
         let id = source_fd.id();
-        let token = Token(id.to_usize());
-
-        // All possible interests.
-        // We only care about the readable and writable interests because those are the only
-        // interests which are available on all platforms. Internally, mio also
-        // registers an error interest.
-        let interest = Interest::READABLE | Interest::WRITABLE;
-
-        // Treat errors from registering as fatal. On UNIX hosts this can only
-        // fail due to system resource errors (e.g. ENOMEM or ENOSPC) or when the source is already registered.
-        source_fd
-            .with_source(&mut |source| poll.registry().register(source, token, interest))
-            .unwrap();
+
+        if let Some(poll) = &self.poll {
+            let token = Token(id.to_usize());
+
+            // All possible interests.
+            // We only care about the readable and writable interests because those are the only
+            // interests which are available on all platforms. Internally, mio also
+            // registers an error interest.
+            let interest = Interest::READABLE | Interest::WRITABLE;
+
+            // Treat errors from registering as fatal. On UNIX hosts this can only
+            // fail due to system resource errors (e.g. ENOMEM or ENOSPC) or when the source is already registered.
+            source_fd
+                .with_source(&mut |source| poll.registry().register(source, token, interest))
+                .unwrap();
+        } else {
+            // When replaying, the source has no host counterpart: its events come from the trace.
+            assert!(self.replays_host, "Blocking I/O should not be called with isolation enabled");
+        }
 
         let source = BlockingIoSource {
             fd: FileDescriptionRef::downgrade(&source_fd),
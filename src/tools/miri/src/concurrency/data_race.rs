@@ -1041,7 +1041,8 @@ pub fn new_allocation(
                 | MiriMemoryKind::WinHeap
                 | MiriMemoryKind::WinLocal
                 | MiriMemoryKind::Mmap
-                | MiriMemoryKind::SocketAddress,
+                | MiriMemoryKind::SocketAddress
+                | MiriMemoryKind::Native,
             )
             | MemoryKind::Stack => {
                 let (alloc_index, clocks) = global.active_thread_state(thread_mgr);
@@ -1591,6 +1592,11 @@ fn validate_atomic_op<A: Debug + Copy>(
             size.bytes()
         );
 
+        // Let the exploration know about this access before the operation updates our clocks.
+        if let Some(explore) = &this.machine.explore {
+            explore.access(&this.machine, alloc_id, base_offset, !access.is_read());
+        }
+
         let current_span = this.machine.current_user_relevant_span();
         // Perform the atomic operation.
         data_race.maybe_perform_sync_operation(
@@ -1995,6 +2001,26 @@ fn active_thread_index(&self, thread_mgr: &ThreadManager<'_>) -> VectorIdx {
         self.thread_index(active_thread_id)
     }
 
+    /// Returns the vector index and timestamp of the current point of the active thread.
+    pub(super) fn active_thread_timestamp(
+        &self,
+        thread_mgr: &ThreadManager<'_>,
+    ) -> (VectorIdx, VTimestamp) {
+        let (index, clocks) = self.active_thread_state(thread_mgr);
+        (index, clocks.clock[index])
+    }
+
+    /// Checks whether the point identified by `timestamp` (as returned by
+    /// `active_thread_timestamp`) happens-before the current point of the active thread.
+    pub(super) fn happened_before_active(
+        &self,
+        (index, timestamp): (VectorIdx, VTimestamp),
+        thread_mgr: &ThreadManager<'_>,
+    ) -> bool {
+        let (_, clocks) = self.active_thread_state(thread_mgr);
+        timestamp <= clocks.clock[index]
+    }
+
     // SC ATOMIC STORE rule in the paper.
     pub(super) fn sc_write(&self, thread_mgr: &ThreadManager<'_>) {
         let (index, clocks) = self.active_thread_state(thread_mgr);
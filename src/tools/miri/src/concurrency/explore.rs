@@ -0,0 +1,469 @@
+//! Bounded systematic exploration of thread interleavings.
+//!
+//! In this mode, the program is executed repeatedly, and every execution follows a different
+//! schedule. This is a stateless model checker in the style of Loom or CHESS: each execution
+//! records the choices it made (which thread to run at each scheduling point, and which store each
+//! weak memory load reads from), and the next execution replays a prefix of those choices before
+//! taking a different one.
+//!
+//! To keep the number of executions manageable, we use two reductions:
+//! - Dynamic partial-order reduction (DPOR): we only consider running another thread at a
+//!   scheduling point if that thread later performs an operation that conflicts with an operation
+//!   performed after that point, and that is not ordered with it by happens-before. Happens-before
+//!   is tracked by the vector clocks of the data race detector.
+//! - Preemption bounding: executions that preempt a thread that could have kept running, or that
+//!   load an outdated value from a store buffer, more than `preemption_bound` times are skipped.
+//!
+//! Scheduling points are placed after every atomic access, every operation on a synchronization
+//! object, every thread creation, and whenever the active thread blocks, terminates, or yields.
+
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::num::NonZeroI32;
+use std::rc::Rc;
+
+use rustc_abi::Size;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_index::Idx;
+
+use super::thread::ThreadManager;
+use super::vector_clock::{VTimestamp, VectorIdx};
+use crate::*;
+
+/// Configuration for exploring thread interleavings.
+#[derive(Clone, Debug)]
+pub struct ExploreConfig {
+    /// The maximum number of preemptions and outdated loads in one execution.
+    pub preemption_bound: u32,
+    /// Stop after this many executions, even if not all interleavings were explored.
+    pub max_executions: Option<u64>,
+    /// Keep exploring after an execution failed.
+    pub keep_going: bool,
+}
+
+impl Default for ExploreConfig {
+    fn default() -> Self {
+        Self { preemption_bound: 2, max_executions: None, keep_going: false }
+    }
+}
+
+#[derive(Debug)]
+enum ChoiceKind {
+    /// Which thread to run next. The alternatives are indices of `candidates`' thread IDs.
+    Schedule {
+        /// The thread that was active before this point.
+        active: ThreadId,
+        /// Whether the active thread could have kept running, i.e. switching to any other thread
+        /// preempts it.
+        preemptible: bool,
+        /// The threads that can run at this point.
+        candidates: Vec<ThreadId>,
+    },
+    /// Which store an atomic load reads from. The alternatives are indices into the list of
+    /// stores the load may read from, where 0 is the latest store.
+    LoadFrom { candidates: usize },
+}
+
+#[derive(Debug)]
+struct ChoicePoint {
+    kind: ChoiceKind,
+    /// The alternative taken in the current execution.
+    chosen: usize,
+    /// Alternatives that still have to be explored.
+    pending: Vec<usize>,
+    /// Alternatives that were already explored, including `chosen`.
+    explored: Vec<usize>,
+    /// The number of preemptions and outdated loads before this point.
+    deviations: u32,
+}
+
+impl ChoicePoint {
+    fn cost(&self, alternative: usize) -> u32 {
+        let deviates = match &self.kind {
+            ChoiceKind::Schedule { active, preemptible, .. } =>
+                *preemptible && ThreadId::new(alternative) != *active,
+            ChoiceKind::LoadFrom { .. } => alternative != 0,
+        };
+        self.deviations.saturating_add(deviates.into())
+    }
+
+    fn add_pending(&mut self, alternative: usize) {
+        if !self.explored.contains(&alternative) && !self.pending.contains(&alternative) {
+            self.pending.push(alternative);
+        }
+    }
+}
+
+/// An access to a shared location, remembered to find operations that might be reordered.
+#[derive(Debug)]
+struct Access {
+    /// The scheduling point that started the segment in which the access happened.
+    point: usize,
+    thread: ThreadId,
+    write: bool,
+    /// The moment of the access in the data race detector's vector clocks.
+    timestamp: (VectorIdx, VTimestamp),
+}
+
+#[derive(Debug)]
+enum SwitchReason {
+    Preempted,
+    Yielded,
+    Blocked,
+}
+
+/// A change of the active thread, remembered to report the schedule of a failing execution.
+#[derive(Debug)]
+struct Switch {
+    from: ThreadId,
+    to: ThreadId,
+    reason: SwitchReason,
+}
+
+#[derive(Debug, Default)]
+struct ExploreState {
+    /// The choice points of the current execution. Up to the last one, they are replayed from the
+    /// previous execution.
+    choices: Vec<ChoicePoint>,
+    /// The index of the next choice point in the current execution.
+    next: usize,
+    /// The number of preemptions and outdated loads so far in the current execution.
+    deviations: u32,
+    /// The latest scheduling point in `choices`, if any.
+    current_segment: Option<usize>,
+    /// Whether the active thread performed an operation that might interact with other threads
+    /// since the last scheduling point.
+    visible_op: bool,
+    /// Accesses to shared locations in the current execution.
+    accesses: FxHashMap<(AllocId, Size), Vec<Access>>,
+    /// The thread switches of the current execution.
+    switches: Vec<Switch>,
+    /// The schedule of the last finished execution, formatted for the user.
+    schedule_report: String,
+    /// The number of executions started so far.
+    executions: u64,
+}
+
+impl ExploreState {
+    /// Returns the choice point that the current execution has to replay, if any. Errors if it is
+    /// not the expected kind of choice.
+    fn replayed<'tcx>(
+        &self,
+        matches: impl FnOnce(&ChoiceKind) -> bool,
+    ) -> InterpResult<'tcx, Option<usize>> {
+        let Some(point) = self.choices.get(self.next) else {
+            return interp_ok(None);
+        };
+        if !matches(&point.kind) {
+            throw_unsup_format!(
+                "the program behaved differently when it was executed again to explore another \
+                interleaving; exploring interleavings requires the program to be deterministic"
+            );
+        }
+        interp_ok(Some(point.chosen))
+    }
+
+    /// Makes `thread` run at `point` in a future execution, or at an earlier point if running it
+    /// at `point` would exceed the preemption bound.
+    fn add_backtrack(&mut self, point: usize, thread: ThreadId, bound: u32) {
+        let ChoiceKind::Schedule { candidates, .. } = &self.choices[point].kind else {
+            unreachable!("accesses are always attributed to scheduling points")
+        };
+        // If `thread` could not run at that point, try all threads that could.
+        let threads = if candidates.contains(&thread) { vec![thread] } else { candidates.clone() };
+        for thread in threads {
+            let alternative = thread.index();
+            let target = if self.choices[point].cost(alternative) <= bound {
+                Some(point)
+            } else {
+                // Look for an earlier point where switching to `thread` is not a preemption.
+                (0..point).rev().find(|&earlier| {
+                    let choice = &self.choices[earlier];
+                    matches!(
+                        &choice.kind,
+                        ChoiceKind::Schedule { preemptible: false, candidates, .. }
+                            if candidates.contains(&thread)
+                    ) && choice.cost(alternative) <= bound
+                })
+            };
+            if let Some(target) = target {
+                self.choices[target].add_pending(alternative);
+            }
+        }
+    }
+}
+
+/// The state of an exploration. It persists across all executions, so it is behind an `Rc`.
+#[derive(Debug)]
+pub struct ExploreCtx {
+    config: ExploreConfig,
+    state: RefCell<ExploreState>,
+}
+
+impl ExploreCtx {
+    fn new(config: ExploreConfig) -> Self {
+        Self { config, state: Default::default() }
+    }
+
+    /// Resets the per-execution state before the next execution starts.
+    fn prepare_next_execution(&self) {
+        let mut state = self.state.borrow_mut();
+        state.next = 0;
+        state.deviations = 0;
+        state.current_segment = None;
+        state.visible_op = false;
+        state.accesses.clear();
+        state.switches.clear();
+        state.executions = state.executions.strict_add(1);
+    }
+
+    /// Picks the choices of the next execution. Returns `false` if everything was explored.
+    fn advance(&self) -> bool {
+        let mut state = self.state.borrow_mut();
+        let reached = state.next;
+        state.choices.truncate(reached);
+        while let Some(point) = state.choices.last_mut() {
+            if let Some(alternative) = point.pending.pop() {
+                point.chosen = alternative;
+                point.explored.push(alternative);
+                return true;
+            }
+            state.choices.pop();
+        }
+        false
+    }
+
+    /// Returns whether the active thread performed an operation that might interact with other
+    /// threads since the last scheduling point. If so, this is a scheduling point.
+    pub(super) fn take_visible_op(&self) -> bool {
+        std::mem::take(&mut self.state.borrow_mut().visible_op)
+    }
+
+    /// Marks the current operation as one that might interact with other threads.
+    pub(crate) fn visible_op(&self) {
+        self.state.borrow_mut().visible_op = true;
+    }
+
+    /// Picks the thread to run at a scheduling point. Returns `None` if no thread can run.
+    pub(super) fn schedule_thread<'tcx>(
+        &self,
+        threads: &ThreadManager<'tcx>,
+    ) -> InterpResult<'tcx, Option<ThreadId>> {
+        let mut state = self.state.borrow_mut();
+        state.visible_op = false;
+
+        let active = threads.active_thread();
+        let yielded = threads.yield_active_thread;
+        let preemptible = threads.active_thread_ref().is_enabled() && !yielded;
+        // Use round-robin order by default, like the regular scheduler.
+        let mut candidates: Vec<ThreadId> = threads
+            .all_threads()
+            .skip(active.index().strict_add(1))
+            .chain(threads.all_threads().take(active.index().strict_add(1)))
+            .filter(|(_id, thread)| thread.is_enabled())
+            .map(|(id, _thread)| id)
+            .collect();
+        if yielded && candidates.len() > 1 {
+            // A thread that yields wants another thread to run.
+            candidates.retain(|&id| id != active);
+        }
+        if preemptible {
+            // By default, keep running the active thread.
+            candidates.rotate_right(1);
+        }
+        let Some(&default) = candidates.first() else {
+            return interp_ok(None);
+        };
+
+        let chosen = if candidates.len() == 1 {
+            default
+        } else {
+            let replayed = state.replayed(|kind| {
+                matches!(
+                    kind,
+                    ChoiceKind::Schedule { candidates: recorded, .. } if *recorded == candidates
+                )
+            })?;
+            let chosen = match replayed {
+                Some(chosen) => ThreadId::new(chosen),
+                None => {
+                    let deviations = state.deviations;
+                    state.choices.push(ChoicePoint {
+                        kind: ChoiceKind::Schedule { active, preemptible, candidates },
+                        chosen: default.index(),
+                        pending: vec![],
+                        explored: vec![default.index()],
+                        deviations,
+                    });
+                    default
+                }
+            };
+            state.current_segment = Some(state.next);
+            state.next = state.next.strict_add(1);
+            if preemptible && chosen != active {
+                state.deviations = state.deviations.strict_add(1);
+            }
+            chosen
+        };
+
+        if chosen != active {
+            let reason = if preemptible {
+                SwitchReason::Preempted
+            } else if yielded {
+                SwitchReason::Yielded
+            } else {
+                SwitchReason::Blocked
+            };
+            state.switches.push(Switch { from: active, to: chosen, reason });
+        }
+        interp_ok(Some(chosen))
+    }
+
+    /// Picks which of `candidates` stores an atomic load reads from, where 0 is the latest store.
+    pub(super) fn choose_load<'tcx>(&self, candidates: usize) -> InterpResult<'tcx, usize> {
+        let mut state = self.state.borrow_mut();
+        if candidates == 1 {
+            return interp_ok(0);
+        }
+        let replayed = state.replayed(|kind| {
+            matches!(kind, ChoiceKind::LoadFrom { candidates: recorded } if *recorded == candidates)
+        })?;
+        let chosen = match replayed {
+            Some(chosen) => chosen,
+            None => {
+                let deviations = state.deviations;
+                // Every outdated load counts against the bound, so all of them are equally costly.
+                let pending = if deviations < self.config.preemption_bound {
+                    (1..candidates).collect()
+                } else {
+                    vec![]
+                };
+                state.choices.push(ChoicePoint {
+                    kind: ChoiceKind::LoadFrom { candidates },
+                    chosen: 0,
+                    pending,
+                    explored: vec![0],
+                    deviations,
+                });
+                0
+            }
+        };
+        state.next = state.next.strict_add(1);
+        if chosen != 0 {
+            state.deviations = state.deviations.strict_add(1);
+        }
+        interp_ok(chosen)
+    }
+
+    /// Records an access of the active thread to a shared location: an atomic access, or an
+    /// operation on a synchronization object. Every earlier conflicting access by another thread
+    /// that does not happen-before this one could also have happened after it, so we make sure to
+    /// explore that order as well.
+    pub(crate) fn access(
+        &self,
+        machine: &MiriMachine<'_>,
+        alloc_id: AllocId,
+        offset: Size,
+        write: bool,
+    ) {
+        let mut state = self.state.borrow_mut();
+        state.visible_op = true;
+        let Some(data_race) = machine.data_race.as_vclocks_ref() else {
+            return;
+        };
+        let Some(point) = state.current_segment else {
+            // So far, only one thread could ever run: there is nothing to reorder.
+            return;
+        };
+        let threads = &machine.threads;
+        let thread = threads.active_thread();
+        let accesses = state.accesses.entry((alloc_id, offset)).or_default();
+        let backtracks: Vec<usize> = accesses
+            .iter()
+            .filter(|access| {
+                access.thread != thread
+                    && (access.write || write)
+                    && !data_race.happened_before_active(access.timestamp, threads)
+            })
+            .map(|access| access.point)
+            .collect();
+        accesses.push(Access {
+            point,
+            thread,
+            write,
+            timestamp: data_race.active_thread_timestamp(threads),
+        });
+        for point in backtracks {
+            state.add_backtrack(point, thread, self.config.preemption_bound);
+        }
+    }
+
+    /// Remembers the schedule of the execution that just ended, to report it if it failed.
+    pub(crate) fn finish_execution(&self, threads: &ThreadManager<'_>) {
+        let mut state = self.state.borrow_mut();
+        let mut report = String::new();
+        for Switch { from, to, reason } in &state.switches {
+            let from = threads.get_thread_display_name(*from);
+            let to = threads.get_thread_display_name(*to);
+            let reason = match reason {
+                SwitchReason::Preempted => format!("preempting `{from}`"),
+                SwitchReason::Yielded => format!("after `{from}` yielded"),
+                SwitchReason::Blocked => format!("after `{from}` blocked or terminated"),
+            };
+            writeln!(report, "  thread `{to}` runs, {reason}").unwrap();
+        }
+        if report.is_empty() {
+            report.push_str("  (only the main thread ran)\n");
+        }
+        state.schedule_report = report;
+    }
+}
+
+/// Do a complete run of the program in exploration mode.
+/// This will call `eval_entry` multiple times, until either:
+/// - An error is detected (unless `keep_going` is set).
+/// - All interleavings within the preemption bound are explored.
+/// - The maximum number of executions is reached.
+pub fn run_explore_mode(
+    config: &MiriConfig,
+    eval_entry: impl Fn(Rc<ExploreCtx>) -> Result<(), NonZeroI32>,
+) -> Result<(), NonZeroI32> {
+    let explore_config = config.explore.clone().unwrap();
+    let explore_ctx = Rc::new(ExploreCtx::new(explore_config.clone()));
+    let mut exit_code = 0i32;
+    let mut num_failed = 0u64;
+    loop {
+        explore_ctx.prepare_next_execution();
+        let res = eval_entry(explore_ctx.clone());
+        let executions = explore_ctx.state.borrow().executions;
+        if let Err(return_code) = res {
+            eprintln!("FAILING SCHEDULE (execution {executions}):");
+            eprint!("{}", explore_ctx.state.borrow().schedule_report);
+            if !explore_config.keep_going {
+                return Err(return_code);
+            }
+            // Preserve the "maximum" return code (when interpreted as `u32`), like many-seeds mode.
+            exit_code =
+                exit_code.cast_unsigned().max(return_code.get().cast_unsigned()).cast_signed();
+            num_failed = num_failed.strict_add(1);
+        }
+        if !explore_ctx.advance() {
+            break;
+        }
+        if explore_config.max_executions.is_some_and(|max| executions >= max) {
+            eprintln!(
+                "warning: stopping after {executions} executions; some interleavings were not explored"
+            );
+            break;
+        }
+    }
+    let executions = explore_ctx.state.borrow().executions;
+    let bound = explore_config.preemption_bound;
+    if num_failed > 0 {
+        eprintln!("{num_failed}/{executions} EXECUTIONS FAILED (preemption bound: {bound})");
+        Err(NonZeroI32::new(exit_code).unwrap())
+    } else {
+        eprintln!("Explored {executions} executions without errors (preemption bound: {bound})");
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@
 pub mod cpu_affinity;
 pub mod data_race;
 mod data_race_handler;
+mod explore;
 pub mod init_once;
 pub mod scheduler;
 pub mod sync;
@@ -23,5 +24,6 @@
 mod genmc;
 
 pub use self::data_race_handler::{AllocDataRaceHandler, GlobalDataRaceHandler};
+pub use self::explore::{ExploreConfig, ExploreCtx, run_explore_mode};
 pub use self::genmc::{ExitType, GenmcConfig, GenmcCtx, GenmcEvalContextExt, run_genmc_mode};
 pub use self::vector_clock::VClock;
@@ -48,7 +48,8 @@ fn run_on_stack_empty(&mut self) -> InterpResult<'tcx, Poll<()>> {
     /// long as we can and switch only when we have to (the active thread was
     /// blocked, terminated, or has explicitly asked to be preempted).
     ///
-    /// If GenMC mode is active, the scheduling is instead handled by GenMC.
+    /// If GenMC mode is active, the scheduling is instead handled by GenMC. When exploring
+    /// interleavings, the exploration picks which thread to run at each scheduling point.
     fn schedule(&mut self) -> InterpResult<'tcx, SchedulingAction> {
         let this = self.eval_context_mut();
 
@@ -81,7 +82,12 @@ fn schedule(&mut self) -> InterpResult<'tcx, SchedulingAction> {
         // We are not in GenMC mode, so we control the scheduling.
         let thread_manager = &this.machine.threads;
         // Check if we can just keep running the current thread.
-        if thread_manager.active_thread_ref().is_enabled() && !thread_manager.yield_active_thread {
+        if thread_manager.active_thread_ref().is_enabled()
+            && !thread_manager.yield_active_thread
+            // When exploring interleavings, other threads may run after any operation that might
+            // interact with them.
+            && !this.machine.explore.as_ref().is_some_and(|explore| explore.take_visible_op())
+        {
             // The currently active thread is still enabled, just continue with it.
             return interp_ok(SchedulingAction::ExecuteStep);
         }
@@ -94,7 +100,7 @@ fn schedule(&mut self) -> InterpResult<'tcx, SchedulingAction> {
         // case there is another call in `ReadinessWatcher::get_ready_interests`.
         DelayedReadinessUpdates::process(this)?;
 
-        if this.machine.communicate() {
+        if this.machine.observes_host() {
             // When isolation is disabled we need to check for events for threads
             // which are blocked on host I/O. Unlike the `poll_and_unblock` before
             // any foreign item, the call here is needed to ensure that threads which
@@ -123,21 +129,38 @@ fn schedule(&mut self) -> InterpResult<'tcx, SchedulingAction> {
         // the threads before the current one and then the current thread itself (i.e., this iterator acts
         // like `threads.rotate_left(self.active_thread.index() + 1)`. This ensures that if we pick the first
         // eligible thread, we do regular round-robin scheduling, and all threads get a chance to take a step.
-        let mut threads_iter = thread_manager
-            .all_threads()
-            .skip(thread_manager.active_thread().index() + 1)
-            .chain(thread_manager.all_threads().take(thread_manager.active_thread().index() + 1))
-            .filter(|(_id, thread)| thread.is_enabled());
-        // Pick a new thread, and switch to it.
-        let new_thread = if thread_manager.fixed_scheduling() {
-            let next = threads_iter.next();
-            drop(threads_iter);
-            next
+        // When exploring interleavings, the exploration picks the thread instead.
+        let new_thread = if let Some(explore) = &this.machine.explore {
+            explore.schedule_thread(thread_manager)?
         } else {
-            threads_iter.choose(rng)
+            let mut threads_iter = thread_manager
+                .all_threads()
+                .skip(thread_manager.active_thread().index() + 1)
+                .chain(
+                    thread_manager.all_threads().take(thread_manager.active_thread().index() + 1),
+                )
+                .filter(|(_id, thread)| thread.is_enabled());
+            // Pick a new thread, and switch to it.
+            let new_thread = if thread_manager.fixed_scheduling() {
+                let next = threads_iter.next();
+                drop(threads_iter);
+                next
+            } else {
+                threads_iter.choose(rng)
+            };
+            new_thread.map(|(id, _thread)| id)
         };
 
-        if let Some((id, _thread)) = new_thread {
+        // The set of enabled threads can depend on host-observed timing and I/O, so the decision
+        // is part of the host trace. This also detects early when a replay goes astray.
+        let new_thread = this.machine.traced("schedule", || interp_ok(new_thread))?;
+        let thread_manager = &mut this.machine.threads;
+        if let Some(id) = new_thread {
+            if !thread_manager.all_threads().any(|(t, thread)| t == id && thread.is_enabled()) {
+                throw_machine_stop!(TerminationInfo::ReplayDiverged(format!(
+                    "the recorded run scheduled thread {id:?}, but that thread cannot run"
+                )));
+            }
             if thread_manager.active_thread() != id {
                 thread_manager.set_active_thread(id);
             }
@@ -201,10 +224,11 @@ fn run_threads(&mut self) -> InterpResult<'tcx, !> {
                     this.step_current_thread()?;
                 }
                 SchedulingAction::SleepAndWaitForIo(duration) => {
-                    if this.machine.communicate() {
+                    if this.machine.observes_host() {
                         // When we're running with isolation disabled, instead of
                         // strictly sleeping the duration we allow waking up
-                        // early for I/O events from the OS.
+                        // early for I/O events from the OS. When replaying, the
+                        // events are taken from the host trace.
 
                         this.poll_and_unblock(duration)?;
                     } else {
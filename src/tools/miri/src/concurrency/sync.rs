@@ -301,6 +301,10 @@ fn get_sync_or_init<'a, T: SyncObj>(
         }
         // This cannot fail now.
         let (alloc, offset, _) = this.ptr_get_alloc_id(ptr, 0).unwrap();
+        // Operations on synchronization objects conflict with each other.
+        if let Some(explore) = &this.machine.explore {
+            explore.access(&this.machine, alloc, offset, /* write */ true);
+        }
         let (alloc_extra, machine) = this.get_alloc_extra_mut(alloc).unwrap();
         // Due to borrow checker reasons, we have to do the lookup twice.
         if alloc_extra.get_sync::<T>(offset).is_none() {
@@ -348,6 +352,10 @@ fn get_immovable_sync_with_static_init<'a, T: SyncObj>(
         let init_field = obj.offset(init_offset, this.machine.layouts.u8, this)?;
 
         let (alloc, offset, _) = this.ptr_get_alloc_id(init_field.ptr(), 0)?;
+        // Operations on synchronization objects conflict with each other.
+        if let Some(explore) = &this.machine.explore {
+            explore.access(&this.machine, alloc, offset, /* write */ true);
+        }
         let (alloc_extra, _machine) = this.get_alloc_extra_mut(alloc)?;
         // Due to borrow checker reasons, we have to do the lookup twice.
         if alloc_extra.get_sync::<T>(offset).is_some() {
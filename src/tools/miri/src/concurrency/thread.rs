@@ -2,7 +2,7 @@
 
 use std::mem;
 use std::task::Poll;
-use std::time::{Duration, SystemTime};
+use std::time::Duration;
 
 use rand::RngExt;
 use rustc_abi::ExternAbi;
@@ -17,7 +17,7 @@
 
 use crate::concurrency::GlobalDataRaceHandler;
 use crate::shims::tls;
-use crate::*;
+use crate::{host_trace, *};
 
 /// What to do with TLS allocations from terminated threads
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -703,6 +703,10 @@ fn start_regular_thread(
                     new_thread_id,
                 )?,
         }
+        // The new thread may run right away when exploring interleavings.
+        if let Some(explore) = &this.machine.explore {
+            explore.visible_op();
+        }
         // Write the current thread-id, switch to the next thread later
         // to treat this write operation as occurring on the current thread.
         if let Some(thread_info_place) = thread {
@@ -828,7 +832,7 @@ fn block_thread(
         if deadline.is_some() && this.machine.data_race.as_genmc_ref().is_some() {
             panic!("Unimplemented: Timeouts not yet supported in GenMC mode.");
         }
-        if matches!(deadline, Some(Deadline::RealTime(_))) && !this.machine.communicate() {
+        if matches!(deadline, Some(Deadline::RealTime(_))) && !this.machine.observes_host() {
             panic!("cannot have `RealTime` timeout with isolation");
         }
         this.machine.threads.block_thread(reason, deadline, callback);
@@ -863,7 +867,7 @@ fn unblock_thread(&mut self, thread: ThreadId, reason: BlockReason) -> InterpRes
     /// If all ready threads have no deadline set, [`None`] is returned.
     fn unblock_expired_deadlines(&mut self) -> InterpResult<'tcx, Option<Duration>> {
         let this = self.eval_context_mut();
-        let communicate = this.machine.communicate();
+        let observes_host = this.machine.observes_host();
 
         let mut min_wait_time = Option::<Duration>::None;
         let mut callbacks = Vec::new();
@@ -875,8 +879,10 @@ fn unblock_expired_deadlines(&mut self) -> InterpResult<'tcx, Option<Duration>>
                         Deadline::Monotonic(instant) =>
                             instant.duration_since(this.machine.monotonic_clock.now()),
                         Deadline::RealTime(time) => {
-                            assert!(communicate, "cannot have `RealTime` timeout with isolation");
-                            time.duration_since(SystemTime::now()).unwrap_or(Duration::ZERO)
+                            assert!(observes_host, "cannot have `RealTime` timeout with isolation");
+                            let now =
+                                host_trace::system_time_now(this.machine.host_trace.as_deref());
+                            time.duration_since(now).unwrap_or(Duration::ZERO)
                         }
                     };
 
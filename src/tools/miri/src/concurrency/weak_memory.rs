@@ -297,6 +297,7 @@ fn buffered_read(
         thread_mgr: &ThreadManager<'_>,
         is_seqcst: bool,
         rng: &mut (impl rand::Rng + ?Sized),
+        explore: Option<&ExploreCtx>,
         validate: impl FnOnce(Option<&VClock>) -> InterpResult<'tcx>,
     ) -> InterpResult<'tcx, (Option<Scalar>, LoadRecency)> {
         // Having a live borrow to store_buffer while calling validate_atomic_load is fine
@@ -307,7 +308,7 @@ fn buffered_read(
             // as the race detector will update it
             let (.., clocks) = global.active_thread_state(thread_mgr);
             // Load from a valid entry in the store buffer
-            self.fetch_store(is_seqcst, &clocks, &mut *rng)
+            self.fetch_store(is_seqcst, &clocks, &mut *rng, explore)?
         };
 
         // Unlike in buffered_atomic_write, thread clock updates have to be done
@@ -335,13 +336,15 @@ fn buffered_write(
         interp_ok(())
     }
 
-    /// Selects a valid store element in the buffer.
+    /// Selects a valid store element in the buffer. When exploring interleavings, the exploration
+    /// picks the store instead of `rng`.
     fn fetch_store<R: rand::Rng + ?Sized>(
         &self,
         is_seqcst: bool,
         clocks: &ThreadClockSet,
         rng: &mut R,
-    ) -> (&StoreElement, LoadRecency) {
+        explore: Option<&ExploreCtx>,
+    ) -> InterpResult<'tcx, (&StoreElement, LoadRecency)> {
         use rand::seq::IteratorRandom;
         let mut found_sc = false;
         // FIXME: we want an inclusive take_while (stops after a false predicate, but
@@ -410,12 +413,19 @@ fn fetch_store<R: rand::Rng + ?Sized>(
                 }
             });
 
-        let chosen = candidates.choose(rng).expect("store buffer cannot be empty");
-        if std::ptr::eq(chosen, self.buffer.back().expect("store buffer cannot be empty")) {
-            (chosen, LoadRecency::Latest)
+        let chosen = if let Some(explore) = explore {
+            let candidates: Vec<_> = candidates.collect();
+            candidates[explore.choose_load(candidates.len())?]
         } else {
-            (chosen, LoadRecency::Outdated)
-        }
+            candidates.choose(rng).expect("store buffer cannot be empty")
+        };
+        interp_ok(
+            if std::ptr::eq(chosen, self.buffer.back().expect("store buffer cannot be empty")) {
+                (chosen, LoadRecency::Latest)
+            } else {
+                (chosen, LoadRecency::Outdated)
+            },
+        )
     }
 
     /// ATOMIC STORE IMPL in the paper
@@ -571,6 +581,7 @@ fn buffered_atomic_read(
                         &this.machine.threads,
                         atomic == AtomicReadOrd::SeqCst,
                         &mut *rng,
+                        this.machine.explore.as_deref(),
                         validate,
                     )?;
                     if global.track_outdated_loads && recency == LoadRecency::Outdated {
@@ -1,5 +1,6 @@
 use std::fmt::{self, Write};
 use std::num::NonZero;
+use std::path::PathBuf;
 use std::sync::Mutex;
 
 use rustc_abi::{Align, Size};
@@ -21,6 +22,8 @@ pub enum TerminationInfo {
     /// Miri was interrupted by a Ctrl+C from the user.
     Interrupted,
     UnsupportedInIsolation(String),
+    /// Replaying a host trace diverged from the recorded run.
+    ReplayDiverged(String),
     StackedBorrowsUb {
         msg: String,
         help: Vec<String>,
@@ -30,6 +33,8 @@ pub enum TerminationInfo {
         title: String,
         details: Vec<String>,
         history: tree_diagnostics::HistoryData,
+        /// The allocation whose tree forbade the operation.
+        alloc_id: AllocId,
     },
     Int2PtrWithStrictProvenance,
     /// GenMC deemed this execution invalid, so Miri drops it, i.e., it skips to the next execution
@@ -75,6 +80,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             Abort(msg) => write!(f, "{msg}"),
             Interrupted => write!(f, "interpretation was interrupted"),
             UnsupportedInIsolation(msg) => write!(f, "{msg}"),
+            ReplayDiverged(msg) => write!(f, "replaying host trace diverged: {msg}"),
             Int2PtrWithStrictProvenance =>
                 write!(
                     f,
@@ -159,6 +165,14 @@ pub enum NonHaltingDiagnostic {
     SocketAddressResolution {
         error: std::io::Error,
     },
+    FuzzingDone {
+        runs: u64,
+        corpus: usize,
+        covered: usize,
+    },
+    FuzzHarnessPanicked {
+        artifact: PathBuf,
+    },
 }
 
 /// Level of Miri specific diagnostics
@@ -254,8 +268,10 @@ pub fn report_result<'tcx>(
             &Exit { code, leak_check } => return Some((code, leak_check)),
             Abort(_) => Some("abnormal termination"),
             Interrupted => None,
-            UnsupportedInIsolation(_) | Int2PtrWithStrictProvenance | UnsupportedForeignItem(_) =>
-                Some("unsupported operation"),
+            UnsupportedInIsolation(_)
+            | ReplayDiverged(_)
+            | Int2PtrWithStrictProvenance
+            | UnsupportedForeignItem(_) => Some("unsupported operation"),
             StackedBorrowsUb { .. } | TreeBorrowsUb { .. } | DataRace { .. } =>
                 Some("Undefined Behavior"),
             GenmcInvalid => {
@@ -296,6 +312,10 @@ pub fn report_result<'tcx>(
         };
         #[rustfmt::skip]
         let helps = match info {
+            UnsupportedInIsolation(_) if ecx.machine.replaying() =>
+                vec![
+                    note!("host traces do not record this operation, so it cannot be replayed"),
+                ],
             UnsupportedInIsolation(_) =>
                 vec![
                     note!("set `MIRIFLAGS=-Zmiri-disable-isolation` to disable isolation;"),
@@ -306,6 +326,11 @@ pub fn report_result<'tcx>(
                     note!("this means the program tried to do something Miri does not support; it does not indicate a bug in the program"),
                 ]
             }
+            ReplayDiverged(_) =>
+                vec![
+                    note!("the program did not perform the same host operations as in the recorded run"),
+                    note!("this can happen if the program or the Miri flags changed since the trace was recorded"),
+                ],
             StackedBorrowsUb { help, history, .. } => {
                 labels.extend(help.clone());
                 let mut helps = vec![
@@ -323,7 +348,7 @@ pub fn report_result<'tcx>(
                 }
                 helps
             },
-            TreeBorrowsUb { title: _, details, history } => {
+            TreeBorrowsUb { title, details, history, alloc_id } => {
                 let mut helps = vec![
                     note!("this indicates a potential bug in the program: it performed an invalid operation, but the Tree Borrows rules it violated are still experimental"),
                     note!("see https://github.com/rust-lang/unsafe-code-guidelines/blob/master/wip/tree-borrows.md for further information"),
@@ -334,6 +359,14 @@ pub fn report_result<'tcx>(
                 for event in history.events.clone() {
                     helps.push(event);
                 }
+                if let Some(path) = &ecx.machine.tree_borrows_history {
+                    match ecx.tb_write_history(*alloc_id, path, title, details) {
+                        Ok(()) =>
+                            helps.push(note!("the history of {alloc_id:?} was written to `{}`", path.display())),
+                        Err(err) =>
+                            helps.push(note!("failed to write the history of {alloc_id:?} to `{}`: {err}", path.display())),
+                    }
+                }
                 helps
             }
             MultipleSymbolDefinitions { first, first_crate, second, second_crate, .. } =>
@@ -491,6 +524,13 @@ pub fn report_result<'tcx>(
 
     eprint!("{extra}"); // newlines are already in the string
 
+    if let Some(artifact) = ecx.machine.fuzzer.artifact() {
+        ecx.tcx.dcx().note(format!(
+            "the fuzzing input that caused this error was saved to `{}`",
+            artifact.display()
+        ));
+    }
+
     // Include a note like `std` does when we omit frames from a backtrace
     if pruned {
         ecx.tcx.dcx().note(
@@ -669,6 +709,9 @@ pub fn emit_diagnostic(&self, e: NonHaltingDiagnostic) {
                 ("Called `getsockname` on connecting socket".to_string(), DiagLevel::Warning),
             SocketAddressResolution { .. } =>
                 ("error during address resolution".to_string(), DiagLevel::Warning),
+            FuzzingDone { .. } => ("fuzzing finished here".to_string(), DiagLevel::Note),
+            FuzzHarnessPanicked { .. } =>
+                ("the fuzzing harness panicked".to_string(), DiagLevel::Warning),
         };
 
         let title = match &e {
@@ -719,6 +762,9 @@ pub fn emit_diagnostic(&self, e: NonHaltingDiagnostic) {
             FileInProcOpened => format!("files in `/proc` can bypass the Abstract Machine and might not work properly in Miri"),
             ConnectingSocketGetsockname => format!("connecting sockets return unspecified socket addresses on Windows hosts"),
             SocketAddressResolution { error } => format!("address resolution failed: {error}"),
+            FuzzingDone { runs, .. } => format!("fuzzing found no errors in {runs} runs"),
+            FuzzHarnessPanicked { artifact } =>
+                format!("the fuzzing harness panicked; its input was saved to `{}`", artifact.display()),
         };
 
         let notes = match &e {
@@ -738,6 +784,10 @@ pub fn emit_diagnostic(&self, e: NonHaltingDiagnostic) {
                 vec![note!(
                     "Miri cannot return proper error information from this call; only a generic error code is being returned"
                 )],
+            FuzzingDone { corpus, covered, .. } =>
+                vec![note!(
+                    "the corpus contains {corpus} inputs that together reach {covered} basic blocks"
+                )],
             _ => vec![],
         };
 
@@ -103,6 +103,8 @@ pub struct MiriConfig {
     pub check_alignment: AlignmentCheck,
     /// Action for an op requiring communication with the host.
     pub isolated_op: IsolatedOp,
+    /// Record the results of host operations to a trace file, or replay them from one.
+    pub host_trace: Option<HostTraceMode>,
     /// Determines if memory leaks should be ignored.
     pub ignore_leaks: bool,
     /// Environment variables that should always be forwarded from the host.
@@ -125,6 +127,11 @@ pub struct MiriConfig {
     pub weak_memory_emulation: bool,
     /// Determine if we are running in GenMC mode and with which settings. In GenMC mode, Miri will explore multiple concurrent executions of the given program.
     pub genmc_config: Option<GenmcConfig>,
+    /// If `Some`, systematically explore the interleavings of the program's threads, with the
+    /// given settings.
+    pub explore: Option<ExploreConfig>,
+    /// The settings for fuzzing mode, which the program enters by calling `miri_fuzz`.
+    pub fuzz: FuzzConfig,
     /// Track when an outdated (weak memory) load happens.
     pub track_outdated_loads: bool,
     /// Rate of spurious failures for compare_exchange_weak atomic operations,
@@ -156,6 +163,11 @@ pub struct MiriConfig {
     pub page_size: Option<u64>,
     /// Whether to collect a backtrace when each allocation is created, just in case it leaks.
     pub collect_leak_backtraces: bool,
+    /// If `Some`, write a heap profile to the given file.
+    pub alloc_profile: Option<PathBuf>,
+    /// If `Some`, record the history of every Tree Borrows tree and write it to the given file
+    /// when Tree Borrows finds UB.
+    pub tree_borrows_history: Option<PathBuf>,
     /// Probability for address reuse.
     pub address_reuse_rate: f64,
     /// Probability for address reuse across threads.
@@ -180,6 +192,7 @@ fn default() -> MiriConfig {
             borrow_tracker: Some(BorrowTrackerMethod::StackedBorrows),
             check_alignment: AlignmentCheck::Int,
             isolated_op: IsolatedOp::Reject(RejectOpWith::Abort),
+            host_trace: None,
             ignore_leaks: false,
             forwarded_env_vars: vec![],
             set_env_vars: FxHashMap::default(),
@@ -191,6 +204,8 @@ fn default() -> MiriConfig {
             data_race_detector: true,
             weak_memory_emulation: true,
             genmc_config: None,
+            explore: None,
+            fuzz: FuzzConfig::default(),
             track_outdated_loads: false,
             cmpxchg_weak_failure_rate: 0.8, // 80%
             measureme_out: None,
@@ -205,6 +220,8 @@ fn default() -> MiriConfig {
             num_cpus: 1,
             page_size: None,
             collect_leak_backtraces: true,
+            alloc_profile: None,
+            tree_borrows_history: None,
             address_reuse_rate: 0.5,
             address_reuse_cross_thread_rate: 0.1,
             fixed_scheduling: false,
@@ -330,6 +347,7 @@ pub fn create_ecx<'tcx>(
     entry_type: MiriEntryFnType,
     config: &MiriConfig,
     genmc_ctx: Option<Rc<GenmcCtx>>,
+    explore_ctx: Option<Rc<ExploreCtx>>,
 ) -> InterpResult<'tcx, InterpCx<'tcx, MiriMachine<'tcx>>> {
     let typing_env = ty::TypingEnv::fully_monomorphized();
     let layout_cx = LayoutCx::new(tcx, typing_env);
@@ -337,7 +355,7 @@ pub fn create_ecx<'tcx>(
         tcx,
         rustc_span::DUMMY_SP,
         typing_env,
-        MiriMachine::new(config, layout_cx, genmc_ctx),
+        MiriMachine::new(config, layout_cx, genmc_ctx, explore_ctx),
     );
 
     // Make sure we have MIR. We check MIR for some stable monomorphic function in libcore. However,
@@ -518,18 +536,20 @@ pub fn eval_entry<'tcx>(
     entry_type: MiriEntryFnType,
     config: &MiriConfig,
     genmc_ctx: Option<Rc<GenmcCtx>>,
+    explore_ctx: Option<Rc<ExploreCtx>>,
 ) -> Result<(), NonZeroI32> {
     // Copy setting before we move `config`.
     let ignore_leaks = config.ignore_leaks;
 
-    let mut ecx = match create_ecx(tcx, entry_id, entry_type, config, genmc_ctx).report_err() {
-        Ok(v) => v,
-        Err(err) => {
-            let (kind, backtrace) = err.into_parts();
-            backtrace.print_backtrace();
-            panic!("Miri initialization error: {kind:?}")
-        }
-    };
+    let mut ecx =
+        match create_ecx(tcx, entry_id, entry_type, config, genmc_ctx, explore_ctx).report_err() {
+            Ok(v) => v,
+            Err(err) => {
+                let (kind, backtrace) = err.into_parts();
+                backtrace.print_backtrace();
+                panic!("Miri initialization error: {kind:?}")
+            }
+        };
 
     // Perform the main execution.
     let res: thread::Result<InterpResult<'_, !>> =
@@ -545,6 +565,17 @@ pub fn eval_entry<'tcx>(
     // Obtain the result of the execution. This is always an `Err`, but that doesn't necessarily
     // indicate an error.
     let Err(res) = res.report_err();
+    // Flush or check the host trace, write the heap profile, and save the failing fuzzing input
+    // before reporting, so that they are complete even if the program failed.
+    let host_trace_failed = ecx.machine.finish_host_trace();
+    let alloc_profile_failed =
+        ecx.machine.alloc_profile.as_ref().is_some_and(|profile| {
+            !profile.borrow_mut().finish(tcx, ecx.machine.basic_block_count)
+        });
+    if let Some(explore) = &ecx.machine.explore {
+        explore.finish_execution(&ecx.machine.threads);
+    }
+    ecx.machine.fuzzer.finish_execution(&res, tcx);
 
     // Error reporting: if we survive all checks, we return the exit code the program gave us.
     'miri_error: {
@@ -552,6 +583,9 @@ pub fn eval_entry<'tcx>(
         let Some((return_code, leak_check)) = report_result(&ecx, res) else {
             break 'miri_error;
         };
+        if host_trace_failed || alloc_profile_failed {
+            break 'miri_error;
+        }
 
         // If we get here there was no fatal error -- yet.
         // Possibly check for memory leaks.
@@ -0,0 +1,394 @@
+//! Coverage-guided fuzzing of a harness function.
+//!
+//! A program enters fuzzing mode by calling `miri_fuzz(harness)` with a `fn(&[u8])`. Miri then
+//! calls the harness over and over: first with the inputs of the seed corpus (or with the empty
+//! input if there is none), then with mutations of the inputs that made the harness execute basic
+//! blocks that no earlier input reached. Fuzzing stops after the configured number of mutated
+//! inputs, or as soon as an input makes the program fail (Undefined Behavior, a panic in the
+//! harness, or any other error); that input is then saved to the artifact file so it can be
+//! reproduced.
+//!
+//! All inputs are run within the same execution: when the harness returns, Miri directly pushes
+//! the next call to the harness instead of returning from `miri_fuzz`. This means we do not pay
+//! for starting up the program again for each input, but it also means that any global state the
+//! harness modifies persists across inputs.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::{fs, io, vec};
+
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+use rustc_abi::{Align, ExternAbi, Size};
+use rustc_data_structures::fx::FxHashSet;
+use rustc_middle::mir;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+
+use crate::*;
+
+/// Values that commonly trigger edge cases, used when overwriting a byte.
+const INTERESTING_BYTES: [u8; 6] = [0x00, 0x01, 0x7f, 0x80, 0xfe, 0xff];
+
+/// Settings for fuzzing mode.
+#[derive(Clone, Debug)]
+pub struct FuzzConfig {
+    /// The number of mutated inputs to run (in addition to the seed corpus).
+    pub runs: u64,
+    /// A directory with the seed corpus. Inputs that cover new basic blocks are added to it.
+    pub corpus: Option<PathBuf>,
+    /// The file that the input which made the program fail is written to.
+    pub artifact: PathBuf,
+    /// The maximal length of the generated inputs.
+    pub max_len: usize,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        FuzzConfig {
+            runs: 1000,
+            corpus: None,
+            artifact: PathBuf::from("miri-fuzz-crash"),
+            max_len: 4096,
+        }
+    }
+}
+
+/// The harness passed to `miri_fuzz`, and where to continue once fuzzing is done.
+#[derive(Clone, Copy)]
+struct Harness<'tcx> {
+    instance: ty::Instance<'tcx>,
+    ret: Option<mir::BasicBlock>,
+    unwind: mir::UnwindAction,
+}
+
+/// The input the harness is currently running with.
+struct Iteration {
+    input: Vec<u8>,
+    /// The copy of `input` in interpreter memory that the harness received.
+    buffer: Pointer,
+    /// Whether this input is part of the seed corpus (and hence already stored on disk).
+    seed: bool,
+    /// Whether this input reached a basic block that no earlier input reached.
+    new_coverage: bool,
+}
+
+/// The state of fuzzing mode.
+pub struct Fuzzer<'tcx> {
+    config: FuzzConfig,
+    /// The source of randomness for mutations. This is separate from the machine RNG so that
+    /// fuzzing does not affect the non-determinism observed by the program.
+    rng: StdRng,
+    /// Set once `miri_fuzz` has been called.
+    harness: Option<Harness<'tcx>>,
+    /// The seed inputs that have not been run yet.
+    seeds: vec::IntoIter<Vec<u8>>,
+    /// The inputs that covered new basic blocks. New inputs are mutations of these.
+    corpus: Vec<Vec<u8>>,
+    /// All basic blocks reached while running the harness so far.
+    covered: FxHashSet<(ty::Instance<'tcx>, mir::BasicBlock)>,
+    current: Option<Iteration>,
+    /// The number of inputs that were run so far.
+    runs: u64,
+    /// The number of mutated inputs that were run so far.
+    mutated_runs: u64,
+    /// Set once an input that made the program fail has been saved.
+    artifact: Option<PathBuf>,
+}
+
+impl VisitProvenance for Fuzzer<'_> {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        if let Some(iteration) = &self.current {
+            iteration.buffer.visit_provenance(visit);
+        }
+    }
+}
+
+impl<'tcx> Fuzzer<'tcx> {
+    /// Sets up the fuzzer, reading the seed corpus if there is one.
+    pub fn new(config: &FuzzConfig, seed: Option<u64>) -> Result<Self, String> {
+        let mut seeds = Vec::new();
+        if let Some(dir) = &config.corpus {
+            seeds = read_corpus(dir).map_err(|err| {
+                format!("failed to read fuzzing corpus `{}`: {err}", dir.display())
+            })?;
+            for input in &mut seeds {
+                input.truncate(config.max_len);
+            }
+        }
+        if seeds.is_empty() {
+            seeds.push(Vec::new());
+        }
+        Ok(Fuzzer {
+            config: config.clone(),
+            rng: StdRng::seed_from_u64(seed.unwrap_or(0)),
+            harness: None,
+            seeds: seeds.into_iter(),
+            corpus: Vec::new(),
+            covered: FxHashSet::default(),
+            current: None,
+            runs: 0,
+            mutated_runs: 0,
+            artifact: None,
+        })
+    }
+
+    /// Whether the harness is currently running.
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Records that the harness reached the given basic block.
+    pub fn cover(&mut self, instance: ty::Instance<'tcx>, block: mir::BasicBlock) {
+        let iteration = self.current.as_mut().unwrap();
+        if self.covered.insert((instance, block)) {
+            iteration.new_coverage = true;
+        }
+    }
+
+    /// The file the failing input was saved to, if there was one.
+    pub fn artifact(&self) -> Option<&Path> {
+        self.artifact.as_deref()
+    }
+
+    /// Saves the input of the running harness, if any, as the program stopped with an error.
+    pub fn finish_execution(&mut self, res: &InterpErrorInfo<'_>, tcx: TyCtxt<'_>) {
+        if let InterpErrorKind::MachineStop(info) = res.kind()
+            && let Some(TerminationInfo::Exit { .. }) = info.downcast_ref::<TerminationInfo>()
+        {
+            // The program exited regularly, so the input did not make it fail.
+            return;
+        }
+        if let Some(iteration) = self.current.take() {
+            self.save_artifact(&iteration.input, tcx);
+        }
+    }
+
+    /// Returns the next input to run, or `None` if fuzzing is done.
+    fn next_input(&mut self) -> Option<(Vec<u8>, bool)> {
+        if let Some(input) = self.seeds.next() {
+            return Some((input, true));
+        }
+        if self.mutated_runs >= self.config.runs {
+            return None;
+        }
+        self.mutated_runs = self.mutated_runs.strict_add(1);
+        let mut input = if self.corpus.is_empty() {
+            Vec::new()
+        } else {
+            self.corpus[self.rng.random_range(0..self.corpus.len())].clone()
+        };
+        for _ in 0..self.rng.random_range(1..=4) {
+            mutate(&mut self.rng, &self.corpus, &mut input);
+        }
+        input.truncate(self.config.max_len);
+        Some((input, false))
+    }
+
+    /// Adds the input to the corpus if it covered new basic blocks.
+    fn finish_iteration(&mut self, iteration: Iteration, tcx: TyCtxt<'_>) {
+        if !iteration.new_coverage {
+            return;
+        }
+        if !iteration.seed
+            && let Some(dir) = &self.config.corpus
+        {
+            let mut hasher = DefaultHasher::new();
+            iteration.input.hash(&mut hasher);
+            let path = dir.join(format!("{:016x}", hasher.finish()));
+            if let Err(err) = fs::write(&path, &iteration.input) {
+                tcx.dcx().warn(format!(
+                    "failed to add input to fuzzing corpus `{}`: {err}",
+                    path.display()
+                ));
+            }
+        }
+        self.corpus.push(iteration.input);
+    }
+
+    fn save_artifact(&mut self, input: &[u8], tcx: TyCtxt<'_>) {
+        let path = &self.config.artifact;
+        match fs::write(path, input) {
+            Ok(()) => self.artifact = Some(path.clone()),
+            Err(err) =>
+                tcx.dcx().warn(format!(
+                    "failed to save the failing fuzzing input to `{}`: {err}",
+                    path.display()
+                )),
+        }
+    }
+}
+
+/// Reads all files in `dir`, in the order of their names.
+fn read_corpus(dir: &Path) -> io::Result<Vec<Vec<u8>>> {
+    fs::create_dir_all(dir)?;
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+    paths.into_iter().map(fs::read).collect()
+}
+
+/// Applies a single random mutation to `input`. `corpus` provides the material for splicing.
+fn mutate(rng: &mut StdRng, corpus: &[Vec<u8>], input: &mut Vec<u8>) {
+    // All mutations other than inserting a byte need a non-empty input.
+    let kind = if input.is_empty() { 0 } else { rng.random_range(0..7) };
+    match kind {
+        // Insert a random byte.
+        0 => {
+            let pos = rng.random_range(0..=input.len());
+            input.insert(pos, rng.random());
+        }
+        // Remove a byte.
+        1 => {
+            let pos = rng.random_range(0..input.len());
+            input.remove(pos);
+        }
+        // Flip a bit.
+        2 => {
+            let pos = rng.random_range(0..input.len());
+            input[pos] ^= 1 << rng.random_range(0..8);
+        }
+        // Overwrite a byte with a random value.
+        3 => {
+            let pos = rng.random_range(0..input.len());
+            input[pos] = rng.random();
+        }
+        // Overwrite a byte with an interesting value.
+        4 => {
+            let pos = rng.random_range(0..input.len());
+            input[pos] = INTERESTING_BYTES[rng.random_range(0..INTERESTING_BYTES.len())];
+        }
+        // Duplicate a chunk of the input.
+        5 => {
+            let start = rng.random_range(0..input.len());
+            let end = rng.random_range(start..input.len()).strict_add(1);
+            let pos = rng.random_range(0..=input.len());
+            let chunk = input[start..end].to_vec();
+            input.splice(pos..pos, chunk);
+        }
+        // Replace the tail of the input by the tail of another corpus entry.
+        6 => {
+            if corpus.is_empty() {
+                return;
+            }
+            let other = &corpus[rng.random_range(0..corpus.len())];
+            let pos = rng.random_range(0..input.len());
+            let other_pos = rng.random_range(0..=other.len());
+            input.truncate(pos);
+            input.extend_from_slice(&other[other_pos..]);
+        }
+        _ => unreachable!(),
+    }
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Handles `miri_fuzz`: starts running the harness with the first input.
+    fn start_fuzzing(
+        &mut self,
+        harness: Pointer,
+        ret: Option<mir::BasicBlock>,
+        unwind: mir::UnwindAction,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        if this.machine.fuzzer.harness.is_some() {
+            throw_unsup_format!("`miri_fuzz` can only be called once per execution");
+        }
+        let instance = this.get_ptr_fn(harness)?.as_instance()?;
+        this.machine.fuzzer.harness = Some(Harness { instance, ret, unwind });
+
+        if !this.run_next_fuzz_input()? {
+            this.return_to_block(ret)?;
+        }
+        interp_ok(())
+    }
+
+    /// Called when a frame pushed by `run_next_fuzz_input` is popped. Continues with the next
+    /// input unless the harness panicked.
+    fn handle_stack_pop_fuzz(&mut self, unwinding: bool) -> InterpResult<'tcx, ReturnAction> {
+        let this = self.eval_context_mut();
+
+        let iteration = this.machine.fuzzer.current.take().unwrap();
+        this.deallocate_ptr(iteration.buffer, None, MiriMemoryKind::Machine.into())?;
+
+        if unwinding {
+            // The harness panicked. We treat that like any other error and stop fuzzing; the
+            // panic keeps propagating to the caller of `miri_fuzz`.
+            this.machine.fuzzer.save_artifact(&iteration.input, *this.tcx);
+            if let Some(artifact) = this.machine.fuzzer.artifact() {
+                let artifact = artifact.to_owned();
+                this.emit_diagnostic(NonHaltingDiagnostic::FuzzHarnessPanicked { artifact });
+            }
+            return interp_ok(ReturnAction::Normal);
+        }
+
+        this.machine.fuzzer.finish_iteration(iteration, *this.tcx);
+        if this.run_next_fuzz_input()? {
+            // We pushed a new stack frame, the engine should not do any jumping now!
+            interp_ok(ReturnAction::NoJump)
+        } else {
+            interp_ok(ReturnAction::Normal)
+        }
+    }
+}
+
+impl<'tcx> EvalContextPrivExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+trait EvalContextPrivExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Pushes a call to the harness with the next input. Returns `false` if there are no inputs
+    /// left, in which case the caller has to return from `miri_fuzz`.
+    fn run_next_fuzz_input(&mut self) -> InterpResult<'tcx, bool> {
+        let this = self.eval_context_mut();
+        let fuzzer = &mut this.machine.fuzzer;
+        let harness = fuzzer.harness.unwrap();
+
+        let Some((input, seed)) = fuzzer.next_input() else {
+            let diag = NonHaltingDiagnostic::FuzzingDone {
+                runs: fuzzer.runs,
+                corpus: fuzzer.corpus.len(),
+                covered: fuzzer.covered.len(),
+            };
+            this.emit_diagnostic(diag);
+            return interp_ok(false);
+        };
+        fuzzer.runs = fuzzer.runs.strict_add(1);
+
+        // Pass a fresh copy of the input to the harness.
+        let buffer = this.allocate_ptr(
+            Size::from_bytes(input.len()),
+            Align::ONE,
+            MiriMemoryKind::Machine.into(),
+            AllocInit::Uninit,
+        )?;
+        let buffer = Pointer::from(buffer);
+        this.write_bytes_ptr(buffer, input.iter().copied())?;
+        let slice_ty = Ty::new_imm_ref(
+            this.tcx.tcx,
+            this.tcx.lifetimes.re_erased,
+            Ty::new_slice(this.tcx.tcx, this.tcx.types.u8),
+        );
+        let slice = ImmTy::from_immediate(
+            Immediate::new_slice(buffer, input.len().to_u64(), this),
+            this.layout_of(slice_ty)?,
+        );
+        this.call_function(
+            harness.instance,
+            ExternAbi::Rust,
+            &[slice],
+            None,
+            // Directly return to the caller of `miri_fuzz`.
+            ReturnContinuation::Goto { ret: harness.ret, unwind: harness.unwind },
+        )?;
+
+        // This lets `handle_stack_pop_fuzz` know that it should continue with the next input
+        // when this frame is popped.
+        this.frame_mut().extra.fuzz_harness = true;
+        this.machine.fuzzer.current = Some(Iteration { input, buffer, seed, new_coverage: false });
+        interp_ok(true)
+    }
+}
@@ -393,10 +393,19 @@ fn gen_random(&mut self, ptr: Pointer, len: u64) -> InterpResult<'tcx> {
 
         let mut data = vec![0; usize::try_from(len).unwrap()];
 
-        if this.machine.communicate() {
+        if this.machine.observes_host() {
             // Fill the buffer using the host's rng.
-            getrandom::fill(&mut data)
-                .map_err(|err| err_unsup_format!("host getrandom failed: {}", err))?;
+            data = this.machine.traced("getrandom", || {
+                getrandom::fill(&mut data)
+                    .map_err(|err| err_unsup_format!("host getrandom failed: {}", err))?;
+                interp_ok(data)
+            })?;
+            if data.len().to_u64() != len {
+                throw_machine_stop!(TerminationInfo::ReplayDiverged(format!(
+                    "the recorded run requested {} random bytes, not {len}",
+                    data.len()
+                )));
+            }
         } else {
             let rng = this.machine.rng.get_mut();
             rng.fill_bytes(&mut data);
@@ -624,6 +633,15 @@ fn check_no_isolation(&self, name: &str) -> InterpResult<'tcx> {
         interp_ok(())
     }
 
+    /// Like `check_no_isolation`, but also allows operations whose results are replayed from a
+    /// host trace that was recorded with isolation disabled.
+    fn check_no_isolation_or_replay(&self, name: &str) -> InterpResult<'tcx> {
+        if !self.eval_context_ref().machine.observes_host() {
+            self.reject_in_isolation(name, RejectOpWith::Abort)?;
+        }
+        interp_ok(())
+    }
+
     /// Helper function used inside the shims of foreign functions which reject the op
     /// when isolation is enabled. It is used to print a warning/backtrace about the rejection.
     fn reject_in_isolation(&self, op_name: &str, reject_with: RejectOpWith) -> InterpResult<'tcx> {
@@ -0,0 +1,560 @@
+//! Recording and replaying of host-observed results.
+//!
+//! With `-Zmiri-record=<file>`, every result that the interpreted program obtains from the host
+//! (clock readings, random bytes, environment variables, file system and socket operations, ...)
+//! and every scheduling decision is written to a trace file. With `-Zmiri-replay=<file>`, those
+//! results are taken from the trace instead, without touching the host. This makes it possible to
+//! reproduce a run with isolation disabled exactly, e.g. to debug a failure that depends on the
+//! host.
+//!
+//! The trace is a text file with one entry per line: the name of the operation, followed by the
+//! space-separated tokens encoding its result (see [`TraceValue`]). The first entries form a
+//! header that records the trace format version and some facts about the recorded run.
+
+use std::cell::RefCell;
+use std::ffi::OsString;
+use std::fmt::{self, Write as _};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::SplitAsciiWhitespace;
+use std::time::{Duration, SystemTime};
+use std::vec;
+
+use crate::shims::io_error::UNIX_IO_ERROR_TABLE;
+use crate::*;
+
+/// The version of the trace format. Bump this whenever the encoding of any entry changes.
+const TRACE_VERSION: u32 = 2;
+
+/// Whether to record a host trace or to replay one.
+#[derive(Clone, Debug)]
+pub enum HostTraceMode {
+    /// Record host-observed results to the given file.
+    Record(PathBuf),
+    /// Replay host-observed results from the given file.
+    Replay(PathBuf),
+}
+
+/// A value that can be stored in a host trace.
+pub trait TraceValue: Sized {
+    /// Appends the tokens encoding `self` to `out`, each preceded by a space.
+    fn encode(&self, out: &mut String);
+
+    /// Decodes a value from the next tokens, or returns `None` if they do not encode one.
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self>;
+}
+
+fn push_token(out: &mut String, token: impl fmt::Display) {
+    write!(out, " {token}").unwrap();
+}
+
+macro_rules! impl_trace_value_for_int {
+    ($($ty:ty),*) => {$(
+        impl TraceValue for $ty {
+            fn encode(&self, out: &mut String) {
+                push_token(out, self);
+            }
+
+            fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+                tokens.next()?.parse().ok()
+            }
+        }
+    )*};
+}
+
+impl_trace_value_for_int!(i32, u32, u64, usize);
+
+impl TraceValue for () {
+    fn encode(&self, _out: &mut String) {}
+
+    fn decode(_tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        Some(())
+    }
+}
+
+impl TraceValue for bool {
+    fn encode(&self, out: &mut String) {
+        push_token(out, u8::from(*self));
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        match tokens.next()? {
+            "0" => Some(false),
+            "1" => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl TraceValue for Duration {
+    fn encode(&self, out: &mut String) {
+        self.as_secs().encode(out);
+        self.subsec_nanos().encode(out);
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        let secs = u64::decode(tokens)?;
+        let nanos = u32::decode(tokens)?;
+        (nanos < 1_000_000_000).then(|| Duration::new(secs, nanos))
+    }
+}
+
+impl TraceValue for SystemTime {
+    fn encode(&self, out: &mut String) {
+        match self.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(duration) => {
+                push_token(out, "+");
+                duration.encode(out);
+            }
+            Err(err) => {
+                push_token(out, "-");
+                err.duration().encode(out);
+            }
+        }
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        let sign = tokens.next()?;
+        let duration = Duration::decode(tokens)?;
+        match sign {
+            "+" => SystemTime::UNIX_EPOCH.checked_add(duration),
+            "-" => SystemTime::UNIX_EPOCH.checked_sub(duration),
+            _ => None,
+        }
+    }
+}
+
+/// Bytes are stored as a single hex token, prefixed by `x` so that it is never empty.
+impl TraceValue for Vec<u8> {
+    fn encode(&self, out: &mut String) {
+        out.push_str(" x");
+        for byte in self {
+            write!(out, "{byte:02x}").unwrap();
+        }
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        let hex = tokens.next()?.strip_prefix('x')?;
+        if !hex.is_ascii() || hex.len() % 2 != 0 {
+            return None;
+        }
+        (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok()).collect()
+    }
+}
+
+impl TraceValue for OsString {
+    fn encode(&self, out: &mut String) {
+        cfg_select! {
+            unix => {
+                use std::os::unix::ffi::OsStrExt;
+                self.as_bytes().to_vec().encode(out);
+            }
+            _ => {
+                self.to_string_lossy().as_bytes().to_vec().encode(out);
+            }
+        }
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        let bytes = Vec::<u8>::decode(tokens)?;
+        cfg_select! {
+            unix => {
+                use std::os::unix::ffi::OsStringExt;
+                Some(OsString::from_vec(bytes))
+            }
+            _ => {
+                String::from_utf8(bytes).ok().map(OsString::from)
+            }
+        }
+    }
+}
+
+/// Socket addresses are stored in their usual textual form, e.g. `127.0.0.1:80` or `[::1]:80`.
+impl TraceValue for SocketAddr {
+    fn encode(&self, out: &mut String) {
+        push_token(out, self);
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        tokens.next()?.parse().ok()
+    }
+}
+
+impl<A: TraceValue, B: TraceValue> TraceValue for (A, B) {
+    fn encode(&self, out: &mut String) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        Some((A::decode(tokens)?, B::decode(tokens)?))
+    }
+}
+
+/// A list of environment variables, stored as its length followed by the name-value pairs.
+impl TraceValue for Vec<(OsString, OsString)> {
+    fn encode(&self, out: &mut String) {
+        self.len().encode(out);
+        for var in self {
+            var.encode(out);
+        }
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        let len = usize::decode(tokens)?;
+        (0..len).map(|_| <(OsString, OsString)>::decode(tokens)).collect()
+    }
+}
+
+impl<T: TraceValue> TraceValue for Option<T> {
+    fn encode(&self, out: &mut String) {
+        match self {
+            None => push_token(out, "none"),
+            Some(value) => {
+                push_token(out, "some");
+                value.encode(out);
+            }
+        }
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        match tokens.next()? {
+            "none" => Some(None),
+            "some" => Some(Some(T::decode(tokens)?)),
+            _ => None,
+        }
+    }
+}
+
+/// Miri only looks at the kind of host errors, so that is all we store: we use the name of the
+/// first Unix error code of that kind, or `other` if there is none.
+impl<T: TraceValue> TraceValue for io::Result<T> {
+    fn encode(&self, out: &mut String) {
+        match self {
+            Ok(value) => {
+                push_token(out, "ok");
+                value.encode(out);
+            }
+            Err(err) => {
+                let name = UNIX_IO_ERROR_TABLE
+                    .iter()
+                    .find(|&&(_, kind)| kind == err.kind())
+                    .map_or("other", |&(name, _)| name);
+                push_token(out, "err");
+                push_token(out, name);
+            }
+        }
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        match tokens.next()? {
+            "ok" => Some(Ok(T::decode(tokens)?)),
+            "err" => {
+                let name = tokens.next()?;
+                let kind = if name == "other" {
+                    io::ErrorKind::Other
+                } else {
+                    UNIX_IO_ERROR_TABLE.iter().find(|&&(n, _)| n == name)?.1
+                };
+                Some(Err(kind.into()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl TraceValue for ThreadId {
+    fn encode(&self, out: &mut String) {
+        self.to_u32().encode(out);
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        Some(ThreadId::new_unchecked(u32::decode(tokens)?))
+    }
+}
+
+/// A host trace that is being recorded or replayed.
+#[derive(Debug)]
+pub struct HostTrace {
+    path: PathBuf,
+    state: RefCell<TraceState>,
+    /// Whether the traced run had isolation disabled, i.e., whether it observed the host.
+    communicate: bool,
+    /// The process ID of the traced run.
+    pid: u32,
+}
+
+#[derive(Debug)]
+enum TraceState {
+    Recording {
+        out: BufWriter<File>,
+        /// The first error that occurred while writing the trace. We do not abort the run because
+        /// of it, but report it at the end.
+        error: Option<io::Error>,
+    },
+    Replaying {
+        /// The entries that have not been replayed yet.
+        entries: vec::IntoIter<String>,
+        /// A divergence that happened during an operation that cannot fail, such as reading the
+        /// clock. It is reported by the next operation that can fail, or at the end of the run.
+        diverged: Option<String>,
+    },
+}
+
+impl HostTrace {
+    /// Starts recording or replaying a trace. `communicate` says whether isolation is disabled
+    /// for this run.
+    pub fn new(mode: &HostTraceMode, communicate: bool) -> Result<Self, String> {
+        match mode {
+            HostTraceMode::Record(path) => {
+                let file = File::create(path).map_err(|err| {
+                    format!("failed to create host trace `{}`: {err}", path.display())
+                })?;
+                let trace = HostTrace {
+                    path: path.clone(),
+                    state: RefCell::new(TraceState::Recording {
+                        out: BufWriter::new(file),
+                        error: None,
+                    }),
+                    communicate,
+                    pid: std::process::id(),
+                };
+                trace.record("miri-host-trace", &TRACE_VERSION);
+                trace.record("communicate", &trace.communicate);
+                trace.record("pid", &trace.pid);
+                Ok(trace)
+            }
+            HostTraceMode::Replay(path) => {
+                let invalid =
+                    |msg: String| format!("invalid host trace `{}`: {msg}", path.display());
+                let file = File::open(path).map_err(|err| {
+                    format!("failed to open host trace `{}`: {err}", path.display())
+                })?;
+                let entries = BufReader::new(file)
+                    .lines()
+                    .collect::<io::Result<Vec<_>>>()
+                    .map_err(|err| invalid(err.to_string()))?;
+                let mut trace = HostTrace {
+                    path: path.clone(),
+                    state: RefCell::new(TraceState::Replaying {
+                        entries: entries.into_iter(),
+                        diverged: None,
+                    }),
+                    communicate: false,
+                    pid: 0,
+                };
+                let version: u32 = trace.replay("miri-host-trace").map_err(invalid)?;
+                if version != TRACE_VERSION {
+                    return Err(invalid(format!(
+                        "it uses format version {version}, but this Miri supports version {TRACE_VERSION}"
+                    )));
+                }
+                trace.communicate = trace.replay("communicate").map_err(invalid)?;
+                trace.pid = trace.replay("pid").map_err(invalid)?;
+                Ok(trace)
+            }
+        }
+    }
+
+    /// Whether this trace is being replayed (rather than recorded).
+    pub fn is_replaying(&self) -> bool {
+        matches!(*self.state.borrow(), TraceState::Replaying { .. })
+    }
+
+    /// Whether this trace is being replayed, and was recorded with isolation disabled.
+    pub fn replays_host(&self) -> bool {
+        self.is_replaying() && self.communicate
+    }
+
+    fn record<T: TraceValue>(&self, op: &str, value: &T) {
+        let TraceState::Recording { out, error } = &mut *self.state.borrow_mut() else {
+            unreachable!("recording a value while replaying a host trace")
+        };
+        if error.is_some() {
+            return;
+        }
+        let mut line = op.to_owned();
+        value.encode(&mut line);
+        line.push('\n');
+        if let Err(err) = out.write_all(line.as_bytes()) {
+            *error = Some(err);
+        }
+    }
+
+    /// Takes the next entry, which must be for `op`. On mismatch, the replay has diverged from the
+    /// recorded run and we return a message describing how.
+    fn replay<T: TraceValue>(&self, op: &str) -> Result<T, String> {
+        let TraceState::Replaying { entries, .. } = &mut *self.state.borrow_mut() else {
+            unreachable!("replaying a value while recording a host trace")
+        };
+        let result = match entries.next() {
+            None => Err(format!("expected `{op}`, but the trace has ended")),
+            Some(line) => {
+                let mut tokens = line.split_ascii_whitespace();
+                if tokens.next() != Some(op) {
+                    Err(format!("expected `{op}`, but the trace has `{line}`"))
+                } else {
+                    match T::decode(&mut tokens) {
+                        Some(value) if tokens.next().is_none() => Ok(value),
+                        _ => Err(format!("malformed `{op}` entry `{line}`")),
+                    }
+                }
+            }
+        };
+        if result.is_err() {
+            // The remaining entries are meaningless after a divergence.
+            *entries = Vec::new().into_iter();
+        }
+        result
+    }
+
+    /// Runs `f` and records its result, or, when replaying, returns the recorded result without
+    /// running `f`.
+    pub fn traced<'tcx, T: TraceValue>(
+        &self,
+        op: &str,
+        f: impl FnOnce() -> InterpResult<'tcx, T>,
+    ) -> InterpResult<'tcx, T> {
+        if !self.is_replaying() {
+            let value = f()?;
+            self.record(op, &value);
+            return interp_ok(value);
+        }
+        if let TraceState::Replaying { diverged, .. } = &mut *self.state.borrow_mut()
+            && let Some(msg) = diverged.take()
+        {
+            throw_machine_stop!(TerminationInfo::ReplayDiverged(msg));
+        }
+        match self.replay(op) {
+            Ok(value) => interp_ok(value),
+            Err(msg) => throw_machine_stop!(TerminationInfo::ReplayDiverged(msg)),
+        }
+    }
+
+    /// Like `traced`, but for operations that cannot fail, such as reading a clock. If the replay
+    /// diverges, `fallback` is returned and the divergence is reported later.
+    pub fn traced_infallible<T: TraceValue>(
+        &self,
+        op: &str,
+        f: impl FnOnce() -> T,
+        fallback: T,
+    ) -> T {
+        if !self.is_replaying() {
+            let value = f();
+            self.record(op, &value);
+            return value;
+        }
+        match self.replay(op) {
+            Ok(value) => value,
+            Err(msg) => {
+                if let TraceState::Replaying { diverged, .. } = &mut *self.state.borrow_mut() {
+                    diverged.get_or_insert(msg);
+                }
+                fallback
+            }
+        }
+    }
+
+    /// Finishes recording or replaying. Returns an error message if the trace could not be
+    /// written, or if the replay diverged without that having been reported yet. Leftover entries
+    /// result in a warning message.
+    fn finish(&self) -> Result<Option<String>, String> {
+        match &mut *self.state.borrow_mut() {
+            TraceState::Recording { out, error } => {
+                match error.take().map_or_else(|| out.flush().err(), Some) {
+                    None => Ok(None),
+                    Some(err) =>
+                        Err(format!("failed to write host trace `{}`: {err}", self.path.display())),
+                }
+            }
+            TraceState::Replaying { entries, diverged } => {
+                if let Some(msg) = diverged.take() {
+                    return Err(format!("replaying host trace diverged: {msg}"));
+                }
+                if entries.len() > 0 {
+                    return Ok(Some(format!(
+                        "the program finished, but host trace `{}` has {} more entries",
+                        self.path.display(),
+                        entries.len()
+                    )));
+                }
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Returns the host's system time, recording it in `trace` or replaying it from there.
+pub fn system_time_now(trace: Option<&HostTrace>) -> SystemTime {
+    match trace {
+        None => SystemTime::now(),
+        Some(trace) =>
+            trace.traced_infallible("system-time", SystemTime::now, SystemTime::UNIX_EPOCH),
+    }
+}
+
+impl<'tcx> MiriMachine<'tcx> {
+    /// Whether we are replaying a host trace.
+    pub(crate) fn replaying(&self) -> bool {
+        self.host_trace.as_ref().is_some_and(|trace| trace.is_replaying())
+    }
+
+    /// Whether we are replaying a host trace that was recorded with isolation disabled. Then the
+    /// results of host operations are available from the trace, even though we do not communicate
+    /// with the host ourselves.
+    pub(crate) fn replays_host(&self) -> bool {
+        self.host_trace.as_ref().is_some_and(|trace| trace.replays_host())
+    }
+
+    /// Whether the program may observe the host, either directly or through a replayed trace.
+    pub(crate) fn observes_host(&self) -> bool {
+        self.communicate() || self.replays_host()
+    }
+
+    /// Runs `f` to obtain a result from the host. If there is a host trace, the result is recorded,
+    /// or, when replaying, the recorded result is returned without running `f`.
+    pub(crate) fn traced<T: TraceValue>(
+        &self,
+        op: &str,
+        f: impl FnOnce() -> InterpResult<'tcx, T>,
+    ) -> InterpResult<'tcx, T> {
+        match &self.host_trace {
+            None => f(),
+            Some(trace) => trace.traced(op, f),
+        }
+    }
+
+    /// The process ID of the host process, or of the recorded one when replaying.
+    pub(crate) fn host_pid(&self) -> u32 {
+        match &self.host_trace {
+            None => std::process::id(),
+            Some(trace) => trace.pid,
+        }
+    }
+
+    /// The host's system time, recorded or replayed if there is a host trace.
+    pub(crate) fn system_time_now(&self) -> SystemTime {
+        system_time_now(self.host_trace.as_deref())
+    }
+
+    /// Finishes recording or replaying the host trace, if any, and reports any problems with it.
+    /// Returns whether an error was reported.
+    pub(crate) fn finish_host_trace(&self) -> bool {
+        let Some(trace) = &self.host_trace else {
+            return false;
+        };
+        match trace.finish() {
+            Ok(None) => false,
+            Ok(Some(warning)) => {
+                self.tcx.dcx().warn(warning);
+                false
+            }
+            Err(err) => {
+                self.tcx.dcx().err(err);
+                true
+            }
+        }
+    }
+}
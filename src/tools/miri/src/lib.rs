@@ -81,13 +81,16 @@
 
 mod alloc;
 mod alloc_addresses;
+mod alloc_profile;
 mod borrow_tracker;
 mod clock;
 mod concurrency;
 mod data_structures;
 mod diagnostics;
 mod eval;
+mod fuzz;
 mod helpers;
+mod host_trace;
 mod intrinsics;
 mod machine;
 mod math;
@@ -124,6 +127,7 @@ pub mod native_lib {
 
 pub use crate::alloc::MiriAllocBytes;
 pub use crate::alloc_addresses::{EvalContextExt as _, ProvenanceMode};
+pub use crate::alloc_profile::AllocProfile;
 pub use crate::borrow_tracker::stacked_borrows::{
     EvalContextExt as _, Item, Permission, Stack, Stacks,
 };
@@ -146,14 +150,18 @@ pub mod native_lib {
     BlockReason, DynUnblockCallback, EvalContextExt as _, StackEmptyCallback, ThreadId,
     ThreadManager, TlsAllocAction, UnblockKind,
 };
-pub use crate::concurrency::{GenmcConfig, GenmcCtx, run_genmc_mode};
+pub use crate::concurrency::{
+    ExploreConfig, ExploreCtx, GenmcConfig, GenmcCtx, run_explore_mode, run_genmc_mode,
+};
 pub use crate::data_structures::dedup_range_map::DedupRangeMap;
 pub use crate::data_structures::mono_hash_map::MonoHashMap;
 pub use crate::diagnostics::{
     EvalContextExt as _, NonHaltingDiagnostic, TerminationInfo, report_result,
 };
 pub use crate::eval::{MiriConfig, MiriEntryFnType, create_ecx, entry_fn, eval_entry};
+pub use crate::fuzz::{EvalContextExt as _, FuzzConfig, Fuzzer};
 pub use crate::helpers::{EvalContextExt as _, ToU64 as _, ToUsize as _};
+pub use crate::host_trace::{HostTrace, HostTraceMode, TraceValue};
 pub use crate::intrinsics::EvalContextExt as _;
 pub use crate::machine::{
     AlignmentCheck, AllocExtra, BacktraceStyle, DynMachineCallback, FloatRoundingErrorMode,
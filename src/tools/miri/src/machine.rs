@@ -4,7 +4,7 @@
 use std::borrow::Cow;
 use std::cell::{Cell, RefCell};
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::{fmt, process};
 
@@ -150,26 +150,51 @@ pub struct FrameExtra<'tcx> {
 
     /// Data race detector per-frame data.
     pub data_race: Option<data_race::FrameState>,
+
+    /// Whether this is a call of the fuzzing harness. When this frame is popped, we continue with
+    /// the next fuzzing input instead of returning to the caller.
+    pub fuzz_harness: bool,
+
+    /// Whether this frame was pushed because native code called a Rust function through a
+    /// function pointer. When this frame is popped, control goes back to the native code instead
+    /// of the interpreted caller.
+    pub native_callback: bool,
 }
 
 impl<'tcx> std::fmt::Debug for FrameExtra<'tcx> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // Omitting `timing`, it does not support `Debug`.
-        let FrameExtra { borrow_tracker, catch_unwind, timing: _, user_relevance, data_race } =
-            self;
+        let FrameExtra {
+            borrow_tracker,
+            catch_unwind,
+            timing: _,
+            user_relevance,
+            data_race,
+            fuzz_harness,
+            native_callback,
+        } = self;
         f.debug_struct("FrameData")
             .field("borrow_tracker", borrow_tracker)
             .field("catch_unwind", catch_unwind)
             .field("user_relevance", user_relevance)
             .field("data_race", data_race)
+            .field("fuzz_harness", fuzz_harness)
+            .field("native_callback", native_callback)
             .finish()
     }
 }
 
 impl VisitProvenance for FrameExtra<'_> {
     fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
-        let FrameExtra { catch_unwind, borrow_tracker, timing: _, user_relevance: _, data_race: _ } =
-            self;
+        let FrameExtra {
+            catch_unwind,
+            borrow_tracker,
+            timing: _,
+            user_relevance: _,
+            data_race: _,
+            fuzz_harness: _,
+            native_callback: _,
+        } = self;
 
         catch_unwind.visit_provenance(visit);
         borrow_tracker.visit_provenance(visit);
@@ -208,6 +233,9 @@ pub enum MiriMemoryKind {
     Mmap,
     /// Memory allocated for `getaddrinfo` result.
     SocketAddress,
+    /// Memory owned by native code that was made accessible via `miri_adopt_native_alloc`.
+    /// This memory may leak.
+    Native,
 }
 
 impl From<MiriMemoryKind> for MemoryKind {
@@ -223,7 +251,7 @@ fn may_leak(self) -> bool {
         use self::MiriMemoryKind::*;
         match self {
             Rust | Miri | C | WinHeap | WinLocal | Runtime => false,
-            Machine | Global | ExternStatic | Tls | Mmap | SocketAddress => true,
+            Machine | Global | ExternStatic | Tls | Mmap | SocketAddress | Native => true,
         }
     }
 }
@@ -234,11 +262,21 @@ fn should_save_allocation_span(self) -> bool {
         use self::MiriMemoryKind::*;
         match self {
             // Heap allocations are fine since the `Allocation` is created immediately.
-            Rust | Miri | C | WinHeap | WinLocal | Mmap => true,
+            Rust | Miri | C | WinHeap | WinLocal | Mmap | Native => true,
             // Everything else is unclear, let's not show potentially confusing spans.
             Machine | Global | ExternStatic | Tls | Runtime | SocketAddress => false,
         }
     }
+
+    /// Whether this is memory the program explicitly allocated on a heap.
+    fn is_heap(self) -> bool {
+        use self::MiriMemoryKind::*;
+        match self {
+            Rust | Miri | C | WinHeap | WinLocal => true,
+            Machine | Runtime | Global | ExternStatic | Tls | Mmap | SocketAddress | Native =>
+                false,
+        }
+    }
 }
 
 impl fmt::Display for MiriMemoryKind {
@@ -257,6 +295,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             Tls => write!(f, "thread-local static"),
             Mmap => write!(f, "mmap"),
             SocketAddress => write!(f, "socket address"),
+            Native => write!(f, "native memory"),
         }
     }
 }
@@ -501,6 +540,10 @@ pub struct MiriMachine<'tcx> {
     /// Invariant: The enum variant must match the enum variant of `AllocDataRaceHandler` in the `data_race` field of all `AllocExtra`.
     pub data_race: GlobalDataRaceHandler,
 
+    /// The state of the interleaving exploration, if it is enabled.
+    /// It persists across executions, so it is behind an `Rc`.
+    pub(crate) explore: Option<Rc<ExploreCtx>>,
+
     /// Ptr-int-cast module global data.
     pub alloc_addresses: alloc_addresses::GlobalState,
 
@@ -525,6 +568,12 @@ pub struct MiriMachine<'tcx> {
     /// file system access.
     pub(crate) isolated_op: IsolatedOp,
 
+    /// The host trace we are recording or replaying, if any.
+    pub(crate) host_trace: Option<Rc<HostTrace>>,
+
+    /// The state of fuzzing mode.
+    pub(crate) fuzzer: Fuzzer<'tcx>,
+
     /// Whether to enforce the validity invariant.
     pub(crate) validation: ValidationMode,
 
@@ -638,6 +687,13 @@ pub struct MiriMachine<'tcx> {
     /// diagnostics.
     pub(crate) allocation_spans: RefCell<FxHashMap<AllocId, (Span, Option<Span>)>>,
 
+    /// The heap profiler, if `-Zmiri-alloc-profile` is set.
+    pub(crate) alloc_profile: Option<RefCell<AllocProfile<'tcx>>>,
+
+    /// Where to write the history of the tree in which Tree Borrows found UB, if
+    /// `-Zmiri-tree-borrows-history` is set.
+    pub(crate) tree_borrows_history: Option<PathBuf>,
+
     /// For each allocation, an offset inside that allocation that was deemed aligned even for
     /// symbolic alignment checks. This cannot be stored in `AllocExtra` since it needs to be
     /// tracked for vtables and function allocations as well as regular allocations.
@@ -673,11 +729,13 @@ pub struct MiriMachine<'tcx> {
 impl<'tcx> MiriMachine<'tcx> {
     /// Create a new MiriMachine.
     ///
-    /// Invariant: `genmc_ctx.is_some() == config.genmc_config.is_some()`
+    /// Invariant: `genmc_ctx.is_some() == config.genmc_config.is_some()`, and likewise for
+    /// `explore_ctx` and `config.explore`.
     pub(crate) fn new(
         config: &MiriConfig,
         layout_cx: LayoutCx<'tcx>,
         genmc_ctx: Option<Rc<GenmcCtx>>,
+        explore_ctx: Option<Rc<ExploreCtx>>,
     ) -> Self {
         let tcx = layout_cx.tcx();
         let user_relevant_crates = Self::get_user_relevant_crates(tcx, config);
@@ -747,15 +805,25 @@ pub(crate) fn new(
             } else {
                 None
             };
-        let blocking_io = BlockingIoManager::new(config.isolated_op == IsolatedOp::Allow)
-            .expect("Couldn't create poll instance");
         let alloc_addresses =
             RefCell::new(alloc_addresses::GlobalStateInner::new(config, stack_addr, tcx));
+        let host_trace = config.host_trace.as_ref().map(|mode| {
+            let trace = HostTrace::new(mode, config.isolated_op == IsolatedOp::Allow)
+                .unwrap_or_else(|err| tcx.dcx().fatal(err));
+            Rc::new(trace)
+        });
+        let replays_host = host_trace.as_ref().is_some_and(|trace| trace.replays_host());
+        // Replaying a trace recorded with isolation disabled observes the host's clock as well.
+        let host_clock = config.isolated_op == IsolatedOp::Allow || replays_host;
+        let blocking_io =
+            BlockingIoManager::new(config.isolated_op == IsolatedOp::Allow, replays_host)
+                .expect("Couldn't create poll instance");
 
         MiriMachine {
             tcx,
             borrow_tracker,
             data_race,
+            explore: explore_ctx,
             alloc_addresses,
             // `env_vars` depends on a full interpreter so we cannot properly initialize it yet.
             env_vars: EnvVars::default(),
@@ -765,6 +833,9 @@ pub(crate) fn new(
             cmd_line: None,
             tls: TlsData::default(),
             isolated_op: config.isolated_op,
+            host_trace: host_trace.clone(),
+            fuzzer: Fuzzer::new(&config.fuzz, config.seed)
+                .unwrap_or_else(|err| tcx.dcx().fatal(err)),
             validation: config.validation,
             fds: shims::FdTable::init(config.mute_stdout_stderr),
             delayed_readiness_updates: Rc::new(DelayedReadinessUpdates::default()),
@@ -792,7 +863,7 @@ pub(crate) fn new(
             preemption_rate: config.preemption_rate,
             report_progress: config.report_progress,
             basic_block_count: 0,
-            monotonic_clock: MonotonicClock::new(config.isolated_op == IsolatedOp::Allow),
+            monotonic_clock: MonotonicClock::new(host_clock, host_trace),
             #[cfg(all(feature = "native-lib", unix))]
             native_lib: config.native_lib.iter().map(|lib_file_path| {
                 let host_triple = rustc_session::config::host_tuple();
@@ -829,6 +900,11 @@ pub(crate) fn new(
             stack_size,
             collect_leak_backtraces: config.collect_leak_backtraces,
             allocation_spans: RefCell::new(FxHashMap::default()),
+            alloc_profile: config
+                .alloc_profile
+                .clone()
+                .map(|path| RefCell::new(AllocProfile::new(path, config.args.join(" ")))),
+            tree_borrows_history: config.tree_borrows_history.clone(),
             symbolic_alignment: RefCell::new(FxHashMap::default()),
             union_data_ranges: FxHashMap::default(),
             pthread_mutex_sanity: Cell::new(false),
@@ -990,6 +1066,12 @@ fn init_allocation(
             Some(ecx.generate_stacktrace())
         };
 
+        if let Some(profile) = &ecx.machine.alloc_profile
+            && matches!(kind, MemoryKind::Machine(kind) if kind.is_heap())
+        {
+            profile.borrow_mut().record_alloc(ecx, id, size);
+        }
+
         if matches!(kind, MemoryKind::Machine(kind) if kind.should_save_allocation_span()) {
             ecx.machine
                 .allocation_spans
@@ -1024,12 +1106,15 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             dirs,
             borrow_tracker,
             data_race,
+            explore: _,
             alloc_addresses,
             fds,
             blocking_io:_,
             delayed_readiness_updates: _,
             tcx: _,
             isolated_op: _,
+            host_trace: _,
+            fuzzer,
             validation: _,
             monotonic_clock: _,
             layouts: _,
@@ -1059,6 +1144,8 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             stack_size: _,
             collect_leak_backtraces: _,
             allocation_spans: _,
+            alloc_profile: _,
+            tree_borrows_history: _,
             symbolic_alignment: _,
             union_data_ranges: _,
             pthread_mutex_sanity: _,
@@ -1076,6 +1163,7 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
         env_vars.visit_provenance(visit);
         dirs.visit_provenance(visit);
         fds.visit_provenance(visit);
+        fuzzer.visit_provenance(visit);
         data_race.visit_provenance(visit);
         borrow_tracker.visit_provenance(visit);
         alloc_addresses.visit_provenance(visit);
@@ -1752,6 +1840,9 @@ fn before_memory_deallocation(
         {
             *deallocated_at = Some(machine.current_user_relevant_span());
         }
+        if let Some(profile) = &machine.alloc_profile {
+            profile.borrow_mut().record_dealloc(alloc_id, machine.basic_block_count);
+        }
         machine.free_alloc_id(alloc_id, size, align, kind);
         interp_ok(())
     }
@@ -1831,6 +1922,8 @@ fn init_frame(
                 .data_race
                 .as_vclocks_ref()
                 .map(|_| data_race::FrameState::default()),
+            fuzz_harness: false,
+            native_callback: false,
         };
 
         interp_ok(frame.with_extra(extra))
@@ -1860,6 +1953,15 @@ fn before_terminator(ecx: &mut InterpCx<'tcx, Self>) -> InterpResult<'tcx> {
             }
         }
 
+        // Record which basic blocks the fuzzing harness reaches.
+        if ecx.machine.fuzzer.is_running() {
+            let frame = ecx.frame();
+            if let Either::Left(loc) = frame.current_loc() {
+                let instance = frame.instance();
+                ecx.machine.fuzzer.cover(instance, loc.block);
+            }
+        }
+
         // Search for BorTags to find all live pointers, then remove all other tags from borrow
         // stacks. Also clean up dropped readiness watchers from the global readiness interest
         // table and closed source file descriptions in the blocking I/O manager.
@@ -1929,7 +2031,18 @@ fn after_stack_pop(
             // Move `frame` into a sub-scope so we control when it will be dropped.
             let mut frame = frame;
             let timing = frame.extra.timing.take();
-            let res = ecx.handle_stack_pop_unwind(frame.extra, unwinding);
+            let res = if frame.extra.fuzz_harness {
+                ecx.handle_stack_pop_fuzz(unwinding)
+            } else if frame.extra.native_callback {
+                // Control goes back to the native code that called this function; the caller of
+                // the native code takes over from there (see `call_rust_from_native`).
+                if unwinding {
+                    throw_unsup_format!("unwinding out of a function called from native code");
+                }
+                interp_ok(ReturnAction::NoJump)
+            } else {
+                ecx.handle_stack_pop_unwind(frame.extra, unwinding)
+            };
             if let Some(profiler) = ecx.machine.profiler.as_ref() {
                 profiler.finish_recording_interval_event(timing.unwrap());
             }
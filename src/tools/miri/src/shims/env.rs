@@ -31,18 +31,23 @@ pub(crate) fn init(
         ecx: &mut InterpCx<'tcx, MiriMachine<'tcx>>,
         config: &MiriConfig,
     ) -> InterpResult<'tcx> {
-        // Initialize the `env_vars` map.
+        // Initialize the `env_vars` map with the forwarded host variables, which are part of the
+        // host trace.
         // Skip the loop entirely if we don't want to forward anything.
-        let mut env_vars = FxHashMap::default();
-        if ecx.machine.communicate() || !config.forwarded_env_vars.is_empty() {
-            for (name, value) in &config.env {
-                let forward = ecx.machine.communicate()
-                    || config.forwarded_env_vars.iter().any(|v| **v == *name);
-                if forward {
-                    env_vars.insert(OsString::from(name), OsString::from(value));
+        let forwarded = ecx.machine.traced("env", || {
+            let mut forwarded = Vec::new();
+            if ecx.machine.communicate() || !config.forwarded_env_vars.is_empty() {
+                for (name, value) in &config.env {
+                    let forward = ecx.machine.communicate()
+                        || config.forwarded_env_vars.iter().any(|v| **v == *name);
+                    if forward {
+                        forwarded.push((OsString::from(name), OsString::from(value)));
+                    }
                 }
             }
-        }
+            interp_ok(forwarded)
+        })?;
+        let mut env_vars: FxHashMap<_, _> = forwarded.into_iter().collect();
 
         for (name, value) in &config.set_env_vars {
             env_vars.insert(OsString::from(name), OsString::from(value));
@@ -106,7 +111,7 @@ fn get_env_var(&mut self, name: &OsStr) -> InterpResult<'tcx, Option<OsString>>
     /// Get the process identifier.
     fn get_pid(&self) -> u32 {
         let this = self.eval_context_ref();
-        if this.machine.communicate() { std::process::id() } else { 1000 }
+        if this.machine.observes_host() { this.machine.host_pid() } else { 1000 }
     }
 
     /// Get an "OS" thread ID for any thread.
@@ -100,7 +100,7 @@ pub fn is_closed(&self) -> bool {
 
 impl<T> VisitProvenance for FileDescriptionRef<T> {
     fn visit_provenance(&self, _visit: &mut VisitWith<'_>) {
-        // All our FileDescription instances do not have any provenance.
+        // The provenance stored in file descriptions is visited via the `FdTable`.
     }
 }
 
@@ -225,6 +225,10 @@ fn readiness_watched(&self) -> Option<&ReadinessWatched> {
     fn readiness(&self) -> Readiness {
         panic!("FD type {} implements `readiness_watched` but not `readiness`", self.name());
     }
+
+    /// Visit the provenance of the pointers stored in this file description. Most file
+    /// descriptions do not store any pointers.
+    fn visit_provenance(&self, _visit: &mut VisitWith<'_>) {}
 }
 
 #[derive(Debug)]
@@ -252,13 +256,13 @@ fn read<'tcx>(
         ecx: &mut MiriInterpCx<'tcx>,
         finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
     ) -> InterpResult<'tcx> {
-        if !communicate_allowed {
+        if !communicate_allowed && !ecx.machine.replays_host() {
             // We want isolation mode to be deterministic, so we have to disallow all reads, even stdin.
             helpers::isolation_abort_error("`read` from stdin")?;
         }
 
         // FIXME: this can block on the host, halting the entire interpreter.
-        let result = ecx.read_from_host(|buf| (&mut &self.stdin).read(buf), len, ptr)?;
+        let result = ecx.read_from_host_traced(|buf| (&mut &self.stdin).read(buf), len, ptr)?;
         finish.call(ecx, result)
     }
 
@@ -448,7 +452,7 @@ fn read<'tcx>(
         }
 
         let mut file = &self.file;
-        let result = ecx.read_from_host(|buf| file.read(buf), len, ptr)?;
+        let result = ecx.read_from_host_traced(|buf| file.read(buf), len, ptr)?;
         finish.call(ecx, result)
     }
 
@@ -471,7 +475,7 @@ fn write<'tcx>(
             // thing.
             return finish.call(ecx, Err(ErrorKind::PermissionDenied.into()));
         }
-        let result = ecx.write_to_host(&self.file, len, ptr)?;
+        let result = ecx.write_to_host_traced(|bytes| (&self.file).write(bytes), len, ptr)?;
         finish.call(ecx, result)
     }
 
@@ -511,6 +515,74 @@ fn as_unix<'tcx>(
     }
 }
 
+/// A file that was opened while replaying a host trace. There is no host file behind it: all
+/// operations on it return the results recorded for the original file.
+#[derive(Debug)]
+pub struct ReplayedFile {
+    pub(crate) readable: bool,
+    pub(crate) writable: bool,
+}
+
+impl FileDescription for ReplayedFile {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn read<'tcx>(
+        self: FileDescriptionRef<Self>,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        if !self.readable {
+            return finish.call(ecx, Err(ErrorKind::PermissionDenied.into()));
+        }
+        let result = ecx.read_from_host_traced(
+            |_| unreachable!("replayed files have no host file"),
+            len,
+            ptr,
+        )?;
+        finish.call(ecx, result)
+    }
+
+    fn write<'tcx>(
+        self: FileDescriptionRef<Self>,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        if !self.writable {
+            return finish.call(ecx, Err(ErrorKind::PermissionDenied.into()));
+        }
+        let result = ecx.write_to_host_traced(
+            |_| unreachable!("replayed files have no host file"),
+            len,
+            ptr,
+        )?;
+        finish.call(ecx, result)
+    }
+
+    fn short_fd_operations(&self) -> bool {
+        // This must match `FileHandle`, so that the replay makes the same random choices.
+        true
+    }
+
+    fn as_unix<'tcx>(
+        self: FileDescriptionRef<Self>,
+        ecx: &MiriInterpCx<'tcx>,
+    ) -> FileDescriptionRef<dyn UnixFileDescription> {
+        assert!(
+            ecx.target_os_is_unix(),
+            "unix file operations are only available for unix targets"
+        );
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct DirHandle {
     pub(crate) dir: Dir,
@@ -540,8 +612,10 @@ pub struct FdTable {
 }
 
 impl VisitProvenance for FdTable {
-    fn visit_provenance(&self, _visit: &mut VisitWith<'_>) {
-        // All our FileDescription instances do not have any provenance.
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        for fd in self.fds.values() {
+            FileDescription::visit_provenance(&**fd, visit);
+        }
     }
 }
 
@@ -656,4 +730,52 @@ fn write_to_host(
         let result = file.write(bytes);
         interp_ok(result.map_err(IoError::HostError))
     }
+
+    /// Like `read_from_host`, but the bytes that were read are recorded in the host trace, or
+    /// replayed from it without calling `read_cb`.
+    fn read_from_host_traced(
+        &mut self,
+        mut read_cb: impl FnMut(&mut [u8]) -> io::Result<usize>,
+        len: usize,
+        ptr: Pointer,
+    ) -> InterpResult<'tcx, Result<usize, IoError>> {
+        let this = self.eval_context_mut();
+
+        let result = this.machine.traced("read", || {
+            let mut bytes = vec![0; len];
+            let result = read_cb(&mut bytes).map(|read_size| {
+                bytes.truncate(read_size);
+                bytes
+            });
+            interp_ok(result)
+        })?;
+        match result {
+            Ok(bytes) => {
+                if bytes.len() > len {
+                    throw_machine_stop!(TerminationInfo::ReplayDiverged(format!(
+                        "the recorded run read {} bytes, but only {len} were requested",
+                        bytes.len()
+                    )));
+                }
+                this.write_bytes_ptr(ptr, bytes.iter().copied())?;
+                interp_ok(Ok(bytes.len()))
+            }
+            Err(e) => interp_ok(Err(IoError::HostError(e))),
+        }
+    }
+
+    /// Like `write_to_host`, but the result is recorded in the host trace, or replayed from it
+    /// without calling `write_cb`. The bytes are read from machine memory either way.
+    fn write_to_host_traced(
+        &mut self,
+        write_cb: impl FnOnce(&[u8]) -> io::Result<usize>,
+        len: usize,
+        ptr: Pointer,
+    ) -> InterpResult<'tcx, Result<usize, IoError>> {
+        let this = self.eval_context_ref();
+
+        let bytes = this.read_bytes_ptr_strip_provenance(ptr, Size::from_bytes(len))?;
+        let result = this.machine.traced("write", || interp_ok(write_cb(bytes)))?;
+        interp_ok(result.map_err(IoError::HostError))
+    }
 }
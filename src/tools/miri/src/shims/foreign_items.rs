@@ -69,6 +69,15 @@ fn emulate_foreign_item(
             }
         }
 
+        // Fuzzing calls the harness and only returns from `miri_fuzz` once that is done, so it needs
+        // to know the return and unwind targets.
+        if link_name.as_str() == "miri_fuzz" {
+            let [harness] = this.check_shim_sig_lenient(abi, CanonAbi::Rust, link_name, args)?;
+            let harness = this.read_pointer(harness)?;
+            this.start_fuzzing(harness, ret, unwind)?;
+            return interp_ok(None);
+        }
+
         // FIXME: avoid allocating memory
         let dest = this.force_allocation(dest)?;
 
@@ -402,6 +411,29 @@ fn emulate_foreign_item_inner(
                 }
                 this.machine.static_roots.push(alloc_id);
             }
+            #[cfg(all(feature = "native-lib", unix))]
+            "miri_adopt_native_alloc" => {
+                use crate::shims::native_lib::EvalContextExt as _;
+                let [ptr, size, align] =
+                    this.check_shim_sig_lenient(abi, CanonAbi::Rust, link_name, args)?;
+                let ptr = this.read_pointer(ptr)?;
+                let size = this.read_target_usize(size)?;
+                let align = this.read_target_usize(align)?;
+                let Ok(align) = Align::from_bytes(align) else {
+                    throw_unsup_format!(
+                        "`miri_adopt_native_alloc`: alignment must be a power of two, got {align}"
+                    );
+                };
+                let ptr = this.adopt_native_alloc(ptr, Size::from_bytes(size), align)?;
+                this.write_pointer(ptr, dest)?;
+            }
+            #[cfg(all(feature = "native-lib", unix))]
+            "miri_release_native_alloc" => {
+                use crate::shims::native_lib::EvalContextExt as _;
+                let [ptr] = this.check_shim_sig_lenient(abi, CanonAbi::Rust, link_name, args)?;
+                let ptr = this.read_pointer(ptr)?;
+                this.release_native_alloc(ptr)?;
+            }
             "miri_host_to_target_path" => {
                 let [ptr, out, out_size] =
                     this.check_shim_sig_lenient(abi, CanonAbi::Rust, link_name, args)?;
@@ -57,7 +57,7 @@ fn from(value: Scalar) -> Self {
 
 // This mapping should match `decode_error_kind` in
 // <https://github.com/rust-lang/rust/blob/HEAD/library/std/src/sys/io/error/unix.rs>.
-const UNIX_IO_ERROR_TABLE: &[(&str, std::io::ErrorKind)] = {
+pub(crate) const UNIX_IO_ERROR_TABLE: &[(&str, std::io::ErrorKind)] = {
     use std::io::ErrorKind::*;
     &[
         ("E2BIG", ArgumentListTooLong),
@@ -1,7 +1,6 @@
 //! Implements calling functions from a native library.
 
 use std::cell::Cell;
-use std::marker::PhantomData;
 use std::ops::Deref;
 use std::os::raw::c_void;
 use std::ptr;
@@ -9,13 +8,15 @@
 
 use libffi::low::CodePtr;
 use libffi::middle::Type as FfiType;
-use rustc_abi::{HasDataLayout, Size};
+use rustc_abi::{Align, HasDataLayout, Size};
 use rustc_data_structures::either;
+use rustc_middle::mir;
 use rustc_middle::ty::layout::TyAndLayout;
 use rustc_middle::ty::{self, Ty};
 use rustc_span::Symbol;
 use serde::{Deserialize, Serialize};
 
+use crate::alloc::MiriAllocParams;
 use crate::*;
 
 #[cfg_attr(
@@ -106,8 +107,12 @@ fn call_native_raw(
         // Placeholder value.
         let alloc = ();
 
-        // Expose InterpCx for use by closure callbacks.
-        this.machine.native_lib_ecx_interchange.set(ptr::from_mut(this).expose_provenance());
+        // Expose InterpCx for use by closure callbacks. Native code can be entered again from such
+        // a callback, so we restore the previous value afterwards.
+        let prev_ecx = this
+            .machine
+            .native_lib_ecx_interchange
+            .replace(ptr::from_mut(this).expose_provenance());
 
         let res = trace::Supervisor::do_ffi(&alloc, || {
             use libffi::middle::{Arg, Cif, Ret};
@@ -120,7 +125,7 @@ fn call_native_raw(
             ret.into()
         });
 
-        this.machine.native_lib_ecx_interchange.set(0);
+        this.machine.native_lib_ecx_interchange.set(prev_ecx);
 
         res
     }
@@ -175,6 +180,50 @@ fn get_func_ptr_explicitly_from_lib(&mut self, link_name: Symbol) -> Option<Code
         None
     }
 
+    /// Prepares all exposed memory (both previously exposed, and just newly exposed since a
+    /// pointer was passed as argument) for being accessed by native code. Uninitialised memory is
+    /// left as-is, but any data exposed this way is garbage anyway.
+    fn prepare_exposed_for_native(&mut self, tracing: bool) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        this.visit_reachable_allocs(this.exposed_allocs(), |this, alloc_id, info| {
+            // If there is no data behind this pointer, skip this.
+            if !matches!(info.kind, AllocKind::LiveData) {
+                return interp_ok(());
+            }
+            // It's okay to get raw access, what we do does not correspond to any actual
+            // AM operation, it just approximates the state to account for the native call.
+            let alloc = this.get_alloc_raw(alloc_id)?;
+            // Also expose the provenance of the interpreter-level allocation, so it can
+            // be read by FFI. The `black_box` is defensive programming as LLVM likes
+            // to (incorrectly) optimize away ptr2int casts whose result is unused.
+            std::hint::black_box(alloc.get_bytes_unchecked_raw().expose_provenance());
+
+            if !tracing {
+                // Expose all provenances in this allocation, since the native code can do
+                // $whatever. Can be skipped when tracing; in that case we'll expose just the
+                // actually-read parts later.
+                for prov in alloc.provenance().provenances() {
+                    this.expose_provenance(prov)?;
+                }
+            }
+
+            // Prepare for possible write from native code if mutable.
+            if info.mutbl.is_mut() {
+                let (alloc, cx) = this.get_alloc_raw_mut(alloc_id)?;
+                // These writes could initialize everything and wreck havoc with the pointers.
+                // We can skip that when tracing; in that case we'll later do that only for the
+                // memory that got actually written.
+                if !tracing {
+                    alloc.process_native_write(&cx.tcx, None);
+                }
+                // Also expose *mutable* provenance for the interpreter-level allocation.
+                std::hint::black_box(alloc.get_bytes_unchecked_raw_mut().expose_provenance());
+            }
+
+            interp_ok(())
+        })
+    }
+
     /// Applies the `events` to Miri's internal state. The event vector must be
     /// ordered sequentially by when the accesses happened, and the sizes are
     /// assumed to be exact.
@@ -339,11 +388,12 @@ fn op_to_ffi_arg(&self, v: &OpTy<'tcx>, tracing: bool) -> InterpResult<'tcx, Own
         interp_ok(OwnedArg::new(ty, bytes))
     }
 
-    fn ffi_ret_to_mem(&mut self, v: Box<[u8]>, dest: &MPlaceTy<'tcx>) -> InterpResult<'tcx> {
+    /// Stores a value that native code produced (a return value, or an argument of a callback)
+    /// in `dest`.
+    fn native_bytes_to_mem(&mut self, v: &[u8], dest: &MPlaceTy<'tcx>) -> InterpResult<'tcx> {
         let this = self.eval_context_mut();
-        let len = v.len();
-        this.write_bytes_ptr(dest.ptr(), v)?;
-        if len == 0 {
+        this.write_bytes_ptr(dest.ptr(), v.iter().copied())?;
+        if v.is_empty() {
             return interp_ok(());
         }
         // We have no idea which provenance these bytes have, so we reset it to wildcard.
@@ -425,14 +475,114 @@ fn ty_to_ffitype(&self, layout: TyAndLayout<'tcx>) -> Result<FfiType, Ty<'tcx>>
             _ => return Err(layout.ty),
         })
     }
+
+    /// Runs a Rust function that native code called through a function pointer, and stores its
+    /// return value in `result` the way libffi expects it.
+    ///
+    /// # Safety
+    /// `args` must point to the libffi argument array of a call to `callee`, and `result` must be
+    /// valid for writing the return value.
+    unsafe fn call_rust_from_native(
+        &mut self,
+        callee: &CallbackSig<'tcx>,
+        args: *const *const c_void,
+        result: *mut u8,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        if trace::Supervisor::is_enabled() {
+            // The tracer would attribute the memory accesses of the interpreter to native code.
+            throw_unsup_format!(
+                "calling a function pointer through the FFI boundary is not supported when tracing native code"
+            );
+        }
+
+        // Copy the arguments into interpreter memory. Like for return values of native calls, we
+        // do not know their provenance.
+        let mut arg_places = Vec::with_capacity(callee.args.len());
+        for (i, &layout) in callee.args.iter().enumerate() {
+            let place = this.allocate(layout, MiriMemoryKind::Machine.into())?;
+            let bytes = unsafe {
+                std::slice::from_raw_parts(
+                    args.add(i).read().cast::<u8>(),
+                    layout.size.bytes_usize(),
+                )
+            };
+            this.native_bytes_to_mem(bytes, &place)?;
+            arg_places.push(place);
+        }
+        let ret_place = this.allocate(callee.ret, MiriMemoryKind::Machine.into())?;
+
+        // Push the frame of the callee. When it is popped, we do not jump anywhere, see the
+        // handling of `native_callback` in `after_stack_pop`.
+        let body = this.load_mir(callee.instance.def, None)?;
+        let caller_fn_abi =
+            this.fn_abi_of_fn_ptr(ty::Binder::dummy(callee.sig), ty::List::empty())?;
+        let fn_args: Vec<_> =
+            arg_places.iter().map(|place| FnArg::Copy(place.clone().into())).collect();
+        this.init_stack_frame(
+            callee.instance,
+            body,
+            caller_fn_abi,
+            &fn_args,
+            /*with_caller_location*/ false,
+            &ret_place.clone().into(),
+            ReturnContinuation::Goto { ret: None, unwind: mir::UnwindAction::Unreachable },
+        )?;
+        this.frame_mut().extra.native_callback = true;
+
+        // Run the callee to completion. We cannot switch to another thread as long as native code
+        // is on the stack, so the callee must not block.
+        let depth = this.active_thread_stack().len();
+        while this.active_thread_stack().len() >= depth {
+            let stepped = this.step()?;
+            assert!(stepped, "the native caller is still on the stack");
+            if !this.machine.threads.active_thread_ref().is_enabled() {
+                throw_unsup_format!("blocking in a function called from native code");
+            }
+        }
+
+        // Pass the return value back to native code.
+        if !callee.ret.is_zst() {
+            let mut ret = this.op_to_ffi_arg(&ret_place.clone().into(), false)?.bytes.into_vec();
+            // libffi expects integers smaller than a register to be widened to a full register.
+            if let rustc_abi::BackendRepr::Scalar(scalar) = callee.ret.backend_repr
+                && let rustc_abi::Primitive::Int(_, signed) = scalar.primitive()
+                && callee.ret.size < this.pointer_size()
+            {
+                let bits = this.read_scalar(&ret_place)?.to_bits(callee.ret.size)?;
+                let bits =
+                    if signed { callee.ret.size.sign_extend(bits).cast_unsigned() } else { bits };
+                ret = vec![0; this.pointer_size().bytes_usize()];
+                write_target_uint(this.data_layout().endian, &mut ret, bits).unwrap();
+            }
+            unsafe { result.copy_from_nonoverlapping(ret.as_ptr(), ret.len()) };
+        }
+
+        for place in arg_places.into_iter().chain([ret_place]) {
+            this.deallocate_ptr(place.ptr(), None, MiriMemoryKind::Machine.into())?;
+        }
+
+        // The callee may have shared more memory with native code, which has to be prepared for
+        // native accesses just like before the original native call.
+        this.prepare_exposed_for_native(/*tracing*/ false)
+    }
+}
+
+/// The Rust function behind a function pointer that was passed to native code, along with the
+/// layouts of its arguments and return value.
+struct CallbackSig<'tcx> {
+    instance: ty::Instance<'tcx>,
+    sig: ty::FnSig<'tcx>,
+    args: Vec<TyAndLayout<'tcx>>,
+    ret: TyAndLayout<'tcx>,
 }
 
 /// The data passed to the closure shim function used to intercept function pointer calls from
 /// native code.
 struct LibffiClosureData<'tcx> {
     ecx_interchange: &'static Cell<usize>,
-    unsupported: bool,
-    marker: PhantomData<MiriInterpCx<'tcx>>,
+    /// `None` if the signature of the function is not supported for native calls.
+    callee: Option<CallbackSig<'tcx>>,
 }
 
 /// This function sets up a new libffi closure to intercept
@@ -443,37 +593,34 @@ struct LibffiClosureData<'tcx> {
 /// code side could store a function pointer and only call it at a later point.
 pub fn build_libffi_closure<'tcx, 'this>(
     this: &'this MiriInterpCx<'tcx>,
+    instance: ty::Instance<'tcx>,
     fn_sig: rustc_middle::ty::FnSig<'tcx>,
 ) -> InterpResult<'tcx, unsafe extern "C" fn()> {
     // Compute argument and return types in libffi representation.
     let closure_builder = try {
         let mut closure_builder = libffi::middle::Builder::new();
+        let mut args = Vec::with_capacity(fn_sig.inputs().len());
         for &input in fn_sig.inputs().iter() {
             let layout = this.layout_of(input).map_err(|_| input)?;
             let ty = this.ty_to_ffitype(layout)?;
             closure_builder = closure_builder.arg(ty);
+            args.push(layout);
         }
         let res_type = fn_sig.output();
-        let res_type = {
-            let layout = this.layout_of(res_type).map_err(|_| res_type)?;
-            this.ty_to_ffitype(layout)?
-        };
-        closure_builder.res(res_type)
+        let ret = this.layout_of(res_type).map_err(|_| res_type)?;
+        let callee = CallbackSig { instance, sig: fn_sig, args, ret };
+        (closure_builder.res(this.ty_to_ffitype(ret)?), callee)
     };
-    let mut unsupported = false;
-    let closure_builder = closure_builder.unwrap_or_else(|_| {
-        unsupported = true;
+    let (closure_builder, callee) = match closure_builder {
+        Ok((closure_builder, callee)) => (closure_builder, Some(callee)),
         // We hope that a closure which aborts execution is works correctly even if we don't
         // set its signature.
-        libffi::middle::Builder::new()
-    });
+        Err(_) => (libffi::middle::Builder::new(), None),
+    };
 
     // Build the actual closure.
-    let data = LibffiClosureData {
-        ecx_interchange: this.machine.native_lib_ecx_interchange,
-        unsupported,
-        marker: PhantomData,
-    };
+    let data =
+        LibffiClosureData { ecx_interchange: this.machine.native_lib_ecx_interchange, callee };
     let data = Box::leak(Box::new(data));
     let closure = closure_builder.into_closure(libffi_closure_callback, data);
     let closure = Box::leak(Box::new(closure));
@@ -488,12 +635,13 @@ pub fn build_libffi_closure<'tcx, 'this>(
 /// A shim function to intercept calls back from native code into the interpreter
 /// via function pointers passed to the native code.
 ///
-/// For now this shim only reports that such constructs are not supported by miri.
-/// As future improvement we might continue execution in the interpreter here.
+/// The call is executed in the interpreter before returning to native code. If that fails, the
+/// error is reported and execution is aborted: there is no way to unwind through the native
+/// frames back to the interpreter.
 unsafe extern "C" fn libffi_closure_callback<'tcx>(
     _cif: &libffi::low::ffi_cif,
-    _result: &mut c_void,
-    _args: *const *const c_void,
+    result: &mut c_void,
+    args: *const *const c_void,
     data: &LibffiClosureData<'tcx>,
 ) {
     let ecx = unsafe {
@@ -501,18 +649,25 @@ pub fn build_libffi_closure<'tcx, 'this>(
             .as_mut()
             .expect("libffi closure called while no FFI call is active")
     };
-    let err = if data.unsupported {
-        err_unsup_format!(
-            "calling a function pointer with unsupported argument/return type through the FFI boundary"
-        )
-    } else {
-        err_unsup_format!("calling a function pointer through the FFI boundary")
+    let err = match &data.callee {
+        Some(callee) => {
+            let result = ptr::from_mut(result).cast::<u8>();
+            match unsafe { ecx.call_rust_from_native(callee, args, result) }.report_err() {
+                Ok(()) => return,
+                Err(err) => err,
+            }
+        }
+        None =>
+            err_unsup_format!(
+                "calling a function pointer with unsupported argument/return type through the FFI boundary"
+            )
+            .into(),
     };
 
-    crate::diagnostics::report_result(ecx, err.into());
     // We abort the execution at this point as we cannot return the
-    // expected value here.
-    std::process::exit(1);
+    // expected value here. If the program asked to exit, we honor its exit code.
+    let code = crate::diagnostics::report_result(ecx, err).map_or(1, |(code, _leak_check)| code);
+    std::process::exit(code);
 }
 
 impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
@@ -549,46 +704,7 @@ fn call_native_fn(
             .ty_to_ffitype(dest.layout)
             .map_err(|ty| err_unsup_format!("unsupported return type for native call: {ty}"))?;
 
-        // Prepare all exposed memory (both previously exposed, and just newly exposed since a
-        // pointer was passed as argument). Uninitialised memory is left as-is, but any data
-        // exposed this way is garbage anyway.
-        this.visit_reachable_allocs(this.exposed_allocs(), |this, alloc_id, info| {
-            // If there is no data behind this pointer, skip this.
-            if !matches!(info.kind, AllocKind::LiveData) {
-                return interp_ok(());
-            }
-            // It's okay to get raw access, what we do does not correspond to any actual
-            // AM operation, it just approximates the state to account for the native call.
-            let alloc = this.get_alloc_raw(alloc_id)?;
-            // Also expose the provenance of the interpreter-level allocation, so it can
-            // be read by FFI. The `black_box` is defensive programming as LLVM likes
-            // to (incorrectly) optimize away ptr2int casts whose result is unused.
-            std::hint::black_box(alloc.get_bytes_unchecked_raw().expose_provenance());
-
-            if !tracing {
-                // Expose all provenances in this allocation, since the native code can do
-                // $whatever. Can be skipped when tracing; in that case we'll expose just the
-                // actually-read parts later.
-                for prov in alloc.provenance().provenances() {
-                    this.expose_provenance(prov)?;
-                }
-            }
-
-            // Prepare for possible write from native code if mutable.
-            if info.mutbl.is_mut() {
-                let (alloc, cx) = this.get_alloc_raw_mut(alloc_id)?;
-                // These writes could initialize everything and wreck havoc with the pointers.
-                // We can skip that when tracing; in that case we'll later do that only for the
-                // memory that got actually written.
-                if !tracing {
-                    alloc.process_native_write(&cx.tcx, None);
-                }
-                // Also expose *mutable* provenance for the interpreter-level allocation.
-                std::hint::black_box(alloc.get_bytes_unchecked_raw_mut().expose_provenance());
-            }
-
-            interp_ok(())
-        })?;
+        this.prepare_exposed_for_native(tracing)?;
 
         // Call the function and store its output.
         let (ret, maybe_memevents) =
@@ -596,7 +712,56 @@ fn call_native_fn(
         if tracing {
             this.tracing_apply_accesses(maybe_memevents.unwrap())?;
         }
-        this.ffi_ret_to_mem(ret, dest)?;
+        this.native_bytes_to_mem(&ret, dest)?;
         interp_ok(true)
     }
+
+    /// Makes `size` bytes of memory owned by native code, starting at `native_ptr`, accessible to
+    /// the interpreted program. Returns a pointer to that memory that can be used for accesses.
+    /// Miri treats the memory as initialized and never frees it; it stays accessible until it is
+    /// released with `release_native_alloc`.
+    fn adopt_native_alloc(
+        &mut self,
+        native_ptr: Pointer,
+        size: Size,
+        align: Align,
+    ) -> InterpResult<'tcx, Pointer> {
+        let this = self.eval_context_mut();
+        if this.machine.native_lib.is_empty() {
+            throw_unsup_format!(
+                "`miri_adopt_native_alloc` is only supported with `-Zmiri-native-lib`"
+            );
+        }
+        let addr = native_ptr.addr();
+        if addr == Size::ZERO {
+            throw_ub_format!("`miri_adopt_native_alloc` called on a null pointer");
+        }
+        if !addr.bytes().is_multiple_of(align.bytes()) {
+            throw_ub_format!(
+                "`miri_adopt_native_alloc` called on a pointer that is not aligned to {} bytes",
+                align.bytes()
+            );
+        }
+        if this.alloc_id_from_addr(addr.bytes(), 0).is_some() {
+            throw_unsup_format!(
+                "`miri_adopt_native_alloc` called on memory that is already managed by Miri"
+            );
+        }
+
+        // Build an allocation whose bytes are the native memory, see `MiriAllocBytes::zeroed`.
+        let params = MiriAllocParams::Native(ptr::with_exposed_provenance_mut(addr.bytes_usize()));
+        let alloc = Allocation::try_new(size, align, AllocInit::Zero, params)?;
+        let alloc_ptr = this.insert_allocation(alloc, MiriMemoryKind::Native.into())?;
+        // Native code can hand out further pointers into this memory, which come with wildcard
+        // provenance.
+        this.expose_provenance(alloc_ptr.provenance)?;
+        interp_ok(alloc_ptr.into())
+    }
+
+    /// Makes memory that was adopted with `adopt_native_alloc` inaccessible again, so that native
+    /// code can free it.
+    fn release_native_alloc(&mut self, ptr: Pointer) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        this.deallocate_ptr(ptr, None, MiriMemoryKind::Native.into())
+    }
 }
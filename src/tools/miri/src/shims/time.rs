@@ -73,8 +73,8 @@ fn clock_gettime(
 
         let duration = match this.parse_clockid(clk_id) {
             Some(TimeoutClock::RealTime) => {
-                this.check_no_isolation("`clock_gettime` with `REALTIME` clocks")?;
-                system_time_to_duration(&SystemTime::now())?
+                this.check_no_isolation_or_replay("`clock_gettime` with `REALTIME` clocks")?;
+                system_time_to_duration(&this.machine.system_time_now())?
             }
             Some(TimeoutClock::Monotonic) =>
                 this.machine
@@ -103,7 +103,7 @@ fn gettimeofday(
         let this = self.eval_context_mut();
 
         this.assert_target_os_is_unix("gettimeofday");
-        this.check_no_isolation("`gettimeofday`")?;
+        this.check_no_isolation_or_replay("`gettimeofday`")?;
 
         let tv = this.deref_pointer_as(tv_op, this.libc_ty_layout("timeval"))?;
 
@@ -113,7 +113,7 @@ fn gettimeofday(
             return this.set_errno_and_return_neg1_i32(LibcError("EINVAL"));
         }
 
-        let duration = system_time_to_duration(&SystemTime::now())?;
+        let duration = system_time_to_duration(&this.machine.system_time_now())?;
         let tv_sec = duration.as_secs();
         let tv_usec = duration.subsec_micros();
 
@@ -218,11 +218,11 @@ fn GetSystemTimeAsFileTime(
         let this = self.eval_context_mut();
 
         this.assert_target_os(Os::Windows, shim_name);
-        this.check_no_isolation(shim_name)?;
+        this.check_no_isolation_or_replay(shim_name)?;
 
         let filetime = this.deref_pointer_as(LPFILETIME_op, this.windows_ty_layout("FILETIME"))?;
 
-        let duration = this.system_time_since_windows_epoch(&SystemTime::now())?;
+        let duration = this.system_time_since_windows_epoch(&this.machine.system_time_now())?;
         let duration_ticks = this.windows_ticks_for(duration)?;
 
         let dwLowDateTime = u32::try_from(duration_ticks & 0x00000000FFFFFFFF).unwrap();
@@ -171,7 +171,9 @@ fn flock(&mut self, fd_num: FdNum, op: i32) -> InterpResult<'tcx, Scalar> {
             throw_unsup_format!("unsupported flags {:#x}", op);
         };
 
-        let result = fd.as_unix(this).flock(this.machine.communicate(), parsed_op)?;
+        let communicate = this.machine.communicate();
+        let result =
+            this.machine.traced("flock", || fd.as_unix(this).flock(communicate, parsed_op))?;
         // return `0` if flock is successful
         let result = result.map(|()| 0i32);
         interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
@@ -458,10 +460,95 @@ fn readv(
             return this.set_errno_and_return_neg1(LibcError("EBADF"), dest);
         };
 
+        let buffers = this.read_iovecs(iov_ptr, iovcnt)?;
+
+        let dest = dest.clone();
+        this.read_vectored_from_fd(
+            fd,
+            buffers,
+            offset,
+            callback!(
+                @capture<'tcx> {
+                    dest: MPlaceTy<'tcx>,
+                }
+                |this, result: Result<usize, IoError>| {
+                    match result {
+                        Ok(size) => this.write_scalar(Scalar::from_target_isize(size.try_into().unwrap(), this), &dest),
+                        Err(e) => this.set_errno_and_return_neg1(e, &dest)
+                    }
+                }
+            ),
+        )
+    }
+
+    /// Vectored writes are implemented by first writing the bytes from all
+    /// buffers of `iov` into a combined temporary buffer and then writing this
+    /// combined buffer into `fd`. This ensures that the vectored write occurs atomically.
+    fn writev(
+        &mut self,
+        fd: &OpTy<'tcx>,
+        iov: &OpTy<'tcx>,
+        iovcnt: &OpTy<'tcx>,
+        offset: Option<&OpTy<'tcx>>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let iov_ptr = this.read_pointer(iov)?;
+        let iovcnt: u64 = this.read_scalar(iovcnt)?.to_i32()?.try_into().unwrap();
+        // `writev` is the same as `pwritev` without an offset.
+        let offset = if let Some(offset) = offset {
+            if matches!(this.tcx.sess.target.os, Os::Solaris) {
+                throw_unsup_format!(
+                    "pwritev: vectored writes with offsets aren't supported on Solaris"
+                )
+            }
+            Some(this.read_scalar(offset)?.to_int(offset.layout.size)?)
+        } else {
+            None
+        };
+
+        // Check that the FD exists.
+        let Some(fd) = this.machine.fds.get(fd) else {
+            return this.set_errno_and_return_neg1(LibcError("EBADF"), dest);
+        };
+
+        let buffers = this.read_iovecs(iov_ptr, iovcnt)?;
+
+        let dest = dest.clone();
+        this.write_vectored_to_fd(
+            fd,
+            buffers,
+            offset,
+            callback!(
+                @capture<'tcx> {
+                    dest: MPlaceTy<'tcx>,
+                }
+                |this, result: Result<usize, IoError>| {
+                    match result {
+                        Ok(size) => this.write_scalar(Scalar::from_target_isize(size.try_into().unwrap(), this), &dest),
+                        Err(e) => this.set_errno_and_return_neg1(e, &dest)
+                    }
+                }
+            ),
+        )
+    }
+}
+
+impl<'tcx> EvalContextPrivExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub(super) trait EvalContextPrivExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Read the list of buffers from the `iovcnt` elements of the `iovec` array at `iov_ptr`.
+    fn read_iovecs(
+        &self,
+        iov_ptr: Pointer,
+        iovcnt: u64,
+    ) -> InterpResult<'tcx, Vec<(Pointer, u64)>> {
+        let this = self.eval_context_ref();
+
         let iovec_layout = this.libc_array_ty_layout("iovec", iovcnt);
         let iov_ptr_mplace = this.ptr_to_mplace(iov_ptr, iovec_layout);
 
-        // Read list of buffers from `iov`.
         let mut buffers = Vec::new();
 
         let mut array = this.project_array_fields(&iov_ptr_mplace)?;
@@ -479,6 +566,21 @@ fn readv(
             buffers.push((iov_base_ptr, iov_len));
         }
 
+        interp_ok(buffers)
+    }
+
+    /// Read from the `fd` file description at `offset` into the given list of buffers.
+    /// This reads into a temporary buffer first, so the vectored read occurs atomically.
+    /// `finish` will be invoked when the read is done.
+    fn read_vectored_from_fd(
+        &mut self,
+        fd: DynFileDescriptionRef,
+        buffers: Vec<(Pointer, u64)>,
+        offset: Option<i128>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
         let total_bytes = buffers.iter().map(|(_, len)| len).sum::<u64>();
 
         // Allocate a temporary buffer which has the combined size of all buffers provided in `iov`.
@@ -491,7 +593,6 @@ fn readv(
             )?
             .into();
 
-        let dest = dest.clone();
         this.read_from_fd(
             fd,
             tmp_ptr,
@@ -501,16 +602,13 @@ fn readv(
                 @capture<'tcx> {
                     tmp_ptr: Pointer,
                     buffers: Vec<(Pointer, u64)>,
-                    dest: MPlaceTy<'tcx>
+                    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
                 } |this, result: Result<usize, IoError>| {
                     let bytes_read = match result {
-                        Ok(size) => {
-                            this.write_scalar(Scalar::from_target_isize(size.try_into().unwrap(), this), &dest)?;
-                            u64::try_from(size).unwrap()
-                        },
+                        Ok(size) => u64::try_from(size).unwrap(),
                         Err(e) => {
                             this.deallocate_ptr(tmp_ptr, None, MemoryKind::Stack)?;
-                            return this.set_errno_and_return_neg1(e, &dest)
+                            return finish.call(this, Err(e));
                         }
                     };
                     let mut remaining_bytes = bytes_read;
@@ -543,65 +641,24 @@ fn readv(
                         }
                     }
 
-                    this.deallocate_ptr(tmp_ptr, None, MemoryKind::Stack)
+                    this.deallocate_ptr(tmp_ptr, None, MemoryKind::Stack)?;
+                    finish.call(this, Ok(usize::try_from(bytes_read).unwrap()))
                 }),
         )
     }
 
-    /// Vectored writes are implemented by first writing the bytes from all
-    /// buffers of `iov` into a combined temporary buffer and then writing this
-    /// combined buffer into `fd`. This ensures that the vectored write occurs atomically.
-    fn writev(
+    /// Write the given list of buffers into the `fd` file description at `offset`.
+    /// This gathers the buffers into a temporary buffer first, so the vectored write occurs
+    /// atomically. `finish` will be invoked when the write is done.
+    fn write_vectored_to_fd(
         &mut self,
-        fd: &OpTy<'tcx>,
-        iov: &OpTy<'tcx>,
-        iovcnt: &OpTy<'tcx>,
-        offset: Option<&OpTy<'tcx>>,
-        dest: &MPlaceTy<'tcx>,
+        fd: DynFileDescriptionRef,
+        buffers: Vec<(Pointer, u64)>,
+        offset: Option<i128>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
     ) -> InterpResult<'tcx> {
         let this = self.eval_context_mut();
 
-        let fd = this.read_scalar(fd)?.to_i32()?;
-        let iov_ptr = this.read_pointer(iov)?;
-        let iovcnt: u64 = this.read_scalar(iovcnt)?.to_i32()?.try_into().unwrap();
-        // `writev` is the same as `pwritev` without an offset.
-        let offset = if let Some(offset) = offset {
-            if matches!(this.tcx.sess.target.os, Os::Solaris) {
-                throw_unsup_format!(
-                    "pwritev: vectored writes with offsets aren't supported on Solaris"
-                )
-            }
-            Some(this.read_scalar(offset)?.to_int(offset.layout.size)?)
-        } else {
-            None
-        };
-
-        // Check that the FD exists.
-        let Some(fd) = this.machine.fds.get(fd) else {
-            return this.set_errno_and_return_neg1(LibcError("EBADF"), dest);
-        };
-
-        let iovec_layout = this.libc_array_ty_layout("iovec", iovcnt);
-        let iov_ptr_mplace = this.ptr_to_mplace(iov_ptr, iovec_layout);
-
-        // Read list of buffers from `iov`.
-        let mut buffers = Vec::new();
-
-        let mut array = this.project_array_fields(&iov_ptr_mplace)?;
-        while let Some((_idx, iovec)) = array.next(this)? {
-            let iov_len_field = this.project_field_named(&iovec, "iov_len")?;
-            let iov_len: u64 = this
-                .read_scalar(&iov_len_field)?
-                .to_int(iov_len_field.layout.size)?
-                .try_into()
-                .unwrap();
-
-            let iov_base_field = this.project_field_named(&iovec, "iov_base")?;
-            let iov_base_ptr = this.read_pointer(&iov_base_field)?;
-
-            buffers.push((iov_base_ptr, iov_len));
-        }
-
         let total_bytes = buffers.iter().map(|(_, len)| len).sum::<u64>();
 
         // Allocate a temporary buffer which has the combined size of all buffers provided in `iov`.
@@ -635,7 +692,6 @@ fn writev(
             bytes_copied = bytes_copied.strict_add(buffer_len);
         }
 
-        let dest = dest.clone();
         // Write bytes from the temporary buffer. This ensures the write is atomic.
         this.write_to_fd(
             fd,
@@ -645,21 +701,15 @@ fn writev(
             callback!(
                 @capture<'tcx> {
                     tmp_ptr: Pointer,
-                    dest: MPlaceTy<'tcx>,
+                    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
                 }
                 |this, result: Result<usize, IoError>| {
                     this.deallocate_ptr(tmp_ptr, None, MemoryKind::Stack)?;
-                    match result {
-                        Ok(size) => this.write_scalar(Scalar::from_target_isize(size.try_into().unwrap(), this), &dest),
-                        Err(e) => this.set_errno_and_return_neg1(e, &dest)
-                    }
+                    finish.call(this, result)
             }),
         )
     }
-}
 
-impl<'tcx> EvalContextPrivExt<'tcx> for crate::MiriInterpCx<'tcx> {}
-trait EvalContextPrivExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
     /// Read `len` bytes from the `fd` file description at `offset` into the buffer
     /// pointed to by `ptr`.
     /// If `offset` is [`Some`], the read occurs at the given absolute position rather
@@ -115,7 +115,7 @@ fn emulate_foreign_item_inner(
     ) -> InterpResult<'tcx, EmulateItemResult> {
         let this = self.eval_context_mut();
 
-        if this.machine.communicate() {
+        if this.machine.observes_host() {
             // When isolation is disabled we need to check for new host I/O events before
             // running any shimmed function. This is needed to ensure that the shim we
             // execute has up-to-date information about host readiness (as reflected
@@ -118,7 +118,9 @@ fn _umtx_op(
                             ))
                         } else if uaddr == timespec_layout.size.bytes() {
                             // RealTime clock can't be used in isolation mode.
-                            this.check_no_isolation("`_umtx_op` with `timespec` timeout")?;
+                            this.check_no_isolation_or_replay(
+                                "`_umtx_op` with `timespec` timeout",
+                            )?;
 
                             // `uaddr2` points to a `struct timespec`.
                             let timespec = this.ptr_to_mplace(uaddr2, timespec_layout);
@@ -241,7 +243,7 @@ fn read_umtx_time(&mut self, ut: &MPlaceTy<'tcx>) -> InterpResult<'tcx, Option<U
             throw_unsup_format!("unsupported clock")
         };
         if timeout_clock == TimeoutClock::RealTime {
-            this.check_no_isolation("`_umtx_op` with `CLOCK_REALTIME`")?;
+            this.check_no_isolation_or_replay("`_umtx_op` with `CLOCK_REALTIME`")?;
         }
 
         interp_ok(Some(UmtxTime { timeout: duration, abs_time: abs_time_flag, timeout_clock }))
@@ -4,7 +4,8 @@
 use std::ffi::OsString;
 use std::fs::{self, DirBuilder, File, FileTimes, FileType, OpenOptions, TryLockError};
 use std::io::{self, ErrorKind, Read, Seek, SeekFrom, Write};
-use std::path::{self, Path};
+use std::path::{self, Path, PathBuf};
+use std::str::SplitAsciiWhitespace;
 use std::time::SystemTime;
 
 use rustc_abi::{FieldIdx, Size};
@@ -13,7 +14,9 @@
 use rustc_target::spec::Os;
 
 use self::shims::time::system_time_to_duration;
-use crate::shims::files::FileHandle;
+use crate::shims::files::{
+    DynFileDescriptionRef, EvalContextExt as _, FileDescriptionRef, FileHandle, ReplayedFile,
+};
 use crate::shims::os_str::bytes_to_os_str;
 use crate::shims::sig::check_min_vararg_count;
 use crate::shims::unix::fd::{FlockOp, UnixFileDescription};
@@ -25,25 +28,18 @@ struct OpenDir {
     /// The "special" entries that must still be yielded by the iterator.
     /// Used for `.` and `..`.
     special_entries: Vec<&'static str>,
-    /// The directory reader on the host.
-    read_dir: fs::ReadDir,
+    /// The directory reader on the host, or `None` if the directory was opened while replaying
+    /// a host trace.
+    read_dir: Option<fs::ReadDir>,
     /// The most recent entry returned by readdir().
     /// Will be freed by the next call.
     entry: Option<Pointer>,
 }
 
 impl OpenDir {
-    fn new(read_dir: fs::ReadDir) -> Self {
+    fn new(read_dir: Option<fs::ReadDir>) -> Self {
         Self { special_entries: vec!["..", "."], read_dir, entry: None }
     }
-
-    fn next_host_entry(&mut self) -> Option<io::Result<Either<fs::DirEntry, &'static str>>> {
-        if let Some(special) = self.special_entries.pop() {
-            return Some(Ok(Either::Right(special)));
-        }
-        let entry = self.read_dir.next()?;
-        Some(entry.map(Either::Left))
-    }
 }
 
 #[derive(Debug)]
@@ -53,6 +49,22 @@ struct DirEntry {
     d_type: i32,
 }
 
+impl TraceValue for DirEntry {
+    fn encode(&self, out: &mut String) {
+        self.name.encode(out);
+        self.ino.encode(out);
+        self.d_type.encode(out);
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        Some(DirEntry {
+            name: TraceValue::decode(tokens)?,
+            ino: TraceValue::decode(tokens)?,
+            d_type: TraceValue::decode(tokens)?,
+        })
+    }
+}
+
 /// What a `futimens` `timespec` asks for: leave the timestamp alone (`UTIME_OMIT`) or set it.
 #[derive(Copy, Clone)]
 enum TimeUpdate {
@@ -75,30 +87,20 @@ fn pread<'tcx>(
             return finish.call(ecx, Err(LibcError("EBADF")));
         }
 
-        let mut bytes = vec![0; len];
         // Emulates pread using seek + read + seek to restore cursor position.
         // Correctness of this emulation relies on sequential nature of Miri execution.
         // The closure is used to emulate `try` block, since we "bubble" `io::Error` using `?`.
         let file = &mut &self.file;
-        let mut f = || {
+        let f = |bytes: &mut [u8]| {
             let cursor_pos = file.stream_position()?;
             file.seek(SeekFrom::Start(offset))?;
-            let res = file.read(&mut bytes);
+            let res = file.read(bytes);
             // Attempt to restore cursor position even if the read has failed
             file.seek(SeekFrom::Start(cursor_pos))
                 .expect("failed to restore file position, this shouldn't be possible");
             res
         };
-        let result = match f() {
-            Ok(read_size) => {
-                // If reading to `bytes` did not fail, we write those bytes to the buffer.
-                // Crucially, if fewer than `bytes.len()` bytes were read, only write
-                // that much into the output buffer!
-                ecx.write_bytes_ptr(ptr, bytes[..read_size].iter().copied())?;
-                Ok(read_size)
-            }
-            Err(e) => Err(IoError::HostError(e)),
-        };
+        let result = ecx.read_from_host_traced(f, len, ptr)?;
         finish.call(ecx, result)
     }
 
@@ -120,8 +122,7 @@ fn pwrite<'tcx>(
         // Correctness of this emulation relies on sequential nature of Miri execution.
         // The closure is used to emulate `try` block, since we "bubble" `io::Error` using `?`.
         let file = &mut &self.file;
-        let bytes = ecx.read_bytes_ptr_strip_provenance(ptr, Size::from_bytes(len))?;
-        let mut f = || {
+        let f = |bytes: &[u8]| {
             let cursor_pos = file.stream_position()?;
             file.seek(SeekFrom::Start(offset))?;
             let res = file.write(bytes);
@@ -130,8 +131,8 @@ fn pwrite<'tcx>(
                 .expect("failed to restore file position, this shouldn't be possible");
             res
         };
-        let result = f();
-        finish.call(ecx, result.map_err(IoError::HostError))
+        let result = ecx.write_to_host_traced(f, len, ptr)?;
+        finish.call(ecx, result)
     }
 
     fn flock<'tcx>(
@@ -164,6 +165,95 @@ fn flock<'tcx>(
     }
 }
 
+impl UnixFileDescription for ReplayedFile {
+    fn pread<'tcx>(
+        &self,
+        _communicate_allowed: bool,
+        _offset: u64,
+        ptr: Pointer,
+        len: usize,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        if !self.readable {
+            return finish.call(ecx, Err(LibcError("EBADF")));
+        }
+        let result = ecx.read_from_host_traced(
+            |_| unreachable!("replayed files have no host file"),
+            len,
+            ptr,
+        )?;
+        finish.call(ecx, result)
+    }
+
+    fn pwrite<'tcx>(
+        &self,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        _offset: u64,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        if !self.writable {
+            return finish.call(ecx, Err(LibcError("EBADF")));
+        }
+        let result = ecx.write_to_host_traced(
+            |_| unreachable!("replayed files have no host file"),
+            len,
+            ptr,
+        )?;
+        finish.call(ecx, result)
+    }
+}
+
+/// A regular file: either a file on the host, or a file that was opened while replaying a host
+/// trace.
+enum RegularFile {
+    Host(FileDescriptionRef<FileHandle>),
+    Replayed(FileDescriptionRef<ReplayedFile>),
+}
+
+impl RegularFile {
+    fn from_fd(fd: DynFileDescriptionRef) -> Option<Self> {
+        if let Some(file) = fd.clone().downcast::<FileHandle>() {
+            Some(RegularFile::Host(file))
+        } else {
+            fd.downcast::<ReplayedFile>().map(RegularFile::Replayed)
+        }
+    }
+
+    fn readable(&self) -> bool {
+        match self {
+            RegularFile::Host(file) => file.readable,
+            RegularFile::Replayed(file) => file.readable,
+        }
+    }
+
+    fn writable(&self) -> bool {
+        match self {
+            RegularFile::Host(file) => file.writable,
+            RegularFile::Replayed(file) => file.writable,
+        }
+    }
+
+    /// Performs `op` on the host file and records the result in the host trace, if any. For
+    /// replayed files, the recorded result is returned instead.
+    fn traced<'tcx, T: TraceValue>(
+        &self,
+        machine: &MiriMachine<'tcx>,
+        name: &str,
+        op: impl FnOnce(&File) -> InterpResult<'tcx, T>,
+    ) -> InterpResult<'tcx, T> {
+        machine.traced(name, || {
+            match self {
+                RegularFile::Host(file) => op(&file.file),
+                RegularFile::Replayed(_) => unreachable!("replayed files have no host file"),
+            }
+        })
+    }
+}
+
 /// The table of open directories.
 /// Curiously, Unix/POSIX does not unify this into the "file descriptor" concept... everything
 /// is a file, except a directory is not?
@@ -185,7 +275,7 @@ pub struct DirTable {
 
 impl DirTable {
     #[expect(clippy::arithmetic_side_effects)]
-    fn insert_new(&mut self, read_dir: fs::ReadDir) -> u64 {
+    fn insert_new(&mut self, read_dir: Option<fs::ReadDir>) -> u64 {
         let id = self.next_id;
         self.next_id += 1;
         self.streams.try_insert(id, OpenDir::new(read_dir)).unwrap();
@@ -239,7 +329,7 @@ fn parse_utimens_timespec(
     ) -> InterpResult<'tcx, Option<TimeUpdate>> {
         let this = self.eval_context_ref();
         // `UTIME_NOW` reads the host clock, which we must not do under isolation.
-        assert!(this.machine.communicate(), "isolation should have prevented reaching this");
+        assert!(this.machine.observes_host(), "isolation should have prevented reaching this");
 
         // `tv_nsec` and the `UTIME_*` constants are `c_long`, i.e. the target's `isize`.
         let nsec_place = this.project_field(tp, FieldIdx::ONE)?;
@@ -249,7 +339,7 @@ fn parse_utimens_timespec(
             return interp_ok(Some(TimeUpdate::Omit));
         }
         if nsec == this.eval_libc("UTIME_NOW").to_target_isize(this)? {
-            return interp_ok(Some(TimeUpdate::Set(SystemTime::now())));
+            return interp_ok(Some(TimeUpdate::Set(this.machine.system_time_now())));
         }
 
         let Some(duration) = this.read_timespec(tp)? else {
@@ -354,33 +444,47 @@ fn file_type_to_d_type(&self, file_type: std::io::Result<FileType>) -> InterpRes
         }
     }
 
-    fn dir_entry_fields(
-        &self,
-        entry: Either<fs::DirEntry, &'static str>,
-    ) -> InterpResult<'tcx, DirEntry> {
+    fn dir_entry_fields(&self, dir_entry: fs::DirEntry) -> InterpResult<'tcx, DirEntry> {
         let this = self.eval_context_ref();
-        interp_ok(match entry {
-            Either::Left(dir_entry) => {
-                DirEntry {
-                    name: dir_entry.file_name(),
-                    d_type: this.file_type_to_d_type(dir_entry.file_type())?,
-                    // If the host is a Unix system, fill in the inode number with its real value.
-                    // If not, use 0 as a fallback value.
-                    #[cfg(unix)]
-                    ino: std::os::unix::fs::DirEntryExt::ino(&dir_entry),
-                    #[cfg(not(unix))]
-                    ino: 0u64,
-                }
-            }
-            Either::Right(special) =>
-                DirEntry {
-                    name: special.into(),
-                    d_type: this.eval_libc("DT_DIR").to_u8()?.into(),
-                    ino: 0,
-                },
+        interp_ok(DirEntry {
+            name: dir_entry.file_name(),
+            d_type: this.file_type_to_d_type(dir_entry.file_type())?,
+            // If the host is a Unix system, fill in the inode number with its real value.
+            // If not, use 0 as a fallback value.
+            #[cfg(unix)]
+            ino: std::os::unix::fs::DirEntryExt::ino(&dir_entry),
+            #[cfg(not(unix))]
+            ino: 0u64,
         })
     }
 
+    /// Returns the next entry of the directory stream `dirp`, or `None` at the end of the stream.
+    /// Entries read from the host are recorded in the host trace, if any.
+    fn next_dir_entry(&mut self, dirp: u64) -> InterpResult<'tcx, Option<io::Result<DirEntry>>> {
+        let this = self.eval_context_mut();
+        let open_dir = this.machine.dirs.streams.get_mut(&dirp).unwrap();
+        if let Some(special) = open_dir.special_entries.pop() {
+            return interp_ok(Some(Ok(DirEntry {
+                name: special.into(),
+                d_type: this.eval_libc("DT_DIR").to_u8()?.into(),
+                ino: 0,
+            })));
+        }
+
+        // Take the host reader out of the table so that `this` can be used to record the entry.
+        let mut read_dir = open_dir.read_dir.take();
+        let entry = this.machine.traced("readdir", || {
+            let read_dir = read_dir.as_mut().expect("replayed directories have no host reader");
+            match read_dir.next() {
+                None => interp_ok(None),
+                Some(Err(e)) => interp_ok(Some(Err(e))),
+                Some(Ok(dir_entry)) => interp_ok(Some(Ok(this.dir_entry_fields(dir_entry)?))),
+            }
+        });
+        this.machine.dirs.streams.get_mut(&dirp).unwrap().read_dir = read_dir;
+        entry
+    }
+
     #[cfg(unix)]
     fn host_permissions_from_mode(&self, mode: u32) -> InterpResult<'tcx, fs::Permissions> {
         use std::os::unix::fs::PermissionsExt;
@@ -531,15 +635,25 @@ fn open(
             throw_unsup_format!("unsupported flags {:#x}", flag);
         }
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`open`", reject_with)?;
             return this.set_errno_and_return_neg1_i32(ErrorKind::PermissionDenied);
         }
 
-        let fd = options
-            .open(path)
-            .map(|file| this.machine.fds.insert_new(FileHandle { file, writable, readable }));
+        // When replaying, there is no host file, only the result of opening it.
+        let mut file = None;
+        let result = this
+            .machine
+            .traced("open", || interp_ok(options.open(path).map(|f| file = Some(f))))?;
+        let fd = result.map(|()| {
+            match file {
+                Some(file) => this.machine.fds.insert_new(FileHandle { file, writable, readable }),
+                None => this.machine.fds.insert_new(ReplayedFile { writable, readable }),
+            }
+        });
 
         interp_ok(Scalar::from_i32(this.try_unwrap_io_result(fd)?))
     }
@@ -575,7 +689,8 @@ fn lseek(
         let Some(fd) = this.machine.fds.get(fd_num) else {
             return this.set_errno_and_return_neg1(LibcError("EBADF"), dest);
         };
-        let result = fd.seek(communicate, seek_from)?.map(|offset| i64::try_from(offset).unwrap());
+        let result = this.machine.traced("seek", || fd.seek(communicate, seek_from))?;
+        let result = result.map(|offset| i64::try_from(offset).unwrap());
         drop(fd);
 
         let result = this.try_unwrap_io_result(result)?;
@@ -588,13 +703,16 @@ fn unlink(&mut self, path_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
 
         let path = this.read_path_from_c_str(this.read_pointer(path_op)?)?;
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`unlink`", reject_with)?;
             return this.set_errno_and_return_neg1_i32(ErrorKind::PermissionDenied);
         }
 
-        let result = fs::remove_file(path).map(|_| 0);
+        let result = this.machine.traced("unlink", || interp_ok(fs::remove_file(path)));
+        let result = result?.map(|_| 0);
         interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
     }
 
@@ -618,13 +736,16 @@ fn create_link(src: &Path, dst: &Path) -> std::io::Result<()> {
         let target = this.read_path_from_c_str(this.read_pointer(target_op)?)?;
         let linkpath = this.read_path_from_c_str(this.read_pointer(linkpath_op)?)?;
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`symlink`", reject_with)?;
             return this.set_errno_and_return_neg1_i32(ErrorKind::PermissionDenied);
         }
 
-        let result = create_link(&target, &linkpath).map(|_| 0);
+        let result = this.machine.traced("symlink", || interp_ok(create_link(&target, &linkpath)));
+        let result = result?.map(|_| 0);
         interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
     }
 
@@ -648,8 +769,10 @@ fn linkat(
         // Relevant libc constants
         let at_fdcwd = this.eval_libc_i32("AT_FDCWD");
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`linkat`", reject_with)?;
             return this.set_errno_and_return_neg1_i32(ErrorKind::PermissionDenied);
         }
@@ -677,7 +800,8 @@ fn linkat(
         }
         let newpath = this.read_path_from_c_str(newpath_ptr)?.into_owned();
 
-        let result = fs::hard_link(&oldpath, &newpath).map(|()| 0);
+        let result = this.machine.traced("link", || interp_ok(fs::hard_link(&oldpath, &newpath)));
+        let result = result?.map(|()| 0);
         interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
     }
 
@@ -694,8 +818,10 @@ fn stat(&mut self, path_op: &OpTy<'tcx>, buf_op: &OpTy<'tcx>) -> InterpResult<'t
         let path_scalar = this.read_pointer(path_op)?;
         let path = this.read_path_from_c_str(path_scalar)?.into_owned();
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`stat`", reject_with)?;
             return this.set_errno_and_return_neg1_i32(LibcError("EACCES"));
         }
@@ -723,8 +849,10 @@ fn lstat(&mut self, path_op: &OpTy<'tcx>, buf_op: &OpTy<'tcx>) -> InterpResult<'
         let path_scalar = this.read_pointer(path_op)?;
         let path = this.read_path_from_c_str(path_scalar)?.into_owned();
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`lstat`", reject_with)?;
             return this.set_errno_and_return_neg1_i32(LibcError("EACCES"));
         }
@@ -749,8 +877,10 @@ fn fstat(&mut self, fd_op: &OpTy<'tcx>, buf_op: &OpTy<'tcx>) -> InterpResult<'tc
 
         let fd = this.read_scalar(fd_op)?.to_i32()?;
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`fstat`", reject_with)?;
             // Set error code as "EBADF" (bad fd)
             return this.set_errno_and_return_neg1_i32(LibcError("EBADF"));
@@ -810,8 +940,10 @@ fn linux_statx(
             )
         }
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`statx`", reject_with)?;
             let ecode = if path.is_absolute() || dirfd == this.eval_libc_i32("AT_FDCWD") {
                 // since `path` is provided, either absolute or
@@ -962,14 +1094,18 @@ fn chmod(&mut self, path_op: &OpTy<'tcx>, mode_op: &OpTy<'tcx>) -> InterpResult<
         }
         let path = this.read_path_from_c_str(path_ptr)?;
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`chmod`", reject_with)?;
             return this.set_errno_and_return_neg1_i32(LibcError("EACCES"));
         }
 
         let permissions = this.host_permissions_from_mode(mode.try_into().unwrap())?;
-        if let Err(err) = fs::set_permissions(path, permissions) {
+        let result =
+            this.machine.traced("chmod", || interp_ok(fs::set_permissions(path, permissions)));
+        if let Err(err) = result? {
             return this.set_errno_and_return_neg1_i32(err);
         }
 
@@ -985,19 +1121,24 @@ fn fchmod(&mut self, fd_op: &OpTy<'tcx>, mode_op: &OpTy<'tcx>) -> InterpResult<'
         let Some(fd) = this.machine.fds.get(fd_num) else {
             return this.set_errno_and_return_neg1_i32(LibcError("EBADF"));
         };
-        let Some(file) = fd.downcast::<FileHandle>() else {
+        let Some(file) = RegularFile::from_fd(fd) else {
             // The docs don't talk about what happens for non-regular files...
             throw_unsup_format!("`fchmod` is only supported on regular files")
         };
-        if !file.writable && !file.readable {
+        if !file.writable() && !file.readable() {
             // Apparently, `fchmod` on a read-only file is fine. But let's not allow it on a
             // path-only file.
             return this.set_errno_and_return_neg1_i32(LibcError("EBADF"));
         }
-        assert!(this.machine.communicate(), "isolation should have prevented even opening a file");
+        assert!(
+            this.machine.observes_host(),
+            "isolation should have prevented even opening a file"
+        );
 
         let permissions = this.host_permissions_from_mode(mode.try_into().unwrap())?;
-        if let Err(err) = file.file.set_permissions(permissions) {
+        let result =
+            file.traced(&this.machine, "fchmod", |f| interp_ok(f.set_permissions(permissions)));
+        if let Err(err) = result? {
             return this.set_errno_and_return_neg1_i32(err);
         }
 
@@ -1021,13 +1162,16 @@ fn rename(
         let oldpath = this.read_path_from_c_str(oldpath_ptr)?;
         let newpath = this.read_path_from_c_str(newpath_ptr)?;
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`rename`", reject_with)?;
             return this.set_errno_and_return_neg1_i32(ErrorKind::PermissionDenied);
         }
 
-        let result = fs::rename(oldpath, newpath).map(|_| 0);
+        let result = this.machine.traced("rename", || interp_ok(fs::rename(oldpath, newpath)));
+        let result = result?.map(|_| 0);
 
         interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
     }
@@ -1044,8 +1188,10 @@ fn mkdir(&mut self, path_op: &OpTy<'tcx>, mode_op: &OpTy<'tcx>) -> InterpResult<
 
         let path = this.read_path_from_c_str(this.read_pointer(path_op)?)?;
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`mkdir`", reject_with)?;
             return this.set_errno_and_return_neg1_i32(ErrorKind::PermissionDenied);
         }
@@ -1061,7 +1207,8 @@ fn mkdir(&mut self, path_op: &OpTy<'tcx>, mode_op: &OpTy<'tcx>) -> InterpResult<
             builder.mode(mode);
         }
 
-        let result = builder.create(path).map(|_| 0i32);
+        let result = this.machine.traced("mkdir", || interp_ok(builder.create(path)));
+        let result = result?.map(|_| 0i32);
 
         interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
     }
@@ -1071,13 +1218,16 @@ fn rmdir(&mut self, path_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
 
         let path = this.read_path_from_c_str(this.read_pointer(path_op)?)?;
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`rmdir`", reject_with)?;
             return this.set_errno_and_return_neg1_i32(ErrorKind::PermissionDenied);
         }
 
-        let result = fs::remove_dir(path).map(|_| 0i32);
+        let result = this.machine.traced("rmdir", || interp_ok(fs::remove_dir(path)));
+        let result = result?.map(|_| 0i32);
 
         interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
     }
@@ -1087,18 +1237,24 @@ fn opendir(&mut self, name_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
 
         let name = this.read_path_from_c_str(this.read_pointer(name_op)?)?;
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`opendir`", reject_with)?;
             this.set_last_error(LibcError("EACCES"))?;
             return interp_ok(Scalar::null_ptr(this));
         }
 
-        let result = fs::read_dir(name);
+        // When replaying, there is no host directory, only the result of opening it.
+        let mut read_dir = None;
+        let result = this
+            .machine
+            .traced("opendir", || interp_ok(fs::read_dir(name).map(|d| read_dir = Some(d))))?;
 
         match result {
-            Ok(dir_iter) => {
-                let id = this.machine.dirs.insert_new(dir_iter);
+            Ok(()) => {
+                let id = this.machine.dirs.insert_new(read_dir);
 
                 // The libc API for opendir says that this method returns a pointer to an opaque
                 // structure, but we are returning an ID number. Thus, pass it as a scalar of
@@ -1124,22 +1280,22 @@ fn readdir(&mut self, dirp_op: &OpTy<'tcx>, dest: &MPlaceTy<'tcx>) -> InterpResu
 
         let dirp = this.read_target_usize(dirp_op)?;
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`readdir`", reject_with)?;
             this.set_last_error(LibcError("EBADF"))?;
             this.write_null(dest)?;
             return interp_ok(());
         }
 
-        let open_dir = this.machine.dirs.streams.get_mut(&dirp).ok_or_else(|| {
-            err_ub_format!("the DIR pointer passed to `readdir` did not come from opendir")
-        })?;
+        if !this.machine.dirs.streams.contains_key(&dirp) {
+            throw_ub_format!("the DIR pointer passed to `readdir` did not come from opendir");
+        }
 
-        let entry = match open_dir.next_host_entry() {
+        let entry = match this.next_dir_entry(dirp)? {
             Some(Ok(dir_entry)) => {
-                let dir_entry = this.dir_entry_fields(dir_entry)?;
-
                 // Write the directory entry into a newly allocated buffer.
                 // The name is written with write_bytes, while the rest of the
                 // dirent64 (or dirent) struct is written using write_int_fields.
@@ -1255,19 +1411,20 @@ fn macos_readdir_r(
         let dirp = this.read_target_usize(dirp_op)?;
         let result_place = this.deref_pointer_as(result_op, this.machine.layouts.mut_raw_ptr)?;
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`readdir_r`", reject_with)?;
             // Return error code, do *not* set `errno`.
             return interp_ok(this.eval_libc("EBADF"));
         }
 
-        let open_dir = this.machine.dirs.streams.get_mut(&dirp).ok_or_else(|| {
-            err_unsup_format!("the DIR pointer passed to readdir_r did not come from opendir")
-        })?;
-        interp_ok(match open_dir.next_host_entry() {
+        if !this.machine.dirs.streams.contains_key(&dirp) {
+            throw_unsup_format!("the DIR pointer passed to readdir_r did not come from opendir");
+        }
+        interp_ok(match this.next_dir_entry(dirp)? {
             Some(Ok(dir_entry)) => {
-                let dir_entry = this.dir_entry_fields(dir_entry)?;
                 // Write into entry, write pointer to result, return 0 on success.
                 // The name is written with write_os_str_to_c_str, while the rest of the
                 // dirent struct is written using write_int_fields.
@@ -1329,8 +1486,10 @@ fn closedir(&mut self, dirp_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
 
         let dirp = this.read_target_usize(dirp_op)?;
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the directory was opened from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`closedir`", reject_with)?;
             return this.set_errno_and_return_neg1_i32(LibcError("EBADF"));
         }
@@ -1353,19 +1512,22 @@ fn ftruncate64(&mut self, fd_num: i32, length: i128) -> InterpResult<'tcx, Scala
         let Some(fd) = this.machine.fds.get(fd_num) else {
             return this.set_errno_and_return_neg1_i32(LibcError("EBADF"));
         };
-        let Some(file) = fd.downcast::<FileHandle>() else {
+        let Some(file) = RegularFile::from_fd(fd) else {
             // The docs say that EINVAL is returned when the FD "does not reference a regular file
             // or a POSIX shared memory object" (and we don't support shmem objects).
             return this.set_errno_and_return_neg1_i32(LibcError("EINVAL"));
         };
-        if !file.writable {
+        if !file.writable() {
             // man page says "EBADF or EINVAL", Linux seems to use EINVAL.
             return this.set_errno_and_return_neg1_i32(LibcError("EINVAL"));
         }
-        assert!(this.machine.communicate(), "isolation should have prevented even opening a file");
+        assert!(
+            this.machine.observes_host(),
+            "isolation should have prevented even opening a file"
+        );
 
         if let Ok(length) = length.try_into() {
-            let result = file.file.set_len(length);
+            let result = file.traced(&this.machine, "set-len", |f| interp_ok(f.set_len(length)))?;
             let result = this.try_unwrap_io_result(result.map(|_| 0i32))?;
             interp_ok(Scalar::from_i32(result))
         } else {
@@ -1383,8 +1545,10 @@ fn posix_fallocate(
     ) -> InterpResult<'tcx, Scalar> {
         let this = self.eval_context_mut();
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`posix_fallocate`", reject_with)?;
             // Return error code "EBADF" (bad fd).
             return interp_ok(this.eval_libc("EBADF"));
@@ -1406,8 +1570,10 @@ fn linux_fallocate(
         // This is mostly a copy of `posix_fallocate` except that errors are returned via errno.
         let this = self.eval_context_mut();
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`fallocate`", reject_with)?;
             // Set error code "EBADF" (bad fd).
             return this.set_errno_and_return_neg1_i32(LibcError("EBADF"));
@@ -1442,17 +1608,19 @@ fn fallocate_impl(
         let Some(fd) = this.machine.fds.get(fd_num) else {
             return interp_ok(Err(LibcError("EBADF")));
         };
-        let Some(file) = fd.downcast::<FileHandle>() else {
+        let Some(file) = RegularFile::from_fd(fd) else {
             // Man page specifies to return ENODEV if `fd` is not a regular file.
             return interp_ok(Err(LibcError("ENODEV")));
         };
 
-        if !file.writable {
+        if !file.writable() {
             return interp_ok(Err(LibcError("EBADF")));
         }
 
-        let current_size = match file.file.metadata() {
-            Ok(metadata) => metadata.len(),
+        let current_size =
+            file.traced(&this.machine, "size", |f| interp_ok(f.metadata().map(|m| m.len())))?;
+        let current_size = match current_size {
+            Ok(size) => size,
             Err(err) => return interp_ok(Err(err.into())),
         };
 
@@ -1466,7 +1634,7 @@ fn fallocate_impl(
         // If the size of the file is less than offset+size, then the file is increased to this
         // size; otherwise the file size is left unchanged.
         if current_size < new_size {
-            match file.file.set_len(new_size) {
+            match file.traced(&this.machine, "set-len", |f| interp_ok(f.set_len(new_size)))? {
                 Ok(()) => interp_ok(Ok(())),
                 Err(err) => interp_ok(Err(err.into())),
             }
@@ -1493,13 +1661,7 @@ fn ffullsync_fd(&mut self, fd_num: i32) -> InterpResult<'tcx, Scalar> {
         let Some(fd) = this.machine.fds.get(fd_num) else {
             return this.set_errno_and_return_neg1_i32(LibcError("EBADF"));
         };
-        // Only regular files support synchronization.
-        let file = fd.downcast::<FileHandle>().ok_or_else(|| {
-            err_unsup_format!("`fsync` is only supported on file-backed file descriptors")
-        })?;
-        assert!(this.machine.communicate(), "isolation should have prevented even opening a file");
-
-        let io_result = maybe_sync_file(&file.file, file.writable, File::sync_all);
+        let io_result = this.sync_fd(fd, /* data_only */ false, "fsync")?;
         interp_ok(Scalar::from_i32(this.try_unwrap_io_result(io_result)?))
     }
 
@@ -1511,14 +1673,36 @@ fn fdatasync(&mut self, fd_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let Some(fd) = this.machine.fds.get(fd) else {
             return this.set_errno_and_return_neg1_i32(LibcError("EBADF"));
         };
+        let io_result = this.sync_fd(fd, /* data_only */ true, "fdatasync")?;
+        interp_ok(Scalar::from_i32(this.try_unwrap_io_result(io_result)?))
+    }
+
+    /// Synchronizes the file behind `fd` with the disk: only its data if `data_only` is set, or
+    /// also its metadata otherwise. `name` is the operation to blame if `fd` is not a regular file.
+    fn sync_fd(
+        &mut self,
+        fd: DynFileDescriptionRef,
+        data_only: bool,
+        name: &str,
+    ) -> InterpResult<'tcx, io::Result<i32>> {
+        let this = self.eval_context_mut();
         // Only regular files support synchronization.
-        let file = fd.downcast::<FileHandle>().ok_or_else(|| {
-            err_unsup_format!("`fdatasync` is only supported on file-backed file descriptors")
+        let file = RegularFile::from_fd(fd).ok_or_else(|| {
+            err_unsup_format!("`{name}` is only supported on file-backed file descriptors")
         })?;
-        assert!(this.machine.communicate(), "isolation should have prevented even opening a file");
-
-        let io_result = maybe_sync_file(&file.file, file.writable, File::sync_data);
-        interp_ok(Scalar::from_i32(this.try_unwrap_io_result(io_result)?))
+        assert!(
+            this.machine.observes_host(),
+            "isolation should have prevented even opening a file"
+        );
+
+        let writable = file.writable();
+        file.traced(&this.machine, "sync", |f| {
+            interp_ok(maybe_sync_file(
+                f,
+                writable,
+                if data_only { File::sync_data } else { File::sync_all },
+            ))
+        })
     }
 
     /// `futimens(fd, times)`: set `fd`'s access/modification times. `times` is `[atime, mtime]`, or
@@ -1536,13 +1720,16 @@ fn futimens(
         let Some(fd) = this.machine.fds.get(fd_num) else {
             return this.set_errno_and_return_neg1_i32(LibcError("EBADF"));
         };
-        let file = fd.downcast::<FileHandle>().ok_or_else(|| {
+        let file = RegularFile::from_fd(fd).ok_or_else(|| {
             err_unsup_format!("`futimens` is only supported on file-backed file descriptors")
         })?;
-        assert!(this.machine.communicate(), "isolation should have prevented even opening a file");
+        assert!(
+            this.machine.observes_host(),
+            "isolation should have prevented even opening a file"
+        );
 
         let (access, modified) = if this.ptr_is_null(times_ptr)? {
-            let now = TimeUpdate::Set(SystemTime::now());
+            let now = TimeUpdate::Set(this.machine.system_time_now());
             (now, now)
         } else {
             let timespec = this.libc_ty_layout("timespec");
@@ -1564,7 +1751,8 @@ fn futimens(
         if let TimeUpdate::Set(modified) = modified {
             filetimes = filetimes.set_modified(modified);
         }
-        let result = file.file.set_times(filetimes);
+        let result =
+            file.traced(&this.machine, "set-times", |f| interp_ok(f.set_times(filetimes)))?;
         interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result.map(|()| 0i32))?))
     }
 
@@ -1596,12 +1784,18 @@ fn sync_file_range(
             return this.set_errno_and_return_neg1_i32(LibcError("EBADF"));
         };
         // Only regular files support synchronization.
-        let file = fd.downcast::<FileHandle>().ok_or_else(|| {
+        let file = RegularFile::from_fd(fd).ok_or_else(|| {
             err_unsup_format!("`sync_data_range` is only supported on file-backed file descriptors")
         })?;
-        assert!(this.machine.communicate(), "isolation should have prevented even opening a file");
-
-        let io_result = maybe_sync_file(&file.file, file.writable, File::sync_data);
+        assert!(
+            this.machine.observes_host(),
+            "isolation should have prevented even opening a file"
+        );
+
+        let writable = file.writable();
+        let io_result = file.traced(&this.machine, "sync", |f| {
+            interp_ok(maybe_sync_file(f, writable, File::sync_data))
+        })?;
         interp_ok(Scalar::from_i32(this.try_unwrap_io_result(io_result)?))
     }
 
@@ -1617,15 +1811,19 @@ fn readlink(
         let buf = this.read_pointer(buf_op)?;
         let bufsize = this.read_target_usize(bufsize_op)?;
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`readlink`", reject_with)?;
             this.set_last_error(LibcError("EACCES"))?;
             return interp_ok(-1);
         }
 
-        let result = std::fs::read_link(pathname);
-        match result {
+        let result = this.machine.traced("readlink", || {
+            interp_ok(std::fs::read_link(pathname).map(PathBuf::into_os_string))
+        })?;
+        match result.map(PathBuf::from) {
             Ok(resolved) => {
                 // 'readlink' truncates the resolved path if the provided buffer is not large
                 // enough, and does *not* add a null terminator. That means we cannot use the usual
@@ -1655,7 +1853,8 @@ fn isatty(&mut self, miri_fd: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         // otherwise 0 is returned, and errno is set to indicate the error"
         let fd = this.read_scalar(miri_fd)?.to_i32()?;
         let error = if let Some(fd) = this.machine.fds.get(fd) {
-            if fd.is_tty(this.machine.communicate()) {
+            let communicate = this.machine.communicate();
+            if this.machine.traced("isatty", || interp_ok(fd.is_tty(communicate)))? {
                 return interp_ok(Scalar::from_i32(1));
             } else {
                 LibcError("ENOTTY")
@@ -1679,15 +1878,19 @@ fn realpath(
         let pathname = this.read_path_from_c_str(this.read_pointer(path_op)?)?;
         let processed_ptr = this.read_pointer(processed_path_op)?;
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`realpath`", reject_with)?;
             this.set_last_error(LibcError("EACCES"))?;
             return interp_ok(Scalar::from_target_usize(0, this));
         }
 
-        let result = std::fs::canonicalize(pathname);
-        match result {
+        let result = this.machine.traced("realpath", || {
+            interp_ok(std::fs::canonicalize(pathname).map(PathBuf::into_os_string))
+        })?;
+        match result.map(PathBuf::from) {
             Ok(resolved) => {
                 let path_max = this
                     .eval_libc_i32("PATH_MAX")
@@ -1752,8 +1955,10 @@ fn mkstemp(&mut self, template_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let mut template = this.eval_context_ref().read_c_str(template_ptr)?.to_owned();
         let template_bytes = template.as_mut_slice();
 
-        // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the result is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`mkstemp`", reject_with)?;
             return this.set_errno_and_return_neg1_i32(LibcError("EACCES"));
         }
@@ -1821,14 +2026,25 @@ fn mkstemp(&mut self, template_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
             this.write_bytes_ptr(template_ptr, template_bytes.iter().copied())?;
 
             // See if we can create and open this file.
-            let file = fopts.open(bytes_to_os_str(template_bytes)?);
-            match file {
-                Ok(f) => {
-                    let fd = this.machine.fds.insert_new(FileHandle {
-                        file: f,
-                        writable: true,
-                        readable: true,
-                    });
+            let path = bytes_to_os_str(template_bytes)?;
+            let mut file = None;
+            let result = this
+                .machine
+                .traced("open", || interp_ok(fopts.open(path).map(|f| file = Some(f))))?;
+            match result {
+                Ok(()) => {
+                    let fd = match file {
+                        Some(file) =>
+                            this.machine.fds.insert_new(FileHandle {
+                                file,
+                                writable: true,
+                                readable: true,
+                            }),
+                        None =>
+                            this.machine
+                                .fds
+                                .insert_new(ReplayedFile { writable: true, readable: true }),
+                    };
                     return interp_ok(Scalar::from_i32(fd));
                 }
                 Err(e) =>
@@ -1916,16 +2132,56 @@ struct FileMetadata {
     blocks: Option<u64>,
 }
 
+/// Metadata of host files is recorded in host traces, so that `stat` and friends can be replayed.
+impl TraceValue for FileMetadata {
+    fn encode(&self, out: &mut String) {
+        self.mode.encode(out);
+        self.size.encode(out);
+        self.created.encode(out);
+        self.accessed.encode(out);
+        self.modified.encode(out);
+        self.dev.encode(out);
+        self.ino.encode(out);
+        self.nlink.encode(out);
+        self.uid.encode(out);
+        self.gid.encode(out);
+        self.blksize.encode(out);
+        self.blocks.encode(out);
+    }
+
+    fn decode(tokens: &mut SplitAsciiWhitespace<'_>) -> Option<Self> {
+        Some(FileMetadata {
+            mode: TraceValue::decode(tokens)?,
+            size: TraceValue::decode(tokens)?,
+            created: TraceValue::decode(tokens)?,
+            accessed: TraceValue::decode(tokens)?,
+            modified: TraceValue::decode(tokens)?,
+            dev: TraceValue::decode(tokens)?,
+            ino: TraceValue::decode(tokens)?,
+            nlink: TraceValue::decode(tokens)?,
+            uid: TraceValue::decode(tokens)?,
+            gid: TraceValue::decode(tokens)?,
+            blksize: TraceValue::decode(tokens)?,
+            blocks: TraceValue::decode(tokens)?,
+        })
+    }
+}
+
 impl FileMetadata {
     fn from_path<'tcx>(
         ecx: &mut MiriInterpCx<'tcx>,
         path: &Path,
         follow_symlink: bool,
     ) -> InterpResult<'tcx, Result<FileMetadata, IoError>> {
-        let metadata =
-            if follow_symlink { std::fs::metadata(path) } else { std::fs::symlink_metadata(path) };
-
-        FileMetadata::from_meta(ecx, metadata)
+        let metadata = ecx.machine.traced("stat", || {
+            let metadata = if follow_symlink {
+                std::fs::metadata(path)
+            } else {
+                std::fs::symlink_metadata(path)
+            };
+            FileMetadata::from_meta(ecx, metadata)
+        })?;
+        interp_ok(metadata.map_err(IoError::HostError))
     }
 
     fn from_fd_num<'tcx>(
@@ -1935,8 +2191,14 @@ fn from_fd_num<'tcx>(
         let Some(fd) = ecx.machine.fds.get(fd_num) else {
             return interp_ok(Err(LibcError("EBADF")));
         };
+        if let Some(file) = RegularFile::from_fd(fd.clone()) {
+            let metadata =
+                file.traced(&ecx.machine, "fstat", |f| Self::from_meta(ecx, f.metadata()))?;
+            return interp_ok(metadata.map_err(IoError::HostError));
+        }
         match fd.metadata()? {
-            Either::Left(host) => Self::from_meta(ecx, host),
+            Either::Left(host) =>
+                interp_ok(Self::from_meta(ecx, host)?.map_err(IoError::HostError)),
             Either::Right(name) => Self::synthetic(ecx, name),
         }
     }
@@ -1966,13 +2228,13 @@ fn synthetic<'tcx>(
     }
 
     fn from_meta<'tcx>(
-        ecx: &mut MiriInterpCx<'tcx>,
+        ecx: &MiriInterpCx<'tcx>,
         metadata: Result<std::fs::Metadata, std::io::Error>,
-    ) -> InterpResult<'tcx, Result<FileMetadata, IoError>> {
+    ) -> InterpResult<'tcx, io::Result<FileMetadata>> {
         let metadata = match metadata {
             Ok(metadata) => metadata,
             Err(e) => {
-                return interp_ok(Err(e.into()));
+                return interp_ok(Err(e));
             }
         };
 
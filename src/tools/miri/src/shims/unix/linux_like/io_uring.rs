@@ -0,0 +1,1184 @@
+//! Linux `io_uring` implementation.
+//!
+//! The submission queue ring, the completion queue ring and the submission queue entries live in
+//! memory that we allocate in `io_uring_setup`, and that the program then maps with `mmap` on the
+//! ring's file descriptor. Requests are processed when the program calls `io_uring_enter`: they
+//! complete right away if they can, and otherwise stay pending until a later `io_uring_enter` call
+//! (on any thread) finds that they can make progress. Pending requests wait for readiness using the
+//! same machinery as `epoll`. In particular, completions are only ever posted during
+//! `io_uring_enter`; a program that busy-polls the completion queue without ever entering the
+//! kernel will not see them.
+//!
+//! Like the kernel, we access the head and tail indices of the rings atomically (with acquire
+//! loads and release stores), so the data race detector checks the program's side of the protocol.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::rc::Rc;
+use std::time::Duration;
+
+use rustc_abi::Size;
+use rustc_middle::ty::layout::TyAndLayout;
+
+use crate::shims::files::{
+    DynFileDescriptionRef, FdId, FdNum, FileDescription, FileDescriptionRef,
+};
+use crate::shims::unix::UnixFileDescription;
+use crate::shims::unix::fd::EvalContextPrivExt as _;
+use crate::shims::unix::fs::EvalContextExt as _;
+use crate::shims::unix::poll::EvalContextPrivExt as _;
+use crate::*;
+
+// The kernel ABI of `io_uring`, see `include/uapi/linux/io_uring.h`. The `libc` crate does not
+// provide these definitions.
+
+/// Size of `struct io_uring_sqe`.
+const SQE_SIZE: u64 = 64;
+/// Size of `struct io_uring_cqe`.
+const CQE_SIZE: u64 = 16;
+/// Maximum number of submission queue entries.
+const IORING_MAX_ENTRIES: u32 = 32768;
+/// Maximum number of completion queue entries.
+const IORING_MAX_CQ_ENTRIES: u32 = 2 * IORING_MAX_ENTRIES;
+/// Maximum number of buffers that can be registered.
+const IORING_MAX_REG_BUFFERS: u64 = 1 << 14;
+
+// `io_uring_params::flags`.
+const IORING_SETUP_CQSIZE: u32 = 1 << 3;
+const IORING_SETUP_CLAMP: u32 = 1 << 4;
+
+// `io_uring_params::features`.
+const IORING_FEAT_NODROP: u32 = 1 << 1;
+const IORING_FEAT_SUBMIT_STABLE: u32 = 1 << 2;
+const IORING_FEAT_RW_CUR_POS: u32 = 1 << 3;
+
+// The `mmap` offsets of the regions shared with the program.
+const IORING_OFF_SQ_RING: i128 = 0;
+const IORING_OFF_CQ_RING: i128 = 0x8000000;
+const IORING_OFF_SQES: i128 = 0x10000000;
+
+// `io_uring_enter` flags.
+const IORING_ENTER_GETEVENTS: u32 = 1 << 0;
+
+// Submission queue ring flags.
+const IORING_SQ_CQ_OVERFLOW: u32 = 1 << 1;
+
+// `io_uring_sqe::flags`.
+const IOSQE_FIXED_FILE: u8 = 1 << 0;
+const IOSQE_ASYNC: u8 = 1 << 4;
+
+// `io_uring_sqe::opcode`.
+const IORING_OP_NOP: u8 = 0;
+const IORING_OP_READV: u8 = 1;
+const IORING_OP_WRITEV: u8 = 2;
+const IORING_OP_FSYNC: u8 = 3;
+const IORING_OP_READ_FIXED: u8 = 4;
+const IORING_OP_WRITE_FIXED: u8 = 5;
+const IORING_OP_POLL_ADD: u8 = 6;
+const IORING_OP_TIMEOUT: u8 = 11;
+const IORING_OP_READ: u8 = 22;
+const IORING_OP_WRITE: u8 = 23;
+
+// Per-opcode flags.
+const IORING_FSYNC_DATASYNC: u32 = 1 << 0;
+const IORING_TIMEOUT_ABS: u32 = 1 << 0;
+const IORING_POLL_ADD_MULTI: u32 = 1 << 0;
+
+// `io_uring_register` opcodes.
+const IORING_REGISTER_BUFFERS: u32 = 0;
+const IORING_UNREGISTER_BUFFERS: u32 = 1;
+const IORING_REGISTER_FILES: u32 = 2;
+const IORING_UNREGISTER_FILES: u32 = 3;
+
+// Where we put the fields of the rings. The program learns these offsets from the
+// `io_sqring_offsets` and `io_cqring_offsets` we return from `io_uring_setup`.
+const SQ_HEAD: u64 = 0;
+const SQ_TAIL: u64 = 4;
+const SQ_RING_MASK: u64 = 8;
+const SQ_RING_ENTRIES: u64 = 12;
+const SQ_FLAGS: u64 = 16;
+const SQ_DROPPED: u64 = 20;
+const SQ_ARRAY: u64 = 64;
+const CQ_HEAD: u64 = 0;
+const CQ_TAIL: u64 = 4;
+const CQ_RING_MASK: u64 = 8;
+const CQ_RING_ENTRIES: u64 = 12;
+const CQ_OVERFLOW: u64 = 16;
+const CQ_FLAGS: u64 = 20;
+const CQ_CQES: u64 = 64;
+
+/// A memory region shared with the program via `mmap`.
+#[derive(Debug)]
+struct Region {
+    ptr: Pointer,
+    size: u64,
+    /// Whether the program has already mapped this region.
+    mapped: Cell<bool>,
+}
+
+/// A submission queue entry, as read from the program's memory.
+#[derive(Debug)]
+struct Sqe {
+    opcode: u8,
+    flags: u8,
+    fd: i32,
+    off: u64,
+    addr: u64,
+    len: u32,
+    /// The opcode-specific flags (`rw_flags`, `fsync_flags`, `poll32_events`, ...).
+    op_flags: u32,
+    user_data: u64,
+    buf_index: u16,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum IoOp {
+    Read { vectored: bool },
+    Write { vectored: bool },
+    Fsync { data_only: bool },
+}
+
+/// A request that performs I/O on a file description.
+#[derive(Debug)]
+struct IoRequest {
+    user_data: u64,
+    fd: DynFileDescriptionRef,
+    op: IoOp,
+    buffers: Vec<(Pointer, u64)>,
+    /// The file offset, or `None` to use (and update) the current file position.
+    offset: Option<i128>,
+}
+
+impl VisitProvenance for IoRequest {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        let IoRequest { user_data: _, fd: _, op: _, buffers, offset: _ } = self;
+        for (ptr, _len) in buffers {
+            ptr.visit_provenance(visit);
+        }
+    }
+}
+
+/// A request that cannot complete yet.
+#[derive(Debug)]
+enum Pending {
+    /// Completes with the readiness of `fd` once that intersects the interest registered for `key`.
+    /// The interest only holds a weak reference to `fd`, so we keep it alive here.
+    Poll { user_data: u64, fd: DynFileDescriptionRef, key: (FdId, FdNum) },
+    /// Waits for the interest registered for `key` before performing the I/O.
+    Io { request: IoRequest, key: (FdId, FdNum) },
+    /// Completes when `deadline` passes, or once `target` completions have been posted.
+    Timeout { user_data: u64, deadline: Instant, target: Option<u64> },
+}
+
+/// A file description created by `io_uring_setup`.
+#[derive(Debug)]
+pub struct IoUring {
+    sq_entries: u32,
+    cq_entries: u32,
+    /// The submission queue ring, the completion queue ring and the submission queue entries.
+    sq_ring: Region,
+    cq_ring: Region,
+    sqes: Region,
+    /// The kernel owns the head of the submission queue and the tail of the completion queue, so we
+    /// keep them here; the copies in the rings are just for the program to read.
+    sq_head: Cell<u32>,
+    cq_tail: Cell<u32>,
+    /// The flags of the submission queue ring.
+    sq_flags: Cell<u32>,
+    /// The number of submission queue entries with an invalid index.
+    sq_dropped: Cell<u32>,
+    /// Completions that did not fit into the completion queue yet (`user_data` and result).
+    overflow: RefCell<VecDeque<(u64, i32)>>,
+    /// The number of completions posted so far, not counting timeouts.
+    completions: Cell<u64>,
+    /// The requests that cannot complete yet, indexed by the `data` of their readiness interest.
+    pending: RefCell<BTreeMap<u64, Pending>>,
+    next_pending_id: Cell<u64>,
+    /// Watches the file descriptions that pending requests wait for.
+    watcher: Rc<ReadinessWatcher>,
+    /// The threads blocked in `io_uring_enter` waiting for completions.
+    waiters: RefCell<Vec<ThreadId>>,
+    /// The buffers registered with `IORING_REGISTER_BUFFERS`.
+    buffers: RefCell<Option<Vec<(Pointer, u64)>>>,
+    /// The files registered with `IORING_REGISTER_FILES`, along with the file descriptor they were
+    /// registered with.
+    files: RefCell<Option<Vec<Option<(FdNum, DynFileDescriptionRef)>>>>,
+}
+
+impl FileDescription for IoUring {
+    fn name(&self) -> &'static str {
+        "io_uring"
+    }
+
+    fn metadata<'tcx>(
+        &self,
+    ) -> InterpResult<'tcx, Either<io::Result<std::fs::Metadata>, &'static str>> {
+        // On Linux, io_uring is an "anonymous inode" reported as S_IFREG.
+        interp_ok(Either::Right("S_IFREG"))
+    }
+
+    fn as_unix<'tcx>(
+        self: FileDescriptionRef<Self>,
+        _ecx: &MiriInterpCx<'tcx>,
+    ) -> FileDescriptionRef<dyn UnixFileDescription> {
+        self
+    }
+
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        for region in [&self.sq_ring, &self.cq_ring, &self.sqes] {
+            region.ptr.visit_provenance(visit);
+        }
+        for (ptr, _len) in self.buffers.borrow().iter().flatten() {
+            ptr.visit_provenance(visit);
+        }
+        for pending in self.pending.borrow().values() {
+            if let Pending::Io { request, .. } = pending {
+                request.visit_provenance(visit);
+            }
+        }
+    }
+}
+
+impl UnixFileDescription for IoUring {}
+
+impl IoUring {
+    fn sq_mask(&self) -> u32 {
+        self.sq_entries.strict_sub(1)
+    }
+
+    fn cq_mask(&self) -> u32 {
+        self.cq_entries.strict_sub(1)
+    }
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Creates an `io_uring` instance with (at least) `entries` submission queue entries, and
+    /// returns its file descriptor. `params` is both used to configure the instance and to tell
+    /// the program where to find the fields of the rings.
+    ///
+    /// Only the `IORING_SETUP_CQSIZE` and `IORING_SETUP_CLAMP` flags are supported.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/io_uring_setup.2.html>
+    fn io_uring_setup(
+        &mut self,
+        entries: &OpTy<'tcx>,
+        params: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let entries = this.read_u32_arg(entries)?;
+        let params = this.read_pointer_arg(params)?;
+
+        let flags = this.read_at(params, 8, this.machine.layouts.u32)?.to_u32()?;
+        if flags & !(IORING_SETUP_CQSIZE | IORING_SETUP_CLAMP) != 0 {
+            throw_unsup_format!(
+                "io_uring_setup: flags {:#x} are unsupported, only IORING_SETUP_CQSIZE and \
+                IORING_SETUP_CLAMP are supported",
+                flags
+            );
+        }
+        // The reserved fields must be zero.
+        for offset in [28, 32, 36] {
+            if this.read_at(params, offset, this.machine.layouts.u32)?.to_u32()? != 0 {
+                return this.set_errno_and_return_neg1_i32(LibcError("EINVAL"));
+            }
+        }
+
+        let clamp = flags & IORING_SETUP_CLAMP != 0;
+        let sq_entries = match entries {
+            0 => return this.set_errno_and_return_neg1_i32(LibcError("EINVAL")),
+            ..=IORING_MAX_ENTRIES => entries,
+            _ if clamp => IORING_MAX_ENTRIES,
+            _ => return this.set_errno_and_return_neg1_i32(LibcError("EINVAL")),
+        }
+        .next_power_of_two();
+        let cq_entries = if flags & IORING_SETUP_CQSIZE != 0 {
+            let cq_entries = this.read_at(params, 4, this.machine.layouts.u32)?.to_u32()?;
+            let cq_entries = match cq_entries {
+                0 => return this.set_errno_and_return_neg1_i32(LibcError("EINVAL")),
+                ..=IORING_MAX_CQ_ENTRIES => cq_entries,
+                _ if clamp => IORING_MAX_CQ_ENTRIES,
+                _ => return this.set_errno_and_return_neg1_i32(LibcError("EINVAL")),
+            }
+            .next_power_of_two();
+            if cq_entries < sq_entries {
+                return this.set_errno_and_return_neg1_i32(LibcError("EINVAL"));
+            }
+            cq_entries
+        } else {
+            sq_entries.strict_mul(2)
+        };
+
+        let sq_ring =
+            this.io_uring_alloc_region(SQ_ARRAY.strict_add(u64::from(sq_entries).strict_mul(4)))?;
+        let cq_ring = this.io_uring_alloc_region(
+            CQ_CQES.strict_add(u64::from(cq_entries).strict_mul(CQE_SIZE)),
+        )?;
+        let sqes = this.io_uring_alloc_region(u64::from(sq_entries).strict_mul(SQE_SIZE))?;
+
+        let u32_layout = this.machine.layouts.u32;
+        this.write_at(
+            sq_ring.ptr,
+            SQ_RING_MASK,
+            Scalar::from_u32(sq_entries.strict_sub(1)),
+            u32_layout,
+        )?;
+        this.write_at(sq_ring.ptr, SQ_RING_ENTRIES, Scalar::from_u32(sq_entries), u32_layout)?;
+        this.write_at(
+            cq_ring.ptr,
+            CQ_RING_MASK,
+            Scalar::from_u32(cq_entries.strict_sub(1)),
+            u32_layout,
+        )?;
+        this.write_at(cq_ring.ptr, CQ_RING_ENTRIES, Scalar::from_u32(cq_entries), u32_layout)?;
+
+        // Fill in `io_uring_params`.
+        let features = IORING_FEAT_NODROP | IORING_FEAT_SUBMIT_STABLE | IORING_FEAT_RW_CUR_POS;
+        let sq_off =
+            [SQ_HEAD, SQ_TAIL, SQ_RING_MASK, SQ_RING_ENTRIES, SQ_FLAGS, SQ_DROPPED, SQ_ARRAY];
+        let cq_off =
+            [CQ_HEAD, CQ_TAIL, CQ_RING_MASK, CQ_RING_ENTRIES, CQ_OVERFLOW, CQ_CQES, CQ_FLAGS];
+        this.write_at(params, 0, Scalar::from_u32(sq_entries), u32_layout)?;
+        this.write_at(params, 4, Scalar::from_u32(cq_entries), u32_layout)?;
+        this.write_at(params, 20, Scalar::from_u32(features), u32_layout)?;
+        for (base, offsets) in [(40, sq_off), (80, cq_off)] {
+            for (idx, offset) in (0..).zip(offsets) {
+                let offset = u32::try_from(offset).unwrap();
+                this.write_at(params, base + 4 * idx, Scalar::from_u32(offset), u32_layout)?;
+            }
+            // `resv1` and `user_addr`.
+            this.write_at(params, base + 28, Scalar::from_u32(0), u32_layout)?;
+            this.write_at(params, base + 32, Scalar::from_u64(0), this.machine.layouts.u64)?;
+        }
+
+        let fd = this.machine.fds.insert_new(IoUring {
+            sq_entries,
+            cq_entries,
+            sq_ring,
+            cq_ring,
+            sqes,
+            sq_head: Cell::new(0),
+            cq_tail: Cell::new(0),
+            sq_flags: Cell::new(0),
+            sq_dropped: Cell::new(0),
+            overflow: RefCell::new(VecDeque::new()),
+            completions: Cell::new(0),
+            pending: RefCell::new(BTreeMap::new()),
+            next_pending_id: Cell::new(0),
+            watcher: Rc::new(ReadinessWatcher::default()),
+            waiters: RefCell::new(Vec::new()),
+            buffers: RefCell::new(None),
+            files: RefCell::new(None),
+        });
+        interp_ok(Scalar::from_i32(fd))
+    }
+
+    /// Submits up to `to_submit` entries from the submission queue. If `IORING_ENTER_GETEVENTS`
+    /// is set, then waits until at least `min_complete` completions are available in the
+    /// completion queue. Returns the number of submitted entries.
+    ///
+    /// Only the `IORING_ENTER_GETEVENTS` flag is supported. Miri does not support signals, so the
+    /// signal mask is ignored.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/io_uring_enter.2.html>
+    fn io_uring_enter(
+        &mut self,
+        fd: &OpTy<'tcx>,
+        to_submit: &OpTy<'tcx>,
+        min_complete: &OpTy<'tcx>,
+        flags: &OpTy<'tcx>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_u32_arg(fd)?.cast_signed();
+        let to_submit = this.read_u32_arg(to_submit)?;
+        let min_complete = this.read_u32_arg(min_complete)?;
+        let flags = this.read_u32_arg(flags)?;
+
+        let Some(ring) = this.machine.fds.get(fd) else {
+            return this.set_errno_and_return_neg1(LibcError("EBADF"), dest);
+        };
+        let Some(ring) = ring.downcast::<IoUring>() else {
+            return this.set_errno_and_return_neg1(LibcError("EOPNOTSUPP"), dest);
+        };
+        if flags & !IORING_ENTER_GETEVENTS != 0 {
+            throw_unsup_format!(
+                "io_uring_enter: flags {:#x} are unsupported, only IORING_ENTER_GETEVENTS is supported",
+                flags
+            );
+        }
+        let min_complete = if flags & IORING_ENTER_GETEVENTS != 0 { min_complete } else { 0 };
+
+        // Consume the submission queue entries. Like the kernel, we read the tail with acquire
+        // semantics and publish the new head with release semantics.
+        let sq_ring = ring.sq_ring.ptr;
+        let tail = this.io_uring_load(sq_ring, SQ_TAIL)?;
+        let mut head = ring.sq_head.get();
+        let available = tail.wrapping_sub(head).min(ring.sq_entries);
+        let mut sqes = Vec::new();
+        for _ in 0..to_submit.min(available) {
+            let array_offset = SQ_ARRAY.strict_add(u64::from(head & ring.sq_mask()).strict_mul(4));
+            let index = this.read_at(sq_ring, array_offset, this.machine.layouts.u32)?.to_u32()?;
+            head = head.wrapping_add(1);
+            if index >= ring.sq_entries {
+                // The kernel skips entries with an invalid index and counts them as dropped.
+                ring.sq_dropped.set(ring.sq_dropped.get().wrapping_add(1));
+                this.io_uring_store(sq_ring, SQ_DROPPED, ring.sq_dropped.get())?;
+                continue;
+            }
+            sqes.push(this.io_uring_read_sqe(&ring, index)?);
+        }
+        ring.sq_head.set(head);
+        this.io_uring_store(sq_ring, SQ_HEAD, head)?;
+        let submitted = u32::try_from(sqes.len()).unwrap();
+
+        // Start the requests. Those that need to perform I/O are collected in `work`, to be
+        // performed in submission order.
+        let mut work = Vec::new();
+        for sqe in sqes {
+            if let Some(request) = this.io_uring_start(&ring, sqe)? {
+                work.push(request);
+            }
+        }
+        work.reverse();
+        this.io_uring_run(ring, work, min_complete, submitted, dest.clone())
+    }
+
+    /// Registers or unregisters buffers or files with an `io_uring` instance.
+    ///
+    /// Only `IORING_(UN)REGISTER_BUFFERS` and `IORING_(UN)REGISTER_FILES` are supported.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/io_uring_register.2.html>
+    fn io_uring_register(
+        &mut self,
+        fd: &OpTy<'tcx>,
+        opcode: &OpTy<'tcx>,
+        arg: &OpTy<'tcx>,
+        nr_args: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_u32_arg(fd)?.cast_signed();
+        let opcode = this.read_u32_arg(opcode)?;
+        let arg = this.read_pointer_arg(arg)?;
+        let nr_args = u64::from(this.read_u32_arg(nr_args)?);
+
+        let Some(ring) = this.machine.fds.get(fd) else {
+            return this.set_errno_and_return_neg1_i32(LibcError("EBADF"));
+        };
+        let Some(ring) = ring.downcast::<IoUring>() else {
+            return this.set_errno_and_return_neg1_i32(LibcError("EOPNOTSUPP"));
+        };
+
+        match opcode {
+            IORING_REGISTER_BUFFERS => {
+                if ring.buffers.borrow().is_some() {
+                    return this.set_errno_and_return_neg1_i32(LibcError("EBUSY"));
+                }
+                if nr_args == 0 || nr_args > IORING_MAX_REG_BUFFERS {
+                    return this.set_errno_and_return_neg1_i32(LibcError("EINVAL"));
+                }
+                let buffers = this.read_iovecs(arg, nr_args)?;
+                for &(ptr, len) in &buffers {
+                    if len == 0 {
+                        return this.set_errno_and_return_neg1_i32(LibcError("EFAULT"));
+                    }
+                    this.check_ptr_access(
+                        ptr,
+                        Size::from_bytes(len),
+                        CheckInAllocMsg::MemoryAccess,
+                    )?;
+                }
+                ring.buffers.replace(Some(buffers));
+            }
+            IORING_UNREGISTER_BUFFERS =>
+                if ring.buffers.take().is_none() {
+                    return this.set_errno_and_return_neg1_i32(LibcError("ENXIO"));
+                },
+            IORING_REGISTER_FILES => {
+                if ring.files.borrow().is_some() {
+                    return this.set_errno_and_return_neg1_i32(LibcError("EBUSY"));
+                }
+                if nr_args == 0 {
+                    return this.set_errno_and_return_neg1_i32(LibcError("EINVAL"));
+                }
+                let fds = this.ptr_to_mplace(arg, this.libc_array_ty_layout("c_int", nr_args));
+                let mut files = Vec::new();
+                let mut array = this.project_array_fields(&fds)?;
+                while let Some((_idx, fd)) = array.next(this)? {
+                    let fd_num = this.read_scalar(&fd)?.to_i32()?;
+                    // -1 leaves the slot empty.
+                    if fd_num == -1 {
+                        files.push(None);
+                        continue;
+                    }
+                    let Some(fd) = this.machine.fds.get(fd_num) else {
+                        return this.set_errno_and_return_neg1_i32(LibcError("EBADF"));
+                    };
+                    files.push(Some((fd_num, fd)));
+                }
+                ring.files.replace(Some(files));
+            }
+            IORING_UNREGISTER_FILES =>
+                if ring.files.take().is_none() {
+                    return this.set_errno_and_return_neg1_i32(LibcError("ENXIO"));
+                },
+            _ => throw_unsup_format!("io_uring_register: unsupported opcode {opcode}"),
+        }
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    /// Maps one of the regions of `ring` into the program's memory. We only support mapping each
+    /// region once, in its entirety.
+    fn io_uring_mmap(
+        &mut self,
+        ring: &IoUring,
+        length: u64,
+        flags: i32,
+        offset: i128,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let map_shared = this.eval_libc_i32("MAP_SHARED");
+        let map_populate = this.eval_libc_i32("MAP_POPULATE");
+        if flags & !map_populate != map_shared {
+            throw_unsup_format!(
+                "Miri only supports mapping an io_uring with the flags MAP_SHARED or \
+                MAP_SHARED|MAP_POPULATE"
+            );
+        }
+
+        let region = match offset {
+            IORING_OFF_SQ_RING => &ring.sq_ring,
+            IORING_OFF_CQ_RING => &ring.cq_ring,
+            IORING_OFF_SQES => &ring.sqes,
+            _ => {
+                this.set_last_error(LibcError("EINVAL"))?;
+                return interp_ok(this.eval_libc("MAP_FAILED"));
+            }
+        };
+        if length.checked_next_multiple_of(this.machine.page_size) != Some(region.size) {
+            throw_unsup_format!("Miri only supports mapping an io_uring region in its entirety");
+        }
+        if region.mapped.replace(true) {
+            throw_unsup_format!("Miri does not support mapping an io_uring region more than once");
+        }
+        interp_ok(Scalar::from_maybe_pointer(region.ptr, this))
+    }
+}
+
+impl<'tcx> EvalContextPrivExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+trait EvalContextPrivExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Reads an integer argument of a syscall. Programs pass the arguments of `syscall` with
+    /// various integer types (often `c_long`), the kernel uses the low 32 bits.
+    fn read_u32_arg(&self, op: &OpTy<'tcx>) -> InterpResult<'tcx, u32> {
+        let this = self.eval_context_ref();
+        let val = this.read_scalar(op)?.to_int(op.layout.size)?;
+        interp_ok(u32::try_from(Size::from_bytes(4).truncate(val.cast_unsigned())).unwrap())
+    }
+
+    /// Reads a pointer argument of a syscall, which programs may also pass as an integer.
+    fn read_pointer_arg(&self, op: &OpTy<'tcx>) -> InterpResult<'tcx, Pointer> {
+        let this = self.eval_context_ref();
+        if op.layout.ty.is_any_ptr() {
+            this.read_pointer(op)
+        } else {
+            let addr = this.read_scalar(op)?.to_int(op.layout.size)?;
+            this.ptr_from_addr_cast(u64::try_from(addr.cast_unsigned()).unwrap_or(u64::MAX))
+        }
+    }
+
+    /// Reads a value of the given layout at `offset` bytes behind `base`.
+    fn read_at(
+        &self,
+        base: Pointer,
+        offset: u64,
+        layout: TyAndLayout<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_ref();
+        let ptr = base.wrapping_offset(Size::from_bytes(offset), this);
+        this.read_scalar(&this.ptr_to_mplace(ptr, layout))
+    }
+
+    /// Writes a value of the given layout at `offset` bytes behind `base`.
+    fn write_at(
+        &mut self,
+        base: Pointer,
+        offset: u64,
+        val: Scalar,
+        layout: TyAndLayout<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        let ptr = base.wrapping_offset(Size::from_bytes(offset), this);
+        this.write_scalar(val, &this.ptr_to_mplace(ptr, layout))
+    }
+
+    /// Loads a ring index with acquire semantics.
+    fn io_uring_load(&self, ring: Pointer, offset: u64) -> InterpResult<'tcx, u32> {
+        let this = self.eval_context_ref();
+        let ptr = ring.wrapping_offset(Size::from_bytes(offset), this);
+        let place = this.ptr_to_mplace(ptr, this.machine.layouts.u32);
+        this.read_scalar_atomic(&place, AtomicReadOrd::Acquire)?.to_u32()
+    }
+
+    /// Stores a ring index or flag with release semantics.
+    fn io_uring_store(&mut self, ring: Pointer, offset: u64, val: u32) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        let ptr = ring.wrapping_offset(Size::from_bytes(offset), this);
+        let place = this.ptr_to_mplace(ptr, this.machine.layouts.u32);
+        this.write_scalar_atomic(Scalar::from_u32(val), &place, AtomicWriteOrd::Release)
+    }
+
+    /// Allocates a zero-initialized region to be shared with the program.
+    fn io_uring_alloc_region(&mut self, size: u64) -> InterpResult<'tcx, Region> {
+        let this = self.eval_context_mut();
+        let size = size.next_multiple_of(this.machine.page_size);
+        let ptr = this.allocate_ptr(
+            Size::from_bytes(size),
+            this.machine.page_align(),
+            MiriMemoryKind::Mmap.into(),
+            AllocInit::Zero,
+        )?;
+        interp_ok(Region { ptr: ptr.into(), size, mapped: Cell::new(false) })
+    }
+
+    /// Reads the submission queue entry with the given index.
+    fn io_uring_read_sqe(&self, ring: &IoUring, index: u32) -> InterpResult<'tcx, Sqe> {
+        let this = self.eval_context_ref();
+        let layouts = &this.machine.layouts;
+        let sqe = ring
+            .sqes
+            .ptr
+            .wrapping_offset(Size::from_bytes(u64::from(index).strict_mul(SQE_SIZE)), this);
+        interp_ok(Sqe {
+            opcode: this.read_at(sqe, 0, layouts.u8)?.to_u8()?,
+            flags: this.read_at(sqe, 1, layouts.u8)?.to_u8()?,
+            fd: this.read_at(sqe, 4, layouts.i32)?.to_i32()?,
+            off: this.read_at(sqe, 8, layouts.u64)?.to_u64()?,
+            addr: this.read_at(sqe, 16, layouts.u64)?.to_u64()?,
+            len: this.read_at(sqe, 24, layouts.u32)?.to_u32()?,
+            op_flags: this.read_at(sqe, 28, layouts.u32)?.to_u32()?,
+            user_data: this.read_at(sqe, 32, layouts.u64)?.to_u64()?,
+            buf_index: this.read_at(sqe, 40, layouts.u16)?.to_u16()?,
+        })
+    }
+
+    /// Starts processing a submission queue entry: requests that can complete right away are
+    /// completed, requests that have to wait become pending, and requests that are ready to perform
+    /// I/O are returned.
+    fn io_uring_start(
+        &mut self,
+        ring: &IoUring,
+        sqe: Sqe,
+    ) -> InterpResult<'tcx, Option<IoRequest>> {
+        let this = self.eval_context_mut();
+
+        if sqe.flags & !(IOSQE_FIXED_FILE | IOSQE_ASYNC) != 0 {
+            throw_unsup_format!(
+                "io_uring: submission queue entry flags {:#x} are unsupported, only \
+                IOSQE_FIXED_FILE and IOSQE_ASYNC are supported",
+                sqe.flags
+            );
+        }
+
+        let op = match sqe.opcode {
+            IORING_OP_NOP => {
+                this.io_uring_post(ring, sqe.user_data, 0)?;
+                return interp_ok(None);
+            }
+            IORING_OP_TIMEOUT => {
+                this.io_uring_timeout(ring, sqe)?;
+                return interp_ok(None);
+            }
+            IORING_OP_POLL_ADD => {
+                this.io_uring_poll_add(ring, sqe)?;
+                return interp_ok(None);
+            }
+            IORING_OP_READ | IORING_OP_READ_FIXED => IoOp::Read { vectored: false },
+            IORING_OP_WRITE | IORING_OP_WRITE_FIXED => IoOp::Write { vectored: false },
+            IORING_OP_READV => IoOp::Read { vectored: true },
+            IORING_OP_WRITEV => IoOp::Write { vectored: true },
+            IORING_OP_FSYNC => {
+                if sqe.op_flags & !IORING_FSYNC_DATASYNC != 0 {
+                    this.io_uring_post_error(ring, sqe.user_data, LibcError("EINVAL"))?;
+                    return interp_ok(None);
+                }
+                IoOp::Fsync { data_only: sqe.op_flags & IORING_FSYNC_DATASYNC != 0 }
+            }
+            opcode => throw_unsup_format!("io_uring: unsupported opcode {opcode}"),
+        };
+
+        let (fd, fd_num) = match this.io_uring_file(ring, &sqe)? {
+            Ok(file) => file,
+            Err(err) => {
+                this.io_uring_post_error(ring, sqe.user_data, err)?;
+                return interp_ok(None);
+            }
+        };
+
+        let buffers = match sqe.opcode {
+            IORING_OP_READV | IORING_OP_WRITEV => {
+                let iov = this.ptr_from_addr_cast(sqe.addr)?;
+                this.read_iovecs(iov, sqe.len.into())?
+            }
+            IORING_OP_READ_FIXED | IORING_OP_WRITE_FIXED => {
+                let registered = ring
+                    .buffers
+                    .borrow()
+                    .as_ref()
+                    .and_then(|buffers| buffers.get(usize::from(sqe.buf_index)).copied());
+                let Some((base, base_len)) = registered else {
+                    this.io_uring_post_error(ring, sqe.user_data, LibcError("EFAULT"))?;
+                    return interp_ok(None);
+                };
+                // The kernel accesses the buffer through its registration, so we do as well.
+                let base_addr = base.addr().bytes();
+                let in_bounds = sqe.addr.checked_sub(base_addr).is_some_and(|start| {
+                    start.checked_add(sqe.len.into()).is_some_and(|end| end <= base_len)
+                });
+                if !in_bounds {
+                    this.io_uring_post_error(ring, sqe.user_data, LibcError("EFAULT"))?;
+                    return interp_ok(None);
+                }
+                let ptr =
+                    base.wrapping_offset(Size::from_bytes(sqe.addr.strict_sub(base_addr)), this);
+                vec![(ptr, sqe.len.into())]
+            }
+            IORING_OP_FSYNC => Vec::new(),
+            _ => vec![(this.ptr_from_addr_cast(sqe.addr)?, sqe.len.into())],
+        };
+        // The kernel caps the size of a single read or write.
+        let total = buffers.iter().map(|(_, len)| len).sum::<u64>();
+        if total > u64::try_from(i32::MAX).unwrap() {
+            this.io_uring_post_error(ring, sqe.user_data, LibcError("EINVAL"))?;
+            return interp_ok(None);
+        }
+        for &(ptr, len) in &buffers {
+            this.check_ptr_access(ptr, Size::from_bytes(len), CheckInAllocMsg::MemoryAccess)?;
+        }
+
+        // File descriptions that can be watched for readiness are streams, for which the kernel
+        // ignores the offset. An offset of -1 means the current file position.
+        let offset = if sqe.off == u64::MAX || fd.readiness_watched().is_some() {
+            None
+        } else {
+            Some(i128::from(sqe.off))
+        };
+        let request = IoRequest { user_data: sqe.user_data, fd, op, buffers, offset };
+
+        // Reads and writes on file descriptions that are not ready yet have to wait.
+        let needed = match op {
+            IoOp::Read { .. } => Readiness { readable: true, ..Readiness::EMPTY },
+            IoOp::Write { .. } => Readiness { writable: true, ..Readiness::EMPTY },
+            IoOp::Fsync { .. } => return interp_ok(Some(request)),
+        };
+        if request.fd.readiness_watched().is_none() {
+            return interp_ok(Some(request));
+        }
+        // We also stop waiting when the operation would fail or hit the end of the stream.
+        let relevant = needed
+            | Readiness { read_closed: true, write_closed: true, error: true, ..Readiness::EMPTY };
+        if request.fd.readiness() & relevant != Readiness::EMPTY {
+            return interp_ok(Some(request));
+        }
+        let Some(fd_num) = fd_num else {
+            throw_unsup_format!(
+                "io_uring: waiting for a registered file whose file descriptor has been closed is unsupported"
+            );
+        };
+        let key = (request.fd.id(), fd_num);
+        this.io_uring_add_pending(ring, fd_num, relevant, Pending::Io { request, key })?;
+        interp_ok(None)
+    }
+
+    /// Determines the file description an entry refers to, and the file descriptor we can use to
+    /// watch its readiness (if any).
+    fn io_uring_file(
+        &self,
+        ring: &IoUring,
+        sqe: &Sqe,
+    ) -> InterpResult<'tcx, Result<(DynFileDescriptionRef, Option<FdNum>), IoError>> {
+        let this = self.eval_context_ref();
+        if sqe.flags & IOSQE_FIXED_FILE != 0 {
+            let files = ring.files.borrow();
+            let Some((fd_num, fd)) =
+                usize::try_from(sqe.fd).ok().and_then(|idx| files.as_ref()?.get(idx)?.as_ref())
+            else {
+                return interp_ok(Err(LibcError("EBADF")));
+            };
+            // The file descriptor might have been closed or reused since the file was registered.
+            let fd_num = this
+                .machine
+                .fds
+                .get(*fd_num)
+                .is_some_and(|current| current.id() == fd.id())
+                .then_some(*fd_num);
+            interp_ok(Ok((fd.clone(), fd_num)))
+        } else {
+            let Some(fd) = this.machine.fds.get(sqe.fd) else {
+                return interp_ok(Err(LibcError("EBADF")));
+            };
+            interp_ok(Ok((fd, Some(sqe.fd))))
+        }
+    }
+
+    /// Starts an `IORING_OP_POLL_ADD` request: it completes with the readiness of the file once
+    /// that intersects the requested events.
+    fn io_uring_poll_add(&mut self, ring: &IoUring, sqe: Sqe) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        if sqe.len & IORING_POLL_ADD_MULTI != 0 {
+            throw_unsup_format!("io_uring: multishot poll requests are unsupported");
+        }
+        let Ok(events) = u16::try_from(sqe.op_flags) else {
+            throw_unsup_format!("io_uring: poll event {:#x} is unsupported", sqe.op_flags);
+        };
+        let relevant = this.poll_bitflag_to_readiness(events)?;
+
+        let (fd, fd_num) = match this.io_uring_file(ring, &sqe)? {
+            Ok(file) => file,
+            Err(err) => return this.io_uring_post_error(ring, sqe.user_data, err),
+        };
+        let Some(fd_num) = fd_num else {
+            throw_unsup_format!(
+                "io_uring: polling a registered file whose file descriptor has been closed is unsupported"
+            );
+        };
+        let key = (fd.id(), fd_num);
+        this.io_uring_add_pending(
+            ring,
+            fd_num,
+            relevant,
+            Pending::Poll { user_data: sqe.user_data, fd, key },
+        )
+    }
+
+    /// Starts an `IORING_OP_TIMEOUT` request: it completes with `ETIME` once the timeout expires,
+    /// or successfully once the given number of other requests have completed.
+    fn io_uring_timeout(&mut self, ring: &IoUring, sqe: Sqe) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        if sqe.len != 1 {
+            return this.io_uring_post_error(ring, sqe.user_data, LibcError("EINVAL"));
+        }
+        let style = match sqe.op_flags {
+            0 => TimeoutStyle::Relative,
+            IORING_TIMEOUT_ABS => TimeoutStyle::Absolute,
+            flags =>
+                throw_unsup_format!(
+                    "io_uring: timeout flags {flags:#x} are unsupported, only IORING_TIMEOUT_ABS is supported"
+                ),
+        };
+        // This is a `struct __kernel_timespec`, which has 64-bit fields on all targets.
+        let timespec = this.ptr_from_addr_cast(sqe.addr)?;
+        let sec = this.read_at(timespec, 0, this.machine.layouts.i64)?.to_i64()?;
+        let nsec = this.read_at(timespec, 8, this.machine.layouts.i64)?.to_i64()?;
+        let (Ok(sec), Ok(nsec)) = (u64::try_from(sec), u32::try_from(nsec)) else {
+            return this.io_uring_post_error(ring, sqe.user_data, LibcError("EINVAL"));
+        };
+        if nsec >= 1_000_000_000 {
+            return this.io_uring_post_error(ring, sqe.user_data, LibcError("EINVAL"));
+        }
+        let Deadline::Monotonic(deadline) =
+            this.machine.timeout(TimeoutClock::Monotonic, style, Duration::new(sec, nsec))
+        else {
+            unreachable!()
+        };
+        let target = (sqe.off != 0).then(|| ring.completions.get().saturating_add(sqe.off));
+
+        let id = ring.next_pending_id.get();
+        ring.next_pending_id.set(id.strict_add(1));
+        ring.pending
+            .borrow_mut()
+            .insert(id, Pending::Timeout { user_data: sqe.user_data, deadline, target });
+        interp_ok(())
+    }
+
+    /// Registers a request that waits for the readiness of `fd_num`.
+    fn io_uring_add_pending(
+        &mut self,
+        ring: &IoUring,
+        fd_num: FdNum,
+        relevant: Readiness,
+        pending: Pending,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let id = ring.next_pending_id.get();
+        ring.next_pending_id.set(id.strict_add(1));
+        // Adding the interest may immediately wake up a thread waiting on this ring, which then
+        // completes the request, so it has to be pending already.
+        ring.pending.borrow_mut().insert(id, pending);
+        let added = ring
+            .watcher
+            .add_interest(fd_num, relevant, /* is_edge_triggered */ false, id, this)?;
+        if added.is_err() {
+            throw_unsup_format!(
+                "io_uring: multiple pending requests waiting for the same file descriptor are unsupported"
+            );
+        }
+        interp_ok(())
+    }
+
+    /// Performs the I/O of the given requests one after the other (`work` is in reverse order),
+    /// then checks for pending requests that can make progress, and finally completes the
+    /// `io_uring_enter` call or blocks until enough completions are available.
+    fn io_uring_run(
+        &mut self,
+        ring: FileDescriptionRef<IoUring>,
+        mut work: Vec<IoRequest>,
+        min_complete: u32,
+        submitted: u32,
+        dest: MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let Some(request) = work.pop() else {
+            return this.io_uring_wait(ring, min_complete, submitted, dest);
+        };
+        let user_data = request.user_data;
+        let finish = callback!(
+            @capture<'tcx> {
+                ring: FileDescriptionRef<IoUring>,
+                work: Vec<IoRequest>,
+                user_data: u64,
+                min_complete: u32,
+                submitted: u32,
+                dest: MPlaceTy<'tcx>,
+            }
+            |this, result: Result<usize, IoError>| {
+                match result {
+                    Ok(size) => this.io_uring_post(&ring, user_data, i32::try_from(size).unwrap())?,
+                    Err(err) => this.io_uring_post_error(&ring, user_data, err)?,
+                };
+                this.io_uring_run(ring, work, min_complete, submitted, dest)
+            }
+        );
+        let IoRequest { user_data: _, fd, op, mut buffers, offset } = request;
+        match op {
+            IoOp::Read { vectored: true } =>
+                this.read_vectored_from_fd(fd, buffers, offset, finish),
+            IoOp::Write { vectored: true } =>
+                this.write_vectored_to_fd(fd, buffers, offset, finish),
+            IoOp::Read { vectored: false } => {
+                let (ptr, len) = buffers.pop().unwrap();
+                this.read_from_fd(fd, ptr, usize::try_from(len).unwrap(), offset, finish)
+            }
+            IoOp::Write { vectored: false } => {
+                let (ptr, len) = buffers.pop().unwrap();
+                this.write_to_fd(fd, ptr, usize::try_from(len).unwrap(), offset, finish)
+            }
+            IoOp::Fsync { data_only } => {
+                let result = this.sync_fd(fd, data_only, "IORING_OP_FSYNC")?;
+                finish.call(this, result.map(|_| 0).map_err(IoError::HostError))
+            }
+        }
+    }
+
+    /// Completes the pending requests that can complete now. If any of them can perform their I/O
+    /// now, does so; otherwise completes the `io_uring_enter` call once at least `min_complete`
+    /// completions are available, and blocks until then.
+    fn io_uring_wait(
+        &mut self,
+        ring: FileDescriptionRef<IoUring>,
+        min_complete: u32,
+        submitted: u32,
+        dest: MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let mut work = this.io_uring_progress(&ring)?;
+        if !work.is_empty() {
+            work.reverse();
+            return this.io_uring_run(ring, work, min_complete, submitted, dest);
+        }
+
+        let head = this.io_uring_load(ring.cq_ring.ptr, CQ_HEAD)?;
+        let available = ring.cq_tail.get().wrapping_sub(head);
+        if available >= min_complete {
+            this.write_int(submitted, &dest)?;
+            // There might be new completions for the other threads waiting on this ring.
+            let waiters = std::mem::take(&mut *ring.waiters.borrow_mut());
+            for thread in waiters {
+                this.unblock_thread(thread, BlockReason::Readiness)?;
+            }
+            return interp_ok(());
+        }
+
+        // Block until a pending request can make progress, until the next timeout expires, or
+        // until another thread posts completions.
+        let mut deadline = None::<Instant>;
+        for pending in ring.pending.borrow().values() {
+            if let Pending::Timeout { deadline: timeout, .. } = pending
+                && deadline.as_ref().is_none_or(|d| !d.duration_since(timeout.clone()).is_zero())
+            {
+                deadline = Some(timeout.clone());
+            }
+        }
+        let thread = this.active_thread();
+        ring.waiters.borrow_mut().push(thread);
+        ring.watcher.add_blocked_thread(thread);
+        this.block_thread(
+            BlockReason::Readiness,
+            deadline.map(Deadline::from),
+            callback!(
+                @capture<'tcx> {
+                    ring: FileDescriptionRef<IoUring>,
+                    min_complete: u32,
+                    submitted: u32,
+                    dest: MPlaceTy<'tcx>,
+                }
+                |this, _unblock: UnblockKind| {
+                    // We might have been woken up by either of these, so make sure we are
+                    // removed from both.
+                    let thread = this.active_thread();
+                    ring.waiters.borrow_mut().retain(|&t| t != thread);
+                    ring.watcher.remove_blocked_thread(thread);
+                    this.io_uring_wait(ring, min_complete, submitted, dest)
+                }
+            ),
+        );
+        interp_ok(())
+    }
+
+    /// Moves overflowed completions into the completion queue, completes the pending requests that
+    /// are done, and returns the pending I/O requests that can be performed now.
+    fn io_uring_progress(&mut self, ring: &IoUring) -> InterpResult<'tcx, Vec<IoRequest>> {
+        let this = self.eval_context_mut();
+
+        // Flush the overflowed completions, as far as they fit.
+        loop {
+            let Some(&(user_data, res)) = ring.overflow.borrow().front() else { break };
+            if !this.io_uring_cq_has_space(ring)? {
+                break;
+            }
+            ring.overflow.borrow_mut().pop_front();
+            this.io_uring_write_cqe(ring, user_data, res)?;
+        }
+        if ring.overflow.borrow().is_empty() && ring.sq_flags.get() & IORING_SQ_CQ_OVERFLOW != 0 {
+            ring.sq_flags.set(ring.sq_flags.get() & !IORING_SQ_CQ_OVERFLOW);
+            this.io_uring_store(ring.sq_ring.ptr, SQ_FLAGS, ring.sq_flags.get())?;
+        }
+
+        let mut work = Vec::new();
+        for interest in ring.watcher.get_ready_interests(ring.watcher.ready_count(), this)? {
+            // Another thread might have completed this request already.
+            let Some(pending) = ring.pending.borrow_mut().remove(&interest.data) else { continue };
+            match pending {
+                Pending::Poll { user_data, fd, key } => {
+                    ring.watcher.remove_interest(key);
+                    drop(fd);
+                    let events = this.readiness_to_poll_bitflag(interest.active());
+                    this.io_uring_post(ring, user_data, i32::from(events))?;
+                }
+                Pending::Io { request, key } => {
+                    ring.watcher.remove_interest(key);
+                    work.push(request);
+                }
+                Pending::Timeout { .. } => unreachable!("timeouts do not wait for readiness"),
+            }
+        }
+
+        // Complete the timeouts that expired or whose completion count was reached.
+        let now = this.machine.monotonic_clock.now();
+        let mut expired = Vec::new();
+        ring.pending.borrow_mut().retain(|_id, pending| {
+            let Pending::Timeout { user_data, deadline, target } = pending else { return true };
+            if deadline.duration_since(now.clone()).is_zero() {
+                expired.push((*user_data, Err(LibcError("ETIME"))));
+            } else if target.is_some_and(|target| ring.completions.get() >= target) {
+                expired.push((*user_data, Ok(())));
+            } else {
+                return true;
+            }
+            false
+        });
+        for (user_data, result) in expired {
+            match result {
+                Ok(()) => this.io_uring_write_or_overflow(ring, user_data, 0)?,
+                Err(err) => {
+                    let errnum = this.io_error_to_errnum(err)?.to_i32()?;
+                    this.io_uring_write_or_overflow(ring, user_data, errnum.strict_neg())?
+                }
+            }
+        }
+
+        interp_ok(work)
+    }
+
+    /// Posts a failed completion for the request with the given `user_data`.
+    fn io_uring_post_error(
+        &mut self,
+        ring: &IoUring,
+        user_data: u64,
+        err: IoError,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        let errnum = this.io_error_to_errnum(err)?.to_i32()?;
+        this.io_uring_post(ring, user_data, errnum.strict_neg())
+    }
+
+    /// Posts a completion for the request with the given `user_data`.
+    fn io_uring_post(&mut self, ring: &IoUring, user_data: u64, res: i32) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        ring.completions.set(ring.completions.get().strict_add(1));
+        this.io_uring_write_or_overflow(ring, user_data, res)
+    }
+
+    /// Writes a completion into the completion queue, or remembers it for later if the queue is
+    /// full.
+    fn io_uring_write_or_overflow(
+        &mut self,
+        ring: &IoUring,
+        user_data: u64,
+        res: i32,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        if ring.overflow.borrow().is_empty() && this.io_uring_cq_has_space(ring)? {
+            return this.io_uring_write_cqe(ring, user_data, res);
+        }
+        ring.overflow.borrow_mut().push_back((user_data, res));
+        if ring.sq_flags.get() & IORING_SQ_CQ_OVERFLOW == 0 {
+            ring.sq_flags.set(ring.sq_flags.get() | IORING_SQ_CQ_OVERFLOW);
+            this.io_uring_store(ring.sq_ring.ptr, SQ_FLAGS, ring.sq_flags.get())?;
+        }
+        interp_ok(())
+    }
+
+    fn io_uring_cq_has_space(&self, ring: &IoUring) -> InterpResult<'tcx, bool> {
+        let this = self.eval_context_ref();
+        let head = this.io_uring_load(ring.cq_ring.ptr, CQ_HEAD)?;
+        interp_ok(ring.cq_tail.get().wrapping_sub(head) < ring.cq_entries)
+    }
+
+    /// Writes a completion queue entry and publishes it by advancing the tail.
+    fn io_uring_write_cqe(
+        &mut self,
+        ring: &IoUring,
+        user_data: u64,
+        res: i32,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        let tail = ring.cq_tail.get();
+        let offset = CQ_CQES.strict_add(u64::from(tail & ring.cq_mask()).strict_mul(CQE_SIZE));
+        let cq_ring = ring.cq_ring.ptr;
+        this.write_at(cq_ring, offset, Scalar::from_u64(user_data), this.machine.layouts.u64)?;
+        this.write_at(
+            cq_ring,
+            offset.strict_add(8),
+            Scalar::from_i32(res),
+            this.machine.layouts.i32,
+        )?;
+        this.write_at(
+            cq_ring,
+            offset.strict_add(12),
+            Scalar::from_u32(0),
+            this.machine.layouts.u32,
+        )?;
+        ring.cq_tail.set(tail.wrapping_add(1));
+        this.io_uring_store(cq_ring, CQ_TAIL, ring.cq_tail.get())
+    }
+}
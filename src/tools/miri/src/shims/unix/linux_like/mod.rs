@@ -1,5 +1,6 @@
 pub mod epoll;
 pub mod eventfd;
+pub mod io_uring;
 pub mod sync;
 pub mod syscall;
 pub mod thread;
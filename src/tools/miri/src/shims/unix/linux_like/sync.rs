@@ -75,7 +75,7 @@ pub fn futex<'tcx>(
                     return ecx.set_errno_and_return_neg1(LibcError("EINVAL"), dest);
                 };
                 let timeout_clock = if op & futex_realtime == futex_realtime {
-                    ecx.check_no_isolation(
+                    ecx.check_no_isolation_or_replay(
                         "`futex` syscall with `op=FUTEX_WAIT` and non-null timeout with `FUTEX_CLOCK_REALTIME`",
                     )?;
                     TimeoutClock::RealTime
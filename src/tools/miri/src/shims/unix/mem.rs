@@ -5,7 +5,8 @@
 //!
 //! mmap/munmap behave a lot like alloc/dealloc, and for simple use they are exactly
 //! equivalent. That is the only part we support: no MAP_FIXED or MAP_SHARED or anything
-//! else that goes beyond a basic allocation API.
+//! else that goes beyond a basic allocation API. The only exception is mapping the rings of an
+//! `io_uring`, which is handled by the `io_uring` shim.
 //!
 //! Note that in addition to only supporting malloc-like calls to mmap, we only support free-like
 //! calls to munmap, but for a very different reason. In principle, according to the man pages, it
@@ -17,6 +18,7 @@
 use rustc_abi::Size;
 use rustc_target::spec::Os;
 
+use crate::shims::unix::linux_like::io_uring::{EvalContextExt as _, IoUring};
 use crate::*;
 
 impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
@@ -67,6 +69,13 @@ fn mmap(
         // to work. It is possible that POSIX gives us enough leeway to return an error, but the
         // outcome for the user (I need to add cfg(miri)) is the same, just more frustrating.
         if fd != -1 {
+            // The one exception are the rings shared with the kernel by an `io_uring`.
+            if this.tcx.sess.target.os == Os::Linux
+                && let Some(ring) = this.machine.fds.get(fd).and_then(|fd| fd.downcast::<IoUring>())
+            {
+                verify_prot(this, prot)?;
+                return this.io_uring_mmap(&ring, length, flags, offset);
+            }
             throw_unsup_format!("Miri does not support file-backed memory mappings");
         }
 
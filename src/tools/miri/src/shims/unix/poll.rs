@@ -146,7 +146,7 @@ fn poll(
 }
 
 impl<'tcx> EvalContextPrivExt<'tcx> for crate::MiriInterpCx<'tcx> {}
-trait EvalContextPrivExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+pub(super) trait EvalContextPrivExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
     /// For all ready interests on the watcher, write the appropriate
     /// readiness into the `revents` field of the associated poll interest.
     fn write_ready_events(
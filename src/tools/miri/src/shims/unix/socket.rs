@@ -175,8 +175,10 @@ fn socket(
         let mut flags = this.read_scalar(type_)?.to_i32()?;
         let protocol = this.read_scalar(protocol)?.to_i32()?;
 
-        // Reject if isolation is enabled
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        // Reject if isolation is enabled, unless the socket is replayed from a host trace.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && !this.machine.replays_host()
+        {
             this.reject_in_isolation("`socket`", reject_with)?;
             return this.set_errno_and_return_neg1_i32(LibcError("EACCES"));
         }
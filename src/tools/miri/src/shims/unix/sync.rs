@@ -932,7 +932,9 @@ fn pthread_cond_timedwait(
             (TimeoutClock::Monotonic, TimeoutStyle::Relative)
         } else {
             if data.clock == TimeoutClock::RealTime {
-                this.check_no_isolation("`pthread_cond_timedwait` with `CLOCK_REALTIME`")?;
+                this.check_no_isolation_or_replay(
+                    "`pthread_cond_timedwait` with `CLOCK_REALTIME`",
+                )?;
             }
 
             (data.clock, TimeoutStyle::Absolute)
@@ -1,6 +1,6 @@
 use std::cell::{Cell, RefCell, RefMut};
 use std::io;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::net::{Ipv4Addr, Shutdown, SocketAddr, SocketAddrV4};
 use std::sync::atomic::AtomicBool;
 use std::time::Duration;
@@ -16,6 +16,9 @@
 use crate::shims::unix::socket::{SocketFamily, UnixSocketFileDescription};
 use crate::*;
 
+/// The state of a [`TcpSocket`]. The host sockets are [`None`] if the socket was
+/// created while replaying a host trace: then the results of all operations on them
+/// are taken from the trace.
 #[derive(Debug)]
 enum SocketState {
     /// No syscall after `socket` has been made.
@@ -25,24 +28,52 @@ enum SocketState {
     Bound(SocketAddr),
     /// The `listen` syscall has been called on the socket.
     /// This is only reachable from the [`SocketState::Bound`] state.
-    Listening(TcpListener),
+    Listening(Option<TcpListener>),
     /// The `connect` syscall has been called and we weren't yet able
     /// to ensure the connection is established. This is only reachable
     /// from the [`SocketState::Initial`] state.
-    Connecting(TcpStream),
+    Connecting(Option<TcpStream>),
     /// The `connect` syscall has been called on the socket and
     /// we ensured that the connection is established, or
     /// the socket was created by the `accept` syscall.
     /// For a socket created using the `connect` syscall, this is
     /// only reachable from the [`SocketState::Connecting`] state.
-    Connected(TcpStream),
+    Connected(Option<TcpStream>),
     /// The SO_ERROR socket option has been set after calling
     /// the `connect` syscall, indicating that the connection
     /// attempt failed. By the POSIX specification, a socket is
     /// is an unspecified state after a failed connection attempt
     /// and thus nothing (except destroying the socket) should be
     /// supported when a socket is in this state.
-    ConnectionFailed(TcpStream),
+    ConnectionFailed(Option<TcpStream>),
+}
+
+/// Returns the host socket of a [`SocketState`].
+fn host<S>(source: &Option<S>) -> &S {
+    source.as_ref().expect("replayed sockets have no host socket")
+}
+
+/// Performs `op` on the host socket `source` and records the result in the host trace, if any.
+/// When replaying, the recorded result is returned instead.
+fn traced_host<'tcx, S, T>(
+    machine: &MiriMachine<'tcx>,
+    name: &str,
+    source: &Option<S>,
+    op: impl FnOnce(&S) -> io::Result<T>,
+) -> InterpResult<'tcx, io::Result<T>>
+where
+    io::Result<T>: TraceValue,
+{
+    machine.traced(name, || interp_ok(op(host(source))))
+}
+
+/// Turns the result of `take_error` on a host socket into an [`Err`] if the socket has a pending
+/// error, so that it can be stored in the host trace.
+fn pending_error(error: io::Result<Option<io::Error>>) -> io::Result<()> {
+    match error.expect("Reading SO_ERROR should not fail") {
+        None => Ok(()),
+        Some(error) => Err(error),
+    }
 }
 
 #[derive(Debug)]
@@ -188,7 +219,7 @@ fn ioctl<'tcx>(
         arg: Option<&OpTy<'tcx>>,
         ecx: &mut MiriInterpCx<'tcx>,
     ) -> InterpResult<'tcx, i32> {
-        assert!(ecx.machine.communicate(), "cannot have `TcpSocket` with isolation enabled!");
+        assert!(ecx.machine.observes_host(), "cannot have `TcpSocket` with isolation enabled!");
 
         let fionbio = ecx.eval_libc("FIONBIO");
 
@@ -234,7 +265,10 @@ fn bind<'tcx>(
         address: SocketAddr,
         ecx: &mut MiriInterpCx<'tcx>,
     ) -> InterpResult<'tcx, Result<(), IoError>> {
-        assert!(communicate_allowed, "cannot have `TcpSocket` with isolation enabled!");
+        assert!(
+            communicate_allowed || ecx.machine.replays_host(),
+            "cannot have `TcpSocket` with isolation enabled!"
+        );
         ecx.ensure_not_failed(&self, "bind")?;
 
         let mut state = self.state.borrow_mut();
@@ -287,15 +321,23 @@ fn listen<'tcx>(
         _backlog: i32,
         ecx: &mut MiriInterpCx<'tcx>,
     ) -> InterpResult<'tcx, Result<(), IoError>> {
-        assert!(communicate_allowed, "cannot have `TcpSocket` with isolation enabled!");
+        assert!(
+            communicate_allowed || ecx.machine.replays_host(),
+            "cannot have `TcpSocket` with isolation enabled!"
+        );
         ecx.ensure_not_failed(&self, "listen")?;
 
         let mut state = self.state.borrow_mut();
 
         match *state {
-            SocketState::Bound(socket_addr) =>
-                match TcpListener::bind(socket_addr) {
-                    Ok(listener) => {
+            SocketState::Bound(socket_addr) => {
+                // When replaying, there is no host listener, only the result of creating it.
+                let mut listener = None;
+                let result = ecx.machine.traced("listen", || {
+                    interp_ok(TcpListener::bind(socket_addr).map(|l| listener = Some(l)))
+                })?;
+                match result {
+                    Ok(()) => {
                         *state = SocketState::Listening(listener);
                         drop(state);
                         // Register the socket to the blocking I/O manager because
@@ -303,7 +345,8 @@ fn listen<'tcx>(
                         ecx.machine.blocking_io.register(self);
                     }
                     Err(e) => return interp_ok(Err(IoError::HostError(e))),
-                },
+                }
+            }
             SocketState::Initial => {
                 throw_unsup_format!(
                     "listen: listening on a tcp socket which isn't bound is unsupported"
@@ -330,7 +373,10 @@ fn accept<'tcx>(
         ecx: &mut MiriInterpCx<'tcx>,
         finish: DynMachineCallback<'tcx, Result<(FdNum, SocketAddr), IoError>>,
     ) -> InterpResult<'tcx> {
-        assert!(communicate_allowed, "cannot have `TcpSocket` with isolation enabled!");
+        assert!(
+            communicate_allowed || ecx.machine.replays_host(),
+            "cannot have `TcpSocket` with isolation enabled!"
+        );
 
         if !matches!(*self.state.borrow(), SocketState::Listening(_)) {
             throw_unsup_format!(
@@ -368,7 +414,10 @@ fn connect<'tcx>(
         ecx: &mut MiriInterpCx<'tcx>,
         finish: DynMachineCallback<'tcx, Result<(), IoError>>,
     ) -> InterpResult<'tcx> {
-        assert!(communicate_allowed, "cannot have `TcpSocket` with isolation enabled!");
+        assert!(
+            communicate_allowed || ecx.machine.replays_host(),
+            "cannot have `TcpSocket` with isolation enabled!"
+        );
         ecx.ensure_not_failed(&self, "connect")?;
 
         match &*self.state.borrow() {
@@ -386,9 +435,13 @@ fn connect<'tcx>(
         }
 
         // This begins establishing the connection, but does not block until the stream is fully connected.
-        // We deal with that below.
-        match TcpStream::connect(address) {
-            Ok(stream) => {
+        // We deal with that below. When replaying, there is no host stream, only the result.
+        let mut stream = None;
+        let result = ecx.machine.traced("connect", || {
+            interp_ok(TcpStream::connect(address).map(|s| stream = Some(s)))
+        })?;
+        match result {
+            Ok(()) => {
                 *self.state.borrow_mut() = SocketState::Connecting(stream);
                 // Register the socket to the blocking I/O manager because
                 // we now have an associated host socket.
@@ -453,7 +506,10 @@ fn send<'tcx>(
         ecx: &mut MiriInterpCx<'tcx>,
         finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
     ) -> InterpResult<'tcx> {
-        assert!(communicate_allowed, "cannot have `TcpSocket` with isolation enabled!");
+        assert!(
+            communicate_allowed || ecx.machine.replays_host(),
+            "cannot have `TcpSocket` with isolation enabled!"
+        );
 
         let is_non_block = is_non_block || self.is_non_block.get();
         let deadline = ecx.action_deadline(is_non_block, self.write_timeout.get());
@@ -501,7 +557,10 @@ fn recv<'tcx>(
         ecx: &mut MiriInterpCx<'tcx>,
         finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
     ) -> InterpResult<'tcx> {
-        assert!(communicate_allowed, "cannot have `TcpSocket` with isolation enabled!");
+        assert!(
+            communicate_allowed || ecx.machine.replays_host(),
+            "cannot have `TcpSocket` with isolation enabled!"
+        );
 
         let is_non_block = is_non_block || self.is_non_block.get();
         let deadline = ecx.action_deadline(is_non_block, self.read_timeout.get());
@@ -621,9 +680,10 @@ fn setsockopt<'tcx>(
                             "setsockopt: setting option IP_TTL on level IPPROTO_IP is only supported \
                            on connected and listening tcp sockets"
                         ),
-                    SocketState::Listening(listener) => listener.set_ttl(ttl),
+                    SocketState::Listening(listener) =>
+                        traced_host(&ecx.machine, "set-ttl", listener, |l| l.set_ttl(ttl))?,
                     SocketState::Connecting(stream) | SocketState::Connected(stream) =>
-                        stream.set_ttl(ttl),
+                        traced_host(&ecx.machine, "set-ttl", stream, |s| s.set_ttl(ttl))?,
                     SocketState::ConnectionFailed(_) => unreachable!(),
                 };
 
@@ -654,7 +714,9 @@ fn setsockopt<'tcx>(
                            on connected tcp sockets"
                         ),
                     SocketState::Connecting(stream) | SocketState::Connected(stream) =>
-                        stream.set_nodelay(nodelay),
+                        traced_host(&ecx.machine, "set-nodelay", stream, |s| {
+                            s.set_nodelay(nodelay)
+                        })?,
                     SocketState::ConnectionFailed(_) => unreachable!(),
                 };
 
@@ -689,7 +751,7 @@ fn getsockopt<'tcx>(
             if option == opt_so_error {
                 // Reading SO_ERROR should always return the latest async error. Because our stored
                 // `socket.error` could be outdated, we attempt to update it here.
-                ecx.update_last_error(&self);
+                ecx.update_last_error(&self)?;
 
                 let return_value = match self.error.take() {
                     Some(err) => ecx.io_error_to_errnum(err)?.to_i32()?,
@@ -746,9 +808,10 @@ fn getsockopt<'tcx>(
                             "getsockopt: reading option IP_TTL on level IPPROTO_IP is only supported \
                             on connected and listening tcp sockets"
                         ),
-                    SocketState::Listening(listener) => listener.ttl(),
+                    SocketState::Listening(listener) =>
+                        traced_host(&ecx.machine, "ttl", listener, |l| l.ttl())?,
                     SocketState::Connecting(stream) | SocketState::Connected(stream) =>
-                        stream.ttl(),
+                        traced_host(&ecx.machine, "ttl", stream, |s| s.ttl())?,
                     SocketState::ConnectionFailed(_) => unreachable!(),
                 };
 
@@ -777,7 +840,7 @@ fn getsockopt<'tcx>(
                             on connected tcp sockets"
                         ),
                     SocketState::Connecting(stream) | SocketState::Connected(stream) =>
-                        stream.nodelay(),
+                        traced_host(&ecx.machine, "nodelay", stream, |s| s.nodelay())?,
                     SocketState::ConnectionFailed(_) => unreachable!(),
                 };
 
@@ -808,7 +871,10 @@ fn getsockname<'tcx>(
         communicate_allowed: bool,
         ecx: &mut MiriInterpCx<'tcx>,
     ) -> InterpResult<'tcx, Result<SocketAddr, IoError>> {
-        assert!(communicate_allowed, "cannot have `TcpSocket` with isolation enabled!");
+        assert!(
+            communicate_allowed || ecx.machine.replays_host(),
+            "cannot have `TcpSocket` with isolation enabled!"
+        );
         ecx.ensure_not_failed(&self, "getsockname")?;
 
         let state = self.state.borrow();
@@ -828,7 +894,7 @@ fn getsockname<'tcx>(
                 *address
             }
             SocketState::Listening(listener) =>
-                match listener.local_addr() {
+                match traced_host(&ecx.machine, "getsockname", listener, |l| l.local_addr())? {
                     Ok(address) => address,
                     Err(e) => return interp_ok(Err(IoError::HostError(e))),
                 },
@@ -845,7 +911,7 @@ fn getsockname<'tcx>(
                         ecx.emit_diagnostic(NonHaltingDiagnostic::ConnectingSocketGetsockname);
                     }
                 }
-                match stream.local_addr() {
+                match traced_host(&ecx.machine, "getsockname", stream, |s| s.local_addr())? {
                     Ok(address) => address,
                     Err(e) => return interp_ok(Err(IoError::HostError(e))),
                 }
@@ -865,7 +931,10 @@ fn getpeername<'tcx>(
         ecx: &mut MiriInterpCx<'tcx>,
         finish: DynMachineCallback<'tcx, Result<SocketAddr, IoError>>,
     ) -> InterpResult<'tcx> {
-        assert!(communicate_allowed, "cannot have `TcpSocket` with isolation enabled!");
+        assert!(
+            communicate_allowed || ecx.machine.replays_host(),
+            "cannot have `TcpSocket` with isolation enabled!"
+        );
 
         let socket = self;
         // It's only safe to call [`TcpStream::peer_addr`] after the socket is connected since
@@ -888,7 +957,9 @@ fn getpeername<'tcx>(
                         unreachable!()
                     };
 
-                    let result = stream.peer_addr().map_err(IoError::HostError);
+                    let result =
+                        traced_host(&this.machine, "getpeername", stream, |s| s.peer_addr())?;
+                    let result = result.map_err(IoError::HostError);
                     finish.call(this, result)
                 }
             ),
@@ -901,7 +972,10 @@ fn shutdown<'tcx>(
         how: Shutdown,
         ecx: &mut MiriInterpCx<'tcx>,
     ) -> InterpResult<'tcx, Result<(), IoError>> {
-        assert!(communicate_allowed, "cannot have `TcpSocket` with isolation enabled!");
+        assert!(
+            communicate_allowed || ecx.machine.replays_host(),
+            "cannot have `TcpSocket` with isolation enabled!"
+        );
         ecx.ensure_not_failed(&self, "shutdown")?;
 
         let state = self.state.borrow();
@@ -910,7 +984,7 @@ fn shutdown<'tcx>(
             return interp_ok(Err(LibcError("ENOTCONN")));
         };
 
-        if let Err(e) = stream.shutdown(how) {
+        if let Err(e) = traced_host(&ecx.machine, "shutdown", stream, |s| s.shutdown(how))? {
             return interp_ok(Err(IoError::HostError(e)));
         };
 
@@ -1032,8 +1106,16 @@ fn try_non_block_accept(
             )
         };
 
-        let (stream, addr) = match listener.accept() {
-            Ok(peer) => peer,
+        // When replaying, there is no host stream, only the address of the peer.
+        let mut stream = None;
+        let result = traced_host(&this.machine, "accept", listener, |l| {
+            l.accept().map(|(s, addr)| {
+                stream = Some(s);
+                addr
+            })
+        })?;
+        let addr = match result {
+            Ok(addr) => addr,
             Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
                 // We know that the source is not readable so we need to update its readiness.
                 socket.io_readiness.borrow_mut().readable = false;
@@ -1128,13 +1210,14 @@ fn try_non_block_send(
     ) -> InterpResult<'tcx, Result<usize, IoError>> {
         let this = self.eval_context_mut();
 
-        let mut state = socket.state.borrow_mut();
-        let SocketState::Connected(stream) = &mut *state else {
+        let state = socket.state.borrow();
+        let SocketState::Connected(stream) = &*state else {
             panic!("try_non_block_send must only be called when the socket is connected")
         };
 
         // This is a *non-blocking* write.
-        let result = this.write_to_host(stream, length, buffer_ptr)?;
+        let result =
+            this.write_to_host_traced(|bytes| host(stream).write(bytes), length, buffer_ptr)?;
 
         drop(state);
 
@@ -1263,15 +1346,15 @@ fn try_non_block_recv(
     ) -> InterpResult<'tcx, Result<usize, IoError>> {
         let this = self.eval_context_mut();
 
-        let mut state = socket.state.borrow_mut();
-        let SocketState::Connected(stream) = &mut *state else {
+        let state = socket.state.borrow();
+        let SocketState::Connected(stream) = &*state else {
             panic!("try_non_block_recv must only be called when the socket is connected")
         };
 
         // This is a *non-blocking* read/peek.
-        let result = this.read_from_host(
+        let result = this.read_from_host_traced(
             |buf| {
-                if should_peek { stream.peek(buf) } else { stream.read(buf) }
+                if should_peek { host(stream).peek(buf) } else { host(stream).read(buf) }
             },
             length,
             buffer_ptr,
@@ -1426,7 +1509,7 @@ fn ensure_connected(
                     drop(state);
 
                     // Set `socket.error` if `socket` currently has an error.
-                    this.update_last_error(&socket);
+                    this.update_last_error(&socket)?;
 
                     if socket.error.borrow().is_some() {
                         // There was an error during connecting.
@@ -1490,18 +1573,24 @@ fn ensure_not_failed(
     /// an error on the host socket, we transition into the [`SocketState::ConnectionFailed`]
     /// state because we know that `socket` can no longer successfully establish a
     /// connection.
-    fn update_last_error(&self, socket: &FileDescriptionRef<TcpSocket>) {
+    fn update_last_error(&self, socket: &FileDescriptionRef<TcpSocket>) -> InterpResult<'tcx> {
+        let this = self.eval_context_ref();
         let mut state = socket.state.borrow_mut();
 
         let new_error = match &*state {
             SocketState::Listening(listener) =>
-                listener.take_error().expect("Reading SO_ERROR should not fail"),
+                traced_host(&this.machine, "take-error", listener, |l| {
+                    pending_error(l.take_error())
+                })?,
             SocketState::Connecting(stream) | SocketState::Connected(stream) =>
-                stream.take_error().expect("Reading SO_ERROR should not fail"),
-            SocketState::Initial | SocketState::Bound(_) | SocketState::ConnectionFailed(_) => None,
+                traced_host(&this.machine, "take-error", stream, |s| {
+                    pending_error(s.take_error())
+                })?,
+            SocketState::Initial | SocketState::Bound(_) | SocketState::ConnectionFailed(_) =>
+                Ok(()),
         };
 
-        let Some(new_error) = new_error else { return };
+        let Err(new_error) = new_error else { return interp_ok(()) };
 
         // Store the error such that we can return it when
         // `getsockopt(SOL_SOCKET, SO_ERROR, ...)` is called on the socket.
@@ -1521,6 +1610,8 @@ fn update_last_error(&self, socket: &FileDescriptionRef<TcpSocket>) {
             };
             *state = SocketState::ConnectionFailed(stream);
         }
+
+        interp_ok(())
     }
 }
 
@@ -1528,11 +1619,12 @@ impl SourceFileDescription for TcpSocket {
     fn with_source(&self, f: &mut dyn FnMut(&mut dyn Source) -> io::Result<()>) -> io::Result<()> {
         let mut state = self.state.borrow_mut();
         match &mut *state {
-            SocketState::Listening(listener) => f(listener),
+            // We never try adding a socket which is not backed by a real socket to the poll
+            // registry, and replayed sockets are not added to it either.
+            SocketState::Listening(listener) => f(listener.as_mut().unwrap()),
             SocketState::Connecting(stream)
             | SocketState::Connected(stream)
-            | SocketState::ConnectionFailed(stream) => f(stream),
-            // We never try adding a socket which is not backed by a real socket to the poll registry.
+            | SocketState::ConnectionFailed(stream) => f(stream.as_mut().unwrap()),
             _ => unreachable!(),
         }
     }
@@ -0,0 +1,31 @@
+//@ignore-target: windows # No pthreads on Windows
+//@compile-flags: -Zmiri-explore -Zmiri-disable-weak-memory-emulation
+
+//! This lock is broken: another thread can take it between the load and the store. That only
+//! happens if the thread is preempted at exactly that point, which `-Zmiri-explore` finds.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{mem, ptr};
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+static mut DATA: usize = 0;
+
+extern "C" fn thread_start(_null: *mut libc::c_void) -> *mut libc::c_void {
+    if !LOCKED.load(Ordering::Acquire) {
+        LOCKED.store(true, Ordering::Relaxed);
+        unsafe { DATA = 1 }; //~ERROR: Data race detected
+        LOCKED.store(false, Ordering::Release);
+    }
+    ptr::null_mut()
+}
+
+fn main() {
+    unsafe {
+        let mut t1: libc::pthread_t = mem::zeroed();
+        let mut t2: libc::pthread_t = mem::zeroed();
+        libc::pthread_create(&mut t1, ptr::null(), thread_start, ptr::null_mut());
+        libc::pthread_create(&mut t2, ptr::null(), thread_start, ptr::null_mut());
+        assert_eq!(libc::pthread_join(t1, ptr::null_mut()), 0);
+        assert_eq!(libc::pthread_join(t2, ptr::null_mut()), 0);
+    }
+}
@@ -0,0 +1,9 @@
+//@only-target: linux # the trace was recorded on Linux
+//@compile-flags: -Zmiri-replay=tests/fail-dep/libc/libc-host-trace-diverged.trace
+// The recorded run asked for random bytes where this program opens a file.
+
+fn main() {
+    unsafe {
+        libc::open(c"/miri-replayed-file".as_ptr(), libc::O_RDONLY); //~ERROR: replaying host trace diverged
+    }
+}
@@ -0,0 +1,18 @@
+//@ignore-host: windows # the failing input is saved to `/dev/stdout`
+//@compile-flags: -Zmiri-fuzz-corpus=tests/fail/fuzz_invalid_bool.corpus -Zmiri-fuzz-runs=0 -Zmiri-fuzz-artifact=/dev/stdout
+#![allow(unnecessary_transmutes)]
+
+#[path = "../utils/mod.rs"]
+mod utils;
+
+fn harness(data: &[u8]) {
+    // The first byte is meant to be `0` or `1`, but that is never checked. The seed corpus
+    // contains both of these, followed by the input `2` which makes this UB.
+    if let Some(&flag) = data.first() {
+        let _flag = unsafe { std::mem::transmute::<u8, bool>(flag - b'0') }; //~ ERROR: expected a boolean
+    }
+}
+
+fn main() {
+    unsafe { utils::miri_fuzz(harness) };
+}
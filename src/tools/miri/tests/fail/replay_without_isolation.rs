@@ -0,0 +1,4 @@
+//@compile-flags: -Zmiri-disable-isolation -Zmiri-replay=tests/fail/replay_without_isolation.trace
+//@error-in-other-file: `-Zmiri-replay` cannot be combined with `-Zmiri-disable-isolation`
+
+fn main() {}
@@ -0,0 +1,12 @@
+//@ignore-target: windows # There is no `/dev/null` on Windows
+//@compile-flags: -Zmiri-tree-borrows -Zmiri-tree-borrows-history=/dev/null
+
+// Check that the history of the allocation is written out when Tree Borrows reports UB.
+fn main() {
+    let x = &mut 0u8;
+    let y = unsafe { &mut *(x as *mut u8) };
+    *y += 1;
+    let _val = *x;
+    //~v ERROR: /write access through .* is forbidden/
+    *y += 1;
+}
@@ -15,3 +15,25 @@ EXPORT void call_fn_ptr_with_arg(int32_t f(int32_t)) {
         f(42);
     }
 }
+
+EXPORT int32_t call_fn_ptr_twice(int32_t f(int32_t), int32_t x) {
+    return f(f(x));
+}
+
+EXPORT void for_each_i32(const int32_t *buf, size_t len, void f(int32_t)) {
+    for (size_t i = 0; i < len; ++i) {
+        f(buf[i]);
+    }
+}
+
+EXPORT void call_fn_ptr_with_data(void f(void *), void *data) {
+    f(data);
+}
+
+EXPORT int64_t call_fn_ptr_with_pair(int64_t f(int32_t, int64_t)) {
+    return f(-3, 1ll << 40);
+}
+
+EXPORT uint8_t call_fn_ptr_returning_u8(uint8_t f(void)) {
+    return f() + 1;
+}
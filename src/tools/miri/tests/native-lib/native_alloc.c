@@ -0,0 +1,28 @@
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// See comments in build_native_lib()
+#define EXPORT __attribute__((visibility("default")))
+
+/* Test: native memory handed to Rust */
+
+EXPORT int32_t *alloc_i32_array(size_t len) {
+    int32_t *arr = malloc(len * sizeof(int32_t));
+    for (size_t i = 0; i < len; ++i) {
+        arr[i] = (int32_t)i;
+    }
+    return arr;
+}
+
+EXPORT int32_t sum_i32_array(const int32_t *arr, size_t len) {
+    int32_t sum = 0;
+    for (size_t i = 0; i < len; ++i) {
+        sum += arr[i];
+    }
+    return sum;
+}
+
+EXPORT void free_i32_array(int32_t *arr) {
+    free(arr);
+}
@@ -0,0 +1,117 @@
+//@compile-flags: -Zmiri-permissive-provenance
+
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+fn main() {
+    test_nop();
+    test_arg_and_return();
+    test_generic();
+    test_pass_buffer();
+    test_pass_data();
+    test_multiple_args();
+    test_small_return();
+}
+
+/// Test a callback without arguments and return value.
+fn test_nop() {
+    extern "C" {
+        fn call_fn_ptr(f: Option<extern "C" fn()>);
+    }
+
+    static CALLED: AtomicBool = AtomicBool::new(false);
+    extern "C" fn mark() {
+        CALLED.store(true, Ordering::Relaxed);
+    }
+
+    unsafe {
+        call_fn_ptr(None); // this one does not call anything
+        call_fn_ptr(Some(mark));
+    }
+    assert!(CALLED.load(Ordering::Relaxed));
+}
+
+/// Test a callback whose return value native code passes to it again.
+fn test_arg_and_return() {
+    extern "C" {
+        fn call_fn_ptr_twice(f: extern "C" fn(i32) -> i32, x: i32) -> i32;
+    }
+
+    extern "C" fn double(x: i32) -> i32 {
+        x * 2
+    }
+
+    assert_eq!(unsafe { call_fn_ptr_twice(double, 5) }, 20);
+}
+
+/// Test a callback that is a generic function.
+fn test_generic() {
+    extern "C" {
+        fn call_fn_ptr_twice(f: extern "C" fn(i32) -> i32, x: i32) -> i32;
+    }
+
+    extern "C" fn id<T>(x: T) -> T {
+        x
+    }
+
+    assert_eq!(unsafe { call_fn_ptr_twice(id::<i32>, 7) }, 7);
+}
+
+/// Test a callback that gets called for every element of a buffer shared with native code.
+fn test_pass_buffer() {
+    extern "C" {
+        fn for_each_i32(buf: *const i32, len: usize, f: extern "C" fn(i32));
+    }
+
+    static SUM: AtomicI32 = AtomicI32::new(0);
+    extern "C" fn add(x: i32) {
+        SUM.fetch_add(x, Ordering::Relaxed);
+    }
+
+    let buf = [1, 2, 3, 4];
+    unsafe { for_each_i32(buf.as_ptr(), buf.len(), add) };
+    assert_eq!(SUM.load(Ordering::Relaxed), 10);
+}
+
+/// Test a callback that gets a pointer to Rust memory back from native code, and
+/// allocates while being called.
+fn test_pass_data() {
+    extern "C" {
+        fn call_fn_ptr_with_data(f: extern "C" fn(*mut c_void), data: *mut c_void);
+    }
+
+    extern "C" fn push(data: *mut c_void) {
+        let v = unsafe { &mut *data.cast::<Vec<i32>>() };
+        v.push(42);
+    }
+
+    let mut v = Vec::<i32>::new();
+    unsafe { call_fn_ptr_with_data(push, (&raw mut v).cast()) };
+    assert_eq!(v, [42]);
+}
+
+/// Test a callback with arguments of different sizes.
+fn test_multiple_args() {
+    extern "C" {
+        fn call_fn_ptr_with_pair(f: extern "C" fn(i32, i64) -> i64) -> i64;
+    }
+
+    extern "C" fn add(a: i32, b: i64) -> i64 {
+        i64::from(a) + b
+    }
+
+    assert_eq!(unsafe { call_fn_ptr_with_pair(add) }, (1 << 40) - 3);
+}
+
+/// Test a callback whose return value is smaller than a register.
+fn test_small_return() {
+    extern "C" {
+        fn call_fn_ptr_returning_u8(f: extern "C" fn() -> u8) -> u8;
+    }
+
+    extern "C" fn answer() -> u8 {
+        41
+    }
+
+    assert_eq!(unsafe { call_fn_ptr_returning_u8(answer) }, 42);
+}
@@ -0,0 +1,37 @@
+//@compile-flags: -Zmiri-permissive-provenance
+
+#[path = "../../utils/mod.rs"]
+mod utils;
+
+use std::slice;
+
+extern "C" {
+    fn alloc_i32_array(len: usize) -> *mut i32;
+    fn sum_i32_array(arr: *const i32, len: usize) -> i32;
+    fn free_i32_array(arr: *mut i32);
+}
+
+fn main() {
+    const LEN: usize = 8;
+    unsafe {
+        let native = alloc_i32_array(LEN);
+        let arr = utils::miri_adopt_native_alloc(
+            native.cast(),
+            LEN * size_of::<i32>(),
+            align_of::<i32>(),
+        )
+        .cast::<i32>();
+        assert_eq!(arr.addr(), native.addr());
+
+        // Rust can read and write the memory that native code initialized...
+        let slice = slice::from_raw_parts_mut(arr, LEN);
+        assert_eq!(slice, [0, 1, 2, 3, 4, 5, 6, 7]);
+        slice[0] = 100;
+        // ...and native code sees the writes.
+        let sum = sum_i32_array(arr, LEN);
+        assert_eq!(sum, 128);
+
+        utils::miri_release_native_alloc(arr.cast());
+        free_i32_array(native);
+    }
+}
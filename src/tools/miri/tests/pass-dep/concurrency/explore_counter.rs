@@ -0,0 +1,27 @@
+//@ignore-target: windows # No pthreads on Windows
+//@compile-flags: -Zmiri-explore
+
+//! The two increments do not happen-before each other, so `-Zmiri-explore` runs both orders, and
+//! nothing else: the number of executions in the `.stderr` file must stay the same.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{mem, ptr};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+extern "C" fn thread_start(_null: *mut libc::c_void) -> *mut libc::c_void {
+    COUNTER.fetch_add(1, Ordering::Relaxed);
+    ptr::null_mut()
+}
+
+fn main() {
+    unsafe {
+        let mut t1: libc::pthread_t = mem::zeroed();
+        let mut t2: libc::pthread_t = mem::zeroed();
+        assert_eq!(libc::pthread_create(&mut t1, ptr::null(), thread_start, ptr::null_mut()), 0);
+        assert_eq!(libc::pthread_create(&mut t2, ptr::null(), thread_start, ptr::null_mut()), 0);
+        assert_eq!(libc::pthread_join(t1, ptr::null_mut()), 0);
+        assert_eq!(libc::pthread_join(t2, ptr::null_mut()), 0);
+    }
+    assert_eq!(COUNTER.load(Ordering::Relaxed), 2);
+}
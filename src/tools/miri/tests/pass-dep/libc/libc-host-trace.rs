@@ -0,0 +1,80 @@
+//@only-target: linux # the trace was written for the Linux shims
+//@compile-flags: -Zmiri-replay=tests/pass-dep/libc/libc-host-trace.trace -Zmiri-no-short-fd-operations
+// All host results in this test come from the trace. It is not an actual recording: it was written
+// by hand, in the format that `-Zmiri-disable-isolation -Zmiri-record=<file>` produces, so that
+// none of the results are available on the actual host. The environment variable is not set, the
+// clocks and process ID are made up, and the file lives in a directory that does not exist.
+
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::{env, io};
+
+#[path = "../../utils/mod.rs"]
+mod utils;
+
+#[path = "../../utils/libc.rs"]
+mod libc_utils;
+
+use libc_utils::{errno_check, errno_result};
+
+fn main() {
+    test_env();
+    test_clocks();
+    test_getrandom();
+    test_file();
+}
+
+fn test_env() {
+    println!("env: {:?}", env::var("MIRI_HOST_TRACE_TEST"));
+    println!("pid: {}", unsafe { libc::getpid() });
+}
+
+fn clock(clk_id: libc::clockid_t) -> (libc::time_t, libc::c_long) {
+    let mut tp = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    errno_check(unsafe { libc::clock_gettime(clk_id, &mut tp) });
+    (tp.tv_sec, tp.tv_nsec)
+}
+
+fn test_clocks() {
+    println!("realtime: {:?}", clock(libc::CLOCK_REALTIME));
+    let start = clock(libc::CLOCK_MONOTONIC);
+    let end = clock(libc::CLOCK_MONOTONIC);
+    println!("monotonic: {start:?} {end:?}");
+}
+
+fn test_getrandom() {
+    let mut buf = [0u8; 8];
+    let ret = unsafe { libc::getrandom(buf.as_mut_ptr().cast(), buf.len(), 0) };
+    assert_eq!(errno_result(ret).unwrap(), 8);
+    println!("random: {buf:02x?}");
+}
+
+fn test_file() {
+    let path = utils::tmp().join("miri_test_libc_host_trace.txt");
+    let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+    let contents = b"hello from the host\n";
+
+    unsafe {
+        let fd = errno_result(libc::open(
+            path.as_ptr(),
+            libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC,
+            0o666,
+        ))
+        .unwrap();
+        let written = errno_result(libc::write(fd, contents.as_ptr().cast(), contents.len()));
+        assert_eq!(written.unwrap(), 20);
+        errno_check(libc::close(fd));
+
+        let fd = errno_result(libc::open(path.as_ptr(), libc::O_RDONLY)).unwrap();
+        let mut buf = [0u8; 64];
+        let read = errno_result(libc::read(fd, buf.as_mut_ptr().cast(), buf.len())).unwrap();
+        println!("read: {:?}", std::str::from_utf8(&buf[..read as usize]).unwrap());
+        let read = errno_result(libc::read(fd, buf.as_mut_ptr().cast(), buf.len())).unwrap();
+        assert_eq!(read, 0);
+        errno_check(libc::close(fd));
+
+        errno_check(libc::unlink(path.as_ptr()));
+        let err = errno_result(libc::open(path.as_ptr(), libc::O_RDONLY)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
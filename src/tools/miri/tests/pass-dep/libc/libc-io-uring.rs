@@ -0,0 +1,483 @@
+//@only-target: linux
+// test_blocking_read depends on a deterministic schedule, test_fsync needs a file.
+//@compile-flags: -Zmiri-deterministic-concurrency -Zmiri-disable-isolation
+
+use std::fs::{self, File};
+use std::mem::size_of;
+use std::os::unix::io::AsRawFd;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::{ptr, thread};
+
+#[path = "../../utils/mod.rs"]
+mod utils;
+
+#[path = "../../utils/libc.rs"]
+mod libc_utils;
+use libc_utils::*;
+
+// The `libc` crate does not have the `io_uring` definitions, see
+// `include/uapi/linux/io_uring.h`. The addresses in submission queue entries are integers, so the
+// buffers they point to need to have exposed provenance.
+const IORING_OFF_SQ_RING: i64 = 0;
+const IORING_OFF_CQ_RING: i64 = 0x8000000;
+const IORING_OFF_SQES: i64 = 0x10000000;
+const IORING_ENTER_GETEVENTS: u32 = 1;
+const IORING_OP_NOP: u8 = 0;
+const IORING_OP_READV: u8 = 1;
+const IORING_OP_WRITEV: u8 = 2;
+const IORING_OP_FSYNC: u8 = 3;
+const IORING_OP_READ_FIXED: u8 = 4;
+const IORING_OP_WRITE_FIXED: u8 = 5;
+const IORING_OP_POLL_ADD: u8 = 6;
+const IORING_OP_TIMEOUT: u8 = 11;
+const IORING_OP_READ: u8 = 22;
+const IORING_OP_WRITE: u8 = 23;
+const IORING_REGISTER_BUFFERS: u32 = 0;
+const IORING_FSYNC_DATASYNC: u32 = 1;
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct SqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    flags: u32,
+    dropped: u32,
+    array: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct CqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    overflow: u32,
+    cqes: u32,
+    flags: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct Params {
+    sq_entries: u32,
+    cq_entries: u32,
+    flags: u32,
+    sq_thread_cpu: u32,
+    sq_thread_idle: u32,
+    features: u32,
+    wq_fd: u32,
+    resv: [u32; 3],
+    sq_off: SqringOffsets,
+    cq_off: CqringOffsets,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct Sqe {
+    opcode: u8,
+    flags: u8,
+    ioprio: u16,
+    fd: i32,
+    off: u64,
+    addr: u64,
+    len: u32,
+    op_flags: u32,
+    user_data: u64,
+    buf_index: u16,
+    personality: u16,
+    splice_fd_in: i32,
+    pad: [u64; 2],
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cqe {
+    user_data: u64,
+    res: i32,
+    flags: u32,
+}
+
+struct Ring {
+    fd: i32,
+    params: Params,
+    sq: *mut u8,
+    cq: *mut u8,
+    sqes: *mut Sqe,
+}
+
+impl Ring {
+    fn new(entries: u32) -> Ring {
+        let mut params = Params::default();
+        let fd = errno_result(unsafe {
+            libc::syscall(libc::SYS_io_uring_setup, entries, &mut params as *mut Params)
+        })
+        .unwrap();
+        let fd = i32::try_from(fd).unwrap();
+        assert_eq!(params.sq_entries, entries.next_power_of_two());
+        assert_eq!(params.cq_entries, 2 * params.sq_entries);
+
+        let map = |len: usize, offset: i64| unsafe {
+            let ptr = libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED | libc::MAP_POPULATE,
+                fd,
+                offset,
+            );
+            assert_ne!(ptr, libc::MAP_FAILED);
+            ptr.cast::<u8>()
+        };
+        let sq_len = params.sq_off.array as usize + params.sq_entries as usize * 4;
+        let cq_len = params.cq_off.cqes as usize + params.cq_entries as usize * size_of::<Cqe>();
+        let sq = map(sq_len, IORING_OFF_SQ_RING);
+        let cq = map(cq_len, IORING_OFF_CQ_RING);
+        let sqes = map(params.sq_entries as usize * size_of::<Sqe>(), IORING_OFF_SQES).cast();
+        Ring { fd, params, sq, cq, sqes }
+    }
+
+    fn index(&self, ring: *mut u8, offset: u32) -> &AtomicU32 {
+        unsafe { AtomicU32::from_ptr(ring.add(offset as usize).cast()) }
+    }
+
+    /// Adds an entry to the submission queue.
+    fn push(&self, sqe: Sqe) {
+        let off = &self.params.sq_off;
+        unsafe {
+            let tail = self.index(self.sq, off.tail).load(Ordering::Relaxed);
+            let idx = tail & self.sq.add(off.ring_mask as usize).cast::<u32>().read();
+            self.sqes.add(idx as usize).write(sqe);
+            self.sq.add(off.array as usize).cast::<u32>().add(idx as usize).write(idx);
+            self.index(self.sq, off.tail).store(tail.wrapping_add(1), Ordering::Release);
+        }
+    }
+
+    fn enter(&self, to_submit: u32, min_complete: u32) -> std::io::Result<libc::c_long> {
+        errno_result(unsafe {
+            libc::syscall(
+                libc::SYS_io_uring_enter,
+                self.fd,
+                to_submit,
+                min_complete,
+                IORING_ENTER_GETEVENTS,
+                ptr::null::<libc::sigset_t>(),
+                0usize,
+            )
+        })
+    }
+
+    /// Removes an entry from the completion queue.
+    fn pop(&self) -> Option<Cqe> {
+        let off = &self.params.cq_off;
+        let head = self.index(self.cq, off.head).load(Ordering::Relaxed);
+        let tail = self.index(self.cq, off.tail).load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        unsafe {
+            let idx = head & self.cq.add(off.ring_mask as usize).cast::<u32>().read();
+            let cqe = self.cq.add(off.cqes as usize).cast::<Cqe>().add(idx as usize).read();
+            self.index(self.cq, off.head).store(head.wrapping_add(1), Ordering::Release);
+            Some(cqe)
+        }
+    }
+
+    /// Pops all available completions, sorted by `user_data`.
+    fn pop_all(&self) -> Vec<(u64, i32)> {
+        let mut cqes = Vec::new();
+        while let Some(cqe) = self.pop() {
+            cqes.push((cqe.user_data, cqe.res));
+        }
+        cqes.sort();
+        cqes
+    }
+}
+
+fn pipe() -> [i32; 2] {
+    let mut fds = [-1, -1];
+    errno_check(unsafe { libc::pipe(fds.as_mut_ptr()) });
+    fds
+}
+
+fn main() {
+    test_nop();
+    test_read_write();
+    test_readv_writev();
+    test_fixed_buffers();
+    test_blocking_read();
+    test_poll_add();
+    test_timeout();
+    test_fsync();
+    test_errors();
+}
+
+fn test_nop() {
+    let ring = Ring::new(3);
+    ring.push(Sqe { opcode: IORING_OP_NOP, user_data: 1, ..Default::default() });
+    ring.push(Sqe { opcode: IORING_OP_NOP, user_data: 2, ..Default::default() });
+    assert_eq!(ring.enter(2, 2).unwrap(), 2);
+    assert_eq!(ring.pop(), Some(Cqe { user_data: 1, res: 0, flags: 0 }));
+    assert_eq!(ring.pop(), Some(Cqe { user_data: 2, res: 0, flags: 0 }));
+    assert_eq!(ring.pop(), None);
+}
+
+fn test_read_write() {
+    let ring = Ring::new(4);
+    let [read_fd, write_fd] = pipe();
+    let data = *b"hello";
+    let mut buf = [0u8; 5];
+    ring.push(Sqe {
+        opcode: IORING_OP_WRITE,
+        fd: write_fd,
+        off: u64::MAX,
+        addr: data.as_ptr().expose_provenance() as u64,
+        len: 5,
+        user_data: 1,
+        ..Default::default()
+    });
+    ring.push(Sqe {
+        opcode: IORING_OP_READ,
+        fd: read_fd,
+        off: u64::MAX,
+        addr: buf.as_mut_ptr().expose_provenance() as u64,
+        len: 5,
+        user_data: 2,
+        ..Default::default()
+    });
+    assert_eq!(ring.enter(2, 2).unwrap(), 2);
+    assert_eq!(ring.pop_all(), [(1, 5), (2, 5)]);
+    assert_eq!(buf, data);
+}
+
+fn test_readv_writev() {
+    let ring = Ring::new(4);
+    let [read_fd, write_fd] = pipe();
+    let (a, b) = (*b"abc", *b"defg");
+    let write_iov = [
+        libc::iovec { iov_base: a.as_ptr() as *mut _, iov_len: a.len() },
+        libc::iovec { iov_base: b.as_ptr() as *mut _, iov_len: b.len() },
+    ];
+    ring.push(Sqe {
+        opcode: IORING_OP_WRITEV,
+        fd: write_fd,
+        addr: write_iov.as_ptr().expose_provenance() as u64,
+        len: 2,
+        user_data: 1,
+        ..Default::default()
+    });
+    assert_eq!(ring.enter(1, 1).unwrap(), 1);
+    assert_eq!(ring.pop_all(), [(1, 7)]);
+
+    let (mut c, mut d) = ([0u8; 2], [0u8; 5]);
+    let read_iov = [
+        libc::iovec { iov_base: c.as_mut_ptr().cast(), iov_len: c.len() },
+        libc::iovec { iov_base: d.as_mut_ptr().cast(), iov_len: d.len() },
+    ];
+    ring.push(Sqe {
+        opcode: IORING_OP_READV,
+        fd: read_fd,
+        addr: read_iov.as_ptr().expose_provenance() as u64,
+        len: 2,
+        user_data: 2,
+        ..Default::default()
+    });
+    assert_eq!(ring.enter(1, 1).unwrap(), 1);
+    assert_eq!(ring.pop_all(), [(2, 7)]);
+    assert_eq!(&c, b"ab");
+    assert_eq!(&d, b"cdefg");
+}
+
+fn test_fixed_buffers() {
+    let ring = Ring::new(4);
+    let [read_fd, write_fd] = pipe();
+    let mut bufs = [*b"fixed", [0u8; 5]];
+    let iov =
+        bufs.each_mut().map(|buf| libc::iovec { iov_base: buf.as_mut_ptr().cast(), iov_len: 5 });
+    errno_result(unsafe {
+        libc::syscall(
+            libc::SYS_io_uring_register,
+            ring.fd,
+            IORING_REGISTER_BUFFERS,
+            iov.as_ptr(),
+            2u32,
+        )
+    })
+    .unwrap();
+
+    ring.push(Sqe {
+        opcode: IORING_OP_WRITE_FIXED,
+        fd: write_fd,
+        addr: iov[0].iov_base.expose_provenance() as u64,
+        len: 5,
+        buf_index: 0,
+        user_data: 1,
+        ..Default::default()
+    });
+    ring.push(Sqe {
+        opcode: IORING_OP_READ_FIXED,
+        fd: read_fd,
+        addr: iov[1].iov_base.expose_provenance() as u64,
+        len: 5,
+        buf_index: 1,
+        user_data: 2,
+        ..Default::default()
+    });
+    // Out of bounds of the registered buffer.
+    ring.push(Sqe {
+        opcode: IORING_OP_READ_FIXED,
+        fd: read_fd,
+        addr: iov[1].iov_base.expose_provenance() as u64,
+        len: 6,
+        buf_index: 1,
+        user_data: 3,
+        ..Default::default()
+    });
+    assert_eq!(ring.enter(3, 3).unwrap(), 3);
+    assert_eq!(ring.pop_all(), [(1, 5), (2, 5), (3, -libc::EFAULT)]);
+    assert_eq!(unsafe { iov[1].iov_base.cast::<[u8; 5]>().read() }, *b"fixed");
+}
+
+/// A read on an empty pipe completes once another thread writes to it.
+fn test_blocking_read() {
+    let ring = Ring::new(4);
+    let [read_fd, write_fd] = pipe();
+    let mut buf = [0u8; 5];
+    ring.push(Sqe {
+        opcode: IORING_OP_READ,
+        fd: read_fd,
+        addr: buf.as_mut_ptr().expose_provenance() as u64,
+        len: 5,
+        user_data: 1,
+        ..Default::default()
+    });
+    // The read cannot complete yet.
+    assert_eq!(ring.enter(1, 0).unwrap(), 1);
+    assert_eq!(ring.pop(), None);
+
+    let writer = thread::spawn(move || {
+        write_all(write_fd, b"world").unwrap();
+    });
+    // Blocks until the writer thread wrote the data.
+    assert_eq!(ring.enter(0, 1).unwrap(), 0);
+    assert_eq!(ring.pop_all(), [(1, 5)]);
+    assert_eq!(&buf, b"world");
+    writer.join().unwrap();
+}
+
+fn test_poll_add() {
+    let ring = Ring::new(4);
+    let [read_fd, write_fd] = pipe();
+    ring.push(Sqe {
+        opcode: IORING_OP_POLL_ADD,
+        fd: read_fd,
+        op_flags: libc::POLLIN as u32,
+        user_data: 1,
+        ..Default::default()
+    });
+    assert_eq!(ring.enter(1, 0).unwrap(), 1);
+    assert_eq!(ring.pop(), None);
+
+    write_all(write_fd, b"x").unwrap();
+    assert_eq!(ring.enter(0, 1).unwrap(), 0);
+    assert_eq!(ring.pop_all(), [(1, libc::POLLIN.into())]);
+}
+
+fn test_timeout() {
+    let ring = Ring::new(4);
+
+    // A timeout that expires.
+    let timespec: [i64; 2] = [0, 1_000_000];
+    ring.push(Sqe {
+        opcode: IORING_OP_TIMEOUT,
+        addr: timespec.as_ptr().expose_provenance() as u64,
+        len: 1,
+        user_data: 1,
+        ..Default::default()
+    });
+    assert_eq!(ring.enter(1, 1).unwrap(), 1);
+    assert_eq!(ring.pop_all(), [(1, -libc::ETIME)]);
+
+    // A timeout that completes after one other completion.
+    let timespec: [i64; 2] = [1000, 0];
+    ring.push(Sqe {
+        opcode: IORING_OP_TIMEOUT,
+        off: 1,
+        addr: timespec.as_ptr().expose_provenance() as u64,
+        len: 1,
+        user_data: 2,
+        ..Default::default()
+    });
+    ring.push(Sqe { opcode: IORING_OP_NOP, user_data: 3, ..Default::default() });
+    assert_eq!(ring.enter(2, 2).unwrap(), 2);
+    assert_eq!(ring.pop_all(), [(2, 0), (3, 0)]);
+}
+
+fn test_fsync() {
+    let ring = Ring::new(4);
+    let path = utils::prepare_with_content("miri_test_libc_io_uring_fsync.txt", b"hello");
+    let file = File::options().write(true).open(&path).unwrap();
+    let fd = file.as_raw_fd();
+    ring.push(Sqe { opcode: IORING_OP_FSYNC, fd, user_data: 1, ..Default::default() });
+    ring.push(Sqe {
+        opcode: IORING_OP_FSYNC,
+        fd,
+        op_flags: IORING_FSYNC_DATASYNC,
+        user_data: 2,
+        ..Default::default()
+    });
+    // Unknown flags are rejected.
+    ring.push(Sqe {
+        opcode: IORING_OP_FSYNC,
+        fd,
+        op_flags: 1 << 5,
+        user_data: 3,
+        ..Default::default()
+    });
+    assert_eq!(ring.enter(3, 3).unwrap(), 3);
+    assert_eq!(ring.pop_all(), [(1, 0), (2, 0), (3, -libc::EINVAL)]);
+    drop(file);
+    fs::remove_file(&path).unwrap();
+}
+
+fn test_errors() {
+    let ring = Ring::new(4);
+    let [read_fd, _write_fd] = pipe();
+    let mut buf = [0u8; 1];
+    ring.push(Sqe {
+        opcode: IORING_OP_READ,
+        fd: 1234,
+        addr: buf.as_mut_ptr().expose_provenance() as u64,
+        len: 1,
+        user_data: 1,
+        ..Default::default()
+    });
+    assert_eq!(ring.enter(1, 1).unwrap(), 1);
+    assert_eq!(ring.pop_all(), [(1, -libc::EBADF)]);
+
+    // `io_uring_enter` on something that is not a ring.
+    let err = errno_result(unsafe {
+        libc::syscall(
+            libc::SYS_io_uring_enter,
+            read_fd,
+            0u32,
+            0u32,
+            0u32,
+            ptr::null::<u8>(),
+            0usize,
+        )
+    })
+    .unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EOPNOTSUPP));
+}
@@ -0,0 +1,12 @@
+//@ignore-host: windows # the profile is written to `/dev/null`
+//@compile-flags: -Zmiri-alloc-profile=/dev/null
+//@normalize-stderr-test: "after [0-9]+ basic blocks" -> "after N basic blocks"
+
+fn main() {
+    // Ten small allocations from the same place...
+    let boxes: [Box<[u8; 16]>; 10] = std::array::from_fn(|i| Box::new([i as u8; 16]));
+    drop(boxes);
+    // ...and a single big one, which makes up the peak.
+    let v = Vec::<u8>::with_capacity(1000);
+    drop(v);
+}
@@ -0,0 +1,30 @@
+//@compile-flags: -Zmiri-fuzz-runs=50 -Zmiri-fuzz-max-len=8
+//@normalize-stderr-test: "[0-9]+ inputs that together reach [0-9]+ basic blocks" -> "N inputs that together reach M basic blocks"
+
+#[path = "../utils/mod.rs"]
+mod utils;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+fn harness(data: &[u8]) {
+    assert!(data.len() <= 8);
+    // Without a seed corpus, the first input is the empty one.
+    if RUNS.fetch_add(1, Ordering::Relaxed) == 0 {
+        assert!(data.is_empty());
+    }
+    // Make different inputs reach different code.
+    match data {
+        [] => {}
+        [0, ..] => {}
+        [b, rest @ ..] if *b > 0x80 => assert!(rest.len() < 8),
+        _ => {}
+    }
+}
+
+fn main() {
+    unsafe { utils::miri_fuzz(harness) };
+    // One run for the empty input, and one for each mutated input.
+    assert_eq!(RUNS.load(Ordering::Relaxed), 51);
+}
@@ -83,6 +83,7 @@ fn build_native_lib(target: &str) -> PathBuf {
             "tests/native-lib/ptr_read_access.c",
             "tests/native-lib/ptr_write_access.c",
             "tests/native-lib/fn_ptr.c",
+            "tests/native-lib/native_alloc.c",
             // Ensure we notice serious problems in the C code.
             "-Wall",
             "-Wextra",
@@ -171,6 +171,28 @@ pub fn miri_host_to_target_path(
     ///
     /// As far as Miri is concerned, this is equivalent to `yield_now`.
     pub fn miri_spin_loop();
+
+    /// Miri-provided extern function to fuzz the given harness: Miri calls it repeatedly with
+    /// inputs guided by the basic blocks they reach, and returns once the configured number of
+    /// inputs has been tried. See the README for the `-Zmiri-fuzz-*` flags that control fuzzing.
+    ///
+    /// Can only be called once per execution.
+    pub fn miri_fuzz(harness: fn(&[u8]));
+
+    /// Miri-provided extern function to make `size` bytes of memory that native code allocated
+    /// accessible to the program. Only available with `-Zmiri-native-lib`.
+    ///
+    /// `ptr` must be aligned to `align`, and must not point to memory that is already managed by
+    /// Miri. Returns a pointer with the same address that can be used to access the memory.
+    /// Miri considers the memory to be initialized, and never frees it.
+    pub fn miri_adopt_native_alloc(ptr: *mut u8, size: usize, align: usize) -> *mut u8;
+
+    /// Miri-provided extern function to make memory that was adopted with
+    /// `miri_adopt_native_alloc` inaccessible again. This must be called before native code
+    /// frees the memory.
+    ///
+    /// `ptr` has to be the pointer returned by `miri_adopt_native_alloc`.
+    pub fn miri_release_native_alloc(ptr: *mut u8);
 }
 
 // Stubs so we can run things without Miri.
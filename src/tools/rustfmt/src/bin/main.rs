@@ -12,6 +12,7 @@
 use std::env;
 use std::fs::File;
 use std::io::{self, Read, Write, stdout};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
@@ -19,7 +20,8 @@
 
 use crate::rustfmt::{
     CliOptions, Color, Config, Edition, EmitMode, FileLines, FileName,
-    FormatReportFormatterBuilder, Input, Session, StyleEdition, Verbosity, Version, load_config,
+    FormatReportFormatterBuilder, Input, Session, StyleEdition, Verbosity, Version, format_range,
+    load_config,
 };
 
 const BUG_REPORT_URL: &str = "https://github.com/rust-lang/rustfmt/issues/new?labels=bug";
@@ -83,6 +85,9 @@ pub enum OperationError {
     /// Attempt to generate a minimal config from standard input.
     #[error("The `--print-config=minimal` option doesn't work with standard input.")]
     MinimalPathWithStdin,
+    /// Attempt to format a byte range of a file.
+    #[error("The `--range` option only works with standard input.")]
+    RangeWithoutStdin,
     /// An io error during reading or writing.
     #[error("{0}")]
     IoError(IoError),
@@ -103,6 +108,7 @@ enum HelpOp {
     None,
     Config,
     FileLines,
+    Range,
 }
 
 fn make_opts() -> Options {
@@ -116,7 +122,7 @@ fn make_opts() -> Options {
     );
     let is_nightly = is_nightly();
     let emit_opts = if is_nightly {
-        "[files|stdout|coverage|checkstyle|json]"
+        "[files|stdout|coverage|checkstyle|json|sarif|code-climate]"
     } else {
         "[files|stdout]"
     };
@@ -187,6 +193,20 @@ fn make_opts() -> Options {
              more detail (unstable).",
             "JSON",
         );
+        opts.optopt(
+            "",
+            "range",
+            "Format the lines of standard input that overlap a byte range, and print \
+             the edits to the input as JSON. Run with `--help=range` for more detail (unstable).",
+            "START..END",
+        );
+        opts.optopt(
+            "",
+            "cursor",
+            "Byte offset of the cursor in standard input, to be mapped to the output \
+             of `--range` (unstable).",
+            "OFFSET",
+        );
         opts.optflag(
             "",
             "error-on-unformatted",
@@ -204,7 +224,7 @@ fn make_opts() -> Options {
     opts.optflag("q", "quiet", "Print less output");
     opts.optflag("V", "version", "Show version information");
     let help_topics = if is_nightly {
-        "`config` or `file-lines`"
+        "`config`, `file-lines` or `range`"
     } else {
         "`config`"
     };
@@ -238,6 +258,10 @@ fn execute(opts: &Options) -> Result<i32> {
             print_help_file_lines();
             Ok(0)
         }
+        Operation::Help(HelpOp::Range) => {
+            print_help_range();
+            Ok(0)
+        }
         Operation::Version => {
             print_version();
             Ok(0)
@@ -267,7 +291,10 @@ fn execute(opts: &Options) -> Result<i32> {
 
             Ok(0)
         }
-        Operation::Stdin { input } => format_string(input, options),
+        Operation::Stdin { input } => match options.range.clone() {
+            Some(range) => format_string_range(input, range, options),
+            None => format_string(input, options),
+        },
         Operation::Format {
             files,
             minimal_config_path,
@@ -280,7 +307,9 @@ fn format_string(input: String, options: GetOptsOptions) -> Result<i32> {
     let (mut config, _) = load_config(Some(Path::new(".")), Some(options.clone()))?;
 
     if options.check {
-        config.set_cli().emit_mode(EmitMode::Diff);
+        config
+            .set_cli()
+            .emit_mode(options.emit_mode.unwrap_or(EmitMode::Diff));
     } else {
         match options.emit_mode {
             // Emit modes which work with standard input
@@ -290,7 +319,11 @@ fn format_string(input: String, options: GetOptsOptions) -> Result<i32> {
                     .set()
                     .emit_mode(options.emit_mode.unwrap_or(EmitMode::Stdout));
             }
-            Some(EmitMode::Stdout) | Some(EmitMode::Checkstyle) | Some(EmitMode::Json) => {
+            Some(EmitMode::Stdout)
+            | Some(EmitMode::Checkstyle)
+            | Some(EmitMode::Json)
+            | Some(EmitMode::Sarif)
+            | Some(EmitMode::CodeClimate) => {
                 config
                     .set_cli()
                     .emit_mode(options.emit_mode.unwrap_or(EmitMode::Stdout));
@@ -328,6 +361,23 @@ fn format_string(input: String, options: GetOptsOptions) -> Result<i32> {
     Ok(exit_code)
 }
 
+fn format_string_range(input: String, range: Range<usize>, options: GetOptsOptions) -> Result<i32> {
+    // try to read config from local directory
+    let (config, _) = load_config(Some(Path::new(".")), Some(options.clone()))?;
+
+    match format_range(&input, range, options.cursor, &config) {
+        Ok(result) => {
+            serde_json::to_writer(stdout(), &result)?;
+            println!();
+            Ok(0)
+        }
+        Err(e) => {
+            eprintln!("Error formatting range: {e}");
+            Ok(1)
+        }
+    }
+}
+
 fn format(
     files: Vec<PathBuf>,
     minimal_config_path: Option<String>,
@@ -455,6 +505,30 @@ fn print_help_file_lines() {
     );
 }
 
+fn print_help_range() {
+    println!(
+        "Editors can format a selection of the text on standard input with the
+`--range` option. Its argument is a range of byte offsets like `120..380`.
+All lines that overlap the range are formatted, and instead of the formatted
+text, rustfmt prints a JSON object with the edits that turn the input into the
+formatted text. The byte ranges of the edits refer to the input, and the edits
+are sorted and do not overlap. With `--cursor`, rustfmt additionally maps a
+byte offset in the input to the corresponding offset in the output. For example,
+
+```
+rustfmt --unstable-features --range 11..21 --cursor 17 < src/lib.rs
+```
+
+would print something like
+
+```
+{{\"edits\":[{{\"range\":{{\"start\":11,\"end\":21}},\"new_text\":\"fn b() {{}}\"}}],\"cursor\":16}}
+```
+
+The `cursor` field is `null` when no `--cursor` is given."
+    );
+}
+
 fn print_version() {
     let version_number = option_env!("CARGO_PKG_VERSION").unwrap_or("unknown");
     let commit_info = include_str!(concat!(env!("OUT_DIR"), "/commit-info.txt"));
@@ -475,6 +549,7 @@ fn determine_operation(matches: &Matches) -> Result<Operation, OperationError> {
         return match topic.as_str() {
             "config" => Ok(Operation::Help(HelpOp::Config)),
             "file-lines" if is_nightly() => Ok(Operation::Help(HelpOp::FileLines)),
+            "range" if is_nightly() => Ok(Operation::Help(HelpOp::Range)),
             _ => Err(OperationError::UnknownHelpTopic(topic)),
         };
     }
@@ -522,6 +597,10 @@ fn determine_operation(matches: &Matches) -> Result<Operation, OperationError> {
         return Ok(Operation::Stdin { input: buffer });
     }
 
+    if matches.opt_present("range") {
+        return Err(OperationError::RangeWithoutStdin);
+    }
+
     Ok(Operation::Format {
         files,
         minimal_config_path,
@@ -545,6 +624,8 @@ struct GetOptsOptions {
     style_edition: Option<StyleEdition>,
     color: Option<Color>,
     file_lines: FileLines, // Default is all lines in all files.
+    range: Option<Range<usize>>,
+    cursor: Option<usize>,
     unstable_features: bool,
     error_on_unformatted: Option<bool>,
     print_misformatted_file_names: bool,
@@ -574,6 +655,19 @@ pub fn from_matches(matches: &Matches) -> Result<GetOptsOptions> {
                 if let Some(ref file_lines) = matches.opt_str("file-lines") {
                     options.file_lines = file_lines.parse()?;
                 }
+                if let Some(ref range) = matches.opt_str("range") {
+                    options.range = Some(range_from_range_str(range)?);
+                }
+                if let Some(ref cursor) = matches.opt_str("cursor") {
+                    if options.range.is_none() {
+                        return Err(format_err!("`--cursor` can only be used with `--range`"));
+                    }
+                    options.cursor = Some(
+                        cursor
+                            .parse()
+                            .map_err(|_| format_err!("Invalid value for `--cursor`"))?,
+                    );
+                }
             } else {
                 let mut unstable_options = vec![];
                 if matches.opt_present("skip-children") {
@@ -585,6 +679,12 @@ pub fn from_matches(matches: &Matches) -> Result<GetOptsOptions> {
                 if matches.opt_present("file-lines") {
                     unstable_options.push("`--file-lines`");
                 }
+                if matches.opt_present("range") {
+                    unstable_options.push("`--range`");
+                }
+                if matches.opt_present("cursor") {
+                    unstable_options.push("`--cursor`");
+                }
                 if !unstable_options.is_empty() {
                     let s = if unstable_options.len() == 1 { "" } else { "s" };
                     return Err(format_err!(
@@ -623,11 +723,13 @@ pub fn from_matches(matches: &Matches) -> Result<GetOptsOptions> {
 
         options.check = matches.opt_present("check");
         if let Some(ref emit_str) = matches.opt_str("emit") {
-            if options.check {
+            let emit_mode = emit_mode_from_emit_str(emit_str)?;
+            // Report formats can replace the diff that `--check` prints.
+            if options.check && !matches!(emit_mode, EmitMode::Sarif | EmitMode::CodeClimate) {
                 return Err(format_err!("Invalid to use `--emit` and `--check`"));
             }
 
-            options.emit_mode = Some(emit_mode_from_emit_str(emit_str)?);
+            options.emit_mode = Some(emit_mode);
         }
 
         if let Some(ref edition_str) = matches.opt_str("edition") {
@@ -718,7 +820,9 @@ fn apply_to(self, config: &mut Config) {
             config.set_cli().style_edition(edition);
         }
         if self.check {
-            config.set_cli().emit_mode(EmitMode::Diff);
+            config
+                .set_cli()
+                .emit_mode(self.emit_mode.unwrap_or(EmitMode::Diff));
         } else if let Some(emit_mode) = self.emit_mode {
             config.set_cli().emit_mode(emit_mode);
         }
@@ -782,6 +886,17 @@ fn style_edition_from_style_edition_str(edition_str: &str) -> Result<StyleEditio
     }
 }
 
+fn range_from_range_str(range_str: &str) -> Result<Range<usize>> {
+    let invalid = || format_err!("Invalid value for `--range`");
+    let (start, end) = range_str.split_once("..").ok_or_else(invalid)?;
+    let start = start.parse().map_err(|_| invalid())?;
+    let end = end.parse().map_err(|_| invalid())?;
+    if start > end {
+        return Err(invalid());
+    }
+    Ok(start..end)
+}
+
 fn emit_mode_from_emit_str(emit_str: &str) -> Result<EmitMode> {
     match emit_str {
         "files" => Ok(EmitMode::Files),
@@ -789,6 +904,8 @@ fn emit_mode_from_emit_str(emit_str: &str) -> Result<EmitMode> {
         "coverage" => Ok(EmitMode::Coverage),
         "checkstyle" => Ok(EmitMode::Checkstyle),
         "json" => Ok(EmitMode::Json),
+        "sarif" => Ok(EmitMode::Sarif),
+        "code-climate" => Ok(EmitMode::CodeClimate),
         _ => Err(format_err!("Invalid value for `--emit`")),
     }
 }
@@ -972,4 +1089,13 @@ fn style_edition_defaults_overridden_from_cli() {
         // `overflow_delimited_expr` is disabled by default in edition 2024.
         assert_eq!(config.overflow_delimited_expr(), true);
     }
+
+    #[test]
+    fn range_from_range_str_parses_byte_ranges() {
+        assert_eq!(range_from_range_str("3..17").unwrap(), 3..17);
+        assert_eq!(range_from_range_str("5..5").unwrap(), 5..5);
+        assert!(range_from_range_str("17..3").is_err());
+        assert!(range_from_range_str("3-17").is_err());
+        assert!(range_from_range_str("..17").is_err());
+    }
 }
@@ -70,6 +70,16 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
 #[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 pub struct MacroSelectors(pub Vec<MacroSelector>);
 
+impl MacroSelectors {
+    /// Whether the macro with the given name is selected.
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.0.iter().any(|selector| match selector {
+            MacroSelector::Name(selected) => selected.0 == name,
+            MacroSelector::All => true,
+        })
+    }
+}
+
 impl fmt::Display for MacroSelectors {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0.iter().format(", "))
@@ -120,6 +130,16 @@ fn macro_names_from_str() {
         );
     }
 
+    #[test]
+    fn macro_names_contains() {
+        let macro_names = MacroSelectors::from_str(r#"["foo", "bar"]"#).unwrap();
+        assert!(macro_names.contains("foo"));
+        assert!(!macro_names.contains("baz"));
+        let macro_names = MacroSelectors::from_str(r#"["*"]"#).unwrap();
+        assert!(macro_names.contains("baz"));
+        assert!(!MacroSelectors::default().contains("foo"));
+    }
+
     #[test]
     fn macro_names_display() {
         let macro_names = MacroSelectors::from_str(r#"["foo", "*", "bar"]"#).unwrap();
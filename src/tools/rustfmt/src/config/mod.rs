@@ -81,6 +81,9 @@
         "Format the bodies of declarative macro definitions";
     skip_macro_invocations: SkipMacroInvocations, false,
         "Skip formatting the bodies of macros invoked with the following names.";
+    format_macro_invocations_by_matchers: FormatMacroInvocationsByMatchers, false,
+        "Format the fragments of invocations of the following local macros, \
+         as classified by the matchers of their `macro_rules!` definitions";
     hex_literal_case: HexLiteralCaseConfig, true, "Format hexadecimal integer literals";
     float_literal_trailing_zero: FloatLiteralTrailingZeroConfig, false,
         "Add or remove trailing zero in floating-point literals";
@@ -783,6 +786,7 @@ fn test_dump_default_config() {
 format_macro_matchers = false
 format_macro_bodies = true
 skip_macro_invocations = []
+format_macro_invocations_by_matchers = []
 hex_literal_case = "Preserve"
 float_literal_trailing_zero = "Preserve"
 empty_item_single_line = true
@@ -876,6 +880,7 @@ fn test_dump_style_edition_2024_config() {
 format_macro_matchers = false
 format_macro_bodies = true
 skip_macro_invocations = []
+format_macro_invocations_by_matchers = []
 hex_literal_case = "Preserve"
 float_literal_trailing_zero = "Preserve"
 empty_item_single_line = true
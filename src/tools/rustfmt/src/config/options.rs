@@ -208,6 +208,13 @@ pub enum EmitMode {
     /// Writes the resulting diffs in a JSON format. Returns an empty array
     /// `[]` if there were no diffs.
     Json,
+    /// Writes a SARIF 2.1.0 log with one result per changed part of a file,
+    /// including the formatted text as a fix.
+    Sarif,
+    /// Writes a GitLab code quality report in the Code Climate format, with
+    /// one issue per changed part of a file.
+    #[value = "code-climate"]
+    CodeClimate,
     /// Output the changed lines (for internal value only)
     ModifiedLines,
     /// Checks if a diff can be generated. If so, rustfmt outputs a diff and
@@ -653,6 +660,7 @@ macro_rules! config_option_with_style_edition_default {
     FormatMacroMatchers, bool, _ => false;
     FormatMacroBodies, bool, _ => true;
     SkipMacroInvocations, MacroSelectors, _ => MacroSelectors::default();
+    FormatMacroInvocationsByMatchers, MacroSelectors, _ => MacroSelectors::default();
     HexLiteralCaseConfig, HexLiteralCase, _ => HexLiteralCase::Preserve;
     FloatLiteralTrailingZeroConfig, FloatLiteralTrailingZero, _ =>
         FloatLiteralTrailingZero::Preserve;
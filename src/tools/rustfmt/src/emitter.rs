@@ -1,20 +1,25 @@
 pub(crate) use self::checkstyle::*;
+pub(crate) use self::code_climate::*;
 pub(crate) use self::diff::*;
 pub(crate) use self::files::*;
 pub(crate) use self::files_with_backup::*;
 pub(crate) use self::json::*;
 pub(crate) use self::modified_lines::*;
+pub(crate) use self::sarif::*;
 pub(crate) use self::stdout::*;
 use crate::FileName;
+use crate::format_range::text_edits;
 use std::io::{self, Write};
 use std::path::Path;
 
 mod checkstyle;
+mod code_climate;
 mod diff;
 mod files;
 mod files_with_backup;
 mod json;
 mod modified_lines;
+mod sarif;
 mod stdout;
 
 pub(crate) struct FormattedFile<'a> {
@@ -50,3 +55,37 @@ fn ensure_real_path(filename: &FileName) -> &Path {
         _ => panic!("cannot format `{filename}` and emit to files"),
     }
 }
+
+/// A part of a file that is changed by formatting.
+struct Hunk {
+    /// The 1-based line and column of the start of the hunk in the original text. Columns count
+    /// characters.
+    start: (usize, usize),
+    /// The line and column of the end of the hunk, exclusive.
+    end: (usize, usize),
+    original: String,
+    replacement: String,
+}
+
+/// Splits the changes that formatting makes to a file into hunks.
+fn hunks(original_text: &str, formatted_text: &str) -> Vec<Hunk> {
+    text_edits(original_text, formatted_text)
+        .into_iter()
+        .map(|edit| Hunk {
+            start: line_column(original_text, edit.range.start),
+            end: line_column(original_text, edit.range.end),
+            original: original_text[edit.range].to_owned(),
+            replacement: edit.new_text,
+        })
+        .collect()
+}
+
+/// The 1-based line and character column of the byte at `offset` in `text`.
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
@@ -0,0 +1,172 @@
+use super::*;
+use serde::Serialize;
+use serde_json::to_writer as to_json_writer;
+
+#[derive(Debug, Default)]
+pub(crate) struct CodeClimateEmitter {
+    issues: Vec<Issue>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Issue {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    check_name: &'static str,
+    description: &'static str,
+    content: Content,
+    categories: [&'static str; 1],
+    location: Location,
+    severity: &'static str,
+    fingerprint: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Content {
+    body: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Location {
+    path: String,
+    positions: Positions,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Positions {
+    begin: Position,
+    end: Position,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+impl Emitter for CodeClimateEmitter {
+    fn emit_footer(&self, output: &mut dyn Write) -> Result<(), io::Error> {
+        to_json_writer(&mut *output, &self.issues)?;
+        writeln!(output)
+    }
+
+    fn emit_formatted_file(
+        &mut self,
+        _output: &mut dyn Write,
+        FormattedFile {
+            filename,
+            original_text,
+            formatted_text,
+        }: FormattedFile<'_>,
+    ) -> Result<EmitterResult, io::Error> {
+        let hunks = hunks(original_text, formatted_text);
+        let has_diff = !hunks.is_empty();
+        let path = format!("{filename}");
+        self.issues.extend(hunks.into_iter().map(|hunk| {
+            let body = if hunk.replacement.is_empty() {
+                String::from("Should be removed.")
+            } else {
+                format!("Should be:\n\n```rust\n{}\n```", hunk.replacement)
+            };
+            Issue {
+                kind: "issue",
+                check_name: "rustfmt",
+                description: "Code is not formatted",
+                content: Content { body },
+                categories: ["Style"],
+                fingerprint: fingerprint(&path, &hunk),
+                location: Location {
+                    path: path.clone(),
+                    positions: Positions {
+                        begin: Position {
+                            line: hunk.start.0,
+                            column: hunk.start.1,
+                        },
+                        end: Position {
+                            line: hunk.end.0,
+                            column: hunk.end.1,
+                        },
+                    },
+                },
+                severity: "minor",
+            }
+        }));
+        Ok(EmitterResult { has_diff })
+    }
+}
+
+/// Identifies an issue across runs, so that the report can tell new issues from fixed ones.
+///
+/// This is a 64-bit FNV-1a hash, which unlike the hashers in `std` is guaranteed to stay the same
+/// between Rust releases.
+fn fingerprint(path: &str, hunk: &Hunk) -> String {
+    let line = hunk.start.0.to_string();
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in [path, &line, &hunk.original, &hunk.replacement] {
+        // Terminate every part, so that moving text from one part to the next changes the hash.
+        for byte in part.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn emits_one_issue_per_hunk() {
+        let mut emitter = CodeClimateEmitter::default();
+        let mut writer = Vec::new();
+        let result = emitter
+            .emit_formatted_file(
+                &mut writer,
+                FormattedFile {
+                    filename: &FileName::Real(PathBuf::from("src/lib.rs")),
+                    original_text: "fn a( ) {}\nfn b() {}\n\n\nfn c() {}\n",
+                    formatted_text: "fn a() {}\nfn b() {}\n\nfn c() {}\n",
+                },
+            )
+            .unwrap();
+        assert!(result.has_diff);
+        assert!(writer.is_empty());
+        assert_eq!(emitter.issues.len(), 2);
+
+        let first = &emitter.issues[0];
+        assert_eq!(first.location.path, "src/lib.rs");
+        assert_eq!(
+            first.location.positions,
+            Positions {
+                begin: Position { line: 1, column: 1 },
+                end: Position {
+                    line: 1,
+                    column: 11
+                },
+            }
+        );
+        assert_eq!(first.content.body, "Should be:\n\n```rust\nfn a() {}\n```");
+        assert_eq!(emitter.issues[1].content.body, "Should be removed.");
+        assert_ne!(first.fingerprint, emitter.issues[1].fingerprint);
+    }
+
+    #[test]
+    fn emits_empty_array_on_no_diffs() {
+        let mut emitter = CodeClimateEmitter::default();
+        let mut writer = Vec::new();
+        let result = emitter
+            .emit_formatted_file(
+                &mut writer,
+                FormattedFile {
+                    filename: &FileName::Real(PathBuf::from("src/lib.rs")),
+                    original_text: "fn empty() {}\n",
+                    formatted_text: "fn empty() {}\n",
+                },
+            )
+            .unwrap();
+        let _ = emitter.emit_footer(&mut writer);
+        assert!(!result.has_diff);
+        assert_eq!(&writer[..], "[]\n".as_bytes());
+    }
+}
@@ -0,0 +1,254 @@
+use super::*;
+use serde::Serialize;
+use serde_json::to_writer as to_json_writer;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const RULE_ID: &str = "rustfmt";
+
+#[derive(Debug, Default)]
+pub(crate) struct SarifEmitter {
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifLog<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: [Run<'a>; 1],
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool,
+    column_kind: &'static str,
+    results: &'a [SarifResult],
+}
+
+#[derive(Debug, Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: [Rule; 1],
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: &'static str,
+    short_description: Message,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: &'static str,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    fixes: Vec<Fix>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: Content,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Content {
+    text: String,
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_footer(&self, output: &mut dyn Write) -> Result<(), io::Error> {
+        let log = SarifLog {
+            schema: SARIF_SCHEMA,
+            version: "2.1.0",
+            runs: [Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: "rustfmt",
+                        information_uri: "https://github.com/rust-lang/rustfmt",
+                        rules: [Rule {
+                            id: RULE_ID,
+                            short_description: Message {
+                                text: String::from("Code is not formatted"),
+                            },
+                        }],
+                    },
+                },
+                column_kind: "unicodeCodePoints",
+                results: &self.results,
+            }],
+        };
+        to_json_writer(&mut *output, &log)?;
+        writeln!(output)
+    }
+
+    fn emit_formatted_file(
+        &mut self,
+        _output: &mut dyn Write,
+        FormattedFile {
+            filename,
+            original_text,
+            formatted_text,
+        }: FormattedFile<'_>,
+    ) -> Result<EmitterResult, io::Error> {
+        let hunks = hunks(original_text, formatted_text);
+        let has_diff = !hunks.is_empty();
+        let artifact_location = ArtifactLocation {
+            uri: format!("{filename}"),
+        };
+        self.results.extend(hunks.into_iter().map(|hunk| {
+            let region = Region {
+                start_line: hunk.start.0,
+                start_column: hunk.start.1,
+                end_line: hunk.end.0,
+                end_column: hunk.end.1,
+            };
+            SarifResult {
+                rule_id: RULE_ID,
+                level: "warning",
+                message: Message {
+                    text: String::from("Code is not formatted"),
+                },
+                locations: vec![Location {
+                    physical_location: PhysicalLocation {
+                        artifact_location: artifact_location.clone(),
+                        region,
+                    },
+                }],
+                fixes: vec![Fix {
+                    description: Message {
+                        text: String::from("Format the code"),
+                    },
+                    artifact_changes: vec![ArtifactChange {
+                        artifact_location: artifact_location.clone(),
+                        replacements: vec![Replacement {
+                            deleted_region: region,
+                            inserted_content: Content {
+                                text: hunk.replacement,
+                            },
+                        }],
+                    }],
+                }],
+            }
+        }));
+        Ok(EmitterResult { has_diff })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn emits_one_result_per_hunk() {
+        let mut emitter = SarifEmitter::default();
+        let mut writer = Vec::new();
+        let result = emitter
+            .emit_formatted_file(
+                &mut writer,
+                FormattedFile {
+                    filename: &FileName::Real(PathBuf::from("src/lib.rs")),
+                    original_text: "fn a( ) {}\nfn b() {}\nfn c( ) {}\n",
+                    formatted_text: "fn a() {}\nfn b() {}\nfn c() {}\n",
+                },
+            )
+            .unwrap();
+        assert!(result.has_diff);
+        assert!(writer.is_empty());
+        assert_eq!(emitter.results.len(), 2);
+
+        let region = Region {
+            start_line: 3,
+            start_column: 1,
+            end_line: 3,
+            end_column: 11,
+        };
+        assert_eq!(
+            emitter.results[1].locations[0].physical_location.region,
+            region
+        );
+        let replacement = &emitter.results[1].fixes[0].artifact_changes[0].replacements[0];
+        assert_eq!(replacement.deleted_region, region);
+        assert_eq!(replacement.inserted_content.text, "fn c() {}");
+    }
+
+    #[test]
+    fn emits_empty_log_without_diff() {
+        let mut emitter = SarifEmitter::default();
+        let mut writer = Vec::new();
+        let result = emitter
+            .emit_formatted_file(
+                &mut writer,
+                FormattedFile {
+                    filename: &FileName::Real(PathBuf::from("src/lib.rs")),
+                    original_text: "fn a() {}\n",
+                    formatted_text: "fn a() {}\n",
+                },
+            )
+            .unwrap();
+        let _ = emitter.emit_footer(&mut writer);
+        assert!(!result.has_diff);
+        let log: serde_json::Value = serde_json::from_slice(&writer).unwrap();
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(log["runs"][0]["results"], serde_json::json!([]));
+    }
+}
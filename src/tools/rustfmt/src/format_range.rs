@@ -0,0 +1,303 @@
+//! Formatting a part of a source text, for editors.
+//!
+//! Instead of the whole formatted text, `format_range` returns the edits that turn the input into
+//! the formatted text, together with the position the editor's cursor should move to.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::config::{self, Config, FileLines, FileName, Verbosity};
+use crate::rustfmt_diff::{ModifiedChunk, ModifiedLines, make_diff};
+use crate::{ErrorKind, Input, Session};
+
+/// A replacement of a part of the input.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct TextEdit {
+    /// The byte range in the input that is replaced.
+    pub range: Range<usize>,
+    /// The text the range is replaced with.
+    pub new_text: String,
+}
+
+/// The result of formatting a range of a source text.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct FormattedRange {
+    /// The edits to apply to the input, sorted by position and non-overlapping.
+    pub edits: Vec<TextEdit>,
+    /// The byte offset in the formatted text that corresponds to the cursor in the input.
+    pub cursor: Option<usize>,
+}
+
+/// Formats the lines of `input` that overlap the byte range `range`, and maps the byte offset
+/// `cursor` in the input to the corresponding offset in the output.
+///
+/// Only whole items and statements are formatted, so the edits may extend beyond `range`.
+pub fn format_range(
+    input: &str,
+    range: Range<usize>,
+    cursor: Option<usize>,
+    config: &Config,
+) -> Result<FormattedRange, ErrorKind> {
+    for offset in [range.start, range.end].into_iter().chain(cursor) {
+        if !input.is_char_boundary(offset) {
+            return Err(ErrorKind::InvalidOffset(offset));
+        }
+    }
+    if range.start > range.end {
+        return Err(ErrorKind::InvalidOffset(range.start));
+    }
+
+    let mut config = config.clone();
+    if config.disable_all_formatting() {
+        return Ok(FormattedRange {
+            edits: Vec::new(),
+            cursor,
+        });
+    }
+    let lo = line_of(input, range.start);
+    let hi = line_of(input, range.end.saturating_sub(1).max(range.start));
+    let ranges = HashMap::from([(FileName::Stdin, vec![config::Range::new(lo, hi)])]);
+    config.set().file_lines(FileLines::from_ranges(ranges));
+    config.set().emit_mode(config::EmitMode::Stdout);
+    config.set().verbose(Verbosity::Quiet);
+
+    let mut out: Vec<u8> = Vec::with_capacity(input.len());
+    {
+        let mut session = Session::new(config, Some(&mut out));
+        session.format(Input::Text(input.to_owned()))?;
+        if session.has_parsing_errors() {
+            return Err(ErrorKind::ParseError);
+        }
+    }
+    let formatted = String::from_utf8(out).map_err(|_| ErrorKind::ParseError)?;
+    // Nothing is emitted when the whole input is skipped.
+    if formatted.is_empty() && !input.is_empty() {
+        return Ok(FormattedRange {
+            edits: Vec::new(),
+            cursor,
+        });
+    }
+
+    let edits = text_edits(input, &formatted);
+    let cursor = cursor.map(|cursor| map_offset(input, &edits, cursor));
+    Ok(FormattedRange { edits, cursor })
+}
+
+/// The 1-based number of the line that contains the byte at `offset`.
+fn line_of(input: &str, offset: usize) -> usize {
+    input.as_bytes()[..offset]
+        .iter()
+        .filter(|&&b| b == b'\n')
+        .count()
+        + 1
+}
+
+/// Computes the edits that turn `original` into `formatted`.
+pub(crate) fn text_edits(original: &str, formatted: &str) -> Vec<TextEdit> {
+    // The lines of the input as the diff sees them: every `\n` starts a new line, but there is no
+    // line at all in an empty input.
+    let mut lines: Vec<Range<usize>> = Vec::new();
+    if !original.is_empty() {
+        let mut start = 0;
+        for line in original.split('\n') {
+            let end = start + line.len();
+            let content_end = if end < original.len() {
+                end - usize::from(line.ends_with('\r'))
+            } else {
+                end
+            };
+            lines.push(start..content_end);
+            start = end + 1;
+        }
+    }
+    let newline = if formatted.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+
+    let diff = ModifiedLines::from(make_diff(original, formatted, 0));
+    diff.chunks
+        .into_iter()
+        .map(|chunk| {
+            let ModifiedChunk {
+                line_number_orig,
+                lines_removed,
+                lines: new_lines,
+            } = chunk;
+            let first = line_number_orig as usize - 1;
+            let removed = lines_removed as usize;
+            let new_text = new_lines.join(newline);
+            if removed > 0 && !new_lines.is_empty() {
+                let range = lines[first].start..lines[first + removed - 1].end;
+                TextEdit { range, new_text }
+            } else if removed > 0 {
+                // Remove the lines together with their line breaks. The last line has no line
+                // break, so when it is removed, the break before it goes instead.
+                let range = if let Some(next) = lines.get(first + removed) {
+                    lines[first].start..next.start
+                } else if first > 0 {
+                    lines[first - 1].end..original.len()
+                } else {
+                    0..original.len()
+                };
+                TextEdit {
+                    range,
+                    new_text: String::new(),
+                }
+            } else if let Some(next) = lines.get(first) {
+                TextEdit {
+                    range: next.start..next.start,
+                    new_text: new_text + newline,
+                }
+            } else if lines.is_empty() {
+                TextEdit {
+                    range: 0..0,
+                    new_text,
+                }
+            } else {
+                TextEdit {
+                    range: original.len()..original.len(),
+                    new_text: newline.to_owned() + &new_text,
+                }
+            }
+        })
+        .collect()
+}
+
+/// Maps the byte offset `offset` in `original` to the text that results from applying `edits`.
+///
+/// An offset inside a replaced range stays behind the same number of non-whitespace characters
+/// of the replaced text, which is what formatting preserves.
+fn map_offset(original: &str, edits: &[TextEdit], offset: usize) -> usize {
+    let mut delta: isize = 0;
+    for edit in edits {
+        if edit.range.start > offset {
+            break;
+        }
+        if edit.range.end <= offset {
+            delta += edit.new_text.len() as isize - edit.range.len() as isize;
+            continue;
+        }
+        let preceding = original[edit.range.start..offset]
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .count();
+        let inner = match preceding.checked_sub(1) {
+            None => 0,
+            Some(n) => edit
+                .new_text
+                .char_indices()
+                .filter(|(_, c)| !c.is_whitespace())
+                .nth(n)
+                .map_or(edit.new_text.len(), |(idx, c)| idx + c.len_utf8()),
+        };
+        return (edit.range.start as isize + delta) as usize + inner;
+    }
+    (offset as isize + delta) as usize
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn apply(original: &str, edits: &[TextEdit]) -> String {
+        let mut result = original.to_owned();
+        for edit in edits.iter().rev() {
+            result.replace_range(edit.range.clone(), &edit.new_text);
+        }
+        result
+    }
+
+    #[test]
+    fn text_edits_reproduce_formatted_text() {
+        let cases = [
+            ("", "fn main() {}\n"),
+            ("a", "a\n"),
+            ("a\n", "a"),
+            ("a\n\n", "a\n"),
+            ("a\nb\nc\n", "a\nc\n"),
+            ("a\nc\n", "a\nb\nc\n"),
+            ("a\nb\nc", "a\nB\nc\nd\n"),
+            ("a\r\nb\r\n", "a\r\nB\r\n"),
+            ("x\ny\n", ""),
+        ];
+        for (original, formatted) in cases {
+            let edits = text_edits(original, formatted);
+            assert_eq!(
+                apply(original, &edits),
+                formatted,
+                "{original:?} -> {formatted:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn text_edits_are_minimal() {
+        let edits = text_edits("a\nb\nc\n", "a\nB\nc\n");
+        assert_eq!(
+            edits,
+            vec![TextEdit {
+                range: 2..3,
+                new_text: "B".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn map_offset_across_edits() {
+        let original = "a\nfn  f( x:u8 ){}\nb\n";
+        let edits = vec![TextEdit {
+            range: 2..17,
+            new_text: "fn f(x: u8) {}\n// new".to_owned(),
+        }];
+        // Before the edit.
+        assert_eq!(map_offset(original, &edits, 1), 1);
+        // At the start of the edit.
+        assert_eq!(map_offset(original, &edits, 2), 2);
+        // Inside the edit, in front of `x`, with or without the whitespace before it.
+        assert_eq!(map_offset(original, &edits, 8), 7);
+        assert_eq!(map_offset(original, &edits, 9), 7);
+        // At the end of the edit, and after it.
+        assert_eq!(map_offset(original, &edits, 17), 23);
+        assert_eq!(map_offset(original, &edits, 18), 24);
+    }
+
+    #[test]
+    fn format_range_only_touches_the_selection() {
+        let input = "fn a( ) {}\nfn b( ) {}\nfn c( ) {}\n";
+        let start = input.find("fn b").unwrap();
+        let cursor = input.find(") {}\nfn c").unwrap();
+        let result = format_range(input, start..start + 4, Some(cursor), &Config::default());
+        let result = result.unwrap();
+        assert_eq!(
+            result.edits,
+            vec![TextEdit {
+                range: 11..21,
+                new_text: "fn b() {}".to_owned(),
+            }]
+        );
+        let output = apply(input, &result.edits);
+        assert_eq!(output, "fn a( ) {}\nfn b() {}\nfn c( ) {}\n");
+        assert_eq!(result.cursor, Some(16));
+    }
+
+    #[test]
+    fn format_range_rejects_bad_offsets() {
+        let config = Config::default();
+        assert!(matches!(
+            format_range("fn main() {}\n", 3..40, None, &config),
+            Err(ErrorKind::InvalidOffset(40))
+        ));
+        assert!(matches!(
+            format_range("// é\n", 0..4, None, &config),
+            Err(ErrorKind::InvalidOffset(4))
+        ));
+        assert!(matches!(
+            format_range("fn main() {}\n", 0..3, Some(20), &config),
+            Err(ErrorKind::InvalidOffset(20))
+        ));
+    }
+}
@@ -114,6 +114,7 @@ fn error_kind_to_snippet_annotation_level(error_kind: &ErrorKind) -> Level {
         | ErrorKind::LostComment
         | ErrorKind::BadAttr
         | ErrorKind::InvalidGlobPattern(_)
+        | ErrorKind::InvalidOffset(_)
         | ErrorKind::VersionMismatch => Level::Error,
         ErrorKind::DeprecatedAttr => Level::Warning,
     }
@@ -49,6 +49,8 @@
     StyleEdition, Verbosity, Version, load_config,
 };
 
+pub use crate::format_range::{FormattedRange, TextEdit, format_range};
+
 pub use crate::format_report_formatter::{FormatReportFormatter, FormatReportFormatterBuilder};
 
 pub use crate::rustfmt_diff::{ModifiedChunk, ModifiedLines};
@@ -71,6 +73,7 @@ macro_rules! static_regex {
 mod coverage;
 mod emitter;
 mod expr;
+mod format_range;
 mod format_report_formatter;
 pub(crate) mod formatting;
 pub(crate) mod header;
@@ -143,6 +146,9 @@ pub enum ErrorKind {
     /// Invalid glob pattern in `ignore` configuration option.
     #[error("Invalid glob pattern found in ignore list: {0}")]
     InvalidGlobPattern(ignore::Error),
+    /// A byte offset passed to `format_range` is not a character boundary of the input.
+    #[error("invalid byte offset {0} in the input")]
+    InvalidOffset(usize),
 }
 
 impl ErrorKind {
@@ -530,6 +536,8 @@ pub(crate) fn create_emitter<'a>(config: &Config) -> Box<dyn Emitter + 'a> {
             Box::new(emitter::StdoutEmitter::new(config.verbose()))
         }
         EmitMode::Json => Box::new(emitter::JsonEmitter::default()),
+        EmitMode::Sarif => Box::new(emitter::SarifEmitter::default()),
+        EmitMode::CodeClimate => Box::new(emitter::CodeClimateEmitter::default()),
         EmitMode::ModifiedLines => Box::new(emitter::ModifiedLinesEmitter::default()),
         EmitMode::Checkstyle => Box::new(emitter::CheckstyleEmitter::default()),
         EmitMode::Diff => Box::new(emitter::DiffEmitter::new(config.clone())),
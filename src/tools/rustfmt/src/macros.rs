@@ -10,8 +10,10 @@
 // and those with brackets will be formatted as array literals.
 
 use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::rc::Rc;
 
 use rustc_ast::ast;
 use rustc_ast::token::{Delimiter, Token, TokenKind};
@@ -31,6 +33,7 @@
 use crate::overflow;
 use crate::parse::macros::cfg_select::{CfgSelectFormatPredicate, parse_cfg_select_arms};
 use crate::parse::macros::lazy_static::parse_lazy_static;
+use crate::parse::macros::matchers::match_macro_rules_arm;
 use crate::parse::macros::{ParsedMacroArgs, parse_expr, parse_macro_args};
 use crate::rewrite::{
     MacroErrorKind, Rewrite, RewriteContext, RewriteError, RewriteErrorExt, RewriteResult,
@@ -39,13 +42,16 @@
 use crate::source_map::SpanUtils;
 use crate::spanned::Spanned;
 use crate::utils::{
-    NodeIdExt, filtered_str_fits, indent_next_line, is_empty_line, mk_sp,
-    remove_trailing_white_spaces, rewrite_ident, trim_left_preserve_layout,
+    NodeIdExt, filtered_str_fits, get_prefix_space_width, indent_next_line, is_empty_line, mk_sp,
+    remove_trailing_white_spaces, rewrite_ident, trim_left_preserve_layout, unicode_str_width,
 };
 use crate::visitor::FmtVisitor;
 
 const FORCED_BRACKET_MACROS: &[&str] = &["vec!"];
 
+/// The bodies of the `macro_rules!` definitions in a file, by macro name.
+pub(crate) type MacroRulesDefs = Rc<RefCell<HashMap<String, TokenStream>>>;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum MacroPosition {
     Item,
@@ -261,6 +267,27 @@ fn rewrite_macro_inner(
         }
     }
 
+    let name = macro_name.trim_end_matches('!');
+    if context
+        .config
+        .format_macro_invocations_by_matchers()
+        .contains(name)
+    {
+        let def = context.macro_rules_defs.borrow().get(name).cloned();
+        if let Some(def) = def {
+            match format_macro_by_matchers(context, mac, &def, shape, position, original_style) {
+                Ok(rw) => return Ok(rw),
+                Err(err) => match err {
+                    // We will move on to parsing macro args just like other macros
+                    // if the invocation matches none of the arms
+                    RewriteError::MacroFailure { kind, span: _ }
+                        if kind == MacroErrorKind::ParseFailure => {}
+                    other => return Err(other),
+                },
+            }
+        }
+    }
+
     // If we're falling through to default macro handling check that the context is correct
     debug_assert!(
         context.inside_macro(),
@@ -373,6 +400,79 @@ fn rewrite_macro_inner(
     }
 }
 
+/// Formats an invocation of a local `macro_rules!` macro by matching it against the arms of the
+/// macro. The fragments that the matching arm captures as expressions, types, patterns, blocks or
+/// items are formatted where they are, and all other tokens are kept as written.
+fn format_macro_by_matchers(
+    context: &RewriteContext<'_>,
+    mac: &ast::MacCall,
+    def: &TokenStream,
+    shape: Shape,
+    position: MacroPosition,
+    style: Delimiter,
+) -> RewriteResult {
+    let span = mac.span();
+    let parse_failure = || RewriteError::MacroFailure {
+        kind: MacroErrorKind::ParseFailure,
+        span,
+    };
+    let parsed_def = MacroParser::new(def.iter())
+        .parse()
+        .ok_or_else(parse_failure)?;
+    let fragments = parsed_def
+        .branches
+        .iter()
+        .find_map(|branch| match branch.args.iter().next()? {
+            TokenTree::Delimited(.., matcher) => {
+                match_macro_rules_arm(context, matcher, mac.args.tokens.clone())
+            }
+            TokenTree::Token(..) => None,
+        })
+        .ok_or_else(parse_failure)?;
+
+    // The fragments are formatted at their position in the original source, so we need the
+    // text that precedes the invocation on its first line.
+    let snippet_provider = context.snippet_provider;
+    let offset = span.lo().to_usize() - snippet_provider.start_pos().to_usize();
+    let line_prefix = snippet_provider.entire_snippet()[..offset]
+        .rsplit('\n')
+        .next()
+        .unwrap_or("");
+
+    let mut result = String::with_capacity(context.snippet(span).len());
+    let mut last_pos = span.lo();
+    for (fragment_span, arg) in &fragments {
+        if fragment_span.lo() < last_pos || fragment_span.hi() > span.hi() {
+            return Err(parse_failure());
+        }
+        result.push_str(context.snippet(mk_sp(last_pos, fragment_span.lo())));
+        last_pos = fragment_span.hi();
+
+        let line = match result.rfind('\n') {
+            Some(pos) => Cow::from(&result[pos + 1..]),
+            None => Cow::from(format!("{line_prefix}{result}")),
+        };
+        let indent_width = get_prefix_space_width(context.config, &line);
+        let fragment_shape = Shape::indented(
+            Indent::from_width(context.config, indent_width),
+            context.config,
+        )
+        .offset_left_opt(unicode_str_width(&line).saturating_sub(indent_width));
+        match fragment_shape.and_then(|fragment_shape| arg.rewrite(context, fragment_shape)) {
+            Some(rewrite) if !rewrite.trim().is_empty() => result.push_str(rewrite.trim()),
+            _ => result.push_str(context.snippet(*fragment_span)),
+        }
+    }
+    result.push_str(context.snippet(mk_sp(last_pos, span.hi())));
+
+    let mut result =
+        trim_left_preserve_layout(&result, shape.indent, context.config).unwrap_or(result);
+    if position == MacroPosition::Item && style != Delimiter::Brace {
+        result.push(';');
+    }
+    Ok(result)
+}
+
 fn handle_vec_semi(
     context: &RewriteContext<'_>,
     shape: Shape,
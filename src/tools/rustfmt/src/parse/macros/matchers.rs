@@ -0,0 +1,233 @@
+use rustc_ast::ast;
+use rustc_ast::token::{Delimiter, NonterminalKind, Token, TokenKind};
+use rustc_ast::tokenstream::{TokenStream, TokenTree};
+use rustc_parse::parser::{ParseNtResult, Parser};
+use rustc_span::Span;
+use rustc_span::edition::Edition;
+
+use crate::macros::MacroArg;
+use crate::parse::macros::build_parser;
+use crate::rewrite::RewriteContext;
+use crate::utils::mk_sp;
+
+/// A part of the matcher of a `macro_rules!` arm.
+#[derive(Debug)]
+enum Matcher {
+    /// A token that has to appear as is.
+    Token(TokenKind),
+    /// Matchers enclosed in delimiters.
+    Delimited(Delimiter, Vec<Matcher>),
+    /// A metavariable such as `$e:expr`.
+    MetaVar(NonterminalKind),
+    /// A repetition such as `$($e:expr),*`.
+    Repeat {
+        matchers: Vec<Matcher>,
+        separator: Option<TokenKind>,
+        op: RepeatOp,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RepeatOp {
+    ZeroOrMore,
+    OneOrMore,
+    ZeroOrOne,
+}
+
+fn repeat_op(kind: &TokenKind) -> Option<RepeatOp> {
+    match kind {
+        TokenKind::Star => Some(RepeatOp::ZeroOrMore),
+        TokenKind::Plus => Some(RepeatOp::OneOrMore),
+        TokenKind::Question => Some(RepeatOp::ZeroOrOne),
+        _ => None,
+    }
+}
+
+fn parse_matchers(tokens: &TokenStream, edition: Edition) -> Option<Vec<Matcher>> {
+    let mut matchers = vec![];
+    let mut iter = tokens.iter();
+    while let Some(tt) = iter.next() {
+        let matcher = match tt {
+            TokenTree::Token(
+                Token {
+                    kind: TokenKind::Dollar,
+                    ..
+                },
+                _,
+            ) => match iter.next()? {
+                // `$name:kind`
+                TokenTree::Token(name, _) if name.ident().is_some() => {
+                    match iter.next()? {
+                        TokenTree::Token(
+                            Token {
+                                kind: TokenKind::Colon,
+                                ..
+                            },
+                            _,
+                        ) => {}
+                        _ => return None,
+                    }
+                    let (kind, _) = match iter.next()? {
+                        TokenTree::Token(kind, _) => kind.ident()?,
+                        TokenTree::Delimited(..) => return None,
+                    };
+                    Matcher::MetaVar(NonterminalKind::from_symbol(kind.name, || edition)?)
+                }
+                // `$(...) sep op`
+                TokenTree::Delimited(_, _, Delimiter::Parenthesis, inner) => {
+                    let matchers = parse_matchers(inner, edition)?;
+                    let token = match iter.next()? {
+                        TokenTree::Token(token, _) => token,
+                        TokenTree::Delimited(..) => return None,
+                    };
+                    let (separator, op) = match repeat_op(&token.kind) {
+                        Some(op) => (None, op),
+                        None => match iter.next()? {
+                            TokenTree::Token(op, _) => {
+                                (Some(token.kind.clone()), repeat_op(&op.kind)?)
+                            }
+                            TokenTree::Delimited(..) => return None,
+                        },
+                    };
+                    Matcher::Repeat {
+                        matchers,
+                        separator,
+                        op,
+                    }
+                }
+                _ => return None,
+            },
+            TokenTree::Token(token, _) => Matcher::Token(token.kind.clone()),
+            TokenTree::Delimited(_, _, delim, inner) => {
+                Matcher::Delimited(*delim, parse_matchers(inner, edition)?)
+            }
+        };
+        matchers.push(matcher);
+    }
+    Some(matchers)
+}
+
+fn eat(parser: &mut Parser<'_>, kind: &TokenKind) -> bool {
+    if parser.token.kind == *kind {
+        parser.bump();
+        true
+    } else {
+        false
+    }
+}
+
+fn match_matchers(
+    parser: &mut Parser<'_>,
+    matchers: &[Matcher],
+    fragments: &mut Vec<(Span, MacroArg)>,
+) -> bool {
+    matchers
+        .iter()
+        .all(|matcher| match_matcher(parser, matcher, fragments))
+}
+
+fn match_matcher(
+    parser: &mut Parser<'_>,
+    matcher: &Matcher,
+    fragments: &mut Vec<(Span, MacroArg)>,
+) -> bool {
+    match matcher {
+        Matcher::Token(kind) => eat(parser, kind),
+        Matcher::Delimited(delim, matchers) => {
+            eat(parser, &delim.as_open_token_kind())
+                && match_matchers(parser, matchers, fragments)
+                && eat(parser, &delim.as_close_token_kind())
+        }
+        Matcher::MetaVar(kind) => match_fragment(parser, *kind, fragments),
+        Matcher::Repeat {
+            matchers,
+            separator,
+            op,
+        } => {
+            // Repetitions are matched greedily, which is what `macro_rules!` does for all
+            // unambiguous matchers.
+            let mut count = 0;
+            loop {
+                let mut repeat_parser = parser.clone();
+                let fragment_count = fragments.len();
+                let matched = (count == 0
+                    || separator
+                        .as_ref()
+                        .map_or(true, |separator| eat(&mut repeat_parser, separator)))
+                    && match_matchers(&mut repeat_parser, matchers, fragments)
+                    && repeat_parser.token.span != parser.token.span;
+                if !matched {
+                    fragments.truncate(fragment_count);
+                    break;
+                }
+                *parser = repeat_parser;
+                count += 1;
+                if *op == RepeatOp::ZeroOrOne {
+                    break;
+                }
+            }
+            count > 0 || *op != RepeatOp::OneOrMore
+        }
+    }
+}
+
+fn match_fragment(
+    parser: &mut Parser<'_>,
+    kind: NonterminalKind,
+    fragments: &mut Vec<(Span, MacroArg)>,
+) -> bool {
+    if !Parser::nonterminal_may_begin_with(kind, &parser.token) {
+        return false;
+    }
+    let lo = parser.token.span.lo();
+    let result = match parser.parse_nonterminal(kind) {
+        Ok(result) => {
+            if parser.psess.dcx().has_errors().is_some() {
+                parser.psess.dcx().reset_err_count();
+                return false;
+            }
+            result
+        }
+        Err(e) => {
+            e.cancel();
+            parser.psess.dcx().reset_err_count();
+            return false;
+        }
+    };
+    let arg = match result {
+        ParseNtResult::Expr(expr, _) => MacroArg::Expr(expr),
+        ParseNtResult::Ty(ty) => MacroArg::Ty(ty.node),
+        ParseNtResult::Pat(pat, _) => MacroArg::Pat(pat.node),
+        ParseNtResult::Item(item) => MacroArg::Item(item),
+        ParseNtResult::Block(block) => MacroArg::Expr(Box::new(ast::Expr {
+            id: ast::NodeId::root(), // dummy value
+            span: block.node.span,
+            kind: ast::ExprKind::Block(block.node, None),
+            attrs: ast::AttrVec::new(),
+            tokens: None,
+        })),
+        // Other fragments, like identifiers or token trees, are kept as they are.
+        _ => return true,
+    };
+    fragments.push((mk_sp(lo, parser.prev_token.span.hi()), arg));
+    true
+}
+
+/// Matches the arguments of a macro invocation against the matcher of a `macro_rules!` arm
+/// (without its delimiters). On success, returns the spans of the fragments matched by
+/// metavariables that rustfmt can format, together with their parsed contents.
+pub(crate) fn match_macro_rules_arm(
+    context: &RewriteContext<'_>,
+    matcher: &TokenStream,
+    tokens: TokenStream,
+) -> Option<Vec<(Span, MacroArg)>> {
+    let matchers = parse_matchers(matcher, context.config.edition().into())?;
+    let mut parser = build_parser(context, tokens);
+    let mut fragments = vec![];
+    if match_matchers(&mut parser, &matchers, &mut fragments) && parser.token.kind == TokenKind::Eof
+    {
+        Some(fragments)
+    } else {
+        None
+    }
+}
@@ -12,6 +12,7 @@
 pub(crate) mod cfg_if;
 pub(crate) mod cfg_select;
 pub(crate) mod lazy_static;
+pub(crate) mod matchers;
 
 fn build_stream_parser<'a>(psess: &'a ParseSess, tokens: TokenStream) -> Parser<'a> {
     Parser::new(psess, tokens, MACRO_ARGUMENTS).recovery(Recovery::Forbidden)
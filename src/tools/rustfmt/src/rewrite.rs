@@ -8,6 +8,7 @@
 
 use crate::FormatReport;
 use crate::config::{Config, IndentStyle};
+use crate::macros::MacroRulesDefs;
 use crate::parse::session::ParseSess;
 use crate::shape::Shape;
 use crate::skip::SkipContext;
@@ -125,6 +126,7 @@ pub(crate) struct RewriteContext<'a> {
     pub(crate) report: FormatReport,
     pub(crate) skip_context: SkipContext,
     pub(crate) skipped_range: Rc<RefCell<Vec<(usize, usize)>>>,
+    pub(crate) macro_rules_defs: MacroRulesDefs,
 }
 
 pub(crate) struct InsideMacroGuard {
@@ -642,6 +642,30 @@ fn stdin_works_with_checkstyle() {
     );
 }
 
+/// Ensures that `EmitMode::Sarif` works with input from `stdin`.
+#[test]
+fn stdin_works_with_sarif() {
+    init_log();
+    assert_stdin_output(
+        Path::new("tests/writemode/source/stdin.rs"),
+        Path::new("tests/writemode/target/stdin.sarif"),
+        EmitMode::Sarif,
+        true,
+    );
+}
+
+/// Ensures that `EmitMode::CodeClimate` works with input from `stdin`.
+#[test]
+fn stdin_works_with_code_climate() {
+    init_log();
+    assert_stdin_output(
+        Path::new("tests/writemode/source/stdin.rs"),
+        Path::new("tests/writemode/target/stdin_code_climate.json"),
+        EmitMode::CodeClimate,
+        true,
+    );
+}
+
 #[test]
 fn stdin_disable_all_formatting_test() {
     init_log();
@@ -735,7 +735,7 @@ pub(crate) fn is_empty_line(s: &str) -> bool {
     s.is_empty() || s.chars().all(char::is_whitespace)
 }
 
-fn get_prefix_space_width(config: &Config, s: &str) -> usize {
+pub(crate) fn get_prefix_space_width(config: &Config, s: &str) -> usize {
     let mut width = 0;
     for c in s.chars() {
         match c {
@@ -2,7 +2,7 @@
 use std::rc::Rc;
 use std::sync::Arc;
 
-use rustc_ast::{ast, token::Delimiter, visit};
+use rustc_ast::{ast, attr, token::Delimiter, visit};
 use rustc_span::{BytePos, Ident, Pos, Span, symbol};
 use tracing::debug;
 
@@ -16,7 +16,7 @@
     FnBraceStyle, FnSig, ItemVisitorKind, StaticParts, StructParts, format_impl, format_trait,
     format_trait_alias, is_mod_decl, is_use_item, rewrite_extern_crate, rewrite_type_alias,
 };
-use crate::macros::{MacroPosition, macro_style, rewrite_macro, rewrite_macro_def};
+use crate::macros::{MacroPosition, MacroRulesDefs, macro_style, rewrite_macro, rewrite_macro_def};
 use crate::modules::Module;
 use crate::parse::session::ParseSess;
 use crate::rewrite::{Rewrite, RewriteContext};
@@ -90,6 +90,8 @@ pub(crate) struct FmtVisitor<'a> {
     pub(crate) report: FormatReport,
     pub(crate) skip_context: SkipContext,
     pub(crate) is_macro_def: bool,
+    /// The `macro_rules!` definitions visited so far, by name.
+    pub(crate) macro_rules_defs: MacroRulesDefs,
 }
 
 impl<'a> Drop for FmtVisitor<'a> {
@@ -440,6 +442,15 @@ pub(crate) fn visit_fn(
     }
 
     pub(crate) fn visit_item(&mut self, item: &ast::Item) {
+        // Remember `macro_rules!` definitions even if they are not formatted, so that
+        // invocations of the macro can be matched against them.
+        if let ast::ItemKind::MacroDef(ident, ref def) = item.kind {
+            if def.macro_rules {
+                self.macro_rules_defs
+                    .borrow_mut()
+                    .insert(ident.name.to_string(), def.body.tokens.clone());
+            }
+        }
         skip_out_of_file_lines_range_visitor!(self, item.span);
 
         // This is where we bail out if there is a skip attribute. This is only
@@ -820,6 +831,7 @@ pub(crate) fn from_context(ctx: &'a RewriteContext<'_>) -> FmtVisitor<'a> {
             ctx.report.clone(),
         );
         visitor.skip_context.update(ctx.skip_context.clone());
+        visitor.macro_rules_defs = ctx.macro_rules_defs.clone();
         visitor.set_parent_context(ctx);
         visitor
     }
@@ -855,6 +867,7 @@ pub(crate) fn from_psess(
             macro_rewrite_failure: false,
             report,
             skip_context,
+            macro_rules_defs: MacroRulesDefs::default(),
         }
     }
 
@@ -1017,7 +1030,13 @@ fn format_mod(
                 self.last_pos = mod_lo;
                 self.block_indent = self.block_indent.block_indent(self.config);
                 self.visit_attrs(attrs, ast::AttrStyle::Inner);
+                // `macro_rules!` definitions inside the module go out of scope at its end,
+                // unless the module is marked with `#[macro_use]`.
+                let outer_macro_rules_defs = self.macro_rules_defs.borrow().clone();
                 self.walk_mod_items(items);
+                if !attr::contains_name(attrs, symbol::sym::macro_use) {
+                    *self.macro_rules_defs.borrow_mut() = outer_macro_rules_defs;
+                }
                 let missing_span = self.next_span(inner_span.hi() - BytePos(1));
                 self.close_block(missing_span, false);
             }
@@ -1081,6 +1100,7 @@ pub(crate) fn get_context(&self) -> RewriteContext<'_> {
             report: self.report.clone(),
             skip_context: self.skip_context.clone(),
             skipped_range: self.skipped_range.clone(),
+            macro_rules_defs: self.macro_rules_defs.clone(),
         }
     }
 
@@ -0,0 +1,74 @@
+// rustfmt-format_macro_invocations_by_matchers: ["route", "html", "late", "m"]
+
+#[rustfmt::skip]
+macro_rules! route {
+    ($method:ident $path:literal => $handler:expr) => {};
+}
+
+#[rustfmt::skip]
+macro_rules! html {
+    ($($tag:ident { $($body:expr),* })*) => {};
+}
+
+#[rustfmt::skip]
+macro_rules! unselected {
+    ($method:ident $path:literal => $handler:expr) => {};
+}
+
+fn main() {
+    // Should format the fragments of these invocations
+    route!(GET "/users" => |req|   handle( req,1+2 ));
+    html! {
+        div { 1+1, foo( x ) }
+        span { }
+    }
+
+    // Should not format the fragments of these invocations
+    unselected!(GET "/users" => |req|   handle( req,1+2 ));
+    route!(GET "/users" | |req|   handle( req,1+2 ));
+}
+
+fn before_definition() {
+    // Should not format the fragments of this invocation, `late!` is not defined yet
+    late!(GET "/users" => |req|   handle( req,1+2 ));
+}
+
+#[rustfmt::skip]
+macro_rules! late {
+    ($method:ident $path:literal => $handler:expr) => {};
+}
+
+mod a {
+    #[rustfmt::skip]
+    macro_rules! m {
+        ($name:ident => $e:expr) => {};
+    }
+
+    fn f() {
+        // Should format the fragments of these invocations
+        m!(x => 1+2);
+        route!(GET "/users" => |req|   handle( req,1+2 ));
+    }
+}
+
+mod b {
+    fn f() {
+        // Should not format the fragments of this invocation, the `m!` of `a` is out of scope
+        m!(x => 1+2);
+    }
+
+    #[rustfmt::skip]
+    macro_rules! m {
+        ($name:ident -> $e:expr) => {};
+    }
+
+    fn g() {
+        // Should format the fragments of this invocation
+        m!(x -> 1+2);
+    }
+}
+
+fn after_modules() {
+    // Should not format the fragments of this invocation, the `m!` of `b` is out of scope
+    m!(x -> 1+2);
+}
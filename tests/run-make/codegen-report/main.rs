@@ -0,0 +1,12 @@
+use std::fmt::Debug;
+
+#[inline(never)]
+fn show<T: Debug>(value: T) -> String {
+    format!("{value:?}")
+}
+
+fn main() {
+    println!("{}", show(1u8));
+    println!("{}", show("two"));
+    println!("{}", show([3.0f32]));
+}
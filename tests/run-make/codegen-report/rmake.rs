@@ -0,0 +1,21 @@
+//@ needs-target-std
+//
+// Checks that `-Z codegen-report` writes a JSON report with the number of instantiations and
+// the size of each function that was codegened.
+
+use run_make_support::{rfs, rustc, serde_json};
+
+fn main() {
+    rustc().input("main.rs").opt_level("0").arg("-Zcodegen-report=report.json").run();
+
+    let report: serde_json::Value =
+        serde_json::from_str(&rfs::read_to_string("report.json")).unwrap();
+    let functions = report.as_array().unwrap();
+    let show = functions.iter().find(|function| function["name"] == "show").unwrap();
+    assert_eq!(show["instantiations"], 3);
+    assert!(show["mir_statements"].as_u64().unwrap() > 0);
+    assert!(show["llvm_instructions"].as_u64().unwrap() > 0);
+    assert!(show["optimization_secs"].as_f64().unwrap() >= 0.0);
+    let main = functions.iter().find(|function| function["name"] == "main").unwrap();
+    assert_eq!(main["instantiations"], 1);
+}
@@ -0,0 +1,15 @@
+const fn fib(n: u32) -> u32 {
+    if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+}
+
+const fn table() -> [u32; 8] {
+    let mut table = [0; 8];
+    let mut i = 0;
+    while i < 8 {
+        table[i] = fib(i as u32);
+        i += 1;
+    }
+    table
+}
+
+pub const TABLE: [u32; 8] = table();
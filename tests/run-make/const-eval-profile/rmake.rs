@@ -0,0 +1,37 @@
+//@ needs-target-std
+//
+// Checks that `-Z const-eval-profile` writes a JSON report and a collapsed stack file
+// with the functions executed while evaluating a constant.
+
+use run_make_support::path_helpers::{has_extension, shallow_find_files};
+use run_make_support::{cwd, rfs, rustc, serde_json};
+
+fn main() {
+    rustc()
+        .crate_type("lib")
+        .emit("metadata")
+        .input("lib.rs")
+        .arg(format!("-Zconst-eval-profile={}", cwd().display()))
+        .run();
+
+    let jsonl = shallow_find_files(cwd(), |path| has_extension(path, "jsonl"));
+    let [jsonl] = &jsonl[..] else { panic!("expected one report, found {jsonl:?}") };
+    let report = rfs::read_to_string(jsonl)
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .find(|report| report["item"] == "TABLE")
+        .unwrap();
+    let functions = report["functions"].as_array().unwrap();
+    let fib = functions.iter().find(|function| function["name"] == "fib").unwrap();
+    // fib(0) through fib(7), including the recursive calls.
+    assert_eq!(fib["calls"], 100);
+    assert!(fib["steps"].as_u64().unwrap() > 0);
+    let table = functions.iter().find(|function| function["name"] == "table").unwrap();
+    assert_eq!(table["calls"], 1);
+    assert!(table["alloc_bytes"].as_u64().unwrap() >= 32);
+
+    let folded = shallow_find_files(cwd(), |path| has_extension(path, "folded"));
+    let [folded] = &folded[..] else { panic!("expected one stack file, found {folded:?}") };
+    let folded = rfs::read_to_string(folded);
+    assert!(folded.lines().any(|line| line.starts_with("TABLE;table;fib;fib ")));
+}
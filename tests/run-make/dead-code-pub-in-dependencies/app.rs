@@ -0,0 +1,4 @@
+pub fn run() {
+    util::used_by_app();
+    util::make_handle().get();
+}
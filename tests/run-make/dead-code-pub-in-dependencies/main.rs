@@ -0,0 +1,8 @@
+fn main() {
+    util::used_by_main();
+    let _config = util::Config::load();
+    let _unit = util::Unit;
+    let _pair = util::Pair(1);
+    let _mode = util::Mode::Fast;
+    app::run();
+}
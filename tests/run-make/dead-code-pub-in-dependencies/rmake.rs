@@ -0,0 +1,57 @@
+//@ needs-target-std
+// Check that `-Z dead-code-pub-in-dependencies` reports the `pub` items of a dependency that no
+// crate of the dependency graph uses, based on the uses recorded in the metadata of each crate
+// with `-Z record-extern-item-uses`.
+
+use run_make_support::{rust_lib_name, rustc};
+
+fn main() {
+    rustc().crate_type("lib").input("util.rs").arg("-Zrecord-extern-item-uses").run();
+
+    let check_main = || {
+        rustc()
+            .input("main.rs")
+            .extern_("util", rust_lib_name("util"))
+            .extern_("app", rust_lib_name("app"))
+            .arg("-Zrecord-extern-item-uses")
+            .arg("-Zdead-code-pub-in-dependencies=util")
+            .arg("-Wdead_code_pub_in_dependencies")
+            .run()
+    };
+
+    rustc()
+        .crate_type("lib")
+        .input("app.rs")
+        .extern_("util", rust_lib_name("util"))
+        .arg("-Zrecord-extern-item-uses")
+        .run();
+    check_main()
+        .assert_stderr_contains("function `util::unused` is never used outside of `util`")
+        .assert_stderr_contains(
+            "method `util::Config::unused_method` is never used outside of `util`",
+        )
+        .assert_stderr_contains(
+            "constant `util::nested::UNUSED_CONST` is never used outside of `util`",
+        )
+        .assert_stderr_contains("struct `util::UnusedUnit` is never used outside of `util`")
+        .assert_stderr_not_contains("`util::used_by_main`")
+        .assert_stderr_not_contains("`util::used_by_app`")
+        .assert_stderr_not_contains("`util::Config`")
+        .assert_stderr_not_contains("`util::Handle`")
+        .assert_stderr_not_contains("`util::make_handle`")
+        .assert_stderr_not_contains("`util::Unit`")
+        .assert_stderr_not_contains("`util::Pair`")
+        .assert_stderr_not_contains("`util::Mode`");
+
+    // Without recorded uses, `app` may use anything, so nothing is reported. The standard library
+    // is built without recorded uses too, but it can't use `util`.
+    rustc().crate_type("lib").input("app.rs").extern_("util", rust_lib_name("util")).run();
+    check_main()
+        .assert_stderr_contains("not checking for `pub` items of dependencies that no crate uses")
+        .assert_stderr_contains(
+            "the items used by `app` are unknown, since it was built without \
+             `-Z record-extern-item-uses`",
+        )
+        .assert_stderr_not_contains("is never used outside of `util`")
+        .assert_stderr_not_contains("`std`");
+}
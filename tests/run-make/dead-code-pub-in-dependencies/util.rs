@@ -0,0 +1,42 @@
+pub fn used_by_main() {}
+
+pub fn used_by_app() {}
+
+pub fn unused() {}
+
+pub struct Config;
+
+impl Config {
+    pub fn load() -> Config {
+        Config
+    }
+
+    pub fn unused_method(&self) {}
+}
+
+// Only ever obtained through `make_handle`, but its method is called.
+pub struct Handle;
+
+impl Handle {
+    pub fn get(&self) {}
+}
+
+pub fn make_handle() -> Handle {
+    Handle
+}
+
+// Only ever constructed.
+pub struct Unit;
+
+pub struct Pair(pub u32);
+
+pub enum Mode {
+    Fast,
+    Slow,
+}
+
+pub struct UnusedUnit;
+
+pub mod nested {
+    pub const UNUSED_CONST: u32 = 0;
+}
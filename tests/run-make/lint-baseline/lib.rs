@@ -0,0 +1,7 @@
+pub fn foo() {
+    let x = 1;
+}
+
+pub fn bar() {
+    let y = 2;
+}
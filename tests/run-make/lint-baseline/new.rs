@@ -0,0 +1,11 @@
+pub fn foo() {
+    let x = 1;
+}
+
+pub fn bar() {
+    let y = 2;
+}
+
+pub fn baz() {
+    let z = 3;
+}
@@ -0,0 +1,41 @@
+//@ needs-target-std
+//
+// Check that `--update-lint-baseline` records the lints of a crate in the
+// `--lint-baseline` file, that the recorded lints are then suppressed, and
+// that lints which are not in the baseline are still reported.
+
+use run_make_support::{assert_contains, rfs, rustc};
+
+fn main() {
+    rustc()
+        .input("lib.rs")
+        .crate_type("lib")
+        .crate_name("baselined")
+        .args(&["-Zunstable-options", "--lint-baseline", "baseline.json"])
+        .arg("--update-lint-baseline")
+        .run()
+        .assert_stderr_not_contains("unused variable");
+    let baseline = rfs::read_to_string("baseline.json");
+    assert_contains(&baseline, r#""lint": "unused_variables""#);
+    assert_contains(&baseline, r#""item": "foo""#);
+    assert_contains(&baseline, r#""item": "bar""#);
+
+    rustc()
+        .input("lib.rs")
+        .crate_type("lib")
+        .crate_name("baselined")
+        .args(&["-Zunstable-options", "--lint-baseline", "baseline.json"])
+        .run()
+        .assert_stderr_not_contains("unused variable");
+
+    // `new.rs` is `lib.rs` with one more unused variable, in `baz`.
+    rustc()
+        .input("new.rs")
+        .crate_type("lib")
+        .crate_name("baselined")
+        .args(&["-Zunstable-options", "--lint-baseline", "baseline.json"])
+        .run()
+        .assert_stderr_contains("unused variable: `z`")
+        .assert_stderr_not_contains("unused variable: `x`")
+        .assert_stderr_not_contains("unused variable: `y`");
+}
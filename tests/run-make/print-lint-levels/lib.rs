@@ -0,0 +1,10 @@
+#![allow(unexpected_cfgs)]
+#![expect(unused_mut)]
+
+pub mod my_mod {
+    #[allow(unsafe_code, reason = "we know what we are doing")]
+    pub fn f() {}
+
+    #[expect(dead_code)]
+    fn unused() {}
+}
@@ -0,0 +1,59 @@
+//! This checks the output of `--print=lint-levels`
+
+extern crate run_make_support;
+
+use run_make_support::rustc;
+use run_make_support::serde_json::{self, Value};
+
+fn main() {
+    let output = rustc()
+        .input("lib.rs")
+        .crate_type("lib")
+        .emit("metadata")
+        .arg("-Zunstable-options")
+        .arg("-Zcrate-attr=warn(missing_docs)")
+        .arg("-Aunused_variables")
+        .print("lint-levels")
+        .run();
+    let lint_levels: Vec<Value> = serde_json::from_str(&output.stdout_utf8()).unwrap();
+
+    let unexpected_cfgs = find(&lint_levels, "unexpected_cfgs");
+    assert_eq!(unexpected_cfgs["level"], "allow");
+    assert_eq!(unexpected_cfgs["scope"]["item"], "lib");
+    assert_eq!(unexpected_cfgs["source"]["kind"], "attribute");
+    assert_eq!(unexpected_cfgs["source"]["span"]["line"], 1);
+    assert!(unexpected_cfgs.get("fulfilled").is_none());
+
+    let unused_mut = find(&lint_levels, "unused_mut");
+    assert_eq!(unused_mut["level"], "expect");
+    assert_eq!(unused_mut["fulfilled"], false);
+
+    let unsafe_code = find(&lint_levels, "unsafe_code");
+    assert_eq!(unsafe_code["level"], "allow");
+    assert_eq!(unsafe_code["scope"]["item"], "my_mod::f");
+    assert_eq!(unsafe_code["scope"]["span"]["line"], 6);
+    assert_eq!(unsafe_code["source"]["span"]["line"], 5);
+    assert_eq!(unsafe_code["source"]["reason"], "we know what we are doing");
+
+    let dead_code = find(&lint_levels, "dead_code");
+    assert_eq!(dead_code["level"], "expect");
+    assert_eq!(dead_code["scope"]["item"], "my_mod::unused");
+    assert_eq!(dead_code["fulfilled"], true);
+
+    let missing_docs = find(&lint_levels, "missing_docs");
+    assert_eq!(missing_docs["level"], "warn");
+    assert_eq!(missing_docs["source"]["kind"], "crate-attr");
+
+    let unused_variables = find(&lint_levels, "unused_variables");
+    assert_eq!(unused_variables["level"], "allow");
+    assert_eq!(unused_variables["source"]["kind"], "command-line");
+    assert_eq!(unused_variables["source"]["level"], "allow");
+}
+
+#[track_caller]
+fn find<'a>(lint_levels: &'a [Value], lint: &str) -> &'a Value {
+    let mut found = lint_levels.iter().filter(|lint_level| lint_level["lint"] == lint);
+    let lint_level = found.next().unwrap_or_else(|| panic!("no level set for `{lint}`"));
+    assert!(found.next().is_none(), "several levels set for `{lint}`");
+    lint_level
+}
@@ -0,0 +1,23 @@
+//! Check that the notes added by `-Zborrowck-explain` are regular children of
+//! the diagnostic in the JSON output.
+
+//@ compile-flags: -Zborrowck-explain --error-format=json
+//@ dont-check-compiler-stderr
+
+fn main() {
+    let mut x = 0;
+    let a = &mut x;
+    //~^ NOTE `x` is borrowed here
+    //~| NOTE the borrow is kept live by this chain of outlives constraints
+    //~| NOTE 1. assignment makes the borrow flow here
+    //~| NOTE the borrow is live along this control-flow path to its later use
+    //~| NOTE 1. the borrow is created here
+    let b = &*a;
+    //~^ NOTE 2. the borrow flows here
+    x = 1;
+    //~^ ERROR cannot assign to `x` because it is borrowed
+    //~| NOTE `x` is assigned to here but it was already borrowed
+    let _y = *b;
+    //~^ NOTE borrow later used here
+    //~| NOTE 2. the borrow is used here, while still live
+}
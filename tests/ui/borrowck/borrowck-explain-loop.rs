@@ -0,0 +1,14 @@
+//! Check that `-Zborrowck-explain` shows the loop back edge along which a
+//! borrow made in one iteration stays live until the next one.
+
+//@ compile-flags: -Zborrowck-explain
+
+fn main() {
+    let mut x = 0;
+    let mut r = &0;
+    loop {
+        let _y = *r;
+        r = &x;
+        x = 1; //~ ERROR cannot assign to `x` because it is borrowed
+    }
+}
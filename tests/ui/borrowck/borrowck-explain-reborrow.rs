@@ -0,0 +1,12 @@
+//! Check that `-Zborrowck-explain` shows how a borrow flows through a reborrow
+//! into the reference that is used later.
+
+//@ compile-flags: -Zborrowck-explain
+
+fn main() {
+    let mut x = 0;
+    let a = &mut x;
+    let b = &*a;
+    x = 1; //~ ERROR cannot assign to `x` because it is borrowed
+    let _y = *b;
+}
@@ -0,0 +1,75 @@
+//@ run-pass
+// Ensure that `const_allocate`d allocations that were not made global through `const_make_global`
+// are promoted to immutable globals if they are only reachable through shared references to memory
+// without interior mutability. `promote_shared_ref_interior_mut.rs` covers `Cell` and `AtomicI32`,
+// which are not promoted.
+
+#![feature(core_intrinsics)]
+#![feature(const_heap)]
+use std::intrinsics;
+
+const FOO: &i32 = foo();
+const FOO_RAW: *const i32 = foo();
+
+const fn foo() -> &'static i32 {
+    let t = unsafe {
+        let i = intrinsics::const_allocate(4, 4) as *mut i32;
+        *i = 20;
+        i
+    };
+    unsafe { &*t }
+}
+
+// The same allocation can be shared by several references.
+const PAIR: (&i32, &i32) = {
+    let r = foo();
+    (r, r)
+};
+
+// Leaking a `Vec` gives a shared reference to its memory.
+const SLICE: &[u32] = {
+    let mut v = Vec::with_capacity(6);
+    let mut x = 1;
+    while x < 42 {
+        v.push(x);
+        x *= 2;
+    }
+    v.leak()
+};
+
+// Allocations that are only reachable from a promoted one are promoted as well.
+const NESTED: &[&[u32]] = {
+    let mut v = Vec::new();
+    v.push(SLICE);
+    v.push({
+        let mut inner = Vec::new();
+        inner.push(7);
+        inner.leak()
+    });
+    v.leak()
+};
+
+static STATIC: &[u32] = {
+    let mut v = Vec::new();
+    v.push(3);
+    v.leak()
+};
+
+// The allocation is immutable even though the static itself is mutable.
+static mut STATIC_MUT: &[u32] = {
+    let mut v = Vec::new();
+    v.push(5);
+    v.leak()
+};
+
+fn main() {
+    assert_eq!(*FOO, 20);
+    assert_eq!(unsafe { *FOO_RAW }, 20);
+    assert_eq!(PAIR, (&20, &20));
+    assert_eq!(SLICE, [1, 2, 4, 8, 16, 32]);
+    assert_eq!(NESTED.len(), 2);
+    assert_eq!(NESTED[0], SLICE);
+    assert_eq!(NESTED[1], [7]);
+    assert_eq!(STATIC, [3]);
+    assert_eq!(unsafe { STATIC_MUT }, [5]);
+}
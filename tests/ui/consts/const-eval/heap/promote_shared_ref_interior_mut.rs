@@ -0,0 +1,33 @@
+// Ensure that `const_allocate`d allocations that were not made global through `const_make_global`
+// are *not* promoted to immutable globals when they are only reachable through shared references
+// to interior mutable types, in both `static` and `static mut`: the memory could still be mutated.
+// `promote_shared_ref.rs` covers the allocations that do get promoted.
+
+#![feature(core_intrinsics)]
+#![feature(const_heap)]
+use std::cell::Cell;
+use std::intrinsics;
+use std::sync::atomic::AtomicI32;
+
+struct SyncCell(Cell<i32>);
+unsafe impl Sync for SyncCell {}
+
+const fn leak<T>(value: T) -> &'static T {
+    unsafe {
+        let p = intrinsics::const_allocate(size_of::<T>(), align_of::<T>()) as *mut T;
+        p.write(value);
+        &*p
+    }
+}
+
+static CELL: &SyncCell = leak(SyncCell(Cell::new(0)));
+//~^ error: encountered `const_allocate` pointer in final value that was not made global
+static ATOMIC: &AtomicI32 = leak(AtomicI32::new(0));
+//~^ error: encountered `const_allocate` pointer in final value that was not made global
+
+static mut CELL_MUT: &Cell<i32> = leak(Cell::new(0));
+//~^ error: encountered `const_allocate` pointer in final value that was not made global
+static mut ATOMIC_MUT: &AtomicI32 = leak(AtomicI32::new(0));
+//~^ error: encountered `const_allocate` pointer in final value that was not made global
+
+fn main() {}
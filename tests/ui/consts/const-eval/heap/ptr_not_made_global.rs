@@ -1,24 +1,27 @@
 // Ensure that we reject interning `const_allocate`d allocations in the final value of constants
-// if they have not been made global through `const_make_global`. The pointers are made *immutable*
-// to focus the test on the missing `make_global`; `ptr_not_made_global_mut.rs` covers the case
-// where the pointer remains mutable.
+// if they have not been made global through `const_make_global`, unless they are only reachable
+// through shared references. Here, there is also a raw pointer to the allocation that was not
+// derived from a shared reference; `ptr_not_made_global_mut.rs` covers the case where there is
+// only such a pointer, and `promote_shared_ref.rs` the allocations that do get promoted.
 
 #![feature(core_intrinsics)]
 #![feature(const_heap)]
 use std::intrinsics;
 
-const FOO: &i32 = foo();
-//~^ error: encountered `const_allocate` pointer in final value that was not made global
-const FOO_RAW: *const i32 = foo();
+const FOO: (&i32, *const i32) = foo();
 //~^ error: encountered `const_allocate` pointer in final value that was not made global
+const BAR: (*const i32, &i32) = {
+    //~^ error: encountered `const_allocate` pointer in final value that was not made global
+    let (r, p) = foo();
+    (p, r)
+};
 
-const fn foo() -> &'static i32 {
-    let t = unsafe {
+const fn foo() -> (&'static i32, *const i32) {
+    unsafe {
         let i = intrinsics::const_allocate(4, 4) as *mut i32;
         *i = 20;
-        i
-    };
-    unsafe { &*t }
+        (&*i, i)
+    }
 }
 
 fn main() {}
@@ -0,0 +1,21 @@
+//! Check that the macro backtrace of a diagnostic is reported as a stack when
+//! `--error-format=sarif` is used.
+
+//@ compile-flags: --error-format=sarif -Zunstable-options
+//@ normalize-stderr: "file:///?[^ ]*/diagnostic-flags/" -> "file://$$DIR/"
+
+macro_rules! mismatch {
+    () => {
+        let _x: () = 42;
+    };
+}
+
+macro_rules! outer {
+    () => {
+        mismatch!()
+    };
+}
+
+fn main() {
+    outer!();
+}
@@ -0,0 +1,12 @@
+//! Check that the secondary labels and the notes of a diagnostic are reported
+//! as related locations when `--error-format=sarif` is used.
+
+//@ compile-flags: --error-format=sarif -Zunstable-options
+//@ normalize-stderr: "file:///?[^ ]*/diagnostic-flags/" -> "file://$$DIR/"
+
+#![deny(unreachable_code)]
+
+fn main() {
+    return;
+    let _x = 0;
+}
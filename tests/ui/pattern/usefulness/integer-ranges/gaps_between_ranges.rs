@@ -0,0 +1,43 @@
+#![deny(gaps_between_ranges)]
+
+const HEADER_END: u8 = 0x0f;
+const PAYLOAD_START: u8 = 0x11;
+const TRAILER_START: u8 = 0x20;
+
+fn main() {
+    match 0u8 {
+        0..=HEADER_END => {} //~ ERROR `16_u8` is not matched by any of the ranges around it
+        PAYLOAD_START..=0x17 => {} //~ ERROR `24_u8..=31_u8` is not matched by any of the ranges
+        TRAILER_START..=0x7f => {}
+        _ => {}
+    }
+    match (true, 0u8) {
+        (true, 0..=9) => {} //~ ERROR `10_u8..=11_u8` is not matched by any of the ranges
+        (true, 12..=20) => {}
+        _ => {}
+    }
+    // Adjacent ranges leave no gap.
+    match 0u8 {
+        0..=9 => {}
+        10..=20 => {}
+        _ => {}
+    }
+    // Gaps next to single values are usually deliberate.
+    match 0u8 {
+        0 => {}
+        2..=9 => {}
+        11 => {}
+        _ => {}
+    }
+    exclusive_range();
+}
+
+#[allow(non_contiguous_range_endpoints)]
+fn exclusive_range() {
+    // This is reported by `non_contiguous_range_endpoints` instead.
+    match 0u8 {
+        0..10 => {}
+        11..=20 => {}
+        _ => {}
+    }
+}
@@ -0,0 +1,50 @@
+//@ run-rustfix
+//@ rustfix-only-machine-applicable
+// Check that non-exhaustive matches come with a machine-applicable suggestion that adds one arm per
+// missing pattern, in the order the constructors are declared, and that missing patterns that print
+// the same only get one arm.
+#![allow(dead_code)]
+
+enum Direction {
+    North,
+    East,
+    South,
+    West,
+    Up,
+    Down,
+}
+
+enum Light {
+    Red,
+    Amber,
+    Green,
+}
+
+#[derive(Copy, Clone)]
+union U8AsBool {
+    n: u8,
+    b: bool,
+}
+
+fn main() {
+    match Direction::North { //~ ERROR non-exhaustive patterns
+        Direction::East => (),
+    }
+
+    // The missing patterns are nested in tuples and options.
+    match (Some(true), Light::Red) { //~ ERROR non-exhaustive patterns
+        (None, Light::Red) => (),
+        (Some(true), Light::Red) => (),
+        (Some(false), Light::Red) => (),
+    }
+
+    // Some of the missing patterns of unions are reported twice.
+    let x = U8AsBool { n: 1 };
+    unsafe {
+        match (x, true) { //~ ERROR non-exhaustive patterns
+            (U8AsBool { b: true }, true) => (),
+            (U8AsBool { b: false }, true) => (),
+            (U8AsBool { n: 1.. }, true) => (),
+        }
+    }
+}